@@ -0,0 +1,220 @@
+# BlinkyBoosts-Bedienoberfläche, Deutsch.
+
+recent-boosts = Letzte Boosts
+outputs = Ausgänge
+inputs = Eingänge
+wled-presets = WLED-Presets
+virtual-strip = Virtueller Streifen
+toggles = Schalter
+fire = Auslösen
+test = Test
+scenes = Szenen
+pending-effects = Ausstehende Effekte
+master-dimmer = Hauptdimmer
+tags = Tags:
+idle-pattern = Ruhemuster
+enabled = Aktiv
+pattern-rainbow = Regenbogen
+pattern-breathing = Atmen
+pattern-sparkle = Funkeln
+log = Protokoll
+latency = Latenz
+input-activity = Eingangsaktivität (letzte Stunde)
+failed-effects = Fehlgeschlagene Effekte
+effect-history = Effektverlauf
+components = Komponenten
+campaigns = Kampagnen
+boost-us = Boost uns
+request-boost = Boost anfordern
+create-invoice = Rechnung erstellen
+invoice-waiting = Warte auf { $sats } Sats…
+invoice-paid = { $sats } Sats bezahlt!
+invoice-expired = Rechnung über { $sats } Sats abgelaufen
+awaiting-approval = Wartet auf Freigabe
+cancel = Abbrechen
+name = Name:
+universe = Universum:
+relays = Relays:
+no-recent-boosts = Keine neuen Boosts
+load-older = Ältere laden
+hide-older = Ältere ausblenden
+no-older-boosts = Keine älteren Boosts im Protokoll
+add-marker = Markierung setzen
+marker-hint = z. B. Teil 2 begonnen
+note = Notiz
+note-hint = Notiz für die Nachbearbeitung
+add = + Hinzufügen
+unix-seconds-hint = Unix-Sekunden; leer für keine
+reload-history-hint = Verlauf dieser Quelle mit diesen Zeiten neu laden und ihren Anteil an der Summe neu zählen
+toggle = Schalter:
+setup = Einrichtung:
+sender = Absender:
+save-changes = Änderungen speichern?
+sats = Sats:
+repeat = Wiederholen:
+finish-show-confirm = Die Show zusammenfassen und die Zusammenfassung der nächsten bei null beginnen?
+pubkey = Pubkey:
+presets = Presets:
+playlists = Playlists:
+playlist = Playlist:
+no-segments = Keine Segmente in [wled] definiert; Presets brauchen mindestens eines
+naddr = NADDR:
+local = Lokal:
+host = Host:
+force = Erzwingen:
+filter = Filter:
+end = Ende:
+effects-not-loaded = Effekte nicht geladen; Namen werden eingetippt
+broadcast = Broadcast:
+brightness = Helligkeit:
+address = Adresse:
+failures-only = Nur Fehler
+start-at-login = Bei Anmeldung starten
+save = Speichern
+reload-history = Verlauf jetzt neu laden
+load-effects = Effekte laden
+find-controllers = Controller suchen
+use = Verwenden
+selected = Ausgewählt
+finish = Beenden
+finish-show = Show beenden
+run-test-sequence = Testablauf starten
+copy-markdown = Markdown kopieren
+clear = Leeren
+add-preset = + Preset hinzufügen
+add-playlist = + Playlist hinzufügen
+show-recap = Show-Zusammenfassung
+save-configuration = Konfiguration speichern
+finish-show-title = Show beenden
+remove-last = ✖ Letztes
+trigger = Auslösen
+stop = Stoppen
+capture = Erfassen
+apply-to-controller = Auf Controller anwenden
+keyboard-shortcuts = Tastenkürzel
+total-sats = Sats gesamt:
+running = Läuft
+stopped = Gestoppt
+this-show = Diese Show
+top-boosters = Top-Booster
+by-source = Nach Quelle
+analytics = Statistik
+sats-per-day = Sats pro Tag
+boosts-per-episode = Boosts pro Folge
+source-share = Anteil nach Quelle
+average-boost = Durchschnittlicher Boost pro Show
+analytics-from = Von:
+analytics-to = Bis:
+reload-recaps = Zusammenfassungen neu laden
+not-a-shortcut = Kein Tastenkürzel
+shortcut-pause-effects = Effekte pausieren:
+shortcut-fire-test = Test auslösen:
+shortcut-dismiss-dialogs = Dialoge schließen:
+shortcut-next-tab = Nächster Show-Tab:
+shortcut-replay-last-boost = Letzten Boost wiederholen:
+load-since = Laden seit:
+after = Nach:
+before = Vor:
+speed = Geschwindigkeit:
+intensity = Intensität:
+durations = Dauern:
+transitions = Übergänge:
+streak-milestone = { $sender } hat { $streak } Folgen in Serie geboostet!
+streak-returning = { $sender } ist zurück: { $streak } Folgen in Serie
+streak-back = { $sender } ist zurück
+none = Keine
+disable = Deaktivieren
+enable = Aktivieren
+hidden = verborgen
+amount-sats = { $sats } Sats
+wallet-balance = Wallet: { $wallet } - Guthaben { $balance }
+wallet-notifications = Zahlungsbenachrichtigungen
+wallet-polling = Abfrage (keine Benachrichtigungen)
+wallet-connecting = verbinde
+wallet-check = Relay-Umlaufzeit: { $latency } - { $mode } - geprüft { $checked }
+nwc-current = Verbunden über Relay { $relay }
+nwc-none = Noch keine Wallet verbunden
+nwc-connect = Wallet verbinden
+nwc-step-create = 1. Lege in deiner Wallet eine Verbindung für BlinkyBoosts an
+nwc-open-alby-hub = Alby Hub öffnen
+nwc-show-qr = Als QR anzeigen
+nwc-hide-qr = QR ausblenden
+nwc-other-wallets = Andere Wallets: füge eine App-Verbindung mit get_info, list_transactions, make_invoice und get_balance hinzu, mit Zahlungsbenachrichtigungen, falls angeboten.
+nwc-step-paste = 2. Füge das Verbindungsgeheimnis aus der Wallet ein
+nwc-check = Prüfen
+nwc-step-review = 3. Verbunden mit { $wallet }
+nwc-can-receive = Empfängt Boosts
+nwc-can-notify = Boosts kommen sofort an (Zahlungsbenachrichtigungen)
+nwc-can-history = Lädt frühere Boosts
+nwc-can-invoice = Erstellt Rechnungen für den QR-Code auf dem Bildschirm
+nwc-can-balance = Zeigt das Guthaben
+nwc-cannot-receive = Diese Verbindung sieht keine eingehenden Zahlungen. Erlaube list_transactions oder Zahlungsbenachrichtigungen und versuche es erneut.
+nwc-in-use = Diese Verbindung wird verwendet
+nwc-use = Diese Verbindung verwenden
+restart = Neu starten
+start = Starten
+episode = Folge: { $episode }
+episode-locked = 🔒 Nur { $episode } wird gezählt
+lock-to-episode = Auf aktuelle Folge festlegen
+lock-to-episode-hint = Nur Boosts für die Folge des letzten Boosts zählen, aus allen Quellen, bis zum Entsperren
+unlock = Entsperren
+wled-effects-loaded = { $count } Effekte von { $host }
+wled-controller = { $name } ({ $host }, WLED { $version })
+no-controllers-found = Kein WLED-Controller in diesem Netzwerk hat geantwortet
+remote-unavailable = Nicht verfügbar, während ein Agent gesteuert wird
+playlist-presets = Presets: { $presets }
+history-up-to = , bis { $time }
+history-loaded = Verlauf von { $source }: { $count } Boosts geladen
+history-loading = Lade Verlauf von { $source }: { $count } Boosts
+dimmer-effective = Wirksam: { $level }%
+toggle-threshold = @ { $sats } Sats
+toggle-last-fired = , zuletzt { $time }
+toggle-stats = { $fired }x ausgelöst, { $sats } Sats getroffen
+mute-output = { $output } stummschalten
+gate-open = { $value } (offen)
+gate-closed = { $value } (geschlossen)
+gate-no-console-open = keine Konsole (offen)
+gate-no-console-closed = keine Konsole (geschlossen)
+gate = Konsole Kanal { $channel }: { $state }
+scenes-saved = Gespeichert: { $scenes }
+latency-stage = Ø { $avg } (max. { $max })
+input-events = { $count } in der letzten Minute
+input-round-trip = Umlaufzeit { $round_trip }
+effect-failed-retries = { $effect } nach { $attempts } Versuchen fehlgeschlagen: { $error }
+history-queued = , eingereiht { $ms }ms
+history-retry = (Versuch { $attempt })
+history-acknowledged = , bestätigt
+history-not-acknowledged = , nicht bestätigt
+history-failed = fehlgeschlagen: { $error }
+pending-effect = [in { $secs }s] { $effect } - { $sats } Sats von { $sender }
+artwork = Cover
+anonymous = anonym
+held-boost = { $sats } Sats von { $sender } über { $source }
+approve = Freigeben
+reject = Ablehnen
+block = Sperren
+episode-total = { $episode } ({ $sats } Sats)
+no-effects = keine
+relay-delay = [Relay { $delay }]
+boost-sender = { $sender } über
+boost-remote-item = für { $item }
+boost-from = { $sats } Sats von { $sender }
+recap-total = { $sats } Sats aus { $boosts } Boosts
+test-run-passed = Testlauf um { $time } bestanden
+test-run-failed = Testlauf um { $time } fehlgeschlagen
+no-recaps = Noch keine beendeten Shows in diesem Zeitraum; Zusammenfassungen werden beim Beenden einer Show in ./summaries gespeichert
+not-a-date = Kein Datum (JJJJ-MM-TT)
+day-sats = { $day }: { $sats } Sats
+share-percent = { $source }: { $percent }%
+recap-biggest = Größter Boost: { $sats } Sats von { $sender } über { $source }
+status-disabled = Deaktiviert
+status-enabled = Aktiviert
+status-running = Läuft
+status-reconnecting = Verbinde neu (Versuch { $attempt })
+status-unreachable = Nicht erreichbar
+status-error = Fehler
+recap-title = Show-Zusammenfassung
+recap-dates = { $start } bis { $end }
+recap-biggest-heading = Größter Boost
+recap-notes = Notizen und Markierungen
+recap-note-boost = { $sats } Sats von { $sender }: { $note }
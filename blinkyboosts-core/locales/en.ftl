@@ -0,0 +1,222 @@
+# BlinkyBoosts operator interface, English. Translations use the same ids: copy this file to
+# ./locales/<locale>.ftl (e.g. fr.ftl), translate the text after each `=` and set
+# `[display] locale`. Messages missing from a translation fall back to these.
+
+recent-boosts = Recent Boosts
+outputs = Outputs
+inputs = Inputs
+wled-presets = WLED Presets
+virtual-strip = Virtual Strip
+toggles = Toggles
+fire = Fire
+test = Test
+scenes = Scenes
+pending-effects = Pending Effects
+master-dimmer = Master Dimmer
+tags = Tags:
+idle-pattern = Idle Pattern
+enabled = Enabled
+pattern-rainbow = Rainbow
+pattern-breathing = Breathing
+pattern-sparkle = Sparkle
+log = Log
+latency = Latency
+input-activity = Input activity (last hour)
+failed-effects = Failed Effects
+effect-history = Effect History
+components = Components
+campaigns = Campaigns
+boost-us = Boost Us
+request-boost = Request a Boost
+create-invoice = Create invoice
+invoice-waiting = Waiting for { $sats } sats…
+invoice-paid = { $sats } sats paid!
+invoice-expired = Invoice for { $sats } sats expired
+awaiting-approval = Awaiting Approval
+cancel = Cancel
+name = Name:
+universe = Universe:
+relays = Relays:
+no-recent-boosts = No recent boosts
+load-older = Load older
+hide-older = Hide older
+no-older-boosts = No older boosts in the log
+add-marker = Add marker
+marker-hint = e.g. Started segment 2
+note = Note
+note-hint = Note for post-production
+add = + Add
+unix-seconds-hint = Unix seconds; empty for none
+reload-history-hint = Load this source's history again with these times and recount its share of the total
+toggle = Toggle:
+setup = Setup:
+sender = Sender:
+save-changes = Save changes?
+sats = Sats:
+repeat = Repeat:
+finish-show-confirm = Recap the show and start the next one's recap from nothing?
+pubkey = Pubkey:
+presets = Presets:
+playlists = Playlists:
+playlist = Playlist:
+no-segments = No segments defined in [wled]; presets need at least one
+naddr = NADDR:
+local = Local:
+host = Host:
+force = Force:
+filter = Filter:
+end = End:
+effects-not-loaded = Effects not loaded; names are typed in
+broadcast = Broadcast:
+brightness = Brightness:
+address = Address:
+failures-only = Failures only
+start-at-login = Start at login
+save = Save
+reload-history = Reload history now
+load-effects = Load effects
+find-controllers = Find controllers
+use = Use
+selected = Selected
+finish = Finish
+finish-show = Finish show
+run-test-sequence = Run test sequence
+copy-markdown = Copy Markdown
+clear = Clear
+add-preset = + Add preset
+add-playlist = + Add playlist
+show-recap = Show Recap
+save-configuration = Save Configuration
+finish-show-title = Finish Show
+remove-last = ✖ Last
+trigger = Trigger
+stop = Stop
+capture = Capture
+apply-to-controller = Apply to controller
+keyboard-shortcuts = Keyboard Shortcuts
+total-sats = Total Sats:
+running = Running
+stopped = Stopped
+this-show = This show
+top-boosters = Top boosters
+by-source = By source
+analytics = Analytics
+sats-per-day = Sats per day
+boosts-per-episode = Boosts per episode
+source-share = Source share
+average-boost = Average boost per show
+analytics-from = From:
+analytics-to = To:
+reload-recaps = Reload recaps
+not-a-shortcut = Not a shortcut
+shortcut-pause-effects = Pause effects:
+shortcut-fire-test = Fire test:
+shortcut-dismiss-dialogs = Dismiss dialogs:
+shortcut-next-tab = Next show tab:
+shortcut-replay-last-boost = Replay last boost:
+load-since = Load since:
+after = After:
+before = Before:
+speed = Speed:
+intensity = Intensity:
+durations = Durations:
+transitions = Transitions:
+streak-milestone = { $sender } has boosted { $streak } episodes in a row!
+streak-returning = { $sender } is back: { $streak } episodes in a row
+streak-back = { $sender } is back
+none = None
+disable = Disable
+enable = Enable
+hidden = hidden
+amount-sats = { $sats } sats
+wallet-balance = Wallet: { $wallet } - balance { $balance }
+wallet-notifications = payment notifications
+wallet-polling = polling (no notifications)
+wallet-connecting = connecting
+wallet-check = Relay round trip: { $latency } - { $mode } - checked { $checked }
+nwc-current = Connected through relay { $relay }
+nwc-none = No wallet connected yet
+nwc-connect = Connect a wallet
+nwc-step-create = 1. Create a connection for BlinkyBoosts in your wallet
+nwc-open-alby-hub = Open Alby Hub
+nwc-show-qr = Show as QR
+nwc-hide-qr = Hide QR
+nwc-other-wallets = Other wallets: add an app connection allowing get_info, list_transactions, make_invoice and get_balance, with payment notifications if offered.
+nwc-step-paste = 2. Paste the connection secret the wallet shows
+nwc-check = Check
+nwc-step-review = 3. Connected to { $wallet }
+nwc-can-receive = Receives boosts
+nwc-can-notify = Boosts arrive instantly (payment notifications)
+nwc-can-history = Loads past boosts
+nwc-can-invoice = Creates invoices for the on-screen QR
+nwc-can-balance = Shows the balance
+nwc-cannot-receive = This connection can't see incoming payments. Allow list_transactions or payment notifications and try again.
+nwc-in-use = This connection is in use
+nwc-use = Use this connection
+restart = Restart
+start = Start
+episode = Episode: { $episode }
+episode-locked = 🔒 Only counting { $episode }
+lock-to-episode = Lock to current episode
+lock-to-episode-hint = Count only boosts for the latest boost's episode, from every source, until unlocked
+unlock = Unlock
+wled-effects-loaded = { $count } effects from { $host }
+wled-controller = { $name } ({ $host }, WLED { $version })
+no-controllers-found = No WLED controllers answered on this network
+remote-unavailable = Not available while controlling an agent
+playlist-presets = Presets: { $presets }
+history-up-to = , up to { $time }
+history-loaded = { $source } history: { $count } boosts loaded
+history-loading = Loading { $source } history: { $count } boosts
+dimmer-effective = Effective: { $level }%
+toggle-threshold = @ { $sats } sats
+toggle-last-fired = , last { $time }
+toggle-stats = fired { $fired }x, { $sats } sats matched
+mute-output = Mute { $output }
+gate-open = { $value } (open)
+gate-closed = { $value } (closed)
+gate-no-console-open = no console (open)
+gate-no-console-closed = no console (closed)
+gate = Console ch{ $channel }: { $state }
+scenes-saved = Saved: { $scenes }
+latency-stage = avg { $avg } (max { $max })
+input-events = { $count } in the last minute
+input-round-trip = round trip { $round_trip }
+effect-failed-retries = { $effect } failed after { $attempts } retries: { $error }
+history-queued = , queued { $ms }ms
+history-retry = (retry { $attempt })
+history-acknowledged = , acknowledged
+history-not-acknowledged = , not acknowledged
+history-failed = failed: { $error }
+pending-effect = [in { $secs }s] { $effect } - { $sats } sats from { $sender }
+artwork = Artwork
+anonymous = anonymous
+held-boost = { $sats } sats from { $sender } via { $source }
+approve = Approve
+reject = Reject
+block = Block
+episode-total = { $episode } ({ $sats } sats)
+no-effects = none
+relay-delay = [relay { $delay }]
+boost-sender = { $sender } via
+boost-remote-item = for { $item }
+boost-from = { $sats } sats from { $sender }
+recap-total = { $sats } sats from { $boosts } boosts
+test-run-passed = Test run at { $time } passed
+test-run-failed = Test run at { $time } failed
+no-recaps = No finished shows in this range yet; recaps are saved to ./summaries when a show is finished
+not-a-date = Not a date (YYYY-MM-DD)
+day-sats = { $day }: { $sats } sats
+share-percent = { $source }: { $percent }%
+recap-biggest = Biggest boost: { $sats } sats from { $sender } via { $source }
+status-disabled = Disabled
+status-enabled = Enabled
+status-running = Running
+status-reconnecting = Reconnecting (attempt { $attempt })
+status-unreachable = Unreachable
+status-error = Error
+recap-title = Show recap
+recap-dates = { $start } to { $end }
+recap-biggest-heading = Biggest boost
+recap-notes = Notes and markers
+recap-note-boost = { $sats } sats from { $sender }: { $note }
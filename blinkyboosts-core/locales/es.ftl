@@ -0,0 +1,220 @@
+# Interfaz de operador de BlinkyBoosts, español.
+
+recent-boosts = Boosts recientes
+outputs = Salidas
+inputs = Entradas
+wled-presets = Presets de WLED
+virtual-strip = Tira virtual
+toggles = Interruptores
+fire = Disparar
+test = Prueba
+scenes = Escenas
+pending-effects = Efectos pendientes
+master-dimmer = Atenuador general
+tags = Etiquetas:
+idle-pattern = Patrón en reposo
+enabled = Activo
+pattern-rainbow = Arcoíris
+pattern-breathing = Respiración
+pattern-sparkle = Destellos
+log = Registro
+latency = Latencia
+input-activity = Actividad de entradas (última hora)
+failed-effects = Efectos fallidos
+effect-history = Historial de efectos
+components = Componentes
+campaigns = Campañas
+boost-us = Haz un boost
+request-boost = Pedir un boost
+create-invoice = Crear factura
+invoice-waiting = Esperando { $sats } sats…
+invoice-paid = ¡{ $sats } sats pagados!
+invoice-expired = La factura de { $sats } sats expiró
+awaiting-approval = Esperando aprobación
+cancel = Cancelar
+name = Nombre:
+universe = Universo:
+relays = Relés:
+no-recent-boosts = No hay boosts recientes
+load-older = Cargar anteriores
+hide-older = Ocultar anteriores
+no-older-boosts = No hay boosts anteriores en el registro
+add-marker = Añadir marcador
+marker-hint = p. ej. Empezó el segmento 2
+note = Nota
+note-hint = Nota para la posproducción
+add = + Añadir
+unix-seconds-hint = Segundos Unix; vacío para ninguno
+reload-history-hint = Vuelve a cargar el historial de esta fuente con estas horas y recalcula su parte del total
+toggle = Interruptor:
+setup = Configuración:
+sender = Remitente:
+save-changes = ¿Guardar los cambios?
+sats = Sats:
+repeat = Repetir:
+finish-show-confirm = ¿Resumir el programa y empezar el resumen del siguiente desde cero?
+pubkey = Clave pública:
+presets = Presets:
+playlists = Listas:
+playlist = Lista:
+no-segments = No hay segmentos definidos en [wled]; los presets necesitan al menos uno
+naddr = NADDR:
+local = Local:
+host = Host:
+force = Forzar:
+filter = Filtro:
+end = Fin:
+effects-not-loaded = Efectos no cargados; escribe los nombres
+broadcast = Difusión:
+brightness = Brillo:
+address = Dirección:
+failures-only = Solo fallos
+start-at-login = Iniciar al abrir sesión
+save = Guardar
+reload-history = Recargar historial ahora
+load-effects = Cargar efectos
+find-controllers = Buscar controladores
+use = Usar
+selected = Seleccionado
+finish = Terminar
+finish-show = Terminar programa
+run-test-sequence = Ejecutar secuencia de prueba
+copy-markdown = Copiar Markdown
+clear = Borrar
+add-preset = + Añadir preset
+add-playlist = + Añadir lista
+show-recap = Resumen del programa
+save-configuration = Guardar configuración
+finish-show-title = Terminar programa
+remove-last = ✖ Último
+trigger = Disparar
+stop = Detener
+capture = Capturar
+apply-to-controller = Aplicar al controlador
+keyboard-shortcuts = Atajos de teclado
+total-sats = Sats totales:
+running = En marcha
+stopped = Detenido
+this-show = Este programa
+top-boosters = Mayores boosters
+by-source = Por fuente
+analytics = Estadísticas
+sats-per-day = Sats por día
+boosts-per-episode = Boosts por episodio
+source-share = Parte por fuente
+average-boost = Boost medio por programa
+analytics-from = Desde:
+analytics-to = Hasta:
+reload-recaps = Recargar resúmenes
+not-a-shortcut = No es un atajo
+shortcut-pause-effects = Pausar efectos:
+shortcut-fire-test = Lanzar prueba:
+shortcut-dismiss-dialogs = Cerrar diálogos:
+shortcut-next-tab = Siguiente pestaña:
+shortcut-replay-last-boost = Repetir último boost:
+load-since = Cargar desde:
+after = Después de:
+before = Antes de:
+speed = Velocidad:
+intensity = Intensidad:
+durations = Duraciones:
+transitions = Transiciones:
+streak-milestone = ¡{ $sender } ha hecho boost en { $streak } episodios seguidos!
+streak-returning = { $sender } ha vuelto: { $streak } episodios seguidos
+streak-back = { $sender } ha vuelto
+none = Ninguno
+disable = Desactivar
+enable = Activar
+hidden = oculto
+amount-sats = { $sats } sats
+wallet-balance = Cartera: { $wallet } - saldo { $balance }
+wallet-notifications = notificaciones de pago
+wallet-polling = consultando (sin notificaciones)
+wallet-connecting = conectando
+wallet-check = Ida y vuelta al relé: { $latency } - { $mode } - comprobado { $checked }
+nwc-current = Conectado a través del relé { $relay }
+nwc-none = Aún no hay ninguna billetera conectada
+nwc-connect = Conectar una billetera
+nwc-step-create = 1. Crea una conexión para BlinkyBoosts en tu billetera
+nwc-open-alby-hub = Abrir Alby Hub
+nwc-show-qr = Mostrar como QR
+nwc-hide-qr = Ocultar QR
+nwc-other-wallets = Otras billeteras: añade una conexión de aplicación que permita get_info, list_transactions, make_invoice y get_balance, con notificaciones de pago si se ofrecen.
+nwc-step-paste = 2. Pega el secreto de conexión que muestra la billetera
+nwc-check = Comprobar
+nwc-step-review = 3. Conectado a { $wallet }
+nwc-can-receive = Recibe boosts
+nwc-can-notify = Los boosts llegan al instante (notificaciones de pago)
+nwc-can-history = Carga boosts anteriores
+nwc-can-invoice = Crea facturas para el QR en pantalla
+nwc-can-balance = Muestra el saldo
+nwc-cannot-receive = Esta conexión no ve los pagos entrantes. Permite list_transactions o las notificaciones de pago e inténtalo de nuevo.
+nwc-in-use = Esta conexión está en uso
+nwc-use = Usar esta conexión
+restart = Reiniciar
+start = Iniciar
+episode = Episodio: { $episode }
+episode-locked = 🔒 Solo se cuenta { $episode }
+lock-to-episode = Fijar al episodio actual
+lock-to-episode-hint = Contar solo los boosts del episodio del último boost, de todas las fuentes, hasta desbloquear
+unlock = Desbloquear
+wled-effects-loaded = { $count } efectos de { $host }
+wled-controller = { $name } ({ $host }, WLED { $version })
+no-controllers-found = Ningún controlador WLED respondió en esta red
+remote-unavailable = No disponible mientras se controla un agente
+playlist-presets = Presets: { $presets }
+history-up-to = , hasta { $time }
+history-loaded = Historial de { $source }: { $count } boosts cargados
+history-loading = Cargando historial de { $source }: { $count } boosts
+dimmer-effective = Efectivo: { $level }%
+toggle-threshold = @ { $sats } sats
+toggle-last-fired = , último { $time }
+toggle-stats = disparado { $fired }x, { $sats } sats coincidentes
+mute-output = Silenciar { $output }
+gate-open = { $value } (abierto)
+gate-closed = { $value } (cerrado)
+gate-no-console-open = sin consola (abierto)
+gate-no-console-closed = sin consola (cerrado)
+gate = Consola canal { $channel }: { $state }
+scenes-saved = Guardadas: { $scenes }
+latency-stage = media { $avg } (máx. { $max })
+input-events = { $count } en el último minuto
+input-round-trip = ida y vuelta { $round_trip }
+effect-failed-retries = { $effect } falló tras { $attempts } reintentos: { $error }
+history-queued = , en cola { $ms }ms
+history-retry = (reintento { $attempt })
+history-acknowledged = , confirmado
+history-not-acknowledged = , sin confirmar
+history-failed = falló: { $error }
+pending-effect = [en { $secs }s] { $effect } - { $sats } sats de { $sender }
+artwork = Portada
+anonymous = anónimo
+held-boost = { $sats } sats de { $sender } vía { $source }
+approve = Aprobar
+reject = Rechazar
+block = Bloquear
+episode-total = { $episode } ({ $sats } sats)
+no-effects = ninguno
+relay-delay = [relé { $delay }]
+boost-sender = { $sender } vía
+boost-remote-item = para { $item }
+boost-from = { $sats } sats de { $sender }
+recap-total = { $sats } sats de { $boosts } boosts
+test-run-passed = Prueba de las { $time } superada
+test-run-failed = Prueba de las { $time } fallida
+no-recaps = Aún no hay programas terminados en este periodo; los resúmenes se guardan en ./summaries al terminar un programa
+not-a-date = No es una fecha (AAAA-MM-DD)
+day-sats = { $day }: { $sats } sats
+share-percent = { $source }: { $percent }%
+recap-biggest = Mayor boost: { $sats } sats de { $sender } vía { $source }
+status-disabled = Desactivado
+status-enabled = Activado
+status-running = En marcha
+status-reconnecting = Reconectando (intento { $attempt })
+status-unreachable = Inaccesible
+status-error = Error
+recap-title = Resumen del programa
+recap-dates = Del { $start } al { $end }
+recap-biggest-heading = Mayor boost
+recap-notes = Notas y marcadores
+recap-note-boost = { $sats } sats de { $sender }: { $note }
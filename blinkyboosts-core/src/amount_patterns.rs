@@ -0,0 +1,133 @@
+use anyhow::{bail, Context, Result};
+
+use crate::config;
+
+/// A rule on the digits of a boost amount
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    EndsWith(String),     // "ends:69" - the amount ends in these digits
+    LastDigit(u8, u8),    // endswith_range - the last digit is within the range (inclusive)
+    Palindrome,           // "palindrome" - reads the same both ways, e.g. 12321
+    Repdigit,             // "repdigit" - every digit the same, e.g. 777
+    Repeats(usize),       // "repeats:3" - ends in at least this many of the same digit, e.g. 5000
+}
+
+impl Pattern {
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let pattern = pattern.trim().to_lowercase();
+        let (name, arg) = pattern.split_once(':').map_or((pattern.as_str(), None), |(name, arg)| (name, Some(arg.trim())));
+        match (name, arg) {
+            ("ends", Some(digits)) if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => {
+                Ok(Self::EndsWith(digits.to_string()))
+            }
+            ("ends", _) => bail!("\"{}\": ends needs digits, e.g. ends:69", pattern),
+            ("palindrome", None) => Ok(Self::Palindrome),
+            ("repdigit", None) => Ok(Self::Repdigit),
+            ("repeats", Some(count)) => {
+                let count: usize = count.parse().context(format!("\"{}\": repeats needs a count, e.g. repeats:3", pattern))?;
+                if count < 2 {
+                    bail!("\"{}\": repeats needs a count of at least 2", pattern);
+                }
+                Ok(Self::Repeats(count))
+            }
+            _ => bail!("Unknown amount pattern \"{}\" (expected ends:<digits>, palindrome, repdigit or repeats:<count>)", pattern),
+        }
+    }
+
+    pub fn matches(&self, sats: i64) -> bool {
+        let digits = sats.unsigned_abs().to_string();
+        match self {
+            Self::EndsWith(suffix) => digits.ends_with(suffix.as_str()),
+            Self::LastDigit(start, end) => {
+                let last = (sats % 10).unsigned_abs() as u8;
+                last >= *start && last <= *end
+            }
+            Self::Palindrome => digits.len() > 1 && digits.chars().eq(digits.chars().rev()),
+            Self::Repdigit => digits.len() > 1 && digits.chars().all(|c| digits.ends_with(c)),
+            Self::Repeats(count) => {
+                let last = digits.chars().last();
+                digits.chars().rev().take_while(|c| Some(*c) == last).count() >= *count
+            }
+        }
+    }
+}
+
+/// A toggle's `amount_patterns`, parsed
+fn patterns(toggle: &config::Toggle) -> Result<Vec<Pattern>> {
+    toggle.amount_patterns.iter().map(|pattern| Pattern::parse(pattern)).collect()
+}
+
+/// Check a toggle's `amount_patterns` parse
+pub fn validate(toggle: &config::Toggle) -> Result<()> {
+    patterns(toggle).map(|_| ())
+}
+
+/// Whether `sats` suits a toggle: its last digit within `endswith_range`, if set, and matching
+/// at least one of `amount_patterns`, if any
+pub fn allows(toggle: &config::Toggle, sats: i64) -> bool {
+    if let Some((start, end)) = toggle.endswith_range {
+        if !Pattern::LastDigit(start, end).matches(sats) {
+            println!("Toggle skipped: {} sats ends with {}, not in range {}-{}", sats, (sats % 10).unsigned_abs(), start, end);
+            return false;
+        }
+    }
+    if toggle.amount_patterns.is_empty() {
+        return true;
+    }
+
+    match patterns(toggle) {
+        Ok(patterns) if patterns.iter().any(|pattern| pattern.matches(sats)) => true,
+        Ok(_) => {
+            println!("Toggle skipped: {} sats matches none of {}", sats, toggle.amount_patterns.join(", "));
+            false
+        }
+        Err(e) => {
+            eprintln!("Toggle skipped: {:#}", e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toggle(extra: &str) -> config::Toggle {
+        toml::from_str(&format!("output = \"wled\"\n{}", extra)).unwrap()
+    }
+
+    #[test]
+    fn patterns_match_the_digits() {
+        assert!(Pattern::parse("ends:69").unwrap().matches(1069));
+        assert!(!Pattern::parse("ends:69").unwrap().matches(1096));
+        assert!(Pattern::parse("palindrome").unwrap().matches(12321));
+        assert!(!Pattern::parse("palindrome").unwrap().matches(7));
+        assert!(Pattern::parse("repdigit").unwrap().matches(777));
+        assert!(!Pattern::parse("repdigit").unwrap().matches(778));
+        assert!(Pattern::parse("repeats:3").unwrap().matches(5000));
+        assert!(!Pattern::parse("repeats:3").unwrap().matches(5500));
+    }
+
+    #[test]
+    fn bad_patterns_are_rejected() {
+        assert!(Pattern::parse("ends:").is_err());
+        assert!(Pattern::parse("ends:6x").is_err());
+        assert!(Pattern::parse("repeats:1").is_err());
+        assert!(Pattern::parse("sevens").is_err());
+    }
+
+    #[test]
+    fn allows_needs_the_range_and_any_pattern() {
+        let any = toggle("");
+        assert!(allows(&any, 123));
+
+        let ranged = toggle("endswith_range = [5, 9]\namount_patterns = [\"ends:69\", \"repdigit\"]");
+        assert!(allows(&ranged, 169));
+        assert!(allows(&ranged, 555));
+        assert!(!allows(&ranged, 111));  // Repdigit, but the last digit is out of range
+        assert!(!allows(&ranged, 168));  // In range, but matches neither pattern
+
+        let broken = toggle("amount_patterns = [\"sevens\"]");
+        assert!(!allows(&broken, 777));
+    }
+}
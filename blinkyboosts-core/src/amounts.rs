@@ -0,0 +1,111 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::Config;
+
+static ABBREVIATE: AtomicBool = AtomicBool::new(false);
+
+/// Load the `[display]` settings; amounts are written out in full without one
+pub fn configure(config: &Config) {
+    ABBREVIATE.store(config.display.as_ref().is_some_and(|display| display.abbreviate), Ordering::Relaxed);
+}
+
+/// Msats for a payment amount in sats, saturating rather than wrapping for absurd amounts
+pub fn sats_to_msats(sats: i64) -> i64 {
+    sats.saturating_mul(1000)
+}
+
+/// Msats from a wallet or invoice amount, which are unsigned
+pub fn msats_from_u64(msats: u64) -> i64 {
+    i64::try_from(msats).unwrap_or(i64::MAX)
+}
+
+/// `part` as a percentage of `whole`, without overflowing on whale-sized totals
+pub fn percent(part: i64, whole: i64) -> i64 {
+    if whole == 0 {
+        return 0;
+    }
+    (part as i128 * 100 / whole as i128).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// `percent` of `sats`, rounded down, e.g. a campaign's split of a boost
+pub fn share(sats: i64, percent: i64) -> i64 {
+    (sats as i128 * percent as i128 / 100).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// `sats` with thousands separators, e.g. 1,234,567
+pub fn separated(sats: i64) -> String {
+    let digits = sats.unsigned_abs().to_string();
+    let mut text = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if sats < 0 {
+        text.push('-');
+    }
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+            text.push(',');
+        }
+        text.push(digit);
+    }
+    text
+}
+
+/// `sats` abbreviated to k, M or B with one decimal place, e.g. 1.2M; below 1,000 as is
+pub fn abbreviated(sats: i64) -> String {
+    let abs = sats.unsigned_abs();
+    let sign = if sats < 0 { "-" } else { "" };
+    let (unit, suffix) = match abs {
+        0..=999 => return sats.to_string(),
+        1_000..=999_949 => (1_000, "k"),
+        999_950..=999_949_999 => (1_000_000, "M"),
+        _ => (1_000_000_000, "B"),
+    };
+    // Round to tenths in integers so huge amounts don't lose precision as floats
+    let tenths = (abs as u128 * 10 + unit as u128 / 2) / unit as u128;
+    match tenths % 10 {
+        0 => format!("{}{}{}", sign, tenths / 10, suffix),
+        frac => format!("{}{}.{}{}", sign, tenths / 10, frac, suffix),
+    }
+}
+
+/// `sats` for display: abbreviated when `[display] abbreviate` is set, otherwise with separators
+pub fn format_sats(sats: i64) -> String {
+    if ABBREVIATE.load(Ordering::Relaxed) {
+        abbreviated(sats)
+    } else {
+        separated(sats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separated_groups_thousands() {
+        assert_eq!(separated(0), "0");
+        assert_eq!(separated(999), "999");
+        assert_eq!(separated(1_234_567), "1,234,567");
+        assert_eq!(separated(-1_000), "-1,000");
+        assert_eq!(separated(i64::MIN), "-9,223,372,036,854,775,808");
+    }
+
+    #[test]
+    fn abbreviated_rounds_to_tenths() {
+        assert_eq!(abbreviated(999), "999");
+        assert_eq!(abbreviated(1_000), "1k");
+        assert_eq!(abbreviated(15_000), "15k");
+        assert_eq!(abbreviated(1_250_000), "1.3M");
+        assert_eq!(abbreviated(999_950), "1M");
+        assert_eq!(abbreviated(-2_100_000_000), "-2.1B");
+    }
+
+    #[test]
+    fn arithmetic_saturates() {
+        assert_eq!(sats_to_msats(21), 21_000);
+        assert_eq!(sats_to_msats(i64::MAX), i64::MAX);
+        assert_eq!(msats_from_u64(u64::MAX), i64::MAX);
+        assert_eq!(percent(50, 200), 25);
+        assert_eq!(percent(5, 0), 0);
+        assert_eq!(percent(i64::MAX, 1), i64::MAX);
+        assert_eq!(share(999, 10), 99);
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::summary::Summary;
+
+/// One finished show, for the trends
+#[derive(Debug, Clone)]
+pub struct ShowPoint {
+    pub date: NaiveDate,
+    pub sats: i64,
+    pub boosts: usize,
+    pub average: f64,  // Sats per boost
+}
+
+/// Figures across the saved show recaps
+#[derive(Debug, Clone, Default)]
+pub struct Analytics {
+    pub total_sats: i64,
+    pub boosts: usize,
+    pub sats_per_day: BTreeMap<NaiveDate, i64>,
+    pub boosts_per_episode: Vec<(String, usize)>,  // Most boosted first
+    pub by_source: BTreeMap<String, i64>,
+    pub shows: Vec<ShowPoint>,  // Oldest first
+}
+
+/// The local day a show ran on, by its first boost
+fn show_date(summary: &Summary) -> Option<NaiveDate> {
+    DateTime::from_timestamp(summary.started_at.unwrap_or(summary.finished_at), 0)
+        .map(|t| t.with_timezone(&Local).date_naive())
+}
+
+impl Analytics {
+    /// Add up the recaps of shows run between `from` and `to` (inclusive; open-ended when None)
+    pub fn from_summaries(summaries: &[Summary], from: Option<NaiveDate>, to: Option<NaiveDate>) -> Self {
+        let mut analytics = Self::default();
+        let mut episodes: BTreeMap<String, usize> = BTreeMap::new();
+
+        for summary in summaries {
+            let Some(date) = show_date(summary) else { continue };
+            if from.is_some_and(|from| date < from) || to.is_some_and(|to| date > to) {
+                continue;
+            }
+
+            analytics.total_sats = analytics.total_sats.saturating_add(summary.total_sats);
+            analytics.boosts += summary.boosts;
+            let day = analytics.sats_per_day.entry(date).or_insert(0);
+            *day = day.saturating_add(summary.total_sats);
+            for (source, sats) in &summary.by_source {
+                let total = analytics.by_source.entry(source.clone()).or_insert(0);
+                *total = total.saturating_add(*sats);
+            }
+            for (episode, boosts) in &summary.by_episode {
+                *episodes.entry(episode.clone()).or_insert(0) += boosts;
+            }
+            if summary.boosts > 0 {
+                analytics.shows.push(ShowPoint {
+                    date,
+                    sats: summary.total_sats,
+                    boosts: summary.boosts,
+                    average: summary.total_sats as f64 / summary.boosts as f64,
+                });
+            }
+        }
+
+        analytics.boosts_per_episode = episodes.into_iter().collect();
+        analytics.boosts_per_episode.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        analytics
+    }
+
+    /// Each source's share of the sats, 0.0 to 1.0
+    pub fn source_shares(&self) -> Vec<(String, f64)> {
+        let total: i64 = self.by_source.values().sum();
+        self.by_source.iter()
+            .filter(|(_, sats)| **sats > 0)
+            .map(|(source, sats)| (source.clone(), *sats as f64 / total.max(1) as f64))
+            .collect()
+    }
+}
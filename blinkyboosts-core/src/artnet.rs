@@ -0,0 +1,136 @@
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use artnet_protocol::*;
+
+use crate::{capture, dimmer, fixtures, mock};
+use crate::sender_color::{self, Rgb};
+
+pub struct ArtNet {
+    sock: UdpSocket,
+    to_addr: SocketAddrV4,
+    universe: u16,
+}
+
+impl ArtNet {
+    pub fn new(broadcast_address: String, local_address: Option<String>, universe: Option<u16>) -> Result<Self> {
+        // Bind to specific local interface if provided, otherwise bind to all interfaces
+        let bind_addr = if let Some(local_addr) = local_address {
+            local_addr.parse::<Ipv4Addr>()
+                .map_err(|e| anyhow::anyhow!("Invalid local address '{}': {}", local_addr, e))?
+        } else {
+            Ipv4Addr::UNSPECIFIED
+        };
+
+        let sock = UdpSocket::bind(SocketAddrV4::new(bind_addr, 0))?;
+
+        // Enable broadcast to allow sending to broadcast addresses
+        sock.set_broadcast(true)?;
+
+        let to_addr = if broadcast_address.contains(':') {
+            broadcast_address.parse()?
+        } else {
+            format!("{}:6454", broadcast_address).parse()?
+        };
+
+        Ok(Self {
+            sock,
+            to_addr,
+            universe: universe.unwrap_or(0),
+        })
+    }
+
+    pub fn send_dmx(&self, data: &[u8]) -> Result<()> {
+        anyhow::ensure!(data.len() <= 512, "DMX data cannot exceed 512 bytes");
+
+        let data: Vec<u8> = data.iter().map(|&v| dimmer::scale(v)).collect();
+        if mock::enabled("artnet") {
+            mock::record("artnet", self.to_addr.to_string(), mock::dmx_payload(self.universe, &data));
+            return Ok(());
+        }
+
+        let output = Output {
+            data: data.clone().into(),
+            port_address: PortAddress::try_from(self.universe)?,
+            ..Output::default()
+        };
+
+        let packet = ArtCommand::Output(output).write_to_buffer()?;
+        self.sock.send_to(&packet, self.to_addr)
+            .map_err(|e| anyhow::anyhow!("Failed to send Art-Net packet to {}: {}. Make sure the broadcast address matches your network interface.", self.to_addr, e))?;
+        capture::record("artnet", self.to_addr.to_string(), self.universe, &data);
+        Ok(())
+    }
+
+    pub fn trigger_channel(&self, channel: u16, value: u8) -> Result<()> {
+        self.trigger_channels(&[(channel, value)])
+    }
+
+    /// Send one frame with each (channel, value) set and every other channel at zero
+    pub fn trigger_channels(&self, values: &[(u16, u8)]) -> Result<()> {
+        let mut data = Vec::new();
+        for &(channel, value) in values {
+            anyhow::ensure!(channel > 0 && channel <= 512, "Channel must be between 1 and 512");
+            if data.len() < channel as usize {
+                data.resize(channel as usize, 0u8);
+            }
+            data[(channel - 1) as usize] = value;
+        }
+
+        self.send_dmx(&data)
+    }
+
+    /// Send an ArtPoll and wait for any node to answer with an ArtPollReply
+    pub fn poll(&self, timeout: Duration) -> Result<()> {
+        if mock::enabled("artnet") {
+            return Ok(());
+        }
+
+        let packet = ArtCommand::Poll(Poll::default()).write_to_buffer()?;
+        self.sock.send_to(&packet, self.to_addr)?;
+
+        let started = Instant::now();
+        let mut buf = [0u8; 1024];
+
+        while started.elapsed() < timeout {
+            self.sock.set_read_timeout(Some(timeout - started.elapsed()))?;
+            match self.sock.recv_from(&mut buf) {
+                Ok((len, _)) => {
+                    if let Ok(ArtCommand::PollReply(_)) = ArtCommand::from_buffer(&buf[..len]) {
+                        return Ok(());
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        Err(anyhow::anyhow!("No ArtPollReply from {} within {}ms", self.to_addr, timeout.as_millis()))
+    }
+
+    pub fn blackout(&self) -> Result<()> {
+        self.send_dmx(&[0u8; 512])
+    }
+
+    /// The (channel, value) pairs an Art-Net toggle sets, with `color` on its RGB channels
+    pub fn toggle_values(artnet_config: &crate::config::ToggleArtNet, color: Option<Rgb>) -> Vec<(u16, u8)> {
+        let values = fixtures::toggle_values(artnet_config.channel, artnet_config.value, artnet_config.count, &artnet_config.channels);
+        sender_color::with_rgb(values, artnet_config.rgb_channel, color)
+    }
+
+    /// Set the toggle's channels on top of the remembered frames, sending every universe they span at once
+    pub fn trigger_toggle(config: &crate::config::Config, toggle: &crate::config::Toggle, color: Option<Rgb>) -> Result<()> {
+        let artnet_config = toggle.artnet.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Art-Net toggle missing 'artnet' configuration"))?;
+        let default_universe = config.artnet.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Art-Net not configured"))?
+            .universe.unwrap_or(0);
+
+        let values = Self::toggle_values(artnet_config, color);
+        anyhow::ensure!(!values.is_empty(), "Art-Net toggle sets no channels");
+        let layer = fixtures::Layer {
+            merge: artnet_config.merge.unwrap_or(fixtures::Layer::output_default(config, "artnet").merge),
+            priority: None,
+        };
+        fixtures::send_layer(config, "artnet", artnet_config.universe.unwrap_or(default_universe), &values, layer)
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use artnet_protocol::ArtCommand;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+use crate::config::{self, Config};
+use crate::dimmer;
+use crate::messages::{ComponentStatus, Event};
+
+/// Component name for the venue console feed
+pub const SOURCE: &str = "Console";
+
+static CONFIG: Mutex<Option<config::ArtNetInput>> = Mutex::new(None);
+static FRAME: Mutex<Option<(Instant, Vec<u8>)>> = Mutex::new(None);
+
+pub fn configure(config: &Config) {
+    *CONFIG.lock().unwrap() = config.artnet_input.clone();
+}
+
+fn covers(gate: &config::ArtNetGate, toggle: &config::Toggle) -> bool {
+    if gate.outputs.is_empty() && gate.toggles.is_empty() {
+        return true;
+    }
+    gate.outputs.iter().any(|output| output.eq_ignore_ascii_case(&toggle.output))
+        || toggle.name.as_ref().is_some_and(|name| gate.toggles.contains(name))
+}
+
+/// The console's last frame, unless it's older than the timeout
+fn live_frame(cfg: &config::ArtNetInput) -> Option<Vec<u8>> {
+    FRAME.lock().unwrap().as_ref()
+        .filter(|(received, _)| received.elapsed() < Duration::from_secs(cfg.timeout_secs))
+        .map(|(_, frame)| frame.clone())
+}
+
+fn channel_value(frame: &[u8], channel: u16) -> u8 {
+    frame.get((channel as usize).wrapping_sub(1)).copied().unwrap_or(0)
+}
+
+/// The console channel holding a toggle back, if any of the gates covering it is closed.
+/// While no frames arrive the gates are open or closed as `fail_open` says.
+pub fn gated_by(toggle: &config::Toggle) -> Option<u16> {
+    let cfg = CONFIG.lock().unwrap().clone()?;
+    let frame = live_frame(&cfg);
+
+    cfg.gates.iter()
+        .filter(|gate| covers(gate, toggle))
+        .find(|gate| match &frame {
+            Some(frame) => channel_value(frame, gate.channel) < gate.min_value,
+            None => !cfg.fail_open,
+        })
+        .map(|gate| gate.channel)
+}
+
+/// Current value of a console channel, or None while the console is gone
+pub fn channel(channel: u16) -> Option<u8> {
+    let cfg = CONFIG.lock().unwrap().clone()?;
+    live_frame(&cfg).map(|frame| channel_value(&frame, channel))
+}
+
+/// The console's current frame if it is on `universe`, or None while the console is gone
+pub fn universe_frame(universe: u16) -> Option<Vec<u8>> {
+    let cfg = CONFIG.lock().unwrap().clone()?;
+    if cfg.universe != universe {
+        return None;
+    }
+    live_frame(&cfg)
+}
+
+fn apply_intensity(cfg: &config::ArtNetInput, frame: Option<&[u8]>) {
+    let Some(intensity_channel) = cfg.intensity_channel else { return };
+    let percent = match frame {
+        Some(frame) => (channel_value(frame, intensity_channel) as u16 * 100 / 255) as u8,
+        None if cfg.fail_open => 100,
+        None => 0,
+    };
+    dimmer::set_console(percent);
+}
+
+/// Listen for ArtDmx frames from the console on `[artnet_input] universe`
+pub async fn run(config: Config, tx: mpsc::Sender<Event>) {
+    let Some(cfg) = config.artnet_input else { return };
+
+    if let Err(e) = receive(&cfg, &tx).await {
+        eprintln!("Art-Net input stopped: {:#}", e);
+        let _ = tx.send(Event::UpdateStatus(SOURCE.to_string(), ComponentStatus::Error(format!("{:#}", e)))).await;
+    }
+}
+
+async fn receive(cfg: &config::ArtNetInput, tx: &mpsc::Sender<Event>) -> Result<()> {
+    let sock = UdpSocket::bind(&cfg.bind_address).await
+        .context(format!("Failed to bind Art-Net input to {}", cfg.bind_address))?;
+    println!("Listening for console Art-Net on {} universe {}", cfg.bind_address, cfg.universe);
+    apply_intensity(cfg, None);
+
+    let timeout = Duration::from_secs(cfg.timeout_secs);
+    let mut buf = [0u8; 1024];
+    let mut live = false;
+
+    loop {
+        let len = match tokio::time::timeout(timeout, sock.recv_from(&mut buf)).await {
+            Ok(received) => received.context("Failed to receive Art-Net")?.0,
+            Err(_) => {
+                if live {
+                    eprintln!("No Art-Net from the console for {}s", cfg.timeout_secs);
+                    let _ = tx.send(Event::UpdateStatus(SOURCE.to_string(), ComponentStatus::Unreachable("no frames".to_string()))).await;
+                    apply_intensity(cfg, None);
+                    live = false;
+                }
+                continue;
+            }
+        };
+
+        let Ok(ArtCommand::Output(output)) = ArtCommand::from_buffer(&buf[..len]) else { continue };
+        if u16::from(output.port_address) != cfg.universe {
+            continue;
+        }
+
+        let frame = output.data.as_ref().clone();
+        apply_intensity(cfg, Some(&frame));
+        *FRAME.lock().unwrap() = Some((Instant::now(), frame));
+
+        if !live {
+            let _ = tx.send(Event::UpdateStatus(SOURCE.to_string(), ComponentStatus::Running)).await;
+            live = true;
+        }
+    }
+}
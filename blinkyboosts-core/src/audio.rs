@@ -0,0 +1,198 @@
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use serde_json::json;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+use crate::config::{self, Config};
+use crate::{artnet, fixtures, mute, sacn, shutdown, wled};
+
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+// Share of the level kept each update while it falls, so hits jump up and fade out
+const RELEASE: f32 = 0.8;
+// WLED only gets a new intensity once it has moved this far, to spare its HTTP server
+const WLED_STEP: u8 = 8;
+
+/// Smoothed show audio level, 0-255
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+static LAST_EFFECT: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub fn level() -> u8 {
+    LEVEL.load(Ordering::Relaxed)
+}
+
+/// Note that a boost effect went out, so the audio leaves the outputs to it for `hold_secs`
+pub fn effect_fired() {
+    *LAST_EFFECT.lock().unwrap() = Some(Instant::now());
+}
+
+fn holding(cfg: &config::Audio) -> bool {
+    LAST_EFFECT.lock().unwrap().is_some_and(|at| at.elapsed() < Duration::from_secs(cfg.hold_secs))
+}
+
+/// Root mean square of a block of 16-bit little-endian samples, from 0.0 to 1.0
+fn rms(block: &[u8]) -> f32 {
+    let samples: Vec<f32> = block.chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]) as f32 / i16::MAX as f32)
+        .collect();
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Run the capture command and keep `LEVEL` up to date until it exits
+async fn measure(cfg: &config::Audio) -> Result<()> {
+    let mut child = Command::new(&cfg.command)
+        .args(&cfg.args)
+        .stdout(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to run {}", cfg.command))?;
+    let mut stdout = child.stdout.take().context("Audio command stdout unavailable")?;
+
+    let samples = (cfg.sample_rate as u64 * cfg.update_ms / 1000).max(1) as usize;
+    let mut block = vec![0u8; samples * 2];
+    let mut smoothed = 0.0f32;
+    loop {
+        stdout.read_exact(&mut block).await.context("Audio command stopped")?;
+        smoothed = (rms(&block) * cfg.gain).min(1.0).max(smoothed * RELEASE);
+        LEVEL.store((smoothed * 255.0) as u8, Ordering::Relaxed);
+    }
+}
+
+/// Log when an output starts failing, rather than on every update
+fn report(result: Result<()>, failing: &mut bool, output: &str) {
+    match result {
+        Ok(()) => *failing = false,
+        Err(e) if !*failing => {
+            eprintln!("Failed to send audio level to {}: {:#}", output, e);
+            *failing = true;
+        }
+        Err(_) => {}
+    }
+}
+
+/// Art-Net and sACN senders for the level, opened on first use and kept while they work
+#[derive(Default)]
+struct DmxOutputs {
+    artnet: Option<artnet::ArtNet>,
+    sacn: Option<sacn::Sacn>,
+}
+
+/// Send with the sender in `slot`, opening it first if needed. A sender that fails is dropped
+/// and opened again on the next update.
+fn send_kept<T>(slot: &mut Option<T>, open: impl FnOnce() -> Result<T>, send: impl FnOnce(&mut T) -> Result<()>) -> Result<()> {
+    if slot.is_none() {
+        *slot = Some(open()?);
+    }
+    let result = send(slot.as_mut().expect("opened above"));
+    if result.is_err() {
+        *slot = None;
+    }
+    result
+}
+
+/// The universe's current frame with the audio channel set, so channels effects or scenes left
+/// there stay put. Before anything has been sent to it that's `[idle] dmx_channels`.
+fn audio_frame(config: &Config, output: &str, universe: u16, channel: u16, value: u8) -> Vec<u8> {
+    let mut frame = fixtures::frame(output, universe).unwrap_or_else(|| {
+        let mut frame = vec![0u8; 512];
+        for &(ch, value) in config.idle.as_ref().map(|idle| idle.dmx_channels.as_slice()).unwrap_or_default() {
+            if let Some(slot) = (ch as usize).checked_sub(1).and_then(|idx| frame.get_mut(idx)) {
+                *slot = value;
+            }
+        }
+        frame
+    });
+    if let Some(slot) = (channel as usize).checked_sub(1).and_then(|idx| frame.get_mut(idx)) {
+        *slot = value;
+    }
+    fixtures::Layer::output_default(config, output).merge(universe, &frame)
+}
+
+/// The level on the audio channel of the default Art-Net and sACN universes
+fn send_dmx(config: &Config, outputs: &mut DmxOutputs, channel: u16, value: u8) -> (Result<()>, Result<()>) {
+    let artnet = match &config.artnet {
+        Some(cfg) if !mute::is_muted("artnet") => {
+            let frame = audio_frame(config, "artnet", cfg.universe.unwrap_or(0), channel, value);
+            send_kept(&mut outputs.artnet,
+                || artnet::ArtNet::new(cfg.broadcast_address.clone(), cfg.local_address.clone(), cfg.universe),
+                |artnet| artnet.send_dmx(&frame))
+        }
+        _ => Ok(()),
+    };
+    let sacn = match &config.sacn {
+        Some(cfg) if !mute::is_muted("sacn") => {
+            let frame = audio_frame(config, "sacn", cfg.universe.unwrap_or(1), channel, value);
+            send_kept(&mut outputs.sacn,
+                || Ok(sacn::Sacn::new(cfg.broadcast_address.clone(), cfg.universe)?.with_priority(cfg.priority)),
+                |sacn| sacn.send_dmx(&frame))
+        }
+        _ => Ok(()),
+    };
+    (artnet, sacn)
+}
+
+/// Send the level to the outputs whenever no boost effect has fired for `hold_secs`
+async fn drive(config: Config, cfg: config::Audio) {
+    let (mut sent_wled, mut sent_dmx) = (None::<u8>, None::<u8>);
+    let (mut wled_failing, mut artnet_failing, mut sacn_failing) = (false, false, false);
+    let mut was_holding = false;
+    let mut dmx = DmxOutputs::default();
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(cfg.update_ms.max(1))).await;
+        if shutdown::is_shutting_down() {
+            return;
+        }
+        if holding(&cfg) {
+            // Whatever an effect left on the outputs is replaced once the hold ends
+            (sent_wled, sent_dmx, was_holding) = (None, None, true);
+            continue;
+        }
+        if std::mem::take(&mut was_holding) && cfg.dmx_channel.is_some() {
+            fixtures::clear_frames();
+        }
+
+        let value = cfg.floor.saturating_add(((255 - cfg.floor) as u32 * level() as u32 / 255) as u8);
+
+        if let Some(wled_cfg) = config.wled.as_ref().filter(|_| cfg.wled_intensity && !mute::is_muted("wled")) {
+            if sent_wled.is_none_or(|sent| sent.abs_diff(value) >= WLED_STEP) {
+                let result = wled::WLed::set_raw_state(wled_cfg, json!({"seg": {"ix": value}})).await;
+                if result.is_ok() {
+                    sent_wled = Some(value);
+                }
+                report(result, &mut wled_failing, "WLED");
+            }
+        }
+
+        if let Some(channel) = cfg.dmx_channel {
+            if sent_dmx != Some(value) {
+                let (artnet, sacn) = send_dmx(&config, &mut dmx, channel, value);
+                sent_dmx = Some(value);
+                report(artnet, &mut artnet_failing, "Art-Net");
+                report(sacn, &mut sacn_failing, "sACN");
+            }
+        }
+    }
+}
+
+/// Measure the show audio from `[audio] command`, restarting it when it exits, and modulate the
+/// outputs with it between boost effects
+pub async fn run(config: Config) {
+    let Some(cfg) = config.audio.clone() else { return };
+    tokio::spawn(drive(config, cfg.clone()));
+
+    println!("Starting audio capture: {} {}", cfg.command, cfg.args.join(" "));
+    while !shutdown::is_shutting_down() {
+        if let Err(e) = measure(&cfg).await {
+            eprintln!("Audio capture failed: {:#}", e);
+        }
+        LEVEL.store(0, Ordering::Relaxed);
+        tokio::time::sleep(RESTART_DELAY).await;
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::messages::Event;
+
+static LOADS: Mutex<BTreeMap<String, CancellationToken>> = Mutex::new(BTreeMap::new());
+
+/// Reports progress of a listener loading its history and lets the GUI cancel it
+#[derive(Clone)]
+pub struct Backfill {
+    source: &'static str,
+    loaded: Arc<AtomicUsize>,
+    token: CancellationToken,
+    tx: mpsc::Sender<Event>,
+}
+
+impl Backfill {
+    pub async fn start(source: &'static str, tx: &mpsc::Sender<Event>) -> Self {
+        let token = CancellationToken::new();
+        LOADS.lock().unwrap().insert(source.to_string(), token.clone());
+        let _ = tx.send(Event::BackfillProgress(source.to_string(), 0, None, false)).await;
+        Self { source, loaded: Arc::new(AtomicUsize::new(0)), token, tx: tx.clone() }
+    }
+
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Count a loaded boost created at `created_at` (unix seconds, 0 if unknown)
+    pub async fn loaded(&self, created_at: i64) {
+        let loaded = self.loaded.fetch_add(1, Ordering::Relaxed) + 1;
+        let at = Some(created_at).filter(|ts| *ts > 0);
+        let _ = self.tx.send(Event::BackfillProgress(self.source.to_string(), loaded, at, false)).await;
+    }
+
+    pub async fn finish(self) {
+        LOADS.lock().unwrap().remove(self.source);
+        let loaded = self.loaded.load(Ordering::Relaxed);
+        if self.is_cancelled() {
+            println!("{} history load cancelled after {} boosts", self.source, loaded);
+        } else {
+            println!("{} history loaded: {} boosts", self.source, loaded);
+        }
+        let _ = self.tx.send(Event::BackfillProgress(self.source.to_string(), loaded, None, true)).await;
+    }
+}
+
+/// Stop a running history load; boosts loaded so far are kept. Returns false if none is running.
+pub fn cancel(source: &str) -> bool {
+    match LOADS.lock().unwrap().get(source) {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
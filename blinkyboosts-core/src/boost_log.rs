@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::sync::Mutex;
+use anyhow::{Context, Result};
+
+use crate::bus::{self, Bus};
+use crate::config::Config;
+use crate::sat_tracker::BoostRecord;
+
+const LOG_PATH: &str = "./boost_log.jsonl";
+const CHUNK: u64 = 64 * 1024;  // Read backwards this much at a time
+
+/// Where each boost logged this run starts in the log, by bus id
+static LOGGED: Mutex<BTreeMap<u64, u64>> = Mutex::new(BTreeMap::new());
+
+/// Where the next page of older boosts ends
+#[derive(Clone, Copy, Debug)]
+pub enum Cursor {
+    OlderThan(Option<u64>),  // First page: before the oldest boost still shown, by id (None when none are)
+    Before(u64),  // Later pages: before this byte offset, as returned by `page`
+}
+
+/// Append every live boost to ./boost_log.jsonl, one JSON object per line, unless
+/// `[recent_boosts] log` is off. Boosts loaded from history were logged when they arrived, so
+/// they aren't again. The GUI pages boosts it no longer keeps back in from it.
+pub fn attach(bus: &Bus, config: &Config) {
+    if !config.recent_boosts.clone().unwrap_or_default().log {
+        return;
+    }
+    bus.consume("log", |event| async move {
+        if !event.live {
+            return;
+        }
+        match append(&bus::record_of(&event)) {
+            Ok(offset) => { LOGGED.lock().unwrap().insert(event.id, offset); }
+            Err(e) => eprintln!("Failed to log boost: {:#}", e),
+        }
+    });
+}
+
+/// Returns the byte offset the record was written at
+fn append(record: &BoostRecord) -> Result<u64> {
+    let mut file = OpenOptions::new().create(true).append(true).open(LOG_PATH)
+        .context(format!("Failed to open {}", LOG_PATH))?;
+    let offset = file.metadata().context(format!("Failed to read {}", LOG_PATH))?.len();
+    writeln!(file, "{}", serde_json::to_string(record)?)
+        .context(format!("Failed to write {}", LOG_PATH))?;
+    Ok(offset)
+}
+
+/// Up to `limit` logged boosts before `cursor`, newest first, and the cursor for the page after
+/// (0 when there's nothing older). The log is read backwards, so only as much of it as the page
+/// needs is read. Lines that can't be read are skipped.
+pub fn page(cursor: Cursor, limit: usize) -> Result<(Vec<BoostRecord>, u64)> {
+    let mut file = match File::open(LOG_PATH) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e).context(format!("Failed to open {}", LOG_PATH)),
+    };
+    let len = file.metadata().context(format!("Failed to read {}", LOG_PATH))?.len();
+    let end = match cursor {
+        Cursor::Before(offset) => offset.min(len),
+        Cursor::OlderThan(id) => id
+            .and_then(|id| LOGGED.lock().unwrap().range(id..).next().map(|(_, &offset)| offset))
+            .unwrap_or(len),
+    };
+    read_back(&mut file, end, limit)
+}
+
+/// `page` over the lines of `log` before byte offset `end`
+fn read_back(log: &mut (impl Read + Seek), end: u64, limit: usize) -> Result<(Vec<BoostRecord>, u64)> {
+    let mut page = Vec::with_capacity(limit);
+    let mut pos = end;  // Everything before this is still to be read
+    let mut unread = Vec::new();  // From `pos` up to the last line taken
+    loop {
+        while let Some(newline) = unread.iter().rposition(|&b| b == b'\n') {
+            let line = unread.split_off(newline + 1);
+            unread.truncate(newline);
+            take(&line, &mut page);
+            if page.len() == limit {
+                return Ok((page, pos + newline as u64 + 1));
+            }
+        }
+        if pos == 0 {
+            take(&unread, &mut page);
+            return Ok((page, 0));
+        }
+
+        let start = pos.saturating_sub(CHUNK);
+        let mut chunk = vec![0; (pos - start) as usize];
+        log.seek(SeekFrom::Start(start))
+            .and_then(|_| log.read_exact(&mut chunk))
+            .context(format!("Failed to read {}", LOG_PATH))?;
+        chunk.extend_from_slice(&unread);
+        unread = chunk;
+        pos = start;
+    }
+}
+
+/// A boost logged this run, by bus id
+pub fn find(id: u64) -> Option<BoostRecord> {
+    let offset = *LOGGED.lock().unwrap().get(&id)?;
+    let mut file = File::open(LOG_PATH).ok()?;
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut line = String::new();
+    BufReader::new(file).read_line(&mut line).ok()?;
+    serde_json::from_str(&line).ok()
+}
+
+fn take(line: &[u8], page: &mut Vec<BoostRecord>) {
+    if let Ok(record) = serde_json::from_slice::<BoostRecord>(line) {
+        page.push(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(count: u64) -> std::io::Cursor<Vec<u8>> {
+        let mut log = Vec::new();
+        for id in 1..=count {
+            let record = BoostRecord {
+                id, source: "Test".to_string(), sats: id as i64 * 100, sender: Some("alice".to_string()),
+                message: Some("x".repeat(200)), episode: None, remote_item: None, received_at: 0, effects: Vec::new(), note: None,
+            };
+            writeln!(log, "{}", serde_json::to_string(&record).unwrap()).unwrap();
+        }
+        std::io::Cursor::new(log)
+    }
+
+    fn ids(page: &[BoostRecord]) -> Vec<u64> {
+        page.iter().map(|record| record.id).collect()
+    }
+
+    #[test]
+    fn pages_run_newest_first_until_the_start() {
+        let mut log = log(5);
+        let len = log.get_ref().len() as u64;
+
+        let (page, cursor) = read_back(&mut log, len, 2).unwrap();
+        assert_eq!(ids(&page), [5, 4]);
+        let (page, cursor) = read_back(&mut log, cursor, 2).unwrap();
+        assert_eq!(ids(&page), [3, 2]);
+        let (page, cursor) = read_back(&mut log, cursor, 2).unwrap();
+        assert_eq!((ids(&page), cursor), (vec![1], 0));
+    }
+
+    #[test]
+    fn pages_span_chunks_and_skip_bad_lines() {
+        let mut log = log(1000);
+        assert!(log.get_ref().len() as u64 > CHUNK * 2);
+        log.get_mut().extend_from_slice(b"not json\n");
+        let len = log.get_ref().len() as u64;
+
+        let mut seen = Vec::new();
+        let mut cursor = len;
+        loop {
+            let (page, next) = read_back(&mut log, cursor, 300).unwrap();
+            seen.extend(ids(&page));
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        assert_eq!(seen, (1..=1000).rev().collect::<Vec<_>>());
+    }
+}
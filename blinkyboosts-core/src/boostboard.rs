@@ -0,0 +1,518 @@
+use nostr_sdk::{Timestamp, Client, Options, Filter, PublicKey, Kind, SubscriptionId, RelayPoolNotification};
+use crate::boosts::Boostagram;
+use crate::config::{self, BoostFiltersConfig};
+use crate::dedup::{Seen, SeenEvents};
+use crate::moderation;
+use nostr_sdk::prelude::Output;
+use serde::{Serialize, Deserialize};
+use anyhow::{Context, Result};
+use std::future::Future;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone, Debug, Default)]
+pub struct BoostFilters {
+    pub podcasts: Option<Vec<String>>,
+    pub episode_guids: Option<Vec<String>>,
+    pub event_guids: Option<Vec<String>>,
+    pub exclude_podcasts: Option<Vec<String>>,
+    pub exclude_episode_guids: Option<Vec<String>>,
+    pub exclude_senders: Option<Vec<String>>,  // Normalized with `moderation::normalize`
+    pub remote_items: Option<Vec<String>>,
+    pub exclude_remote_items: Option<Vec<String>>,
+    pub app_names: Option<Vec<String>>,
+    pub actions: Option<Vec<String>>,
+    pub min_sats: Option<i64>,
+    pub max_sats: Option<i64>,
+    pub before: Option<Timestamp>,
+    pub after: Option<Timestamp>,
+}
+
+impl BoostFilters {
+    pub fn from_config(cfg: &BoostFiltersConfig) -> Self {
+        let parse = |s: &Option<String>| s.as_ref().and_then(|s| s.parse::<u64>().ok()).map(Timestamp::from_secs);
+
+        Self {
+            podcasts: cfg.podcasts.clone(),
+            episode_guids: cfg.episode_guids.clone(),
+            event_guids: cfg.event_guids.clone(),
+            exclude_podcasts: cfg.exclude_podcasts.clone(),
+            exclude_episode_guids: cfg.exclude_episode_guids.clone(),
+            exclude_senders: cfg.exclude_senders.as_ref().map(|senders| senders.iter().map(|s| moderation::normalize(s)).collect()),
+            remote_items: cfg.remote_items.clone(),
+            exclude_remote_items: cfg.exclude_remote_items.clone(),
+            app_names: cfg.app_names.clone(),
+            actions: cfg.actions.clone(),
+            min_sats: cfg.min_sats,
+            max_sats: cfg.max_sats,
+            before: parse(&cfg.before),
+            after: parse(&cfg.after),
+        }
+    }
+
+    fn has_content_filters(&self) -> bool {
+        self.podcasts.is_some() || self.episode_guids.is_some() || self.event_guids.is_some() || self.remote_items.is_some()
+    }
+
+    /// Filters for zaps, which only have a sender and an amount to go on
+    pub fn from_zaps_config(cfg: &config::Zaps) -> Self {
+        Self {
+            exclude_senders: cfg.exclude_senders.as_ref().map(|senders| senders.iter().map(|s| moderation::normalize(s)).collect()),
+            min_sats: cfg.min_sats,
+            max_sats: cfg.max_sats,
+            ..Self::default()
+        }
+    }
+
+    pub fn matches_sats(&self, sats: i64) -> bool {
+        self.min_sats.is_none_or(|min| sats >= min) && self.max_sats.is_none_or(|max| sats <= max)
+    }
+
+    /// Whether any of a payment's sender names or pubkeys is excluded
+    pub fn excludes_sender(&self, senders: &[&str]) -> bool {
+        self.exclude_senders.as_ref().is_some_and(|excluded| {
+            senders.iter()
+                .filter(|sender| !sender.trim().is_empty())
+                .any(|sender| excluded.contains(&moderation::normalize(sender)))
+        })
+    }
+
+    fn is_excluded(&self, boost: &Boostagram) -> bool {
+        let podcast_excluded = self.exclude_podcasts.as_ref()
+            .is_some_and(|ps| ps.iter().any(|p| boost.podcast.to_lowercase().contains(&p.to_lowercase())));
+
+        let episode_excluded = self.exclude_episode_guids.as_ref()
+            .is_some_and(|guids| !boost.episode_guid.is_empty() && guids.contains(&boost.episode_guid));
+
+        let remote_excluded = self.exclude_remote_items.as_ref()
+            .is_some_and(|items| boost.remote().is_some_and(|remote| items.iter().any(|item| remote.matches(item))));
+
+        podcast_excluded || episode_excluded || remote_excluded || self.excludes_sender(&[&boost.sender_name])
+    }
+
+    /// Whether boosts with this action are counted: the configured `actions`, or just "boost"
+    pub fn counts_action(&self, action: &str) -> bool {
+        match &self.actions {
+            Some(actions) => actions.iter().any(|a| a.eq_ignore_ascii_case(action)),
+            None => action == "boost",
+        }
+    }
+
+    fn matches_app(&self, app_name: &str) -> bool {
+        self.app_names.as_ref()
+            .is_none_or(|apps| apps.iter().any(|app| app_name.to_lowercase().contains(&app.to_lowercase())))
+    }
+
+    /// Action, app, amount and exclusions narrow every source; past those, a boost matches any of the
+    /// podcast/episode/event/remote item allow-lists, or anything when there are none
+    pub fn matches_boost(&self, boost: &Boostagram) -> bool {
+        if !self.counts_action(&boost.action) || !self.matches_app(&boost.app_name) || !self.matches_sats(boost.sats)
+            || self.is_excluded(boost) {
+            return false;
+        }
+
+        if !self.has_content_filters() {
+            return true;
+        }
+
+        let podcast_match = self.podcasts.as_ref()
+            .is_some_and(|ps| ps.iter().any(|p| boost.podcast.to_lowercase().contains(&p.to_lowercase())));
+
+        let episode_match = self.episode_guids.as_ref()
+            .is_some_and(|guids| !boost.episode_guid.is_empty() && guids.contains(&boost.episode_guid));
+
+        let event_match = self.event_guids.as_ref()
+            .is_some_and(|guids| !boost.event_guid.is_empty() && guids.contains(&boost.event_guid));
+
+        let remote_match = self.remote_items.as_ref()
+            .is_some_and(|items| boost.remote().is_some_and(|remote| items.iter().any(|item| remote.matches(item))));
+
+        podcast_match || episode_match || event_match || remote_match
+    }
+
+    pub fn matches_timestamp(&self, ts: i64) -> bool {
+        self.after.is_none_or(|a| ts > a.as_u64() as i64)
+            && self.before.is_none_or(|b| ts < b.as_u64() as i64)
+    }
+
+    fn get_since_timestamp(&self, since: Option<Timestamp>) -> Timestamp {
+        since.max(self.after).unwrap_or_else(|| Timestamp::from_secs(0))
+    }
+}
+
+#[derive(Clone)]
+pub struct BoostBoard {
+    client: Client,
+    pubkey: PublicKey,
+    filters: BoostFilters,
+    seen: SeenEvents,
+}
+
+impl BoostBoard {
+    pub async fn new(relay_addrs: &[String], pubkey: &str, filters: BoostFilters, seen: SeenEvents) -> Result<Self> {
+        let client = Client::builder()
+            .opts(Options::new().wait_for_send(false))
+            .build();
+
+        for addr in relay_addrs {
+            client.add_relay(addr).await
+                .context(format!("Failed to add relay: {}", addr))?;
+        }
+        client.connect().await;
+
+        let pubkey = PublicKey::from_hex(pubkey)
+            .context(format!("Failed to parse pubkey: {}", pubkey))?;
+
+        Ok(Self { client, pubkey, filters, seen })
+    }
+
+    /// The relay connection, e.g. to time its round trips
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Boost events from the board, without a start time
+    pub fn filter(&self) -> Filter {
+        let filter = Filter::new()
+            .author(self.pubkey)
+            .kind(Kind::ApplicationSpecificData);
+
+        match self.filters.before {
+            Some(before) => filter.until(before),
+            None => filter,
+        }
+    }
+
+    pub async fn subscribe(&self, since: Option<Timestamp>) -> Result<SubscriptionId> {
+        let filter = self.filter().since(self.filters.get_since_timestamp(since));
+println!("Boostboard subscribe filters: {:#?}", filter);
+        let Output { val: sub_id, .. } = self.client
+            .subscribe(vec![filter], None)
+            .await
+            .context("Failed to subscribe to boostboard")?;
+
+        Ok(sub_id)
+    }
+
+    pub async fn handle_boosts<F, Fut>(&self, sub_id: SubscriptionId, func: F) -> Result<()>
+    where
+        F: Fn(Boostagram, Timestamp) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let filters = self.filters.clone();
+        let seen = self.seen.clone();
+
+        self.client.handle_notifications(move |notification| {
+            let filters = filters.clone();
+            let seen = seen.clone();
+            let sub_id_check = sub_id.clone();
+            let func = func.clone();
+
+            async move {
+                if let RelayPoolNotification::Event { subscription_id, event, .. } = notification {
+                    if subscription_id != sub_id_check || !filters.matches_timestamp(event.created_at.as_u64() as i64) {
+                        println!("Timestamp not matched: {:#?}", event);
+                        return Ok(false);
+                    }
+
+                    let seen_state = seen.check(&event.id);
+                    if seen_state == Seen::Duplicate {
+                        println!("Duplicate boostboard event dropped: {}", event.id);
+                        return Ok(false);
+                    }
+
+                    match serde_json::from_str::<StoredBoostInfo>(&event.content) {
+                        Ok(info) => {
+                            match info.to_boostagram() {
+                                Some(mut boost) => {
+                                    boost.is_old = seen_state == Seen::PreviousRun;
+                                    if filters.matches_boost(&boost) {
+                                        println!("Live boost: {:#?}", boost);
+                                        func(boost, event.created_at).await;
+                                    } else {
+                                        println!("Boost doesn't match filters: {:#?}", boost);
+                                    }
+                                }
+                                None => {
+                                    println!("Event has no boost info: {:#?}", info);
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            println!("Error parsing boost event: {:#?}", e);
+                        }
+                    }
+                }
+                Ok(false)
+            }
+        })
+        .await
+        .context("Failed to handle boostboard notifications")?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct StoredBoostInfo {
+    identifier: String,
+    creation_date: i64,
+    boostagram: Option<StoredBoostagram>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoredBoostagram {
+    pub action: Option<String>,
+    pub app_name: Option<String>,
+    #[serde(rename = "blockGuid")]
+    pub block_guid: Option<String>,
+    #[serde(rename = "eventApi")]
+    pub event_api: Option<String>,
+    #[serde(rename = "eventGuid")]
+    pub event_guid: Option<String>,
+    pub boost_link: Option<String>,
+    pub episode: Option<String>,
+    pub episode_guid: Option<String>,
+    pub guid: Option<String>,
+    pub message: Option<String>,
+    pub name: Option<String>,
+    pub podcast: Option<String>,
+    #[serde(rename = "remoteFeedGuid")]
+    pub remote_feed_guid: Option<String>,
+    #[serde(rename = "remoteItemGuid")]
+    pub remote_item_guid: Option<String>,
+    #[serde(rename = "remoteItemTitle")]
+    pub remote_item_title: Option<String>,
+    pub sender_id: Option<String>,
+    pub sender_name: Option<String>,
+    pub ts: Option<i64>,
+    pub value_msat_total: Option<i64>,
+}
+
+impl StoredBoostInfo {
+    pub fn to_boostagram(&self) -> Option<Boostagram> {
+        let boost = self.boostagram.as_ref()?;
+
+        Some(Boostagram {
+            boost_type: "stored_boost".to_string(),
+            action: boost.action.clone().unwrap_or_default(),
+            identifier: self.identifier.clone(),
+            creation_date: self.creation_date,
+            sender_name: boost.sender_name.clone().unwrap_or_default(),
+            app_name: boost.app_name.clone().unwrap_or_default(),
+            podcast: boost.podcast.clone().unwrap_or_default(),
+            episode: boost.episode.clone().unwrap_or_default(),
+            sats: boost.value_msat_total.unwrap_or_default() / 1000,
+            msats: boost.value_msat_total.unwrap_or_default(),
+            message: boost.message.clone().unwrap_or_default(),
+            event_guid: boost.event_guid.clone().unwrap_or_default(),
+            episode_guid: boost.episode_guid.clone().unwrap_or_default(),
+            remote_feed: boost.remote_feed_guid.clone(),
+            remote_item: boost.remote_item_guid.clone(),
+            remote_item_title: boost.remote_item_title.clone(),
+            is_old: true,
+        })
+    }
+}
+
+
+const PAGE_SIZE: usize = 1000;
+
+/// What to do when a page of stored boosts fails to load
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PageAction {
+    Retry,  // Fetch the same page again
+    Skip,   // Move on to the next page, losing this one's boosts
+    Abort,  // Stop and return the error
+}
+
+/// Where a previous run's stored-boost load got to, kept in a local file
+#[derive(Serialize, Deserialize, Default)]
+struct Resume {
+    last_boost_at: u64,
+}
+
+pub struct StoredBoosts {
+    filters: BoostFilters,
+    cancel: Option<CancellationToken>,
+    resume_path: Option<String>,
+}
+
+impl StoredBoosts {
+    pub fn new(filters: BoostFilters) -> Self {
+        Self { filters, cancel: None, resume_path: None }
+    }
+
+    /// Stop loading (keeping what was loaded so far) once `token` is cancelled
+    pub fn with_cancel(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Only load boosts newer than the last one loaded by a previous run, saving progress to
+    /// `path` once a load has gone through every page. A load that's cancelled, fails or skips
+    /// a page saves nothing, so the next run loads those boosts again.
+    pub fn with_resume(mut self, path: &str) -> Self {
+        let resume: Resume = std::fs::read_to_string(path).ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        if resume.last_boost_at > 0 {
+            let saved = Timestamp::from_secs(resume.last_boost_at);
+            println!("Resuming stored boosts after {}", saved);
+            self.filters.after = Some(self.filters.after.map_or(saved, |after| after.max(saved)));
+        }
+
+        self.resume_path = Some(path.to_string());
+        self
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|token| token.is_cancelled())
+    }
+
+    fn save_resume(&self, last_boost_at: Option<Timestamp>) {
+        let (Some(path), Some(ts)) = (&self.resume_path, last_boost_at) else { return };
+        let written = serde_json::to_string(&Resume { last_boost_at: ts.as_u64() }).map_err(anyhow::Error::from)
+            .and_then(|json| std::fs::write(path, json).map_err(anyhow::Error::from));
+        if let Err(e) = written {
+            eprintln!("Failed to save {}: {:#}", path, e);
+        }
+    }
+
+    /// Load every stored boost, stopping at the first page that fails
+    pub async fn load<F, Fut>(&self, callback: F) -> Result<Option<Timestamp>>
+    where
+        F: FnMut(Boostagram) -> Fut + Send,
+        Fut: Future<Output = ()> + Send,
+    {
+        self.load_with_errors(callback, |_, _, _| PageAction::Abort).await
+    }
+
+    /// Load every stored boost, asking `on_error` what to do whenever a page fails. It's called
+    /// with the page's offset, how many times that page has failed so far, and the error.
+    pub async fn load_with_errors<F, Fut, E>(&self, mut callback: F, mut on_error: E) -> Result<Option<Timestamp>>
+    where
+        F: FnMut(Boostagram) -> Fut + Send,
+        Fut: Future<Output = ()> + Send,
+        E: FnMut(usize, u32, &anyhow::Error) -> PageAction + Send,
+    {
+        let mut offset = 0;
+        let mut failures = 0;
+        let mut skipped = false;
+        let mut last_boost_at = self.filters.after;
+
+        loop {
+            let boosts = match self.fetch_page(offset).await {
+                Ok(boosts) => boosts,
+                Err(e) => {
+                    failures += 1;
+                    match on_error(offset, failures, &e) {
+                        PageAction::Retry => {
+                            eprintln!("Stored boosts page at {} failed ({}), retrying: {:#}", offset, failures, e);
+                            tokio::time::sleep(crate::reconnect::backoff_delay(failures)).await;
+                            continue;
+                        }
+                        PageAction::Skip => {
+                            eprintln!("Skipping stored boosts {}..{}: {:#}", offset, offset + PAGE_SIZE, e);
+                            failures = 0;
+                            skipped = true;
+                            offset += PAGE_SIZE;
+                            continue;
+                        }
+                        PageAction::Abort => return Err(e),
+                    }
+                }
+            };
+            failures = 0;
+
+            if boosts.is_empty() || self.cancelled() {
+                break;
+            }
+
+            let count = boosts.len();
+            last_boost_at = self.update_last_boost_timestamp(last_boost_at, &boosts);
+            self.process_boosts(boosts, &mut callback).await;
+
+            if count < PAGE_SIZE {
+                break;
+            }
+            offset += count;
+        }
+
+        if !self.cancelled() && !skipped {
+            self.save_resume(last_boost_at);
+        }
+        Ok(last_boost_at)
+    }
+
+    fn update_last_boost_timestamp(&self, current: Option<Timestamp>, boosts: &[StoredBoostInfo]) -> Option<Timestamp> {
+        boosts.iter()
+            .map(|b| Timestamp::from_secs(b.creation_date as u64))
+            .max()
+            .map(|new_ts| current.map_or(new_ts, |old| if new_ts > old { new_ts } else { old }))
+            .or(current)
+    }
+
+    async fn process_boosts<F, Fut>(&self, mut boosts: Vec<StoredBoostInfo>, callback: &mut F)
+    where
+        F: FnMut(Boostagram) -> Fut + Send,
+        Fut: Future<Output = ()> + Send,
+    {
+        boosts.sort_by_key(|b| b.creation_date);
+
+        for invoice in boosts {
+            if self.cancelled() {
+                return;
+            }
+            if let Some(boost) = invoice.to_boostagram() {
+                if self.filters.matches_timestamp(invoice.creation_date) && self.filters.matches_boost(&boost) {
+                    callback(boost).await;
+                } else {
+                    println!("Stored boost doesn't match filters: {:#?}", boost);
+                }
+            }
+        }
+    }
+
+    async fn fetch_page(&self, offset: usize) -> Result<Vec<StoredBoostInfo>> {
+        let url = self.build_url(offset)?;
+        println!("StoredBoosts url: {:#?}", url);
+        let response = reqwest::get(url).await
+            .context("Failed to fetch boosts from API")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("API error: {}", response.status()));
+        }
+
+        response.json().await.context("Failed to parse API response")
+    }
+
+    fn build_url(&self, offset: usize) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse("https://boostboard.vercel.app/api/boosts")
+            .context("Failed to parse base URL")?;
+
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("limit", &PAGE_SIZE.to_string());
+            query.append_pair("offset", &offset.to_string());
+
+            if let Some(ref podcasts) = self.filters.podcasts {
+                query.append_pair("podcast", &podcasts.join(","));
+            }
+            if let Some(ref guids) = self.filters.episode_guids {
+                query.append_pair("episodeGuid", &guids.join(","));
+            }
+            if let Some(ref guids) = self.filters.event_guids {
+                query.append_pair("eventGuid", &guids.join(","));
+            }
+            if let Some(ts) = self.filters.before {
+                query.append_pair("created_at_lt", &ts.as_u64().to_string());
+            }
+            if let Some(ts) = self.filters.after {
+                query.append_pair("created_at_gt", &ts.as_u64().to_string());
+            }
+        }
+
+        Ok(url)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,65 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Boostagram {
+	pub boost_type: String,
+	pub action: String,
+	pub identifier: String,
+	pub creation_date: i64,
+	pub sender_name: String,
+	pub app_name: String,
+	pub podcast: String,
+	pub episode: String,
+	pub sats: i64,
+	#[serde(default)]
+	pub msats: i64,  // Exact amount when the source reports one; 0 falls back to sats
+	pub message: String,
+
+	pub event_guid: String,
+	pub episode_guid: String,
+
+	#[serde(default, alias = "remote_feed_guid", alias = "remoteFeedGuid")]
+	pub remote_feed: Option<String>,
+	#[serde(default, alias = "remote_item_guid", alias = "remoteItemGuid")]
+	pub remote_item: Option<String>,
+	#[serde(default, alias = "remoteItemTitle")]
+	pub remote_item_title: Option<String>,  // Sent by some apps alongside the guids
+
+	pub is_old: bool,
+}
+
+/// The feed and item a boost was sent for when it isn't the show's own, e.g. a guest's feed or a
+/// song played during the episode
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RemoteItem {
+	pub feed_guid: Option<String>,
+	pub item_guid: Option<String>,
+	pub title: Option<String>,
+}
+
+impl RemoteItem {
+	/// The title, or a guid when the boost didn't name the item
+	pub fn label(&self) -> String {
+		self.title.clone().or_else(|| self.item_guid.clone()).or_else(|| self.feed_guid.clone()).unwrap_or_default()
+	}
+
+	/// Whether `pattern` is the item or feed guid, or part of the title
+	pub fn matches(&self, pattern: &str) -> bool {
+		let pattern = pattern.trim().to_lowercase();
+		[&self.item_guid, &self.feed_guid].into_iter().flatten().any(|guid| guid.to_lowercase() == pattern)
+			|| self.title.as_ref().is_some_and(|title| title.to_lowercase().contains(&pattern))
+	}
+}
+
+impl Boostagram {
+	/// The remote item, with blank fields dropped; None for boosts to the show itself
+	pub fn remote(&self) -> Option<RemoteItem> {
+		let clean = |field: &Option<String>| field.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+		let remote = RemoteItem {
+			feed_guid: clean(&self.remote_feed),
+			item_guid: clean(&self.remote_item),
+			title: clean(&self.remote_item_title),
+		};
+		(remote != RemoteItem::default()).then_some(remote)
+	}
+}
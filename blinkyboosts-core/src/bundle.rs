@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+const VERSION: u32 = 1;
+const CONFIG_PATH: &str = "config.toml";
+// The setup a show is run from, beside config.toml; state such as totals, streaks and the
+// outbox stays with the machine
+const SETUP_FILES: &[&str] = &["scenes.json", "moderation.json"];
+const SETUP_DIRS: &[(&str, &str)] = &[("locales", ".ftl")];  // Directory, file extension
+
+/// A show's whole setup in one file: config.toml (toggles, WLED presets, fixtures, overlay and
+/// display settings), captured scenes, moderation lists and custom translations
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Bundle {
+    pub version: u32,
+    pub exported_at: i64,  // Unix seconds
+    pub files: BTreeMap<String, String>,  // Path relative to the show directory -> contents
+}
+
+/// Collect the setup files from `dir`
+pub fn export(dir: &Path) -> Result<Bundle> {
+    let mut files = BTreeMap::new();
+    let config = fs::read_to_string(dir.join(CONFIG_PATH)).context(format!("Failed to read {}", CONFIG_PATH))?;
+    files.insert(CONFIG_PATH.to_string(), config);
+
+    for name in SETUP_FILES {
+        if let Ok(contents) = fs::read_to_string(dir.join(name)) {
+            files.insert(name.to_string(), contents);
+        }
+    }
+    for (sub, extension) in SETUP_DIRS {
+        let Ok(entries) = fs::read_dir(dir.join(sub)) else { continue };
+        for entry in entries.flatten().filter(|entry| entry.path().is_file()) {
+            let name = format!("{}/{}", sub, entry.file_name().to_string_lossy());
+            if !name.ends_with(extension) {
+                continue;
+            }
+            let contents = fs::read_to_string(entry.path()).context(format!("Failed to read {}", name))?;
+            files.insert(name, contents);
+        }
+    }
+
+    Ok(Bundle { version: VERSION, exported_at: chrono::Utc::now().timestamp(), files })
+}
+
+/// Whether a bundled path is one `export` writes, so a bundle can't write anywhere else
+fn allowed(path: &str) -> bool {
+    path == CONFIG_PATH
+        || SETUP_FILES.contains(&path)
+        || SETUP_DIRS.iter().any(|(sub, extension)| path.strip_prefix(sub).and_then(|rest| rest.strip_prefix('/'))
+            .is_some_and(|name| name.ends_with(extension) && !name.contains(['/', '\\'])))
+}
+
+/// Write a bundle's files into `dir`, keeping any file it replaces as `<name>.bak`. The config
+/// is checked before anything is written. Returns the paths written.
+pub fn import(dir: &Path, bundle: &Bundle) -> Result<Vec<String>> {
+    if bundle.version > VERSION {
+        bail!("Bundle version {} is newer than this BlinkyBoosts understands ({})", bundle.version, VERSION);
+    }
+    let config = bundle.files.get(CONFIG_PATH).context("Bundle has no config.toml")?;
+    toml::from_str::<Config>(config).context("The bundled config.toml doesn't parse")?;
+    if let Some(path) = bundle.files.keys().find(|path| !allowed(path)) {
+        bail!("Bundle contains an unexpected file: {}", path);
+    }
+
+    let mut written = Vec::new();
+    for (name, contents) in &bundle.files {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+        }
+        if path.exists() {
+            let backup = dir.join(format!("{}.bak", name));
+            fs::rename(&path, &backup).context(format!("Failed to back up {}", name))?;
+        }
+        fs::write(&path, contents).context(format!("Failed to write {}", name))?;
+        written.push(name.clone());
+    }
+    Ok(written)
+}
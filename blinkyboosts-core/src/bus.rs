@@ -0,0 +1,109 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::messages::Event;
+use crate::metrics::TriggeredEffect;
+use crate::sat_tracker::{BoostRecord, SatTracker};
+use crate::sanitize;
+
+const CAPACITY: usize = 256;  // Per consumer; `publish` waits for room rather than dropping boosts
+
+/// A boost once it has been counted, moderated and had its effects fired, in the one shape every
+/// consumer reads, whichever input it came from
+#[derive(Debug, Clone)]
+pub struct BoostEvent {
+    pub id: u64,  // Set by `publish`; notes on the boost refer to it
+    pub source: String,
+    pub sats: i64,
+    pub sender: Option<String>,
+    pub message: Option<String>,  // Unsanitized; None when moderation dropped it
+    pub episode: Option<String>,  // Title, or the guid when there's no title
+    pub remote_item: Option<String>,  // Title or guid of the guest feed or song boosted
+    pub artwork: Option<String>,
+    pub effects: Vec<TriggeredEffect>,
+    pub relay_delay: Option<Duration>,  // Live boosts only
+    pub live: bool,  // False for boosts loaded from history, which fire no effects
+    pub received_at: i64,  // Unix seconds
+}
+
+/// Where the pipeline announces each boost. Each consumer has its own queue, so adding one
+/// doesn't touch the inputs or `process_boost`, and none misses a boost when another is slow.
+#[derive(Clone)]
+pub struct Bus {
+    consumers: Arc<std::sync::Mutex<Vec<mpsc::Sender<BoostEvent>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bus {
+    pub fn new() -> Self {
+        Self { consumers: Arc::default(), next_id: Arc::new(AtomicU64::new(1)) }
+    }
+
+    /// Number a boost and hand it to every consumer, waiting while any consumer's queue is full;
+    /// a no-op when there are none
+    pub async fn publish(&self, mut event: BoostEvent) {
+        event.id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let consumers = self.consumers.lock().unwrap().clone();
+        for consumer in consumers {
+            let _ = consumer.send(event.clone()).await;
+        }
+        self.consumers.lock().unwrap().retain(|consumer| !consumer.is_closed());
+    }
+
+    /// Run `consumer` on each boost published from now on. It's registered before this returns,
+    /// so no boost published afterwards is missed.
+    pub fn consume<F, Fut>(&self, name: &'static str, mut consumer: F) -> JoinHandle<()>
+    where
+        F: FnMut(BoostEvent) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let (tx, mut rx) = mpsc::channel(CAPACITY);
+        self.consumers.lock().unwrap().push(tx);
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                consumer(event).await;
+            }
+            println!("Boost consumer {} stopped", name);
+        })
+    }
+}
+
+/// Start the standard consumers: the tracker's recent boost history and the GUI report
+pub fn attach(bus: &Bus, tx: mpsc::Sender<Event>, tracker: Arc<Mutex<SatTracker>>) {
+    bus.consume("history", move |event| {
+        let tracker = tracker.clone();
+        async move { tracker.lock().await.record(record_of(&event)) }
+    });
+    bus.consume("gui", move |event| {
+        let tx = tx.clone();
+        async move {
+            let BoostEvent { id, source, sats, sender, message, episode, remote_item, artwork, effects, relay_delay, .. } = event;
+            let _ = tx.send(Event::BoostReceived(id, source, sats, sender, message, episode, remote_item, artwork, effects, relay_delay)).await;
+        }
+    });
+}
+
+pub(crate) fn record_of(event: &BoostEvent) -> BoostRecord {
+    BoostRecord {
+        id: event.id,
+        source: event.source.clone(),
+        sats: event.sats,
+        sender: event.sender.clone(),
+        message: event.message.as_deref().map(|m| sanitize::clean("api", m)),
+        episode: event.episode.clone(),
+        remote_item: event.remote_item.clone(),
+        received_at: event.received_at,
+        effects: event.effects.iter().map(|e| e.description.clone()).collect(),
+        note: None,
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+use serde::Serialize;
+
+use crate::amounts;
+use crate::config::{self, Config};
+use crate::sat_tracker::SatTracker;
+
+/// A campaign's progress, for the API and front ends
+#[derive(Serialize, Debug, Clone)]
+pub struct Progress {
+    pub name: String,
+    pub total: i64,
+    pub target_sats: i64,
+    pub showing: bool,  // In the rotating display slot right now
+}
+
+/// Sats a boost adds to each campaign: the whole boost goes to the first campaign with one of its
+/// keywords in the message, otherwise every campaign gets its `split_percent` share
+pub fn attribute<'a>(campaigns: &'a [config::Campaign], message: Option<&str>, sats: i64) -> Vec<(&'a config::Campaign, i64)> {
+    let message = message.unwrap_or_default().to_lowercase();
+    let matched = campaigns.iter().find(|campaign| {
+        campaign.keywords.iter().any(|kw| !kw.is_empty() && message.contains(&kw.to_lowercase()))
+    });
+
+    match matched {
+        Some(campaign) => vec![(campaign, sats)],
+        None => campaigns.iter()
+            .filter(|campaign| campaign.split_percent > 0)
+            .map(|campaign| (campaign, amounts::share(sats, campaign.split_percent.min(100) as i64)))
+            .filter(|(_, share)| *share > 0)
+            .collect(),
+    }
+}
+
+/// Count a boost toward its campaigns, returning each changed campaign's new total and whether
+/// this boost is the one that reached its target
+pub fn count<'a>(config: &'a Config, tracker: &mut SatTracker, message: Option<&str>, sats: i64) -> Vec<(&'a config::Campaign, i64, bool)> {
+    let campaigns = config.campaigns.as_deref().unwrap_or_default();
+    attribute(campaigns, message, sats).into_iter()
+        .map(|(campaign, share)| {
+            let before = tracker.campaign_total(&campaign.name);
+            let after = tracker.add_to_campaign(&campaign.name, share);
+            (campaign, after, before < campaign.target_sats && after >= campaign.target_sats)
+        })
+        .collect()
+}
+
+/// Which display slot is showing: None for the overall goal, or an index into `config.campaigns`.
+/// Slots rotate every `[goal] rotate_secs`, starting with the overall goal when there is one.
+pub fn showing(config: &Config) -> Option<usize> {
+    static STARTED: OnceLock<Instant> = OnceLock::new();
+
+    let campaigns = config.campaigns.as_deref().unwrap_or_default().len();
+    let rotate_secs = config.goal.as_ref().map_or(config::default_goal_rotate(), |goal| goal.rotate_secs);
+    if campaigns == 0 || rotate_secs == 0 {
+        return None;
+    }
+
+    let has_overall = config.goal.is_some();
+    let slots = campaigns + has_overall as usize;
+    let elapsed = STARTED.get_or_init(Instant::now).elapsed();
+    let slot = (elapsed.as_secs() / rotate_secs) as usize % slots;
+
+    match has_overall {
+        true => slot.checked_sub(1),
+        false => Some(slot),
+    }
+}
+
+pub fn progress(config: &Config, tracker: &SatTracker) -> Vec<Progress> {
+    let showing = showing(config);
+    config.campaigns.as_deref().unwrap_or_default().iter().enumerate()
+        .map(|(idx, campaign)| Progress {
+            name: campaign.name.clone(),
+            total: tracker.campaign_total(&campaign.name),
+            target_sats: campaign.target_sats,
+            showing: showing == Some(idx),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campaigns() -> Vec<config::Campaign> {
+        let config: Config = toml::from_str(r##"
+            [[campaigns]]
+            name = "Mic"
+            target_sats = 1000
+            keywords = ["#mic"]
+            split_percent = 10
+
+            [[campaigns]]
+            name = "Travel"
+            target_sats = 1000
+            keywords = ["#travel"]
+            split_percent = 25
+        "##).unwrap();
+        config.campaigns.unwrap()
+    }
+
+    fn shares(attributed: Vec<(&config::Campaign, i64)>) -> Vec<(String, i64)> {
+        attributed.into_iter().map(|(campaign, sats)| (campaign.name.clone(), sats)).collect()
+    }
+
+    #[test]
+    fn a_keyword_takes_the_whole_boost() {
+        let campaigns = campaigns();
+        assert_eq!(shares(attribute(&campaigns, Some("For the #TRAVEL fund"), 500)), [("Travel".to_string(), 500)]);
+        assert_eq!(shares(attribute(&campaigns, Some("#travel and #mic"), 500)), [("Mic".to_string(), 500)]);
+    }
+
+    #[test]
+    fn other_boosts_are_split() {
+        let campaigns = campaigns();
+        assert_eq!(shares(attribute(&campaigns, None, 1000)), [("Mic".to_string(), 100), ("Travel".to_string(), 250)]);
+        assert_eq!(shares(attribute(&campaigns, Some("hi"), 5)), [("Travel".to_string(), 1)]);
+    }
+}
@@ -0,0 +1,68 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::sync::Mutex;
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// A DMX frame as it went out on the wire, after the dimmer
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Frame {
+    pub time: String,  // RFC 3339 with milliseconds
+    pub output: String,  // "artnet" or "sacn"
+    pub target: String,
+    pub universe: u16,
+    pub data: String,  // Hex, channel 1 first, without the sACN start code
+}
+
+impl Frame {
+    pub fn values(&self) -> Result<Vec<u8>> {
+        hex::decode(&self.data).context("Invalid frame data")
+    }
+
+    pub fn at(&self) -> Result<DateTime<Utc>> {
+        Ok(DateTime::parse_from_rfc3339(&self.time).context("Invalid frame time")?.with_timezone(&Utc))
+    }
+}
+
+static FILE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Start appending sent frames to `[capture] file`, if set
+pub fn configure(config: &Config) {
+    let file = config.capture.as_ref().map(|cfg| cfg.file.clone());
+    if let Some(path) = &file {
+        println!("Capturing DMX frames to {}", path);
+    }
+    *FILE.lock().unwrap() = file;
+}
+
+/// Append a frame that was just sent
+pub fn record(output: &str, target: impl Into<String>, universe: u16, data: &[u8]) {
+    let file = FILE.lock().unwrap();
+    let Some(path) = file.as_ref() else { return };
+
+    let frame = Frame {
+        time: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        output: output.to_string(),
+        target: target.into(),
+        universe,
+        data: hex::encode(data),
+    };
+    let written = OpenOptions::new().create(true).append(true).open(path)
+        .and_then(|mut file| writeln!(file, "{}", serde_json::to_string(&frame).unwrap_or_default()));
+    if let Err(e) = written {
+        eprintln!("Failed to write DMX capture {}: {}", path, e);
+    }
+}
+
+/// Frames from a capture file, oldest first
+pub fn load(path: &str) -> Result<Vec<Frame>> {
+    let contents = fs::read_to_string(path).context(format!("Failed to read capture {}", path))?;
+    contents.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| serde_json::from_str(line).context(format!("{}:{}: invalid frame", path, idx + 1)))
+        .collect()
+}
@@ -0,0 +1,52 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use nostr_sdk::Timestamp;
+
+use crate::config::{self, Config};
+
+static PAST_TOLERANCE: AtomicU64 = AtomicU64::new(config::DEFAULT_PAST_TOLERANCE);
+static FUTURE_TOLERANCE: AtomicU64 = AtomicU64::new(config::DEFAULT_FUTURE_TOLERANCE);
+static SPEED: AtomicU64 = AtomicU64::new(0x3FF0_0000_0000_0000);  // 1.0 as f64 bits
+
+/// Load the skew tolerances; the defaults apply without a [clock] section
+pub fn configure(config: &Config) {
+    let cfg = config.clock.clone().unwrap_or_default();
+    PAST_TOLERANCE.store(cfg.past_tolerance_secs, Ordering::Relaxed);
+    FUTURE_TOLERANCE.store(cfg.future_tolerance_secs, Ordering::Relaxed);
+}
+
+/// An event's created_at, or now if it claims to be from further in the future than the
+/// tolerance allows. A sender with a fast clock can't then push resume points past events
+/// still to come.
+pub fn clamp(created_at: Timestamp) -> Timestamp {
+    let now = Timestamp::now();
+    if created_at.as_u64() > now.as_u64() + FUTURE_TOLERANCE.load(Ordering::Relaxed) {
+        eprintln!("Event timestamp {} is ahead of our clock, using {}", created_at, now);
+        return now;
+    }
+    created_at
+}
+
+/// Whether an event seen for the first time now is new since `live_since`, when live listening
+/// started. Events created up to the past tolerance before it still count, so a sender whose
+/// clock runs behind ours isn't treated as history, while events replayed after a reconnect are.
+/// Callers drop repeat deliveries of an event (see `dedup`) so this is only asked once per event.
+pub fn is_new(created_at: Timestamp, live_since: Timestamp) -> bool {
+    created_at.as_u64() + PAST_TOLERANCE.load(Ordering::Relaxed) >= live_since.as_u64()
+}
+
+/// Run the show's own timers (toggle delays, holds, the idle delay and latency offsets) `speed`
+/// times faster than real time, for rehearsals. Fixed lengths on the devices themselves, like
+/// WLED playlists, can't be sped up unless the device is mocked.
+pub fn set_speed(speed: f64) {
+    SPEED.store(speed.max(0.01).to_bits(), Ordering::Relaxed);
+}
+
+pub fn speed() -> f64 {
+    f64::from_bits(SPEED.load(Ordering::Relaxed))
+}
+
+/// How long a show timer of `duration` really waits at the current speed
+pub fn scaled(duration: Duration) -> Duration {
+    duration.div_f64(speed())
+}
@@ -0,0 +1,1221 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use anyhow::{Context, Result};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Config {
+    pub nwc: Option<NWC>,
+    pub boostboard: Option<BoostBoard>,
+    pub zaps: Option<Zaps>,
+    pub osc: Option<OSC>,
+    pub artnet: Option<ArtNet>,
+    pub sacn: Option<Sacn>,
+    pub artnet_input: Option<ArtNetInput>,
+    pub wled: Option<WLed>,
+    pub toggles: Option<Vec<Toggle>>,
+    pub active_tags: Option<Vec<String>>,  // Tagged toggles only fire with one of these tags; all tags are active when unset
+    pub emoji: Option<BTreeMap<String, String>>,  // Emoji in a boost message to the name of the toggle it fires instead of the defaults
+    pub watchdog: Option<Watchdog>,
+    pub shutdown: Option<Shutdown>,
+    pub health: Option<Health>,
+    pub test_run: Option<TestRun>,
+    pub retry: Option<Retry>,
+    pub api: Option<Api>,
+    pub remote: Option<Remote>,
+    pub plugins: Option<Vec<Plugin>>,
+    pub companion: Option<Companion>,
+    pub mqtt: Option<Mqtt>,
+    pub qr: Option<PaymentQr>,
+    pub idle: Option<Idle>,
+    pub dimmer: Option<Dimmer>,
+    pub goal: Option<Goal>,
+    pub fixtures: Option<Vec<Fixture>>,
+    pub mock: Option<Mock>,
+    pub capture: Option<Capture>,
+    pub sanitize: Option<Sanitize>,
+    pub moderation: Option<Moderation>,
+    pub episodes: Option<Episodes>,
+    pub campaigns: Option<Vec<Campaign>>,
+    pub total_brackets: Option<Vec<TotalBracket>>,
+    pub sync: Option<ShowSync>,
+    pub clock: Option<Clock>,
+    pub shows: Option<Vec<Show>>,
+    pub streaks: Option<Streaks>,
+    pub summary: Option<Summary>,
+    pub notify: Option<Notify>,
+    pub rate_limits: Option<BTreeMap<String, RateLimit>>,  // By output: "wled", "osc", "artnet", ...
+    pub latency_ms: Option<BTreeMap<String, u64>>,  // By output: how long after sending it shows, e.g. { osc = 300 }
+    pub display: Option<Display>,
+    pub recent_boosts: Option<RecentBoosts>,
+    pub shortcuts: Option<Shortcuts>,
+    pub podcast_index: Option<PodcastIndex>,
+    pub outbox: Option<Outbox>,
+    pub audio: Option<Audio>,
+    pub sources: Option<BTreeMap<String, SourceLabel>>,  // By input: "Zaps", "Boostboard", "NWC", "Test", ...
+}
+
+/// Common filter fields for boost sources
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct BoostFiltersConfig {
+    pub load_since: Option<String>,
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub podcasts: Option<Vec<String>>,
+    pub episode_guids: Option<Vec<String>>,
+    pub event_guids: Option<Vec<String>>,
+    pub exclude_podcasts: Option<Vec<String>>,
+    pub exclude_episode_guids: Option<Vec<String>>,
+    pub exclude_senders: Option<Vec<String>>,  // Sender names or pubkeys (hex or npub)
+    pub remote_items: Option<Vec<String>>,  // Boosts sent for these remote items: item or feed guids, or part of the title
+    pub exclude_remote_items: Option<Vec<String>>,
+    pub app_names: Option<Vec<String>>,  // Only boosts sent from these apps (e.g. "Fountain")
+    pub actions: Option<Vec<String>>,  // Boostagram actions to count; only "boost" when unset
+    pub min_sats: Option<i64>,  // Ignore payments smaller than this
+    pub max_sats: Option<i64>,  // Ignore payments larger than this
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BoostBoard {
+    #[serde(default)]
+    pub relay_addrs: Vec<String>,
+    pub pubkey: String,
+    #[serde(default)]
+    pub resume_history: bool,  // Only load stored boosts newer than the last one loaded by a previous run
+    #[serde(default = "default_page_retries")]
+    pub page_retries: u32,  // Retries for a page of stored boosts that fails to load before giving up
+    #[serde(flatten)]
+    pub filters: BoostFiltersConfig,
+}
+
+pub fn default_page_retries() -> u32 {
+    3
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct NWC {
+    pub uri: String,
+    #[serde(default = "default_true")]
+    pub memo_boosts: bool,  // Read boost details from invoice memos when a payment has no boostagram TLV
+    #[serde(default = "default_nwc_wallet_check")]
+    pub wallet_check_secs: u64,  // Check the wallet's info and balance this often; 0 disables
+    #[serde(flatten)]
+    pub filters: BoostFiltersConfig,
+}
+
+pub fn default_nwc_wallet_check() -> u64 {
+    60
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Zaps {
+    pub relay_addrs: Vec<String>,
+    pub naddr: String,
+    pub load_since: Option<String>,  // Load zaps since this timestamp (e.g., "2025-01-11 00:00:00")
+    #[serde(default = "default_zap_splits")]
+    pub splits: String,  // "separate", "aggregate" or "local"
+    #[serde(default)]
+    pub local_pubkeys: Vec<String>,  // Recipients counted when splits = "local" (hex or npub)
+    #[serde(default = "default_zap_split_window")]
+    pub split_window_secs: u64,
+    pub exclude_senders: Option<Vec<String>>,  // Sender names or pubkeys (hex or npub)
+    pub min_sats: Option<i64>,  // Ignore zaps smaller than this
+    pub max_sats: Option<i64>,  // Ignore zaps larger than this
+}
+
+fn default_zap_splits() -> String {
+    "separate".to_string()
+}
+
+fn default_zap_split_window() -> u64 {
+    5
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct OSC {
+    pub address: String,
+    #[serde(default)]
+    pub mock: bool,  // Record what would be sent instead of sending it (see [mock])
+    pub ack_timeout_ms: Option<u64>,  // Wait this long for the target to reply to each cue; no reply is retried
+    pub reply_address: Option<String>,  // Listen for replies here instead of on the sending port, e.g. Resolume's OSC output
+    pub totals: Option<OscTotals>,
+}
+
+/// Another show run alongside this one, from its own directory with its own config.toml
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Show {
+    pub name: String,
+    pub dir: String,
+}
+
+/// Periodic broadcast of the running totals for VJ software and media servers to bind to
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OscTotals {
+    pub address: Option<String>,  // Defaults to [osc] address
+    #[serde(default = "default_osc_totals_prefix")]
+    pub prefix: String,
+    #[serde(default = "default_osc_totals_interval")]
+    pub interval_ms: u64,
+}
+
+fn default_osc_totals_prefix() -> String {
+    "/blinky".to_string()
+}
+
+fn default_osc_totals_interval() -> u64 {
+    1000
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ArtNet {
+    pub broadcast_address: String,
+    pub local_address: Option<String>,
+    pub universe: Option<u16>,
+    #[serde(default)]
+    pub merge: Merge,  // How toggles combine with the console's levels; toggles can override it
+    #[serde(default)]
+    pub mock: bool,  // Record what would be sent instead of sending it (see [mock])
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Sacn {
+    pub broadcast_address: String,
+    pub universe: Option<u16>,
+    #[serde(default = "default_sacn_priority")]
+    pub priority: u8,  // 0-200; receivers follow the highest-priority source (consoles usually send 100)
+    #[serde(default)]
+    pub merge: Merge,  // How toggles combine with the console's levels; toggles can override it
+    #[serde(default)]
+    pub mock: bool,  // Record what would be sent instead of sending it (see [mock])
+}
+
+pub fn default_sacn_priority() -> u8 {
+    100
+}
+
+/// How an effect's DMX values combine with the house console's, as read by `[artnet_input]`
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Merge {
+    #[default]
+    Ltp,  // Latest takes precedence: the effect's values are sent as they are
+    Htp,  // Highest takes precedence: each channel is sent at the higher of the effect's and the console's value
+}
+
+/// Channels read from the venue console over Art-Net to gate and scale boost effects
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ArtNetInput {
+    #[serde(default = "default_artnet_input_bind")]
+    pub bind_address: String,
+    #[serde(default)]
+    pub universe: u16,
+    #[serde(default)]
+    pub gates: Vec<ArtNetGate>,
+    pub intensity_channel: Option<u16>,  // Scales all effect output like the master dimmer (0-255 = 0-100%)
+    #[serde(default = "default_artnet_input_timeout")]
+    pub timeout_secs: u64,  // Console counts as gone after this long without a frame
+    #[serde(default = "default_true")]
+    pub fail_open: bool,  // Let gated effects fire while the console is gone
+}
+
+/// Only fire the matching toggles while a console channel is at or above `min_value`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ArtNetGate {
+    pub channel: u16,
+    #[serde(default = "default_artnet_gate_min")]
+    pub min_value: u8,
+    #[serde(default)]
+    pub outputs: Vec<String>,  // Output types gated, e.g. ["artnet"]
+    #[serde(default)]
+    pub toggles: Vec<String>,  // Toggle names gated; with no outputs or toggles every toggle is
+}
+
+fn default_artnet_input_bind() -> String {
+    "0.0.0.0:6454".to_string()
+}
+
+fn default_artnet_input_timeout() -> u64 {
+    5
+}
+
+fn default_artnet_gate_min() -> u8 {
+    128
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WLed {
+    pub host: String,
+    pub boost_playlist: String,
+    pub brightness: u64,
+    pub segments: Option<Vec<WLedSegment>>,
+    pub presets: Option<Vec<WLedPreset>>,
+    pub playlists: Option<Vec<WLedPlaylist>>,
+    pub setup: bool,
+    pub force: bool,
+    #[serde(default = "default_wled_connect_timeout")]
+    pub connect_timeout_ms: u64,
+    #[serde(default = "default_wled_request_timeout")]
+    pub request_timeout_ms: u64,
+    #[serde(default = "default_wled_retries")]
+    pub retries: u32,  // Extra attempts after a connection failure, timeout or 5xx response
+    #[serde(default)]
+    pub mock: bool,  // Record what would be sent instead of sending it (see [mock])
+    #[serde(default)]
+    pub while_playing: WhilePlaying,
+}
+
+/// What a boost's WLED effect does while a playlist started by an earlier one is still running
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WhilePlaying {
+    #[default]
+    Defer,      // Wait for the playlist to end, then run; several boosts run one after another
+    Coalesce,   // Wait for the playlist to end, then run once for all boosts that came in meanwhile
+    Interrupt,  // Run straight away, cutting the playlist off
+}
+
+pub fn default_wled_connect_timeout() -> u64 {
+    1000
+}
+
+pub fn default_wled_request_timeout() -> u64 {
+    3000
+}
+
+pub fn default_wled_retries() -> u32 {
+    2
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WLedSegment {
+    pub name: String,
+    pub start: u64,
+    pub stop: u64,
+    pub reverse: Option<bool>,
+    pub grouping: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WLedPreset {
+    pub name: String,
+    pub speed: Option<u64>,
+    pub intensity: Option<u64>,
+    pub colors: Vec<Vec<u64>>,
+    pub colors2: Option<Vec<Vec<u64>>>,
+    pub colors3: Option<Vec<Vec<u64>>>,
+    pub effects: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WLedPlaylist {
+    pub name: String,
+    pub presets: Vec<String>,
+    pub durations: Vec<u64>,
+    pub transitions: Vec<u64>,
+    pub repeat: u64,
+    pub end: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum OscArgValue {
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ToggleOsc {
+    pub path: String,
+    pub arg_value: OscArgValue,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ToggleArtNet {
+    pub universe: Option<u16>,
+    pub channel: Option<u16>,
+    #[serde(default)]
+    pub value: u8,
+    pub count: Option<u16>,  // Set this many channels from `channel` to `value`
+    #[serde(default)]
+    pub channels: Vec<(u16, u8)>,  // More (channel, value) pairs; channels past 512 continue into the following universes
+    pub rgb_channel: Option<u16>,  // First of three R, G, B channels set to the sender's color
+    pub merge: Option<Merge>,  // Defaults to `[artnet] merge`
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ToggleSacn {
+    pub universe: Option<u16>,
+    pub channel: Option<u16>,
+    #[serde(default)]
+    pub value: u8,
+    pub count: Option<u16>,  // Set this many channels from `channel` to `value`
+    #[serde(default)]
+    pub channels: Vec<(u16, u8)>,  // More (channel, value) pairs; channels past 512 continue into the following universes
+    pub rgb_channel: Option<u16>,  // First of three R, G, B channels set to the sender's color
+    pub priority: Option<u8>,  // Defaults to `[sacn] priority`
+    pub merge: Option<Merge>,  // Defaults to `[sacn] merge`
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ToggleWled {
+    pub preset: String,
+    pub color_segments: Option<Vec<u64>>,  // Segment ids recolored with the sender's color (default: selected segments)
+}
+
+/// Sets channels on a fixture from the `[[fixtures]]` library by role
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ToggleFixture {
+    pub name: String,
+    pub color: Option<[u8; 3]>,  // Sets the red/green/blue channels
+    pub dimmer: Option<u8>,
+    pub strobe: Option<u8>,
+    #[serde(default)]
+    pub values: BTreeMap<String, u8>,  // Any other role by name, e.g. { pan = 128 }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TogglePlugin {
+    pub name: String,
+    pub params: Option<serde_json::Value>,  // Passed through to the plugin as-is
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Toggle {
+    pub name: Option<String>,  // Lets the control API fire this toggle directly
+    #[serde(default)]
+    pub threshold: i64,
+    #[serde(default)]
+    pub output: String,
+    #[serde(default)]
+    pub outputs: Vec<String>,  // Further outputs fired from the same match, e.g. ["wled", "artnet"], each using its own section below
+    #[serde(default)]
+    pub is_default: bool,
+    #[serde(default)]
+    pub use_total: bool,  // If true, trigger based on cumulative sat total instead of individual boost amount
+    #[serde(default = "default_true")]
+    pub trigger_multiple: bool,  // If true, trigger for every multiple of the threshold (e.g., 250k triggers at 250k, 500k, 750k, etc.)
+    pub endswith_range: Option<(u8, u8)>,  // If set, only trigger when the last digit of sats is within this range (inclusive), e.g., (0, 3) for 0-3
+    #[serde(default)]
+    pub amount_patterns: Vec<String>,  // If set, only trigger when sats match one, e.g. ["ends:69", "ends:33", "palindrome"]
+    #[serde(default)]
+    pub sender_color: bool,  // If true, color the effect with a color derived from the sender's pubkey or name
+    pub color: Option<[u8; 3]>,  // Color for the effect when sender_color doesn't pick one
+    #[serde(default)]
+    pub sources: Vec<String>,  // Only fire for boosts from these sources, e.g. ["Zaps"]; such defaults replace the catch-all ones
+    #[serde(default)]
+    pub remote_items: Vec<String>,  // Only fire for boosts sent for these remote items (item or feed guids, or part of the title); such defaults replace the catch-all ones
+    #[serde(default)]
+    pub repeat: u32,  // Fire this many times per trigger (0 or 1 fires once), e.g. for strobe bursts
+    #[serde(default = "default_repeat_interval")]
+    pub repeat_interval_ms: u64,  // Time between repeats; DMX channels drop to 0 halfway between them
+    #[serde(default)]
+    pub delay_secs: u64,  // Hold the effect this long after the boost, e.g. until the host has read the message
+    #[serde(default)]
+    pub tags: Vec<String>,  // e.g. ["loud", "projector"], to switch classes of toggles together (see `active_tags`)
+    pub chance: Option<f64>,  // Only fire this share of the time it matches, 0.0 to 1.0, e.g. 0.1 for one boost in ten; firing by name ignores it
+    pub hold: Option<Hold>,  // Revert the effect after a time set by the boost amount
+
+    // Protocol-specific configuration
+    pub osc: Option<ToggleOsc>,
+    pub artnet: Option<ToggleArtNet>,
+    pub sacn: Option<ToggleSacn>,
+    pub wled: Option<ToggleWled>,
+    pub plugin: Option<TogglePlugin>,
+    pub fixture: Option<ToggleFixture>,
+    pub scene: Option<String>,  // Scene captured from the live outputs (output = "scene")
+}
+
+fn default_repeat_interval() -> u64 {
+    200
+}
+
+/// How long a toggle's effect stays up for a boost before its output reverts: WLED returns to the
+/// idle preset (or switches off without one) and DMX channels drop to 0
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Hold {
+    pub sats_per_sec: u64,  // e.g. 100 for one second per 100 sats
+    #[serde(default)]
+    pub min_secs: u64,
+    #[serde(default = "default_hold_max")]
+    pub max_secs: u64,
+}
+
+fn default_hold_max() -> u64 {
+    60
+}
+
+/// Policy for restarting listener tasks that exit or panic unexpectedly
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Watchdog {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_watchdog_interval")]
+    pub check_interval_secs: u64,
+    #[serde(default = "default_watchdog_max_restarts")]
+    pub max_restarts: usize,  // Give up on a listener after this many restarts within restart_window_secs
+    #[serde(default = "default_watchdog_window")]
+    pub restart_window_secs: u64,
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_secs: default_watchdog_interval(),
+            max_restarts: default_watchdog_max_restarts(),
+            restart_window_secs: default_watchdog_window(),
+        }
+    }
+}
+
+/// What to send to outputs when the app exits so fixtures aren't left mid-effect
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Shutdown {
+    #[serde(default = "default_true")]
+    pub reset_outputs: bool,
+    pub wled_preset: Option<String>,  // Preset to run on exit; WLED is switched off if unset
+    #[serde(default = "default_true")]
+    pub dmx_blackout: bool,  // Zero Art-Net/sACN universes (and terminate sACN streams)
+    #[serde(default = "default_shutdown_drain")]
+    pub drain_timeout_secs: u64,  // How long to wait for in-flight effects to finish
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            reset_outputs: true,
+            wled_preset: None,
+            dmx_blackout: true,
+            drain_timeout_secs: default_shutdown_drain(),
+        }
+    }
+}
+
+/// Ambient look restored once boost effects have had their moment
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Idle {
+    #[serde(default = "default_idle_delay")]
+    pub delay_secs: u64,  // Restore this long after the last effect was sent
+    pub wled_preset: Option<String>,  // Idle preset or playlist; WLED is left alone if unset
+    #[serde(default)]
+    pub dmx_channels: Vec<(u16, u8)>,  // (channel, value) look sent to the default Art-Net/sACN universe
+    pub scene: Option<String>,  // Captured scene to restore, sent before wled_preset/dmx_channels
+    #[serde(default = "default_true")]
+    pub on_start: bool,  // Also restore the idle look at startup
+    pub pattern: Option<IdlePattern>,  // Drawn over DDP while idle, for controllers without an idle preset
+}
+
+/// A resting look generated by BlinkyBoosts and streamed to a strip over DDP
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct IdlePattern {
+    #[serde(default)]
+    pub style: PatternStyle,
+    pub host: Option<String>,  // DDP receiver, defaults to the WLED host
+    #[serde(default)]
+    pub start: usize,  // First LED of the pattern
+    pub leds: usize,
+    #[serde(default = "default_pattern_color")]
+    pub color: [u8; 3],  // For breathing and sparkle
+    #[serde(default = "default_pattern_speed")]
+    pub speed: u8,  // 1-100
+    #[serde(default = "default_pattern_brightness")]
+    pub brightness: u8,  // Percent, under the master dimmer
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternStyle {
+    #[default]
+    Rainbow,    // Hues scrolling along the strip
+    Breathing,  // The whole strip fading in and out in `color`
+    Sparkle,    // Random LEDs flashing `color` and fading
+}
+
+impl PatternStyle {
+    pub const ALL: [PatternStyle; 3] = [PatternStyle::Rainbow, PatternStyle::Breathing, PatternStyle::Sparkle];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rainbow => "rainbow",
+            Self::Breathing => "breathing",
+            Self::Sparkle => "sparkle",
+        }
+    }
+}
+
+fn default_pattern_color() -> [u8; 3] {
+    [255, 120, 20]
+}
+
+fn default_pattern_speed() -> u8 {
+    20
+}
+
+fn default_pattern_brightness() -> u8 {
+    60
+}
+
+fn default_idle_delay() -> u64 {
+    30
+}
+
+/// Master intensity scaling WLED brightness and every DMX value sent
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Dimmer {
+    #[serde(default = "default_dimmer_master")]
+    pub master: u8,  // Percent, adjustable from the GUI
+    #[serde(default)]
+    pub schedule: Vec<DimmerPeriod>,
+}
+
+/// A daily period during which the master is further scaled to `level` percent
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DimmerPeriod {
+    pub from: String,  // Local time "HH:MM"
+    pub to: String,    // Local time "HH:MM", may be earlier than `from` to wrap past midnight
+    pub level: u8,
+}
+
+fn default_dimmer_master() -> u8 {
+    100
+}
+
+/// A DMX fixture patched at `start_channel`, with one role per channel in order
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Fixture {
+    pub name: String,
+    pub output: String,  // "artnet" or "sacn"
+    pub universe: Option<u16>,  // Defaults to the output's universe
+    pub start_channel: u16,
+    pub channels: Vec<String>,  // e.g. ["dimmer", "red", "green", "blue", "strobe"]; any other name works with `values`
+}
+
+/// Cleanup applied to boost messages before each output shows them
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Sanitize {
+    #[serde(default = "default_sanitize_level")]
+    pub level: String,  // "off", "light" (mask words, limit length) or "strict" (also strip URLs and emoji)
+    #[serde(default)]
+    pub outputs: BTreeMap<String, String>,  // Per-output level: "console", "gui", "events", "api", "export", "notify", "osc", "mqtt"
+    #[serde(default = "default_true")]
+    pub default_words: bool,  // Mask the built-in list of common profanity
+    #[serde(default)]
+    pub words: Vec<String>,  // Extra words to mask; a trailing * matches any ending
+    #[serde(default = "default_sanitize_max_length")]
+    pub max_length: usize,  // Characters; 0 for no limit
+}
+
+fn default_sanitize_level() -> String {
+    "light".to_string()
+}
+
+fn default_sanitize_max_length() -> usize {
+    280
+}
+
+/// Trust and block lists for senders; live boosts from anyone else wait for an operator's approval
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Moderation {
+    #[serde(default = "default_true")]
+    pub hold_unknown: bool,  // If false only `blocked` applies
+    #[serde(default = "default_true")]
+    pub hold_anonymous: bool,  // Also hold boosts with no sender name or pubkey
+    #[serde(default)]
+    pub trusted: Vec<String>,  // Pubkeys (hex or npub) or sender names
+    #[serde(default)]
+    pub blocked: Vec<String>,  // Counted toward the total but never trigger effects or show messages
+    #[serde(default = "default_true")]
+    pub trust_on_approve: bool,  // Approving a boost trusts its sender's pubkey (or name without one) from then on
+    #[serde(default = "default_true")]
+    pub trust_history: bool,  // Senders of boosts loaded from history count as known
+}
+
+/// Episode sessions are always detected from boost metadata; this reacts when one starts
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Episodes {
+    pub toggle: Option<String>,  // Named toggle fired when a live boost starts a new episode
+}
+
+/// Recognizes senders who boost episode after episode, from streaks kept in ./streaks.json
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Streaks {
+    pub returning_toggle: Option<String>,  // Named toggle fired for a sender's first boost in an episode when they've boosted before
+    pub milestone_toggle: Option<String>,  // Named toggle fired when a streak reaches one of `milestones`
+    #[serde(default)]
+    pub milestones: Vec<u32>,  // Episodes in a row, e.g. [3, 5, 10]
+}
+
+/// Mirrors live boosts between instances over nostr so each runs the same light show
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ShowSync {
+    pub role: String,  // "publish" sends this instance's live boosts, "mirror" fires effects for a peer's
+    pub relay_addrs: Vec<String>,  // Preferably a private relay
+    pub secret_key: String,  // This instance's key (nsec or hex)
+    pub peers: Vec<String>,  // Publish: instances to send to; mirror: instances to accept boosts from (npub or hex)
+}
+
+/// Where calls to outputs with `mock = true` are recorded
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Mock {
+    pub file: Option<String>,  // Append each call as a JSON line
+    #[serde(default = "default_mock_keep")]
+    pub keep: usize,  // Calls kept in memory for the API and `test`
+}
+
+impl Default for Mock {
+    fn default() -> Self {
+        Self { file: None, keep: default_mock_keep() }
+    }
+}
+
+fn default_mock_keep() -> usize {
+    500
+}
+
+/// Where every Art-Net and sACN frame sent is recorded, for `blinkyboosts playback`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Capture {
+    pub file: String,  // Append each frame as a JSON line
+}
+
+/// Fundraising thermometer drawn on a LED strip over DDP
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Goal {
+    pub target_sats: i64,
+    pub host: Option<String>,  // DDP receiver, defaults to the WLED host
+    #[serde(default)]
+    pub start: usize,  // First LED of the thermometer
+    pub leds: usize,
+    pub sats_per_led: Option<i64>,  // Defaults to target_sats / leds
+    #[serde(default = "default_goal_fill")]
+    pub fill_color: [u8; 3],
+    #[serde(default)]
+    pub empty_color: [u8; 3],
+    #[serde(default)]
+    pub display_secs: u64,  // Hold the display this long after each change; 0 shows it continuously
+    #[serde(default = "default_goal_rotate")]
+    pub rotate_secs: u64,  // Time each of the overall goal and `[[campaigns]]` is shown; 0 shows only the overall goal
+    #[serde(default)]
+    pub mock: bool,  // Record what would be sent instead of sending it (see [mock])
+}
+
+fn default_goal_fill() -> [u8; 3] {
+    [255, 160, 0]
+}
+
+pub fn default_goal_rotate() -> u64 {
+    10
+}
+
+/// A fundraiser counted alongside the overall total
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Campaign {
+    pub name: String,
+    pub target_sats: i64,
+    #[serde(default)]
+    pub keywords: Vec<String>,  // Boosts with one of these in the message count toward this campaign in full
+    #[serde(default)]
+    pub split_percent: u8,  // Share of every boost without a keyword counted toward this campaign
+    pub toggle: Option<String>,  // Named toggle fired when a live boost reaches the target
+    pub fill_color: Option<[u8; 3]>,  // Goal display color while this campaign is shown
+}
+
+/// Ambient WLED look for a range of the show total
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TotalBracket {
+    pub from_sats: i64,  // Bracket runs from this total up to the next bracket's from_sats
+    pub wled_preset: String,  // Preset or playlist name
+}
+
+/// Periodic reachability checks for configured outputs
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Health {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_health_interval")]
+    pub interval_secs: u64,
+    #[serde(default = "default_health_timeout")]
+    pub timeout_ms: u64,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: default_health_interval(),
+            timeout_ms: default_health_timeout(),
+        }
+    }
+}
+
+/// A test sequence fired at set times to check every output responds before a show
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TestRun {
+    pub times: Vec<String>,  // Local "HH:MM", every day, e.g. ["18:50"] for ten minutes before a 19:00 show
+    #[serde(default)]
+    pub toggles: Vec<String>,  // Fired in order, by name or position; with none only the outputs are probed
+    #[serde(default = "default_test_run_step")]
+    pub step_ms: u64,  // Time between toggles
+    #[serde(default)]
+    pub discord: bool,  // Also post the results to `[summary] discord_webhook`
+}
+
+fn default_test_run_step() -> u64 {
+    2000
+}
+
+/// An external output executable, see `plugins::Plugins` for the protocol
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Plugin {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_plugin_timeout")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub mock: bool,  // Record what would be sent instead of sending it (see [mock])
+}
+
+fn default_plugin_timeout() -> u64 {
+    2000
+}
+
+/// Local HTTP control API
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Api {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_api_bind")]
+    pub bind: String,
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,  // Without any, the API is open to anyone who can reach it
+}
+
+impl Default for Api {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bind: default_api_bind(),
+            tokens: Vec::new(),
+        }
+    }
+}
+
+/// A secret the API accepts as `Authorization: Bearer <token>` or `?token=<token>`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ApiToken {
+    pub name: String,  // Who it was given to, for logs
+    pub token: String,
+    pub role: ApiRole,
+}
+
+/// What a token may do. Operators can do everything viewers can.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiRole {
+    Viewer,    // Totals, recent boosts, sessions, toggle stats and the event stream
+    Operator,  // Also test boosts, firing toggles, moderation, scenes and starting or stopping listeners
+}
+
+/// The headless agent `blinkyboosts connect` controls, e.g. one running by the lights at the venue
+/// with its control API reachable from the booth
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Remote {
+    pub url: String,  // The agent's API, e.g. "ws://192.168.1.50:8088"
+    pub token: Option<String>,  // An operator token from the agent's [[api.tokens]]
+}
+
+fn default_api_bind() -> String {
+    "127.0.0.1:8088".to_string()
+}
+
+/// Line-based TCP control for Bitfocus Companion and similar control surfaces
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Companion {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_companion_bind")]
+    pub bind: String,
+}
+
+impl Default for Companion {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bind: default_companion_bind(),
+        }
+    }
+}
+
+fn default_companion_bind() -> String {
+    "127.0.0.1:8089".to_string()
+}
+
+/// On-screen QR code the audience can pay directly. Uses `lightning_address` or `lnurl` if set,
+/// otherwise asks the NWC wallet for an invoice (refreshed every `invoice_expiry_secs`).
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PaymentQr {
+    pub lightning_address: Option<String>,
+    pub lnurl: Option<String>,
+    #[serde(default = "default_qr_amount")]
+    pub invoice_sats: u64,
+    #[serde(default = "default_qr_description")]
+    pub invoice_description: String,
+    #[serde(default = "default_qr_expiry")]
+    pub invoice_expiry_secs: u64,
+}
+
+fn default_qr_amount() -> u64 {
+    1000
+}
+
+fn default_qr_description() -> String {
+    "Boost the show".to_string()
+}
+
+fn default_qr_expiry() -> u64 {
+    600
+}
+
+/// MQTT broker connection, used to publish Home Assistant discovery topics
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Mqtt {
+    pub host: String,
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+    #[serde(default = "default_mqtt_base_topic")]
+    pub base_topic: String,
+    #[serde(default = "default_mqtt_discovery_prefix")]
+    pub discovery_prefix: String,
+    pub boost_template: Option<String>,  // Published to <base_topic>/boost for each boost, e.g. "{{sats}} sats from {{sender}}"
+}
+
+fn default_mqtt_port() -> u16 {
+    1883
+}
+
+fn default_mqtt_client_id() -> String {
+    "blinkyboosts".to_string()
+}
+
+fn default_mqtt_base_topic() -> String {
+    "blinkyboosts".to_string()
+}
+
+fn default_mqtt_discovery_prefix() -> String {
+    "homeassistant".to_string()
+}
+
+pub const DEFAULT_PAST_TOLERANCE: u64 = 30;
+pub const DEFAULT_FUTURE_TOLERANCE: u64 = 60;
+
+/// How far event timestamps may be off from our clock
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Clock {
+    #[serde(default = "default_past_tolerance")]
+    pub past_tolerance_secs: u64,  // Events created this long before listening started still fire effects
+    #[serde(default = "default_future_tolerance")]
+    pub future_tolerance_secs: u64,  // Events timestamped further ahead than this are treated as created now
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            past_tolerance_secs: default_past_tolerance(),
+            future_tolerance_secs: default_future_tolerance(),
+        }
+    }
+}
+
+fn default_past_tolerance() -> u64 {
+    DEFAULT_PAST_TOLERANCE
+}
+
+fn default_future_tolerance() -> u64 {
+    DEFAULT_FUTURE_TOLERANCE
+}
+
+/// How amounts are shown in the GUI and recaps
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Display {
+    #[serde(default)]
+    pub abbreviate: bool,  // 1.2M and 15k instead of 1,200,000 and 15,000
+    pub locale: Option<String>,  // Language for the GUI and show recaps, e.g. "es" or "de"; English when unset
+}
+
+/// How many boosts the GUI keeps listed, and the log older ones are paged back in from
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RecentBoosts {
+    #[serde(default = "default_recent_boosts_keep")]
+    pub keep: usize,  // Newest boosts kept in the list
+    #[serde(default)]
+    pub max_age_hours: u64,  // Also drop boosts older than this; 0 keeps them regardless of age
+    #[serde(default = "default_true")]
+    pub log: bool,  // Append every boost to ./boost_log.jsonl, from which "Load older" pages
+}
+
+impl Default for RecentBoosts {
+    fn default() -> Self {
+        Self { keep: default_recent_boosts_keep(), max_age_hours: 0, log: true }
+    }
+}
+
+fn default_recent_boosts_keep() -> usize {
+    200
+}
+
+/// Podcast Index API credentials (https://api.podcastindex.org), for episode and remote item
+/// titles and artwork when boosts only carry guids
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PodcastIndex {
+    pub api_key: String,
+    pub api_secret: String,
+    pub feed_guid: Option<String>,  // The show's own podcast:guid; without it only guest feeds and songs are looked up
+    #[serde(default = "default_podcast_index_timeout")]
+    pub timeout_ms: u64,  // Give up on a lookup after this long
+}
+
+fn default_podcast_index_timeout() -> u64 {
+    1000
+}
+
+/// GUI keyboard shortcuts for operator actions, e.g. "Ctrl+P", "Shift+F2" or "Escape"; empty to disable one
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Shortcuts {
+    #[serde(default = "default_shortcut_pause_effects")]
+    pub pause_effects: String,  // Mute every output, or unmute them all when they're all muted
+    #[serde(default = "default_shortcut_fire_test")]
+    pub fire_test: String,  // Trigger a test boost of the amount in the Test section
+    #[serde(default = "default_shortcut_dismiss_dialogs")]
+    pub dismiss_dialogs: String,  // Close open dialogs as if cancelled, discarding unsaved settings
+    #[serde(default = "default_shortcut_next_tab")]
+    pub next_tab: String,  // Switch to the next [[shows]] tab
+    #[serde(default = "default_shortcut_replay_last_boost")]
+    pub replay_last_boost: String,  // Fire the newest boost's effects again without counting it
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        Self {
+            pause_effects: default_shortcut_pause_effects(),
+            fire_test: default_shortcut_fire_test(),
+            dismiss_dialogs: default_shortcut_dismiss_dialogs(),
+            next_tab: default_shortcut_next_tab(),
+            replay_last_boost: default_shortcut_replay_last_boost(),
+        }
+    }
+}
+
+fn default_shortcut_pause_effects() -> String {
+    "Ctrl+P".to_string()
+}
+
+fn default_shortcut_fire_test() -> String {
+    "Ctrl+T".to_string()
+}
+
+fn default_shortcut_dismiss_dialogs() -> String {
+    "Escape".to_string()
+}
+
+fn default_shortcut_next_tab() -> String {
+    "Ctrl+Tab".to_string()
+}
+
+fn default_shortcut_replay_last_boost() -> String {
+    "Ctrl+R".to_string()
+}
+
+/// At most `max` toggles sent to an output in any `per_ms`; toggles over the limit are skipped
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RateLimit {
+    pub max: u32,
+    pub per_ms: u64,
+}
+
+/// How an input is shown in the GUI, show recaps and the event stream
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SourceLabel {
+    pub color: Option<[u8; 3]>,
+    pub icon: Option<String>,  // Usually an emoji, shown before the source's name
+}
+
+/// End-of-show recap, saved to ./summaries when the show is finished and optionally posted
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Summary {
+    #[serde(default = "default_summary_top")]
+    pub top_boosters: usize,
+    pub discord_webhook: Option<String>,
+    pub nostr: Option<SummaryNostr>,
+}
+
+/// Push notifications to the hosts' phones for big boosts
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Notify {
+    #[serde(default)]
+    pub service: NotifyService,
+    #[serde(default = "default_notify_server")]
+    pub server: String,  // e.g. a self-hosted ntfy or the Gotify server's URL
+    pub topic: Option<String>,  // ntfy topic; Gotify doesn't use one
+    pub token: Option<String>,  // ntfy access token, or the Gotify application token
+    pub min_sats: i64,  // Only live boosts of at least this many sats
+    pub priority: Option<u8>,  // ntfy 1-5 or Gotify 0-10; the service's default when unset
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyService {
+    #[default]
+    Ntfy,
+    Gotify,
+}
+
+fn default_notify_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+impl Default for Summary {
+    fn default() -> Self {
+        Self { top_boosters: default_summary_top(), discord_webhook: None, nostr: None }
+    }
+}
+
+fn default_summary_top() -> usize {
+    5
+}
+
+/// Posts the recap as a note from the show's account
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SummaryNostr {
+    pub secret_key: String,
+    pub relay_addrs: Vec<String>,
+}
+
+/// Keeps posts that can go out late (show recaps to Discord and nostr) in ./outbox.json while
+/// their service is unreachable. Light cues are never queued: a late cue is worse than none.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Outbox {
+    #[serde(default = "default_outbox_retry")]
+    pub retry_secs: u64,  // How often queued posts are tried again
+    #[serde(default = "default_outbox_max_age")]
+    pub max_age_hours: u64,  // Drop a post that still hasn't gone out after this long
+}
+
+fn default_outbox_retry() -> u64 {
+    60
+}
+
+fn default_outbox_max_age() -> u64 {
+    72
+}
+
+/// Show audio read from a capture command, whose level drives WLED effect intensity and a DMX
+/// channel between boost effects
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Audio {
+    pub command: String,  // Writes raw signed 16-bit little-endian mono samples to stdout, e.g. "arecord" or "parec"
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_audio_sample_rate")]
+    pub sample_rate: u32,  // Must match what the command records at
+    #[serde(default = "default_audio_update")]
+    pub update_ms: u64,  // How often the level is measured and sent
+    #[serde(default = "default_audio_gain")]
+    pub gain: f32,  // Scales the measured level; raise it for quiet sources
+    #[serde(default = "default_audio_hold")]
+    pub hold_secs: u64,  // Leave the outputs to a boost effect for this long after it fires
+    #[serde(default)]
+    pub wled_intensity: bool,  // Set the effect intensity of the selected WLED segments
+    pub dmx_channel: Option<u16>,  // Channel in the default Art-Net/sACN universe, set in its current frame
+    #[serde(default)]
+    pub floor: u8,  // Value sent in silence; full volume sends 255
+}
+
+fn default_audio_sample_rate() -> u32 {
+    44100
+}
+
+fn default_audio_update() -> u64 {
+    50
+}
+
+fn default_audio_gain() -> f32 {
+    1.0
+}
+
+fn default_audio_hold() -> u64 {
+    5
+}
+
+/// Retry policy for effect triggers that fail to send
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Retry {
+    #[serde(default = "default_retry_max")]
+    pub max_retries: u32,
+    #[serde(default = "default_retry_delay")]
+    pub base_delay_ms: u64,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            max_retries: default_retry_max(),
+            base_delay_ms: default_retry_delay(),
+        }
+    }
+}
+
+fn default_retry_max() -> u32 {
+    3
+}
+
+fn default_retry_delay() -> u64 {
+    1000
+}
+
+fn default_health_interval() -> u64 {
+    30
+}
+
+fn default_health_timeout() -> u64 {
+    1000
+}
+
+fn default_shutdown_drain() -> u64 {
+    5
+}
+
+fn default_watchdog_interval() -> u64 {
+    5
+}
+
+fn default_watchdog_max_restarts() -> usize {
+    5
+}
+
+fn default_watchdog_window() -> u64 {
+    3600
+}
+
+fn default_true() -> bool {
+    true
+}
+
+pub fn load_config() -> Result<Config> {
+    let filename = "./config.toml";
+
+    let contents = fs::read_to_string(filename)
+        .context(format!("Failed to read config file: {}", filename))?;
+    let cfg: Config = toml::from_str(&contents)
+        .context("Failed to parse config file as TOML")?;
+
+    Ok(cfg)
+}
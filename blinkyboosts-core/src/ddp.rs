@@ -0,0 +1,56 @@
+use std::net::UdpSocket;
+use anyhow::{Context, Result};
+
+use crate::mock;
+use crate::sender_color::Rgb;
+
+const HEADER_LEN: usize = 10;
+const MAX_DATA_LEN: usize = 1440;  // 480 RGB pixels per packet
+const FLAGS_V1: u8 = 0x40;
+const FLAG_PUSH: u8 = 0x01;
+const TYPE_RGB8: u8 = 0x0B;
+const DEST_DISPLAY: u8 = 0x01;
+
+/// Realtime pixel output over DDP (Distributed Display Protocol, UDP port 4048), as accepted by WLED
+pub struct Ddp {
+    sock: UdpSocket,
+    to_addr: String,
+    sequence: u8,
+}
+
+impl Ddp {
+    pub fn new(host: &str) -> Result<Self> {
+        let sock = UdpSocket::bind("0.0.0.0:0").context("Failed to bind DDP socket")?;
+        let to_addr = if host.contains(':') { host.to_string() } else { format!("{}:4048", host) };
+        Ok(Self { sock, to_addr, sequence: 0 })
+    }
+
+    /// Send pixels starting at LED `start`, split across packets as needed.
+    /// The last packet carries the push flag so the display updates once.
+    pub fn send_pixels(&mut self, start: usize, pixels: &[Rgb]) -> Result<()> {
+        if mock::enabled("ddp") {
+            let hex: Vec<String> = pixels.iter().map(|p| crate::sender_color::hex(*p)).collect();
+            mock::record("ddp", self.to_addr.clone(), serde_json::json!({ "start": start, "pixels": hex }));
+            return Ok(());
+        }
+
+        let data: Vec<u8> = pixels.iter().flatten().copied().collect();
+        self.sequence = self.sequence % 15 + 1;
+
+        let chunks: Vec<&[u8]> = data.chunks(MAX_DATA_LEN).collect();
+        for (idx, chunk) in chunks.iter().enumerate() {
+            let offset = (start * 3 + idx * MAX_DATA_LEN) as u32;
+            let flags = if idx == chunks.len() - 1 { FLAGS_V1 | FLAG_PUSH } else { FLAGS_V1 };
+
+            let mut packet = Vec::with_capacity(HEADER_LEN + chunk.len());
+            packet.extend_from_slice(&[flags, self.sequence, TYPE_RGB8, DEST_DISPLAY]);
+            packet.extend_from_slice(&offset.to_be_bytes());
+            packet.extend_from_slice(&(chunk.len() as u16).to_be_bytes());
+            packet.extend_from_slice(chunk);
+
+            self.sock.send_to(&packet, &self.to_addr)
+                .context(format!("Failed to send DDP packet to {}", self.to_addr))?;
+        }
+        Ok(())
+    }
+}
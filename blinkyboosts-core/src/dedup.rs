@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use nostr_sdk::EventId;
+
+const CAPACITY: usize = 10_000;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);  // Changes written to disk at most this often
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seen {
+    New,
+    Duplicate,    // Already delivered during this run: drop it
+    PreviousRun,  // Handled before a restart: count it again, but don't re-trigger effects
+}
+
+#[derive(Debug)]
+struct Inner {
+    path: String,
+    order: VecDeque<(String, u64)>,  // Least recently seen first; an entry is stale once its ID is seen again
+    stamps: HashMap<String, u64>,  // Each kept ID's latest entry in `order`
+    next_stamp: u64,
+    previous: HashSet<String>,
+    current: HashSet<String>,
+    dirty: bool,  // Changed since last written
+}
+
+/// Bounded set of processed nostr event IDs, persisted to disk so redeliveries after a
+/// reconnect are dropped and events handled before a restart don't fire effects again. When
+/// full, the least recently seen ID makes room.
+#[derive(Clone, Debug)]
+pub struct SeenEvents {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// Every store opened, by source, so listener restarts share them and shutdown can flush them
+static STORES: Mutex<Option<HashMap<String, SeenEvents>>> = Mutex::new(None);
+static WRITING: Mutex<()> = Mutex::new(());  // Held from snapshot to write, so an older snapshot never lands last
+
+impl SeenEvents {
+    /// Seen-event store for a source, e.g. "zaps" -> ./seen_events_zaps.json. Shared per source
+    /// for the life of the process so listener restarts keep their history. Written to disk in
+    /// the background every few seconds, and by `flush_all` on shutdown.
+    pub fn open(source: &str) -> Self {
+        let source = source.to_lowercase();
+        Self::shared(source.clone(), || format!("./seen_events_{}.json", source))
+    }
+
+    /// Seen store for a source that lasts only this run, e.g. for NWC payments, whose history
+    /// is loaded again at every start. Shared per source like `open`.
+    pub fn for_run(source: &str) -> Self {
+        Self::shared(format!("{}/run", source.to_lowercase()), String::new)
+    }
+
+    fn shared(key: String, path: impl FnOnce() -> String) -> Self {
+        STORES.lock().unwrap()
+            .get_or_insert_with(HashMap::new)
+            .entry(key)
+            .or_insert_with(|| {
+                let store = Self::load(path());
+                store.flush_periodically();
+                store
+            })
+            .clone()
+    }
+
+    /// Seen-event store that isn't persisted, e.g. for the test harness
+    pub fn in_memory() -> Self {
+        Self::load(String::new())
+    }
+
+    fn load(path: String) -> Self {
+        let order: VecDeque<String> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        let previous = order.iter().cloned().collect();
+        let mut inner = Inner {
+            path, order: VecDeque::new(), stamps: HashMap::new(), next_stamp: 0, previous, current: HashSet::new(), dirty: false,
+        };
+        for id in order {
+            inner.touch(id);
+        }
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Record an event as delivered and report whether it had been seen before
+    pub fn check(&self, id: &EventId) -> Seen {
+        self.check_key(&id.to_hex())
+    }
+
+    /// `check` for things identified other than by a nostr event, e.g. payment hashes
+    pub fn check_key(&self, key: &str) -> Seen {
+        let mut inner = self.inner.lock().unwrap();
+
+        let seen = if inner.current.contains(key) {
+            Seen::Duplicate
+        } else if inner.previous.contains(key) {
+            Seen::PreviousRun
+        } else {
+            Seen::New
+        };
+        inner.current.insert(key.to_string());
+        inner.touch(key.to_string());
+        inner.dirty = true;
+        seen
+    }
+
+    /// Write the store if it changed since it was last written
+    fn flush(&self) {
+        let _writing = WRITING.lock().unwrap();
+        let (path, json) = {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.path.is_empty() || !inner.dirty {
+                return;
+            }
+            inner.dirty = false;
+            (inner.path.clone(), serde_json::to_string(&inner.ids()))
+        };
+        match json {
+            Ok(json) => {
+                if let Err(e) = fs::write(&path, json) {
+                    eprintln!("Failed to persist seen events to {}: {}", path, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize seen events: {}", e),
+        }
+    }
+
+    /// Flush every `FLUSH_INTERVAL` off the async threads, when opened inside the runtime
+    fn flush_periodically(&self) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else { return };
+        if self.inner.lock().unwrap().path.is_empty() {
+            return;
+        }
+        let store = self.clone();
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                let store = store.clone();
+                let _ = tokio::task::spawn_blocking(move || store.flush()).await;
+            }
+        });
+    }
+}
+
+impl Inner {
+    /// Make `id` the most recently seen, evicting the least recently seen when full
+    fn touch(&mut self, id: String) {
+        self.next_stamp += 1;
+        self.stamps.insert(id.clone(), self.next_stamp);
+        self.order.push_back((id, self.next_stamp));
+
+        while self.stamps.len() > CAPACITY {
+            let Some((oldest, stamp)) = self.order.pop_front() else { break };
+            if self.stamps.get(&oldest) == Some(&stamp) {
+                self.stamps.remove(&oldest);
+                self.previous.remove(&oldest);
+                self.current.remove(&oldest);
+            }
+        }
+        if self.order.len() > CAPACITY * 2 {
+            let stamps = &self.stamps;
+            self.order.retain(|(id, stamp)| stamps.get(id) == Some(stamp));
+        }
+    }
+
+    /// Kept IDs, least recently seen first
+    fn ids(&self) -> Vec<&str> {
+        self.order.iter()
+            .filter(|(id, stamp)| self.stamps.get(id) == Some(stamp))
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+}
+
+/// Write every store with unsaved changes, e.g. on shutdown
+pub fn flush_all() {
+    let stores: Vec<SeenEvents> = STORES.lock().unwrap().iter().flat_map(|stores| stores.values().cloned()).collect();
+    for store in stores {
+        store.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_reports_repeats() {
+        let seen = SeenEvents::in_memory();
+        assert_eq!(seen.check_key("a"), Seen::New);
+        assert_eq!(seen.check_key("a"), Seen::Duplicate);
+        assert_eq!(seen.check_key("b"), Seen::New);
+    }
+
+    #[test]
+    fn least_recently_seen_is_evicted() {
+        let seen = SeenEvents::in_memory();
+        for n in 0..CAPACITY {
+            seen.check_key(&n.to_string());
+        }
+        seen.check_key("0");  // Seen again, so "1" is now the oldest
+        seen.check_key("new");
+
+        let inner = seen.inner.lock().unwrap();
+        let ids = inner.ids();
+        assert_eq!(ids.len(), CAPACITY);
+        assert_eq!(ids[0], "2");
+        assert_eq!(&ids[ids.len() - 2..], ["0", "new"]);
+        assert!(!inner.current.contains("1"));
+        drop(inner);
+        assert_eq!(seen.check_key("1"), Seen::New);
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveTime};
+
+use crate::config::{self, Config};
+use crate::wled;
+
+static MASTER: AtomicU8 = AtomicU8::new(100);
+static CONSOLE: AtomicU8 = AtomicU8::new(100);
+static SCHEDULE: Mutex<Vec<Period>> = Mutex::new(Vec::new());
+
+struct Period {
+    from: NaiveTime,
+    to: NaiveTime,
+    level: u8,
+}
+
+impl Period {
+    fn parse(period: &config::DimmerPeriod) -> Result<Self> {
+        let parse = |time: &str| NaiveTime::parse_from_str(time, "%H:%M")
+            .context(format!("Invalid time \"{}\" (expected HH:MM)", time));
+        Ok(Self { from: parse(&period.from)?, to: parse(&period.to)?, level: period.level.min(100) })
+    }
+
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.from <= self.to {
+            time >= self.from && time < self.to
+        } else {
+            time >= self.from || time < self.to
+        }
+    }
+}
+
+/// Check the dimmer schedule without applying it
+pub fn validate(cfg: &config::Dimmer) -> Result<()> {
+    cfg.schedule.iter().try_for_each(|period| Period::parse(period).map(|_| ()))
+}
+
+/// Load the master level and schedule from config
+pub fn configure(config: &Config) {
+    let Some(cfg) = &config.dimmer else { return };
+    set_master(cfg.master);
+
+    let schedule = cfg.schedule.iter()
+        .filter_map(|period| Period::parse(period)
+            .inspect_err(|e| eprintln!("Ignoring dimmer period {}-{}: {:#}", period.from, period.to, e))
+            .ok())
+        .collect();
+    *SCHEDULE.lock().unwrap() = schedule;
+}
+
+pub fn master() -> u8 {
+    MASTER.load(Ordering::Relaxed)
+}
+
+pub fn set_master(percent: u8) {
+    MASTER.store(percent.min(100), Ordering::Relaxed);
+}
+
+/// Level set from the venue console's intensity channel (see `artnet_input`)
+pub fn set_console(percent: u8) {
+    CONSOLE.store(percent.min(100), Ordering::Relaxed);
+}
+
+/// Effective intensity in percent: the master scaled by any scheduled period active now
+/// and by the console's intensity channel
+pub fn level() -> u8 {
+    let now = Local::now().time();
+    let scheduled = SCHEDULE.lock().unwrap().iter()
+        .find(|period| period.contains(now))
+        .map_or(100, |period| period.level);
+    let console = CONSOLE.load(Ordering::Relaxed);
+    (master() as u32 * scheduled as u32 * console as u32 / 10_000) as u8
+}
+
+pub fn scale(value: u8) -> u8 {
+    (value as u16 * level() as u16 / 100) as u8
+}
+
+/// Push WLED brightness whenever the effective level changes (GUI slider or schedule)
+pub async fn run(config: Config) {
+    let Some(wled_cfg) = config.wled else { return };
+    let mut applied = 100;
+
+    loop {
+        let current = level();
+        if current != applied {
+            let brightness = wled_cfg.brightness * current as u64 / 100;
+            println!("Master dimmer at {}%, WLED brightness {}", current, brightness);
+            match wled::WLed::set_brightness(&wled_cfg, brightness).await {
+                Ok(()) => applied = current,
+                Err(e) => eprintln!("Failed to apply master dimmer to WLED: {:#}", e),
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::Serialize;
+
+const CAPACITY: usize = 1000;
+
+/// One attempt at firing a toggle
+#[derive(Serialize, Debug, Clone)]
+pub struct Execution {
+    pub at: i64,                   // Unix seconds
+    pub toggle: Option<String>,    // The toggle's configured name, if it has one
+    pub output: String,
+    pub parameters: String,        // As the toggle is described elsewhere, e.g. "WLED: Rainbow"
+    pub duration_ms: u64,          // Time the output took to send
+    pub latency_ms: Option<u64>,   // Boost received -> dispatch started; None for retries
+    pub attempt: u32,              // 0 for the first try, then the retry number
+    pub error: Option<String>,     // None if it was sent
+    pub acknowledged: Option<bool>,  // Whether the target replied, for OSC with ack_timeout_ms set
+}
+
+impl Execution {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+static LOG: Mutex<VecDeque<Execution>> = Mutex::new(VecDeque::new());
+
+pub fn record(execution: Execution) {
+    let mut log = LOG.lock().unwrap();
+    if log.len() == CAPACITY {
+        log.pop_front();
+    }
+    log.push_back(execution);
+}
+
+/// Executions oldest first, up to the last 1000
+pub fn recent() -> Vec<Execution> {
+    LOG.lock().unwrap().iter().cloned().collect()
+}
+
+pub fn millis(d: Duration) -> u64 {
+    d.as_millis() as u64
+}
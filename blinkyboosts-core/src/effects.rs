@@ -0,0 +1,697 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use anyhow::{Context, Result};
+
+use crate::config::{self, Config};
+use crate::messages::Event;
+use crate::metrics::{self, TriggeredEffect};
+use crate::bus::Bus;
+use crate::plugins::Plugins;
+use crate::sender_color::{self, Rgb};
+use crate::{amount_patterns, amounts, artnet, artnet_input, audio, capture, clock, dimmer, effect_log, emoji, fixtures, hold, i18n, idle, idle_pattern, mock, moderation, mute, osc, podcast_index, rate_limit, recovery, sacn, sanitize, sat_tracker, scenes, sequencer, shutdown, source_labels, streaks, tags, templates, toggle_stats, wled, wled_playlist};
+
+/// A toggle that failed to fire and is waiting to be retried
+pub struct RetryJob {
+    toggle: config::Toggle,
+    index: Option<usize>,  // Position in the toggle list, for its stats
+    color: Option<Rgb>,
+    vars: templates::Vars,  // For templated text, e.g. OSC string args
+    attempt: u32,
+    delay: Duration,
+    saved: Option<u64>,  // Recovery id, see `recovery::remember`
+}
+
+/// Fires toggles for boosts; failed triggers are handed to a background retry queue
+#[derive(Clone)]
+pub struct Effects {
+    config: Config,
+    retry_tx: mpsc::UnboundedSender<RetryJob>,
+    plugins: Plugins,
+    bus: Bus,
+}
+
+pub async fn setup_effects(config: config::Config) -> Result<()> {
+    let Some(cfg) = config.wled else { return Ok(()) };
+    if !cfg.setup { return Ok(()) };
+    apply_wled_setup(&cfg).await
+}
+
+/// Create the `[wled]` presets and playlists on the controller
+pub async fn apply_wled_setup(cfg: &config::WLed) -> Result<()> {
+    let mut wled = wled::WLed::new(cfg)?;
+    wled.load().await.context("Unable to load from WLED")?;
+
+    if let Some(presets) = &cfg.presets {
+        for (idx, preset) in presets.iter().enumerate() {
+            wled.set_preset(idx, cfg, preset).await?;
+        }
+    }
+
+    if let Some(playlists) = &cfg.playlists {
+        for (idx, playlist) in playlists.iter().enumerate() {
+            wled.set_playlist(idx, cfg, playlist).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The outputs a toggle sends to: `output` then any `outputs`
+pub fn outputs_of(toggle: &config::Toggle) -> Vec<String> {
+    std::iter::once(&toggle.output).chain(&toggle.outputs)
+        .filter(|output| !output.is_empty())
+        .map(|output| output.to_lowercase())
+        .collect()
+}
+
+/// A toggle by its configured name, or by its position in the toggle list
+pub fn find_toggle<'a>(config: &'a Config, name: &str) -> Option<&'a config::Toggle> {
+    let toggles = config.toggles.as_deref().unwrap_or_default();
+    toggles.iter()
+        .find(|t| t.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+        .or_else(|| name.parse::<usize>().ok().and_then(|idx| toggles.get(idx)))
+}
+
+/// A copy of the toggle for each of its outputs, sending only there
+pub fn fan_out(toggle: &config::Toggle) -> Vec<config::Toggle> {
+    outputs_of(toggle).into_iter()
+        .map(|output| config::Toggle { output, outputs: Vec::new(), ..toggle.clone() })
+        .collect()
+}
+
+/// How long to hold each branch of a fanned out toggle back so they all show at once: the
+/// slowest output's `[latency_ms]` less the branch's own
+pub fn sync_offsets(config: &Config, branches: &[config::Toggle]) -> Vec<Duration> {
+    let latency = |output: &str| config.latency_ms.as_ref()
+        .and_then(|latencies| latencies.iter().find(|(name, _)| name.eq_ignore_ascii_case(output)))
+        .map_or(0, |(_, ms)| *ms);
+    let slowest = branches.iter().map(|branch| latency(&branch.output)).max().unwrap_or_default();
+    branches.iter().map(|branch| clock::scaled(Duration::from_millis(slowest - latency(&branch.output)))).collect()
+}
+
+fn describe_output(toggle: &config::Toggle) -> String {
+    match toggle.output.to_lowercase().as_str() {
+        "osc" => toggle.osc.as_ref().map_or("OSC".to_string(), |osc| {
+            use crate::config::OscArgValue;
+            let value_str = match &osc.arg_value {
+                OscArgValue::String(s) => format!("\"{}\"", s),
+                OscArgValue::Int(i) => i.to_string(),
+                OscArgValue::Float(f) => f.to_string(),
+            };
+            format!("OSC {}: {}", osc.path, value_str)
+        }),
+        "artnet" => toggle.artnet.as_ref()
+            .map_or("Art-Net".to_string(), |a| format!("Art-Net {}", fixtures::describe_channels(&artnet::ArtNet::toggle_values(a, None)))),
+        "sacn" => toggle.sacn.as_ref()
+            .map_or("sACN".to_string(), |s| format!("sACN {}", fixtures::describe_channels(&sacn::Sacn::toggle_values(s, None)))),
+        "wled" => toggle.wled.as_ref()
+            .map_or("WLED".to_string(), |w| format!("WLED: {}", w.preset)),
+        "plugin" => toggle.plugin.as_ref()
+            .map_or("Plugin".to_string(), |p| format!("Plugin: {}", p.name)),
+        "fixture" => toggle.fixture.as_ref()
+            .map_or("Fixture".to_string(), fixtures::describe),
+        "scene" => toggle.scene.as_ref()
+            .map_or("Scene".to_string(), |s| format!("Scene: {}", s)),
+        _ => toggle.output.clone()
+    }
+}
+
+pub fn format_toggle_description(toggle: &config::Toggle) -> String {
+    let description = fan_out(toggle).iter().map(describe_output).collect::<Vec<_>>().join(" + ");
+    let description = match toggle.repeat {
+        0 | 1 => description,
+        repeat => format!("{} x{} every {}ms", description, repeat, toggle.repeat_interval_ms),
+    };
+    match toggle.delay_secs {
+        0 => description,
+        delay => format!("{} after {}s", description, delay),
+    }
+}
+
+/// Whether a toggle fires for boosts from `source`: any source unless it lists some
+fn for_source(toggle: &config::Toggle, source: &str) -> bool {
+    toggle.sources.is_empty() || toggle.sources.iter().any(|s| s.eq_ignore_ascii_case(source))
+}
+
+/// Whether a toggle fires for a boost's remote item: any boost unless it lists some
+fn for_remote(toggle: &config::Toggle, vars: &templates::Vars) -> bool {
+    toggle.remote_items.is_empty()
+        || vars.remote.as_ref().is_some_and(|remote| toggle.remote_items.iter().any(|item| remote.matches(item)))
+}
+
+/// Whether a matched toggle fires this time: always unless it has a `chance`
+fn by_chance(toggle: &config::Toggle) -> bool {
+    let Some(chance) = toggle.chance else { return true };
+    if rand::random::<f64>() < chance {
+        true
+    } else {
+        println!("Toggle skipped: not picked at {:.0}% chance", chance * 100.0);
+        false
+    }
+}
+
+/// The default toggles for a boost. Defaults listing its source or remote item replace the
+/// catch-all ones, so each platform, guest feed or song can have an effect of its own.
+/// Whether a matched toggle fires for this boost: it's for the boost's source and remote item,
+/// the amount fits its patterns and it wins its `chance`
+fn passes_gates(toggle: &config::Toggle, vars: &templates::Vars) -> bool {
+    if !for_source(toggle, &vars.source) {
+        println!("Toggle skipped: only for {}", toggle.sources.join(", "));
+        false
+    } else if !for_remote(toggle, vars) {
+        println!("Toggle skipped: only for remote items {}", toggle.remote_items.join(", "));
+        false
+    } else {
+        amount_patterns::allows(toggle, vars.sats) && by_chance(toggle)
+    }
+}
+
+fn default_toggles<'a>(toggles: &'a [config::Toggle], vars: &templates::Vars) -> Vec<&'a config::Toggle> {
+    let defaults = toggles.iter().filter(|t| t.is_default && tags::allows(t));
+    let own: Vec<_> = defaults.clone()
+        .filter(|t| !(t.sources.is_empty() && t.remote_items.is_empty()) && for_source(t, &vars.source) && for_remote(t, vars))
+        .collect();
+    if own.is_empty() {
+        defaults.filter(|t| t.sources.is_empty() && t.remote_items.is_empty()).collect()
+    } else {
+        own
+    }
+}
+
+/// Why a toggle didn't reach its output
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Skipped {
+    Muted,
+    Gated(u16),  // Console channel
+    RateLimited,
+}
+
+impl Skipped {
+    fn describe(&self) -> String {
+        match self {
+            Self::Muted => "muted".to_string(),
+            Self::Gated(channel) => format!("console ch{} closed", channel),
+            Self::RateLimited => "rate limited".to_string(),
+        }
+    }
+}
+
+async fn trigger_single_toggle(config: &config::Config, plugins: &Plugins, toggle: &config::Toggle, color: Option<Rgb>, vars: &templates::Vars) -> Result<Option<Skipped>> {
+    if mute::is_muted(&toggle.output) {
+        println!("Skipping {}: output muted", format_toggle_description(toggle));
+        return Ok(Some(Skipped::Muted));
+    }
+    if let Some(channel) = artnet_input::gated_by(toggle) {
+        println!("Skipping {}: console channel {} is closed", format_toggle_description(toggle), channel);
+        return Ok(Some(Skipped::Gated(channel)));
+    }
+    if !rate_limit::allow(&toggle.output) {
+        println!("Skipping {}: {} rate limit reached", format_toggle_description(toggle), toggle.output);
+        return Ok(Some(Skipped::RateLimited));
+    }
+
+    match toggle.output.to_lowercase().as_str() {
+        "osc" => {
+            let osc_cfg = config.osc.as_ref().context("OSC not configured")?;
+            osc::Osc::from_config(osc_cfg)?.trigger_toggle(toggle, vars).await?;
+        },
+        "artnet" => artnet::ArtNet::trigger_toggle(config, toggle, color)?,
+        "sacn" => sacn::Sacn::trigger_toggle(config, toggle, color)?,
+        "wled" => {
+            let cfg = config.wled.as_ref().context("WLED not configured")?;
+            wled::WLed::trigger_toggle(toggle, cfg, color).await?;
+            if let Some(wled_toggle) = &toggle.wled {
+                wled_playlist::started(cfg, &wled_toggle.preset);
+            }
+        },
+        "plugin" => plugins.trigger(toggle).await?,
+        "fixture" => fixtures::trigger_toggle(config, toggle, color)?,
+        "scene" => scenes::apply(config, toggle.scene.as_deref().context("Scene toggle missing 'scene' name")?).await?,
+        _ => eprintln!("Unknown toggle output type: {}", toggle.output),
+    }
+    Ok(None)
+}
+
+fn log_execution(config: &Config, toggle: &config::Toggle, took: Duration, latency: Option<Duration>, attempt: u32, result: &Result<Option<Skipped>>) {
+    let sent = !matches!(result, Ok(Some(_)));
+    let awaits_ack = toggle.output.eq_ignore_ascii_case("osc")
+        && config.osc.as_ref().is_some_and(|osc| osc.ack_timeout_ms.is_some()) && !mock::enabled("osc");
+    effect_log::record(effect_log::Execution {
+        at: chrono::Utc::now().timestamp(),
+        toggle: toggle.name.clone(),
+        output: toggle.output.to_lowercase(),
+        parameters: match result {
+            Ok(Some(skipped)) => format!("{} ({})", format_toggle_description(toggle), skipped.describe()),
+            _ => format_toggle_description(toggle),
+        },
+        duration_ms: effect_log::millis(took),
+        latency_ms: latency.map(effect_log::millis),
+        attempt,
+        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+        acknowledged: (sent && awaits_ack).then_some(result.is_ok()),
+    });
+}
+
+impl Effects {
+    pub fn new(config: Config) -> (Self, mpsc::UnboundedReceiver<RetryJob>) {
+        let (retry_tx, retry_rx) = mpsc::unbounded_channel();
+        let plugins = Plugins::new(config.plugins.clone().unwrap_or_default());
+        amounts::configure(&config);
+        capture::configure(&config);
+        clock::configure(&config);
+        dimmer::configure(&config);
+        i18n::configure(&config);
+        idle_pattern::configure(&config);
+        mock::configure(&config);
+        podcast_index::configure(&config);
+        sanitize::configure(&config);
+        source_labels::configure(&config);
+        moderation::configure(&config);
+        rate_limit::configure(&config);
+        streaks::configure(&config);
+        tags::configure(&config);
+        artnet_input::configure(&config);
+        (Self { config, retry_tx, plugins, bus: Bus::new() }, retry_rx)
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn plugins(&self) -> &Plugins {
+        &self.plugins
+    }
+
+    /// Where this pipeline announces each boost it handles (see `bus::attach`)
+    pub fn bus(&self) -> &Bus {
+        &self.bus
+    }
+
+    /// Fire a toggle and time how long it waited after receipt and how long the output took to send.
+    /// The boost's sender picks the color for toggles with `sender_color` set, otherwise the toggle's
+    /// own `color` is used. On failure the toggle is queued for retry. `toggle` sends to a single
+    /// output, and `index` is the position of the configured toggle it came from.
+    async fn dispatch_toggle(&self, toggle: &config::Toggle, index: Option<usize>, vars: &templates::Vars, received: Instant) -> Result<TriggeredEffect> {
+        let queued = received.elapsed();
+        let started = Instant::now();
+        let color = vars.identity().filter(|_| toggle.sender_color).map(sender_color::derive).or(toggle.color);
+
+        let result = trigger_single_toggle(&self.config, &self.plugins, toggle, color, vars).await;
+        log_execution(&self.config, toggle, started.elapsed(), Some(queued), 0, &result);
+        let skipped = match result {
+            Ok(skipped) => skipped,
+            Err(e) => {
+                self.queue_retry(toggle, index, color, vars, 1);
+                return Err(e);
+            }
+        };
+        if let Some(idx) = index {
+            toggle_stats::fired(idx);
+        }
+        if toggle.repeat > 1 {
+            tokio::spawn(self.clone().repeat_toggle(toggle.clone(), color, vars.clone()));
+        }
+        if skipped.is_none() {
+            hold::fired(&self.config, toggle, vars.sats);
+        }
+        idle::schedule(&self.config);
+        audio::effect_fired();
+
+        let description = match color {
+            Some(color) => format!("{} ({})", format_toggle_description(toggle), sender_color::hex(color)),
+            None => format_toggle_description(toggle),
+        };
+        let description = match skipped {
+            Some(skipped) => format!("{} ({})", description, skipped.describe()),
+            None => description,
+        };
+
+        let effect = TriggeredEffect {
+            description,
+            output: toggle.output.to_lowercase(),
+            queued,
+            sent: started.elapsed(),
+            skipped: skipped.is_some(),
+        };
+
+        println!("Effect sent: {} (queued {}, output {})",
+            effect.description, metrics::format_duration(effect.queued), metrics::format_duration(effect.sent));
+
+        Ok(effect)
+    }
+
+    /// Fire a toggle, or hand it to the sequencer when it has a `delay_secs`, is a WLED effect
+    /// waiting for a playlist to end (see `[wled] while_playing`) or has a sync `offset` to wait
+    /// out. A held back toggle is reported as pending straight away and fires (or is retried) on
+    /// its own once its time comes.
+    async fn dispatch_or_schedule(&self, toggle: &config::Toggle, index: Option<usize>, vars: &templates::Vars, received: Instant, offset: Duration) -> Result<TriggeredEffect> {
+        let (delay, note) = if toggle.delay_secs > 0 {
+            (clock::scaled(Duration::from_secs(toggle.delay_secs)), "pending")
+        } else {
+            match self.playlist_slot(toggle) {
+                wled_playlist::Slot::Now if offset.is_zero() => return self.dispatch_toggle(toggle, index, vars, received).await,
+                wled_playlist::Slot::Now => return Ok(self.hold_back(toggle, index, vars, received, offset, "in sync")),
+                wled_playlist::Slot::After(wait) => (wait, "after playlist"),
+                wled_playlist::Slot::Coalesced => {
+                    println!("Skipping {}: already waiting for the playlist to end", format_toggle_description(toggle));
+                    return Ok(TriggeredEffect {
+                        description: format!("{} (coalesced)", format_toggle_description(toggle)),
+                        output: toggle.output.to_lowercase(),
+                        queued: received.elapsed(),
+                        sent: Duration::ZERO,
+                        skipped: false,
+                    });
+                }
+            }
+        };
+
+        Ok(self.hold_back(toggle, index, vars, received, delay + offset, note))
+    }
+
+    /// Schedule a toggle to fire after `delay`, reporting it with `note` for now
+    fn hold_back(&self, toggle: &config::Toggle, index: Option<usize>, vars: &templates::Vars, received: Instant, delay: Duration, note: &str) -> TriggeredEffect {
+        let description = self.schedule_toggle(toggle, index, vars, delay);
+        TriggeredEffect {
+            description: format!("{} ({})", description, note),
+            output: toggle.output.to_lowercase(),
+            queued: received.elapsed(),
+            sent: Duration::ZERO,
+            skipped: false,
+        }
+    }
+
+    /// When a WLED toggle may run given the playlists started for earlier boosts; other outputs
+    /// always run now
+    fn playlist_slot(&self, toggle: &config::Toggle) -> wled_playlist::Slot {
+        match (&self.config.wled, &toggle.wled) {
+            (Some(cfg), Some(wled_toggle)) if toggle.output.eq_ignore_ascii_case("wled") =>
+                wled_playlist::claim(cfg, &wled_toggle.preset),
+            _ => wled_playlist::Slot::Now,
+        }
+    }
+
+    /// Hand a single-output toggle to the sequencer to fire after `delay`, keeping it in the
+    /// recovery file until then. Returns its description.
+    fn schedule_toggle(&self, toggle: &config::Toggle, index: Option<usize>, vars: &templates::Vars, delay: Duration) -> String {
+        let description = format_toggle_description(toggle);
+        let saved = recovery::remember(recovery::Pending {
+            toggle: toggle.clone(),
+            color: None,
+            vars: vars.clone(),
+            due: recovery::due_in(delay),
+            attempt: 0,
+        });
+        let (effects, delayed, delayed_vars) = (self.clone(), toggle.clone(), vars.clone());
+        sequencer::schedule(delay, description.clone(), vars.sats, vars.sender.clone(), saved, async move {
+            if shutdown::is_shutting_down() {
+                return;
+            }
+            if delayed.output.eq_ignore_ascii_case("wled") {
+                wled_playlist::settled().await;
+            }
+            let _in_flight = shutdown::track();
+            if let Err(e) = effects.dispatch_toggle(&delayed, index, &delayed_vars, Instant::now()).await {
+                eprintln!("Failed to trigger delayed toggle: {:#}", e);
+            }
+        });
+        println!("Scheduled {}", description);
+        description
+    }
+
+    /// Fire a configured toggle at each of its outputs, holding back delayed ones when `schedule`
+    /// is set and faster outputs until slower ones catch up (see `[latency_ms]`). One failing
+    /// output doesn't stop the others; it's an error only when none went out.
+    async fn dispatch_outputs(&self, toggle: &config::Toggle, vars: &templates::Vars, received: Instant, schedule: bool) -> Result<Vec<TriggeredEffect>> {
+        let index = toggle_stats::index_of(&self.config, toggle);
+        let branches = fan_out(toggle);
+        let offsets = sync_offsets(&self.config, &branches);
+        let mut effects = Vec::new();
+        let mut failure = None;
+        for (branch, offset) in branches.iter().zip(offsets) {
+            let result = match (schedule, offset.is_zero()) {
+                (true, _) => self.dispatch_or_schedule(branch, index, vars, received, offset).await,
+                (false, true) => self.dispatch_toggle(branch, index, vars, received).await,
+                (false, false) => Ok(self.hold_back(branch, index, vars, received, offset, "in sync")),
+            };
+            match result {
+                Ok(effect) => effects.push(effect),
+                Err(e) => {
+                    if branches.len() > 1 {
+                        eprintln!("Failed to trigger {} output: {:#}", branch.output, e);
+                    }
+                    failure.get_or_insert(e);
+                }
+            }
+        }
+        match failure {
+            Some(e) if effects.is_empty() => Err(e),
+            _ => Ok(effects),
+        }
+    }
+
+    /// Fire the rest of a toggle's `repeat` pulses, `repeat_interval_ms` apart. DMX channels drop
+    /// to 0 halfway between pulses, and after the last, so each pulse shows as a flash.
+    async fn repeat_toggle(self, toggle: config::Toggle, color: Option<Rgb>, vars: templates::Vars) {
+        let _in_flight = shutdown::track();
+        let interval = Duration::from_millis(toggle.repeat_interval_ms);
+        let description = format_toggle_description(&toggle);
+
+        for pulse in 1..=toggle.repeat {
+            tokio::time::sleep(interval / 2).await;
+            if shutdown::is_shutting_down() {
+                return;
+            }
+            if !mute::is_muted(&toggle.output) && artnet_input::gated_by(&toggle).is_none() {
+                if let Err(e) = fixtures::release_toggle(&self.config, &toggle) {
+                    eprintln!("Failed to release {} between repeats: {:#}", description, e);
+                }
+            }
+            if pulse == toggle.repeat {
+                return;
+            }
+
+            tokio::time::sleep(interval - interval / 2).await;
+            if shutdown::is_shutting_down() {
+                return;
+            }
+            let started = Instant::now();
+            let result = trigger_single_toggle(&self.config, &self.plugins, &toggle, color, &vars).await;
+            log_execution(&self.config, &toggle, started.elapsed(), None, 0, &result);
+            if let Err(e) = result {
+                eprintln!("Repeat {} of {} failed for {}: {:#}", pulse + 1, toggle.repeat, description, e);
+                return;
+            }
+        }
+    }
+
+    async fn trigger_toggles(
+        &self,
+        vars: &templates::Vars,
+        tracker: Option<Arc<Mutex<sat_tracker::SatTracker>>>,
+        received: Instant
+    ) -> Result<Vec<TriggeredEffect>> {
+        let Some(toggles) = &self.config.toggles else { return Ok(Vec::new()) };
+        let sats = vars.sats;
+
+        let mut triggered_effects = Vec::new();
+        let mut threshold_triggered = false;
+
+        // Check threshold-based toggles
+        let threshold_toggles: Vec<_> = toggles.iter()
+            .filter(|t| !t.is_default && t.use_total && t.threshold > 0 && tags::allows(t))
+            .collect();
+
+        if let (false, Some(tracker_ref)) = (threshold_toggles.is_empty(), tracker.as_ref()) {
+            let all_thresholds: Vec<i64> = threshold_toggles.iter().map(|t| t.threshold).collect();
+            let max_threshold = *all_thresholds.iter().max().unwrap();
+
+            let mut tracker_guard = tracker_ref.lock().await;
+            let thresholds_to_trigger = tracker_guard.get_thresholds_to_trigger(vars.msats(), &all_thresholds, max_threshold);
+            drop(tracker_guard);
+
+            if let Some(&max_crossed) = thresholds_to_trigger.iter().max() {
+                if thresholds_to_trigger.len() > 1 {
+                    println!("Multiple thresholds crossed ({:?}), applying only maximum: {} sats", thresholds_to_trigger, max_crossed);
+                } else {
+                    println!("Triggering threshold: {} sats", max_crossed);
+                }
+
+                if let Some(toggle) = threshold_toggles.iter().find(|t| t.threshold == max_crossed) {
+                    if passes_gates(toggle, vars) {
+                        if let Some(idx) = toggle_stats::index_of(&self.config, toggle) {
+                            toggle_stats::matched(idx, sats);
+                        }
+                        match self.dispatch_outputs(toggle, vars, received, true).await {
+                            Ok(effects) => {
+                                // A muted or rate limited threshold leaves the boost to the other toggles
+                                threshold_triggered = effects.iter().any(|effect| !effect.skipped);
+                                triggered_effects.extend(effects);
+                            }
+                            Err(e) => eprintln!("Failed to trigger toggle at {} sats: {:#}", max_crossed, e),
+                        }
+                    }
+                }
+            }
+        }
+
+        // Trigger the toggles picked by emoji in the message, or else the default toggles, if no
+        // threshold was triggered
+        let picked: Vec<_> = emoji::picked(&self.config, vars.message.as_deref()).into_iter()
+            .filter(|t| tags::allows(t))
+            .collect();
+        if !threshold_triggered && !picked.is_empty() {
+            for toggle in picked.into_iter().filter(|toggle| passes_gates(toggle, vars)) {
+                println!("Emoji toggle triggered for {} sats - {}", sats, toggle.name.as_deref().unwrap_or_default());
+                if let Some(idx) = toggle_stats::index_of(&self.config, toggle) {
+                    toggle_stats::matched(idx, sats);
+                }
+                match self.dispatch_outputs(toggle, vars, received, true).await {
+                    Ok(effects) => triggered_effects.extend(effects),
+                    Err(e) => eprintln!("Failed to trigger emoji toggle: {:#}", e),
+                }
+            }
+        } else if !threshold_triggered {
+            for toggle in default_toggles(toggles, vars) {
+                if passes_gates(toggle, vars) {
+                    println!("Default toggle triggered for {} sats - {} output", sats, outputs_of(toggle).join(", "));
+                    if let Some(idx) = toggle_stats::index_of(&self.config, toggle) {
+                        toggle_stats::matched(idx, sats);
+                    }
+                    match self.dispatch_outputs(toggle, vars, received, true).await {
+                        Ok(effects) => triggered_effects.extend(effects),
+                        Err(e) => eprintln!("Failed to trigger default toggle: {:#}", e),
+                    }
+                }
+            }
+        }
+
+        Ok(triggered_effects)
+    }
+
+    /// Fire the toggles matching a boost. The sender's pubkey (or name when there isn't one) is
+    /// used to derive the color for toggles with `sender_color` set, and `vars` fill in templated text.
+    pub async fn trigger_effects(
+        &self,
+        vars: &templates::Vars,
+        tracker: Option<Arc<Mutex<sat_tracker::SatTracker>>>,
+        received: Instant
+    ) -> Result<Vec<TriggeredEffect>> {
+        println!("Triggering effects for {} sats", vars.sats);
+        self.trigger_toggles(vars, tracker, received).await
+            .inspect_err(|e| eprintln!("Failed to trigger toggles: {:#}", e))
+            .or(Ok(Vec::new()))
+    }
+
+    /// Fire a toggle by its configured name, or by its position in the toggle list
+    pub async fn fire_toggle(&self, name: &str) -> Option<Result<Vec<TriggeredEffect>>> {
+        let toggle = find_toggle(&self.config, name)?;
+
+        let _in_flight = shutdown::track();
+        Some(self.dispatch_outputs(toggle, &templates::Vars::default(), Instant::now(), false).await)
+    }
+
+    /// Pick up the effects the last run left waiting (see `recovery::start`). Delayed ones fire
+    /// when their delay would have ended, or straight away when it already has; retries carry on.
+    pub fn resume(&self, pending: Vec<recovery::Pending>) {
+        if !pending.is_empty() {
+            println!("Resuming {} effects left waiting by the last run", pending.len());
+        }
+        for effect in pending {
+            let delay = effect.remaining();
+            match effect.attempt {
+                0 => {
+                    self.schedule_toggle(&effect.toggle, None, &effect.vars, delay);
+                }
+                attempt => self.send_retry(effect.toggle, None, effect.color, effect.vars, attempt, delay),
+            }
+        }
+    }
+
+    fn queue_retry(&self, toggle: &config::Toggle, index: Option<usize>, color: Option<Rgb>, vars: &templates::Vars, attempt: u32) {
+        let cfg = self.config.retry.clone().unwrap_or_default();
+        if attempt > cfg.max_retries {
+            return;
+        }
+        let delay = Duration::from_millis(cfg.base_delay_ms.saturating_mul(1 << (attempt - 1).min(6)));
+        self.send_retry(toggle.clone(), index, color, vars.clone(), attempt, delay);
+    }
+
+    /// Queue a retry to run after `delay`, keeping it in the recovery file until then
+    fn send_retry(&self, toggle: config::Toggle, index: Option<usize>, color: Option<Rgb>, vars: templates::Vars, attempt: u32, delay: Duration) {
+        let saved = recovery::remember(recovery::Pending {
+            toggle: toggle.clone(),
+            color,
+            vars: vars.clone(),
+            due: recovery::due_in(delay),
+            attempt,
+        });
+        let _ = self.retry_tx.send(RetryJob { toggle, index, color, vars, attempt, delay, saved });
+    }
+
+    /// Retry failed toggles with exponential backoff, reporting ones that never succeed to the GUI
+    pub async fn run_retries(self, mut retry_rx: mpsc::UnboundedReceiver<RetryJob>, tx: mpsc::Sender<Event>) {
+        let max_retries = self.config.retry.clone().unwrap_or_default().max_retries;
+
+        while let Some(job) = retry_rx.recv().await {
+            let (effects, tx) = (self.clone(), tx.clone());
+
+            tokio::spawn(async move {
+                tokio::time::sleep(job.delay).await;
+                if shutdown::is_shutting_down() {
+                    return;
+                }
+                recovery::forget(job.saved);
+
+                let description = format_toggle_description(&job.toggle);
+                println!("Retrying {} (attempt {} of {})", description, job.attempt, max_retries);
+
+                let _in_flight = shutdown::track();
+                let started = Instant::now();
+                let result = trigger_single_toggle(&effects.config, &effects.plugins, &job.toggle, job.color, &job.vars).await;
+                log_execution(&effects.config, &job.toggle, started.elapsed(), None, job.attempt, &result);
+                match result {
+                    Ok(_) => {
+                        println!("Retry succeeded: {}", description);
+                        if let Some(idx) = job.index {
+                            toggle_stats::fired(idx);
+                        }
+                        idle::schedule(&effects.config);
+                        audio::effect_fired();
+                    }
+                    Err(e) if job.attempt < max_retries => {
+                        eprintln!("Retry {} failed for {}: {:#}", job.attempt, description, e);
+                        effects.queue_retry(&job.toggle, job.index, job.color, &job.vars, job.attempt + 1);
+                    }
+                    Err(e) => {
+                        eprintln!("Giving up on {} after {} retries: {:#}", description, job.attempt, e);
+                        let _ = tx.send(Event::EffectFailed(description, format!("{:#}", e), job.attempt)).await;
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_offsets_hold_faster_outputs_back() {
+        let config: Config = toml::from_str(r#"
+            latency_ms = { osc = 300, WLED = 100 }
+
+            [[toggles]]
+            name = "confetti"
+            output = "osc"
+            outputs = ["wled", "ArtNet"]
+        "#).unwrap();
+        let branches = fan_out(find_toggle(&config, "confetti").unwrap());
+        assert_eq!(branches.iter().map(|b| b.output.as_str()).collect::<Vec<_>>(), ["osc", "wled", "artnet"]);
+        assert_eq!(sync_offsets(&config, &branches), [0, 200, 300].map(Duration::from_millis));
+
+        let bare = Config { latency_ms: None, ..config.clone() };
+        assert_eq!(sync_offsets(&bare, &branches), [Duration::ZERO; 3]);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::config::{self, Config};
+
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+
+/// Drop emoji presentation selectors so "❤️" and "❤" are the same emoji
+fn normalize(text: &str) -> String {
+    text.chars().filter(|c| *c != VARIATION_SELECTOR).collect()
+}
+
+/// The toggles picked by emoji in a boost message, in the order their emoji first appear.
+/// Names missing from `[[toggles]]` are skipped.
+pub fn picked<'a>(config: &'a Config, message: Option<&str>) -> Vec<&'a config::Toggle> {
+    let (Some(triggers), Some(message)) = (&config.emoji, message) else { return Vec::new() };
+    let message = normalize(message);
+    let toggles = config.toggles.as_deref().unwrap_or_default();
+
+    let mut found: Vec<(usize, &str)> = triggers.iter()
+        .filter(|(emoji, _)| !emoji.is_empty())
+        .filter_map(|(emoji, name)| message.find(&normalize(emoji)).map(|at| (at, name.as_str())))
+        .collect();
+    found.sort();
+
+    let mut picked: Vec<&config::Toggle> = Vec::new();
+    for (_, name) in found {
+        let Some(toggle) = toggles.iter().find(|t| t.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name))) else { continue };
+        if !picked.iter().any(|p| std::ptr::eq(*p, toggle)) {
+            picked.push(toggle);
+        }
+    }
+    picked
+}
+
+/// Emoji that name a toggle missing from `[[toggles]]`
+pub fn unknown_toggles(config: &Config) -> Vec<(String, String)> {
+    let toggles = config.toggles.as_deref().unwrap_or_default();
+    config.emoji.iter().flatten()
+        .filter(|(_, name)| !toggles.iter().any(|t| t.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name))))
+        .map(|(emoji, name)| (emoji.clone(), name.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        toml::from_str(r#"
+            [emoji]
+            "❤️" = "hearts"
+            "🔥" = "Fire"
+            "🎉" = "missing"
+
+            [[toggles]]
+            name = "fire"
+            output = "wled"
+
+            [[toggles]]
+            name = "hearts"
+            output = "wled"
+        "#).unwrap()
+    }
+
+    fn names(picked: Vec<&config::Toggle>) -> Vec<&str> {
+        picked.into_iter().filter_map(|t| t.name.as_deref()).collect()
+    }
+
+    #[test]
+    fn picked_in_message_order() {
+        let config = config();
+        assert_eq!(names(picked(&config, Some("🔥 then ❤ 🔥 🎉"))), ["fire", "hearts"]);
+        assert_eq!(names(picked(&config, Some("❤️"))), ["hearts"]);
+        assert!(picked(&config, Some("no emoji")).is_empty());
+        assert!(picked(&config, None).is_empty());
+    }
+
+    #[test]
+    fn unknown_toggles_are_reported() {
+        assert_eq!(unknown_toggles(&config()), [("🎉".to_string(), "missing".to_string())]);
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Mutex;
+
+/// An episode boosts were seen for, by guid
+#[derive(Debug, Clone, PartialEq)]
+pub struct Episode {
+    pub guid: String,
+    pub label: String,  // Title, or the guid when there's no title
+}
+
+static LATEST: Mutex<Option<Episode>> = Mutex::new(None);
+static LOCKED: Mutex<Option<Episode>> = Mutex::new(None);
+
+/// Note the episode of a boost as it arrives, so the lock can be set to it
+pub fn observe(guid: Option<&str>, title: Option<&str>) {
+    let Some(guid) = guid.filter(|guid| !guid.is_empty()) else { return };
+    *LATEST.lock().unwrap() = Some(Episode { guid: guid.to_string(), label: title.unwrap_or(guid).to_string() });
+}
+
+/// Only count boosts for the episode of the latest boost, until unlocked or restarted. Returns
+/// the episode, or None if no boost has named one yet.
+pub fn lock_to_latest() -> Option<Episode> {
+    let latest = LATEST.lock().unwrap().clone()?;
+    println!("Locked to episode {} ({})", latest.label, latest.guid);
+    *LOCKED.lock().unwrap() = Some(latest.clone());
+    Some(latest)
+}
+
+pub fn unlock() {
+    if LOCKED.lock().unwrap().take().is_some() {
+        println!("Episode lock removed");
+    }
+}
+
+pub fn locked() -> Option<Episode> {
+    LOCKED.lock().unwrap().clone()
+}
+
+/// Whether a boost counts under the lock: always while unlocked, and otherwise when it's for the
+/// locked episode or names no episode at all (zaps and test boosts)
+pub fn allows(guid: Option<&str>) -> bool {
+    match (LOCKED.lock().unwrap().as_ref(), guid.filter(|guid| !guid.is_empty())) {
+        (Some(locked), Some(guid)) => locked.guid.eq_ignore_ascii_case(guid),
+        _ => true,
+    }
+}
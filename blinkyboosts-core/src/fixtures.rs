@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use anyhow::{bail, Context, Result};
+
+use crate::config::{self, Config};
+use crate::sender_color::Rgb;
+use crate::{artnet, artnet_input, sacn};
+
+/// Last frame sent per (output, universe), so fixtures sharing a universe don't blank each other
+type Frames = BTreeMap<(String, u16), Vec<u8>>;
+static FRAMES: Mutex<Frames> = Mutex::new(BTreeMap::new());
+
+pub fn find<'a>(config: &'a Config, name: &str) -> Option<&'a config::Fixture> {
+    config.fixtures.as_deref().unwrap_or_default().iter()
+        .find(|f| f.name.eq_ignore_ascii_case(name))
+}
+
+/// The universe a fixture is patched into
+pub fn universe(config: &Config, fixture: &config::Fixture) -> Result<u16> {
+    match fixture.output.to_lowercase().as_str() {
+        "artnet" => Ok(fixture.universe.or(config.artnet.as_ref().and_then(|a| a.universe)).unwrap_or(0)),
+        "sacn" => Ok(fixture.universe.or(config.sacn.as_ref().and_then(|s| s.universe)).unwrap_or(1)),
+        other => bail!("Fixture {}: unknown output \"{}\" (expected artnet or sacn)", fixture.name, other),
+    }
+}
+
+/// Translate roles to (channel, value) pairs. `color` overrides the toggle's color (sender colors).
+pub fn channel_values(fixture: &config::Fixture, toggle: &config::ToggleFixture, color: Option<Rgb>) -> Result<Vec<(u16, u8)>> {
+    let color = color.or(toggle.color);
+    let mut values = Vec::new();
+
+    for (offset, role) in fixture.channels.iter().enumerate() {
+        let role = role.to_lowercase();
+        let value = match role.as_str() {
+            "red" => color.map(|c| c[0]),
+            "green" => color.map(|c| c[1]),
+            "blue" => color.map(|c| c[2]),
+            "dimmer" => toggle.dimmer,
+            "strobe" => toggle.strobe,
+            _ => None,
+        }.or_else(|| toggle.values.iter().find(|(name, _)| name.eq_ignore_ascii_case(&role)).map(|(_, v)| *v));
+
+        if let Some(value) = value {
+            let channel = u16::try_from(offset).ok().and_then(|offset| fixture.start_channel.checked_add(offset))
+                .filter(|channel| (1..=512).contains(channel));
+            let Some(channel) = channel else {
+                bail!("Fixture {} {} channel {} is outside 1-512", fixture.name, role, fixture.start_channel as usize + offset);
+            };
+            values.push((channel, value));
+        }
+    }
+
+    Ok(values)
+}
+
+pub fn trigger_toggle(config: &Config, toggle: &config::Toggle, color: Option<Rgb>) -> Result<()> {
+    let toggle_cfg = toggle.fixture.as_ref().context("Fixture toggle missing 'fixture' configuration")?;
+    let fixture = find(config, &toggle_cfg.name)
+        .with_context(|| format!("Fixture {} not configured", toggle_cfg.name))?;
+
+    let universe = universe(config, fixture)?;
+    let values = channel_values(fixture, toggle_cfg, color)?;
+    send_channels(config, &fixture.output.to_lowercase(), universe, &values)
+}
+
+/// Drop the channels a DMX toggle sets back to 0, e.g. between repeats. Other outputs are left alone.
+pub fn release_toggle(config: &Config, toggle: &config::Toggle) -> Result<()> {
+    let (output, universe, values) = match toggle.output.to_lowercase().as_str() {
+        "artnet" => {
+            let (Some(cfg), Some(toggle_cfg)) = (&config.artnet, &toggle.artnet) else { return Ok(()) };
+            ("artnet", toggle_cfg.universe.or(cfg.universe).unwrap_or(0), artnet::ArtNet::toggle_values(toggle_cfg, Some([0; 3])))
+        }
+        "sacn" => {
+            let (Some(cfg), Some(toggle_cfg)) = (&config.sacn, &toggle.sacn) else { return Ok(()) };
+            ("sacn", toggle_cfg.universe.or(cfg.universe).unwrap_or(1), sacn::Sacn::toggle_values(toggle_cfg, Some([0; 3])))
+        }
+        "fixture" => {
+            let Some(toggle_cfg) = &toggle.fixture else { return Ok(()) };
+            let fixture = find(config, &toggle_cfg.name)
+                .with_context(|| format!("Fixture {} not configured", toggle_cfg.name))?;
+            let output = if fixture.output.eq_ignore_ascii_case("sacn") { "sacn" } else { "artnet" };
+            (output, universe(config, fixture)?, channel_values(fixture, toggle_cfg, Some([0; 3]))?)
+        }
+        _ => return Ok(()),
+    };
+
+    let zeroed: Vec<(u16, u8)> = values.into_iter().map(|(channel, _)| (channel, 0)).collect();
+    send_channels(config, output, universe, &zeroed)
+}
+
+/// Group (channel, value) pairs by universe. Channels count on from `universe`, so channel 513
+/// is channel 1 of the next universe.
+pub fn span(universe: u16, values: &[(u16, u8)]) -> Result<BTreeMap<u16, Vec<(u16, u8)>>> {
+    let mut universes: BTreeMap<u16, Vec<(u16, u8)>> = BTreeMap::new();
+    for &(channel, value) in values {
+        if channel == 0 {
+            bail!("DMX channel 0 is invalid, channels start at 1");
+        }
+        let target = universe.checked_add((channel - 1) / 512)
+            .with_context(|| format!("DMX channel {} is past the last universe", channel))?;
+        universes.entry(target).or_default().push(((channel - 1) % 512 + 1, value));
+    }
+    Ok(universes)
+}
+
+/// How channels go out on top of the console: the merge and, for sACN, the packet priority
+#[derive(Clone, Copy, Debug)]
+pub struct Layer {
+    pub merge: config::Merge,
+    pub priority: Option<u8>,  // sACN only; None sends at `[sacn] priority`
+}
+
+impl Layer {
+    /// `[artnet]` or `[sacn]` merge and priority
+    pub fn output_default(config: &Config, output: &str) -> Self {
+        let merge = match output {
+            "artnet" => config.artnet.as_ref().map(|cfg| cfg.merge),
+            "sacn" => config.sacn.as_ref().map(|cfg| cfg.merge),
+            _ => None,
+        };
+        Self { merge: merge.unwrap_or_default(), priority: None }
+    }
+
+    /// The frame to send: as remembered, or raised to the console's levels with HTP
+    pub fn merge(&self, universe: u16, frame: &[u8]) -> Vec<u8> {
+        let console = match self.merge {
+            config::Merge::Htp => artnet_input::universe_frame(universe),
+            config::Merge::Ltp => None,
+        };
+        let Some(console) = console else { return frame.to_vec() };
+        frame.iter().enumerate()
+            .map(|(idx, &value)| value.max(console.get(idx).copied().unwrap_or(0)))
+            .collect()
+    }
+}
+
+/// Set channels on top of the remembered frames and send every universe they touch, so
+/// effects sharing a universe don't blank each other
+pub fn send_channels(config: &Config, output: &str, universe: u16, values: &[(u16, u8)]) -> Result<()> {
+    send_layer(config, output, universe, values, Layer::output_default(config, output))
+}
+
+/// `send_channels` with a toggle's own merge and priority
+pub fn send_layer(config: &Config, output: &str, universe: u16, values: &[(u16, u8)], layer: Layer) -> Result<()> {
+    let frames: Vec<(u16, Vec<u8>)> = {
+        let mut frames = FRAMES.lock().unwrap();
+        span(universe, values)?.into_iter()
+            .map(|(universe, values)| {
+                let frame = frames.entry((output.to_string(), universe))
+                    .or_insert_with(|| vec![0u8; 512]);
+                for (channel, value) in values {
+                    frame[(channel - 1) as usize] = value;
+                }
+                (universe, frame.clone())
+            })
+            .collect()
+    };
+
+    for (universe, frame) in frames {
+        send_layer_frame(config, output, universe, &layer.merge(universe, &frame), layer.priority)?;
+    }
+    Ok(())
+}
+
+/// The (channel, value) pairs an Art-Net or sACN toggle sets: `count` channels from `channel`,
+/// then any listed `channels`
+pub fn toggle_values(channel: Option<u16>, value: u8, count: Option<u16>, channels: &[(u16, u8)]) -> Vec<(u16, u8)> {
+    channel.into_iter()
+        .flat_map(|start| (start..=u16::MAX).take(count.unwrap_or(1) as usize))
+        .map(|channel| (channel, value))
+        .chain(channels.iter().copied())
+        .collect()
+}
+
+/// Short summary of DMX channel values, e.g. "ch5: 255" or "ch1-24: 255"
+pub fn describe_channels(values: &[(u16, u8)]) -> String {
+    let (Some(&(first, value)), Some(&(last, _))) = (values.first(), values.last()) else {
+        return "no channels".to_string();
+    };
+    if values.len() == 1 {
+        format!("ch{}: {}", first, value)
+    } else if values.iter().all(|&(_, v)| v == value) && last as usize + 1 == first as usize + values.len() {
+        format!("ch{}-{}: {}", first, last, value)
+    } else {
+        format!("{} channels from ch{}", values.len(), first)
+    }
+}
+
+/// Send a whole frame to a universe, without remembering it
+pub fn send_frame(config: &Config, output: &str, universe: u16, frame: &[u8]) -> Result<()> {
+    send_layer_frame(config, output, universe, frame, None)
+}
+
+fn send_layer_frame(config: &Config, output: &str, universe: u16, frame: &[u8], priority: Option<u8>) -> Result<()> {
+    match output {
+        "artnet" => {
+            let cfg = config.artnet.as_ref().context("Art-Net not configured")?;
+            artnet::ArtNet::new(cfg.broadcast_address.clone(), cfg.local_address.clone(), Some(universe))?
+                .send_dmx(frame)
+        }
+        "sacn" => {
+            let cfg = config.sacn.as_ref().context("sACN not configured")?;
+            sacn::Sacn::new(cfg.broadcast_address.clone(), Some(universe))?
+                .with_priority(priority.unwrap_or(cfg.priority))
+                .send_dmx(frame)
+        }
+        other => bail!("Unknown DMX output \"{}\"", other),
+    }
+}
+
+/// The remembered frame for every universe as (output, universe, values)
+pub fn frames() -> Vec<(String, u16, Vec<u8>)> {
+    FRAMES.lock().unwrap().iter()
+        .map(|((output, universe), frame)| (output.clone(), *universe, frame.clone()))
+        .collect()
+}
+
+/// The remembered frame for one universe, if anything has been sent to it
+pub fn frame(output: &str, universe: u16) -> Option<Vec<u8>> {
+    FRAMES.lock().unwrap().get(&(output.to_string(), universe)).cloned()
+}
+
+/// Remember `frame` as the current state of a universe
+pub fn latch(output: &str, universe: u16, frame: &[u8]) {
+    FRAMES.lock().unwrap().insert((output.to_string(), universe), frame.to_vec());
+}
+
+/// Forget remembered frames, e.g. after a blackout
+pub fn clear_frames() {
+    FRAMES.lock().unwrap().clear();
+}
+
+pub fn describe(toggle: &config::ToggleFixture) -> String {
+    let mut parts = Vec::new();
+    if let Some([r, g, b]) = toggle.color {
+        parts.push(format!("#{:02x}{:02x}{:02x}", r, g, b));
+    }
+    if let Some(dimmer) = toggle.dimmer {
+        parts.push(format!("dimmer {}", dimmer));
+    }
+    if let Some(strobe) = toggle.strobe {
+        parts.push(format!("strobe {}", strobe));
+    }
+    parts.extend(toggle.values.iter().map(|(role, value)| format!("{} {}", role, value)));
+
+    if parts.is_empty() {
+        format!("Fixture {}", toggle.name)
+    } else {
+        format!("Fixture {}: {}", toggle.name, parts.join(", "))
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::config::{self, Config};
+use crate::ddp::Ddp;
+use crate::{campaigns, dimmer};
+use crate::sat_tracker::SatTracker;
+use crate::sender_color::Rgb;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);  // WLED drops realtime mode after ~2.5s without data
+
+/// Pixels for the thermometer: one LED per `sats_per_led`, with the next LED partly lit
+fn render(cfg: &config::Goal, total: i64) -> Vec<Rgb> {
+    let sats_per_led = cfg.sats_per_led.unwrap_or(cfg.target_sats / cfg.leds.max(1) as i64).max(1);
+    let total = total.clamp(0, cfg.target_sats.max(0));
+    let full = (total / sats_per_led) as usize;
+    let partial = (total % sats_per_led) as f32 / sats_per_led as f32;
+
+    (0..cfg.leds)
+        .map(|idx| {
+            let color = if idx < full {
+                cfg.fill_color
+            } else if idx == full {
+                blend(cfg.empty_color, cfg.fill_color, partial)
+            } else {
+                cfg.empty_color
+            };
+            color.map(dimmer::scale)
+        })
+        .collect()
+}
+
+fn blend(from: Rgb, to: Rgb, amount: f32) -> Rgb {
+    [0, 1, 2].map(|i| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * amount).round() as u8)
+}
+
+/// Draw the sat total toward `goal.target_sats` on a strip over DDP, redrawing whenever the total
+/// changes. With `display_secs` set the thermometer is only held that long after each change so
+/// the controller falls back to its presets; otherwise it is shown continuously. With
+/// `[[campaigns]]` configured the strip rotates between the overall goal and each campaign's.
+pub async fn run(config: Config, tracker: Arc<Mutex<SatTracker>>) {
+    let Some(cfg) = config.goal.clone() else { return };
+    let Some(host) = cfg.host.clone().or_else(|| config.wled.as_ref().map(|w| w.host.clone())) else {
+        eprintln!("Goal display needs goal.host or a [wled] host");
+        return;
+    };
+
+    let mut ddp = match Ddp::new(&host) {
+        Ok(ddp) => ddp,
+        Err(e) => {
+            eprintln!("Failed to start goal display: {:#}", e);
+            return;
+        }
+    };
+
+    println!("Showing sat goal of {} on {} (LEDs {}-{})", cfg.target_sats, host, cfg.start, cfg.start + cfg.leds);
+    let mut shown: Option<i64> = None;
+    let mut changed_at = Instant::now();
+    let mut sent_at: Option<Instant> = None;
+
+    loop {
+        let (overall, slot_cfg, total) = {
+            let tracker = tracker.lock().await;
+            let campaign = campaigns::showing(&config)
+                .and_then(|idx| config.campaigns.as_ref().and_then(|campaigns| campaigns.get(idx)));
+            let (slot_cfg, total) = match campaign {
+                Some(campaign) => (
+                    config::Goal {
+                        target_sats: campaign.target_sats,
+                        sats_per_led: None,
+                        fill_color: campaign.fill_color.unwrap_or(cfg.fill_color),
+                        ..cfg.clone()
+                    },
+                    tracker.campaign_total(&campaign.name),
+                ),
+                None => (cfg.clone(), tracker.total()),
+            };
+            (tracker.total(), slot_cfg, total)
+        };
+
+        // Only boosts restart the hold, not the display rotating to another slot
+        if shown != Some(overall) {
+            shown = Some(overall);
+            changed_at = Instant::now();
+            sent_at = None;
+        }
+
+        let holding = cfg.display_secs == 0 || changed_at.elapsed() < Duration::from_secs(cfg.display_secs);
+        let due = sent_at.is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL);
+
+        if holding && due {
+            if let Err(e) = ddp.send_pixels(cfg.start, &render(&slot_cfg, total)) {
+                eprintln!("Failed to update goal display: {:#}", e);
+            }
+            sent_at = Some(Instant::now());
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,373 @@
+//! In-process stand-ins for a nostr relay and a WLED controller, so the whole pipeline
+//! (event → filter → tracker → toggle → output) can run without a network: in `cargo test`
+//! and from `blinkyboosts simulate` when debugging a config offline.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use nostr_sdk::{ClientMessage, Event, EventBuilder, Filter, JsonUtil, Keys, Kind, RelayMessage, SubscriptionId, Tag, Timestamp};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::boostboard::{BoostBoard, BoostFilters};
+use crate::config::{self, Config};
+use crate::dedup::SeenEvents;
+use crate::listeners::{process_boost, IncomingBoost};
+use crate::messages;
+use crate::metrics::TriggeredEffect;
+use crate::{bus, effects, sat_tracker};
+
+async fn serve(router: Router) -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.context("Failed to bind mock server")?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    Ok(addr)
+}
+
+#[derive(Default)]
+struct RelayState {
+    events: Mutex<Vec<Event>>,
+    subscriptions: Mutex<usize>,
+}
+
+/// A relay keeping every event it's sent and passing new ones to matching subscriptions
+pub struct MockRelay {
+    addr: SocketAddr,
+    state: Arc<RelayState>,
+    live: broadcast::Sender<Event>,
+}
+
+impl MockRelay {
+    pub async fn start() -> Result<Self> {
+        let state = Arc::new(RelayState::default());
+        let (live, _) = broadcast::channel(256);
+
+        let router = Router::new()
+            .route("/", get(relay_socket))
+            .with_state((state.clone(), live.clone()));
+        Ok(Self { addr: serve(router).await?, state, live })
+    }
+
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    /// Store an event and send it to the subscriptions it matches
+    pub fn publish(&self, event: Event) {
+        self.state.events.lock().unwrap().push(event.clone());
+        let _ = self.live.send(event);
+    }
+
+    /// Open subscriptions across all connections
+    pub fn subscriptions(&self) -> usize {
+        *self.state.subscriptions.lock().unwrap()
+    }
+}
+
+type RelayShared = (Arc<RelayState>, broadcast::Sender<Event>);
+
+async fn relay_socket(ws: WebSocketUpgrade, State(shared): State<RelayShared>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| relay_connection(socket, shared))
+}
+
+async fn relay_connection(mut socket: WebSocket, (state, live): RelayShared) {
+    let mut incoming = live.subscribe();
+    let mut subscriptions: HashMap<SubscriptionId, Vec<Filter>> = HashMap::new();
+
+    loop {
+        let replies = tokio::select! {
+            msg = socket.recv() => {
+                let text = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,  // Pings are answered by axum
+                };
+                let Ok(msg) = ClientMessage::from_json(&text) else { continue };
+                relay_reply(&state, &live, &mut subscriptions, msg)
+            }
+            event = incoming.recv() => {
+                let Ok(event) = event else { continue };
+                subscriptions.iter()
+                    .filter(|(_, filters)| filters.iter().any(|f| f.match_event(&event)))
+                    .map(|(id, _)| RelayMessage::event(id.clone(), event.clone()))
+                    .collect()
+            }
+        };
+
+        for reply in replies {
+            if socket.send(Message::Text(reply.as_json())).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    *state.subscriptions.lock().unwrap() -= subscriptions.len();
+}
+
+fn relay_reply(
+    state: &RelayState,
+    live: &broadcast::Sender<Event>,
+    subscriptions: &mut HashMap<SubscriptionId, Vec<Filter>>,
+    msg: ClientMessage
+) -> Vec<RelayMessage> {
+    match msg {
+        ClientMessage::Event(event) => {
+            state.events.lock().unwrap().push(*event.clone());
+            let _ = live.send(*event.clone());
+            vec![RelayMessage::ok(event.id, true, "")]
+        }
+        ClientMessage::Req { subscription_id, filters } => {
+            let mut replies: Vec<RelayMessage> = state.events.lock().unwrap().iter()
+                .filter(|event| filters.iter().any(|f| f.match_event(event)))
+                .map(|event| RelayMessage::event(subscription_id.clone(), event.clone()))
+                .collect();
+            replies.push(RelayMessage::eose(subscription_id.clone()));
+            if subscriptions.insert(subscription_id, filters).is_none() {
+                *state.subscriptions.lock().unwrap() += 1;
+            }
+            replies
+        }
+        ClientMessage::Close(subscription_id) => {
+            if subscriptions.remove(&subscription_id).is_some() {
+                *state.subscriptions.lock().unwrap() -= 1;
+            }
+            Vec::new()
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Default)]
+struct WledState {
+    effects: Vec<String>,
+    presets: Mutex<BTreeMap<u64, String>>,
+    states: Mutex<Vec<Value>>,
+}
+
+/// A WLED controller answering the JSON API and recording every state change it's sent
+pub struct MockWled {
+    addr: SocketAddr,
+    state: Arc<WledState>,
+}
+
+impl MockWled {
+    /// Offer these effects, and presets with ids counting from 1
+    pub async fn start(effects: &[String], presets: &[String]) -> Result<Self> {
+        let state = Arc::new(WledState {
+            effects: effects.to_vec(),
+            presets: Mutex::new(presets.iter().enumerate().map(|(idx, name)| (idx as u64 + 1, name.clone())).collect()),
+            states: Mutex::new(Vec::new()),
+        });
+
+        let router = Router::new()
+            .route("/json/effects", get(wled_effects))
+            .route("/json/info", get(|| async { Json(json!({"ver": "mock", "name": "Mock WLED"})) }))
+            .route("/json/state", get(wled_state).post(wled_set_state))
+            .route("/presets.json", get(wled_presets))
+            .with_state(state.clone());
+        Ok(Self { addr: serve(router).await?, state })
+    }
+
+    /// Host and port, for `[wled] host`
+    pub fn host(&self) -> String {
+        self.addr.to_string()
+    }
+
+    /// Bodies posted to /json/state, oldest first
+    pub fn states(&self) -> Vec<Value> {
+        self.state.states.lock().unwrap().clone()
+    }
+
+    /// Names of the presets run with `{"ps": id}`, oldest first
+    pub fn presets_run(&self) -> Vec<String> {
+        let presets = self.state.presets.lock().unwrap();
+        self.states().iter()
+            .filter_map(|state| state.get("ps")?.as_u64())
+            .map(|id| presets.get(&id).cloned().unwrap_or_else(|| format!("#{}", id)))
+            .collect()
+    }
+}
+
+async fn wled_effects(State(state): State<Arc<WledState>>) -> Json<Value> {
+    Json(json!(state.effects))
+}
+
+async fn wled_presets(State(state): State<Arc<WledState>>) -> Json<Value> {
+    let mut presets = serde_json::Map::new();
+    presets.insert("0".to_string(), json!({}));
+    for (id, name) in state.presets.lock().unwrap().iter() {
+        presets.insert(id.to_string(), json!({"n": name}));
+    }
+    Json(Value::Object(presets))
+}
+
+async fn wled_state(State(state): State<Arc<WledState>>) -> Json<Value> {
+    let bri = state.states.lock().unwrap().iter().rev().find_map(|s| s.get("bri").cloned());
+    Json(json!({"on": true, "bri": bri.unwrap_or(json!(128)), "seg": []}))
+}
+
+async fn wled_set_state(State(state): State<Arc<WledState>>, body: String) -> Json<Value> {
+    let Ok(value) = serde_json::from_str::<Value>(&body) else {
+        return Json(json!({"error": 9}));
+    };
+    if let (Some(id), Some(name)) = (value.get("psave").and_then(Value::as_u64), value.get("n").and_then(Value::as_str)) {
+        state.presets.lock().unwrap().insert(id, name.to_string());
+    }
+    state.states.lock().unwrap().push(value);
+    Json(json!({"success": true}))
+}
+
+/// A Boostboard stored-boost event (kind 30078) carrying a boostagram
+pub fn boostboard_event(keys: &Keys, sats: i64, sender: &str, message: &str) -> Result<Event> {
+    let now = Timestamp::now().as_u64() as i64;
+    let content = json!({
+        "identifier": format!("mock-{}-{}", now, sats),
+        "creation_date": now,
+        "boostagram": {
+            "action": "boost",
+            "app_name": "Mock",
+            "podcast": "Mock Podcast",
+            "episode": "Mock Episode",
+            "sender_name": sender,
+            "message": message,
+            "value_msat_total": sats * 1000,
+        },
+    });
+    EventBuilder::new(Kind::ApplicationSpecificData, content.to_string(), [Tag::identifier(format!("mock-{}", sats))])
+        .to_event(keys)
+        .context("Failed to sign mock boost")
+}
+
+/// Every WLED preset a config might run: toggle, idle and bracket presets plus `[wled]` presets
+/// and playlists
+fn wled_preset_names(config: &Config) -> Vec<String> {
+    let wled = config.wled.as_ref();
+    let mut names: Vec<String> = wled.and_then(|w| w.presets.as_ref()).into_iter().flatten().map(|p| p.name.clone())
+        .chain(wled.and_then(|w| w.playlists.as_ref()).into_iter().flatten().map(|p| p.name.clone()))
+        .chain(config.toggles.iter().flatten().filter_map(|t| t.wled.as_ref()).map(|w| w.preset.clone()))
+        .chain(config.idle.as_ref().and_then(|idle| idle.wled_preset.clone()))
+        .chain(config.total_brackets.iter().flatten().map(|bracket| bracket.wled_preset.clone()))
+        .chain(wled.map(|w| w.boost_playlist.clone()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// A boost as it came out of the pipeline
+#[derive(Debug, Clone)]
+pub struct Delivered {
+    pub source: String,
+    pub sats: i64,
+    pub sender: Option<String>,
+    pub effects: Vec<TriggeredEffect>,
+}
+
+/// A config rewired to the mocks: Boostboard listens on the mock relay and WLED talks to the
+/// mock controller, with every other output left as configured (set `mock = true` on those to
+/// record them too)
+pub struct Harness {
+    pub relay: MockRelay,
+    pub wled: MockWled,
+    pub config: Config,
+    keys: Keys,
+    tracker: Arc<tokio::sync::Mutex<sat_tracker::SatTracker>>,
+    rx: mpsc::Receiver<messages::Event>,
+    listener: JoinHandle<()>,
+}
+
+impl Harness {
+    pub async fn start(mut config: Config) -> Result<Self> {
+        let relay = MockRelay::start().await?;
+        let wled = MockWled::start(&["Solid".to_string(), "Blink".to_string()], &wled_preset_names(&config)).await?;
+        let keys = Keys::generate();
+
+        let filters = config.boostboard.as_ref().map(|bb| bb.filters.clone()).unwrap_or_default();
+        config.boostboard = Some(config::BoostBoard {
+            relay_addrs: vec![relay.url()],
+            pubkey: keys.public_key().to_hex(),
+            resume_history: false,
+            page_retries: 0,
+            filters: config::BoostFiltersConfig { load_since: None, after: None, before: None, ..filters },
+        });
+        if let Some(wled_cfg) = &mut config.wled {
+            wled_cfg.host = wled.host();
+            wled_cfg.mock = false;
+            wled_cfg.retries = 0;
+        }
+
+        let (effects, _retry_rx) = effects::Effects::new(config.clone());
+        let tracker = Arc::new(tokio::sync::Mutex::new(sat_tracker::SatTracker::new()));
+        let (tx, rx) = mpsc::channel(100);
+        bus::attach(effects.bus(), tx.clone(), tracker.clone());
+
+        let bb_cfg = config.boostboard.clone().unwrap_or_else(|| unreachable!());
+        let boostboard = BoostBoard::new(&bb_cfg.relay_addrs, &bb_cfg.pubkey, BoostFilters::from_config(&bb_cfg.filters), SeenEvents::in_memory()).await?;
+        let sub_id = boostboard.subscribe(Some(Timestamp::now())).await?;
+
+        let listener = tokio::spawn({
+            let tracker = tracker.clone();
+            async move {
+                let result = boostboard.handle_boosts(sub_id, move |boost, event_ts| {
+                    let (effects, tx, tracker) = (effects.clone(), tx.clone(), tracker.clone());
+                    async move {
+                        process_boost(IncomingBoost::from_boostagram("Boostboard", &boost, Some(event_ts)), &tx, &tracker, &effects, true).await;
+                    }
+                }).await;
+                if let Err(e) = result {
+                    eprintln!("Mock Boostboard listener stopped: {:#}", e);
+                }
+            }
+        });
+
+        let harness = Self { relay, wled, config, keys, tracker, rx, listener };
+        for _ in 0..50 {
+            if harness.relay.subscriptions() > 0 {
+                return Ok(harness);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        bail!("Boostboard never subscribed to the mock relay");
+    }
+
+    /// Publish a boost on the mock relay
+    pub fn boost(&self, sats: i64, sender: &str, message: &str) -> Result<()> {
+        self.relay.publish(boostboard_event(&self.keys, sats, sender, message)?);
+        Ok(())
+    }
+
+    /// Wait for the next boost to make it through the pipeline, effects and all
+    pub async fn delivered(&mut self, timeout: Duration) -> Result<Delivered> {
+        let wait = async {
+            while let Some(msg) = self.rx.recv().await {
+                if let messages::Event::BoostReceived(_, source, sats, sender, _, _, _, _, effects, _) = msg {
+                    return Some(Delivered { source, sats, sender, effects });
+                }
+            }
+            None
+        };
+        tokio::time::timeout(timeout, wait).await
+            .context("No boost came through the pipeline")?
+            .context("Pipeline stopped")
+    }
+
+    /// Running sat total
+    pub async fn total(&self) -> i64 {
+        self.tracker.lock().await.total()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
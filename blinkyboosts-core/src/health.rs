@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use tokio::sync::mpsc::Sender;
+
+use crate::config::Config;
+use crate::messages::{ComponentStatus, Event};
+use crate::{artnet, osc, wled};
+
+/// Periodically probe configured outputs and report reachability changes to the GUI
+pub async fn poll_outputs(config: Config, tx: Sender<Event>) {
+    let cfg = config.health.clone().unwrap_or_default();
+    if !cfg.enabled {
+        return;
+    }
+
+    let timeout = Duration::from_millis(cfg.timeout_ms);
+    let mut last: HashMap<&str, ComponentStatus> = HashMap::new();
+    let mut interval = tokio::time::interval(Duration::from_secs(cfg.interval_secs.max(1)));
+
+    loop {
+        interval.tick().await;
+
+        for (name, result) in check_outputs(&config, timeout).await {
+            let status = match result {
+                Ok(()) => ComponentStatus::Enabled,
+                Err(e) => {
+                    eprintln!("Health check failed for {}: {:#}", name, e);
+                    ComponentStatus::Unreachable(format!("{:#}", e))
+                }
+            };
+
+            if last.get(name) != Some(&status) {
+                let _ = tx.send(Event::UpdateStatus(name.to_string(), status.clone())).await;
+                last.insert(name, status);
+            }
+        }
+    }
+}
+
+/// Probe WLED, OSC and Art-Net once, by component name
+pub async fn check_outputs(config: &Config, timeout: Duration) -> Vec<(&'static str, Result<()>)> {
+    let mut results = Vec::new();
+
+    if let Some(wled_cfg) = &config.wled {
+        let result = tokio::time::timeout(timeout, wled::WLed::ping(wled_cfg)).await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("WLED unreachable: no response within {}ms", timeout.as_millis())));
+        results.push(("WLED", result));
+    }
+
+    if let Some(osc_cfg) = config.osc.clone() {
+        let result = tokio::task::spawn_blocking(move || {
+            osc::Osc::new(&osc_cfg.address)?.ping(timeout)
+        }).await.context("OSC health check panicked").and_then(|r| r);
+        results.push(("OSC", result));
+    }
+
+    if let Some(artnet_cfg) = config.artnet.clone() {
+        let result = tokio::task::spawn_blocking(move || {
+            artnet::ArtNet::new(artnet_cfg.broadcast_address, artnet_cfg.local_address, artnet_cfg.universe)?
+                .poll(timeout)
+        }).await.context("Art-Net health check panicked").and_then(|r| r);
+        results.push(("Art-Net", result));
+    }
+
+    results
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::{self, Config};
+use crate::{clock, fixtures, idle, shutdown, total_brackets, wled};
+
+/// Bumped per output on every effect, so a hold only reverts when nothing has fired there since
+static GENERATIONS: Mutex<Option<HashMap<String, u64>>> = Mutex::new(None);
+/// When each output's running hold is up
+static HELD_UNTIL: Mutex<Option<HashMap<String, Instant>>> = Mutex::new(None);
+
+/// How long `sats` keeps an effect up under `hold`
+pub fn duration(hold: &config::Hold, sats: i64) -> Duration {
+    let secs = sats.max(0) as u64 / hold.sats_per_sec.max(1);
+    Duration::from_secs(secs.clamp(hold.min_secs, hold.max_secs.max(hold.min_secs)))
+}
+
+fn bump(output: &str) -> u64 {
+    let mut generations = GENERATIONS.lock().unwrap();
+    let generation = generations.get_or_insert_with(HashMap::new).entry(output.to_string()).or_default();
+    *generation += 1;
+    *generation
+}
+
+fn is_latest(output: &str, generation: u64) -> bool {
+    GENERATIONS.lock().unwrap().as_ref().and_then(|generations| generations.get(output)) == Some(&generation)
+}
+
+fn hold_until(output: &str, until: Option<Instant>) {
+    let mut held = HELD_UNTIL.lock().unwrap();
+    let held = held.get_or_insert_with(HashMap::new);
+    match until {
+        Some(until) => { held.insert(output.to_string(), until); }
+        None => { held.remove(output); }
+    }
+}
+
+/// How long until every running hold is up, so the idle look doesn't cut one short
+pub fn remaining() -> Duration {
+    let now = Instant::now();
+    HELD_UNTIL.lock().unwrap().iter()
+        .flat_map(|held| held.values())
+        .map(|until| until.saturating_duration_since(now))
+        .max()
+        .unwrap_or_default()
+}
+
+/// Note that `toggle` fired for a boost of `sats`, and revert its output once its hold is up
+pub fn fired(config: &Config, toggle: &config::Toggle, sats: i64) {
+    let output = toggle.output.to_lowercase();
+    let generation = bump(&output);
+    let hold = toggle.hold.as_ref().filter(|_| matches!(output.as_str(), "wled" | "artnet" | "sacn" | "fixture"));
+    let Some(hold) = hold else {
+        // This effect replaced whatever was held on the output
+        hold_until(&output, None);
+        return;
+    };
+
+    let held = duration(hold, sats);
+    hold_until(&output, Some(Instant::now() + clock::scaled(held)));
+    let (config, toggle) = (config.clone(), toggle.clone());
+    tokio::spawn(async move {
+        tokio::time::sleep(clock::scaled(held)).await;
+        if !is_latest(&output, generation) || shutdown::is_shutting_down() {
+            return;
+        }
+        hold_until(&output, None);
+        println!("Hold of {}s for {} sats is up, reverting {}", held.as_secs(), sats, output);
+        if let Err(e) = revert(&config, &toggle).await {
+            eprintln!("Failed to revert {} after its hold: {:#}", output, e);
+        }
+    });
+}
+
+/// Release the held output, back to the idle look (see `idle::restore`) when `[idle]` is
+/// configured and no other hold is still running
+async fn revert(config: &Config, toggle: &config::Toggle) -> anyhow::Result<()> {
+    let is_wled = toggle.output.eq_ignore_ascii_case("wled");
+    if !is_wled {
+        fixtures::release_toggle(config, toggle)?;
+    }
+    if config.idle.is_some() && remaining().is_zero() {
+        idle::restore(config).await;
+        return Ok(());
+    }
+    let Some(wled_cfg) = config.wled.as_ref().filter(|_| is_wled) else { return Ok(()) };
+    let idle_preset = config.idle.as_ref().and_then(|idle| idle.wled_preset.as_deref());
+    wled::WLed::reset(wled_cfg, total_brackets::current(config).or(idle_preset)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_scales_with_sats_within_bounds() {
+        let hold = config::Hold { sats_per_sec: 100, min_secs: 2, max_secs: 10 };
+        assert_eq!(duration(&hold, 500), Duration::from_secs(5));
+        assert_eq!(duration(&hold, 50), Duration::from_secs(2));
+        assert_eq!(duration(&hold, 5_000), Duration::from_secs(10));
+        assert_eq!(duration(&hold, -100), Duration::from_secs(2));
+
+        let odd = config::Hold { sats_per_sec: 0, min_secs: 8, max_secs: 3 };
+        assert_eq!(duration(&odd, 1_000_000), Duration::from_secs(8));
+    }
+}
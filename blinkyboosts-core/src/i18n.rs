@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use anyhow::{bail, Result};
+
+use crate::config::Config;
+
+/// Translations built in, by locale. Others can be added as ./locales/<locale>.ftl, which also
+/// override built-in messages.
+const BUILT_IN: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+const LOCALES_DIR: &str = "./locales";
+const FALLBACK: &str = "en";
+
+static MESSAGES: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Parse the `id = text` lines of a Fluent file; comments, blank lines and anything fancier
+/// than `{ $name }` placeables are skipped
+fn parse(ftl: &str) -> HashMap<String, String> {
+    ftl.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(id, text)| (id.trim().to_string(), text.trim().to_string()))
+        .collect()
+}
+
+/// The locale and the language it falls back to, e.g. "pt-BR" then "pt"
+fn candidates(locale: &str) -> Vec<&str> {
+    let mut candidates = vec![locale];
+    if let Some((language, _)) = locale.split_once(['-', '_']) {
+        candidates.push(language);
+    }
+    candidates
+}
+
+fn load(locale: &str) -> HashMap<String, String> {
+    let mut messages = parse(BUILT_IN[0].1);
+    for candidate in candidates(locale).into_iter().rev() {
+        if let Some((_, ftl)) = BUILT_IN.iter().find(|(built_in, _)| built_in.eq_ignore_ascii_case(candidate)) {
+            messages.extend(parse(ftl));
+        }
+        let path = Path::new(LOCALES_DIR).join(format!("{}.ftl", candidate));
+        if let Ok(ftl) = fs::read_to_string(&path) {
+            messages.extend(parse(&ftl));
+        }
+    }
+    messages
+}
+
+fn messages() -> MutexGuard<'static, Option<HashMap<String, String>>> {
+    let mut messages = MESSAGES.lock().unwrap();
+    messages.get_or_insert_with(|| load(FALLBACK));
+    messages
+}
+
+/// Load the messages for `[display] locale`; English without one
+pub fn configure(config: &Config) {
+    let locale = config.display.as_ref().and_then(|display| display.locale.as_deref()).unwrap_or(FALLBACK);
+    *MESSAGES.lock().unwrap() = Some(load(locale));
+}
+
+/// Check there are messages for a locale, built in or in ./locales
+pub fn validate(locale: &str) -> Result<()> {
+    let found = candidates(locale).into_iter().any(|candidate| {
+        BUILT_IN.iter().any(|(built_in, _)| built_in.eq_ignore_ascii_case(candidate))
+            || Path::new(LOCALES_DIR).join(format!("{}.ftl", candidate)).is_file()
+    });
+    if !found {
+        let built_in: Vec<_> = BUILT_IN.iter().map(|(locale, _)| *locale).collect();
+        bail!("No translation for \"{}\" (built in: {}; or add {}/{}.ftl)", locale, built_in.join(", "), LOCALES_DIR, locale);
+    }
+    Ok(())
+}
+
+/// The message for `id` in the configured locale, or the id itself if no locale has it
+pub fn t(id: &str) -> String {
+    messages().as_ref().and_then(|messages| messages.get(id).cloned()).unwrap_or_else(|| id.to_string())
+}
+
+/// The message for `id` with its `{ $name }` placeables filled in from `args`
+pub fn tr(id: &str, args: &[(&str, String)]) -> String {
+    let mut text = t(id);
+    for (name, value) in args {
+        text = text.replace(&format!("{{ ${} }}", name), value);
+    }
+    text
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::{artnet, clock, fixtures, hold, sacn, scenes, shutdown, total_brackets, wled};
+
+/// Bumped on every effect so only the timer started by the most recent one restores the idle look
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static RESTING: AtomicBool = AtomicBool::new(false);
+
+/// Whether the idle look is showing, i.e. no effect has fired since it was last restored
+pub fn is_resting() -> bool {
+    RESTING.load(Ordering::SeqCst)
+}
+
+/// Restore the idle look `idle.delay_secs` after this effect unless another effect fires first.
+/// A `hold` still running when the delay is up is left to finish, and restores the idle look
+/// itself when it's up.
+pub fn schedule(config: &Config) {
+    let Some(cfg) = config.idle.clone() else { return };
+    RESTING.store(false, Ordering::SeqCst);
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let config = config.clone();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(clock::scaled(Duration::from_secs(cfg.delay_secs))).await;
+        if GENERATION.load(Ordering::SeqCst) != generation || shutdown::is_shutting_down() || !hold::remaining().is_zero() {
+            return;
+        }
+        println!("No effects for {}s, restoring idle look", cfg.delay_secs);
+        restore(&config).await;
+    });
+}
+
+/// Send the configured idle preset, or the `[[total_brackets]]` preset for the show total, and
+/// DMX look to the outputs
+pub async fn restore(config: &Config) {
+    let Some(cfg) = &config.idle else { return };
+    RESTING.store(true, Ordering::SeqCst);
+
+    if let Some(scene) = &cfg.scene {
+        if let Err(e) = scenes::apply(config, scene).await {
+            eprintln!("Failed to restore idle scene: {:#}", e);
+        }
+    }
+
+    let preset = total_brackets::current(config).or(cfg.wled_preset.as_deref());
+    if let (Some(wled_cfg), Some(preset)) = (&config.wled, preset) {
+        if let Err(e) = wled::WLed::reset(wled_cfg, Some(preset)).await {
+            eprintln!("Failed to restore WLED idle preset: {:#}", e);
+        }
+    }
+
+    if cfg.dmx_channels.is_empty() {
+        return;
+    }
+    fixtures::clear_frames();
+
+    if let Some(artnet_cfg) = &config.artnet {
+        let result = artnet::ArtNet::new(artnet_cfg.broadcast_address.clone(), artnet_cfg.local_address.clone(), artnet_cfg.universe)
+            .and_then(|a| a.trigger_channels(&cfg.dmx_channels));
+        if let Err(e) = result {
+            eprintln!("Failed to restore Art-Net idle look: {:#}", e);
+        }
+    }
+
+    if let Some(sacn_cfg) = &config.sacn {
+        let result = sacn::Sacn::new(sacn_cfg.broadcast_address.clone(), sacn_cfg.universe)
+            .and_then(|s| s.with_priority(sacn_cfg.priority).trigger_channels(&cfg.dmx_channels));
+        if let Err(e) = result {
+            eprintln!("Failed to restore sACN idle look: {:#}", e);
+        }
+    }
+}
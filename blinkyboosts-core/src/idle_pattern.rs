@@ -0,0 +1,146 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::{Config, PatternStyle};
+use crate::ddp::Ddp;
+use crate::sender_color::Rgb;
+use crate::{dimmer, idle, wled};
+
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);  // ~30 frames a second
+
+/// What the generator draws; starts from `[idle.pattern]` and can be changed live from the GUI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settings {
+    pub enabled: bool,
+    pub style: PatternStyle,
+    pub color: Rgb,
+    pub speed: u8,       // 1-100
+    pub brightness: u8,  // Percent, under the master dimmer
+}
+
+static SETTINGS: Mutex<Option<Settings>> = Mutex::new(None);
+
+/// Load the pattern from `[idle.pattern]`, if there is one
+pub fn configure(config: &Config) {
+    *SETTINGS.lock().unwrap() = config.idle.as_ref().and_then(|idle| idle.pattern.as_ref()).map(|cfg| Settings {
+        enabled: true,
+        style: cfg.style,
+        color: cfg.color,
+        speed: cfg.speed.clamp(1, 100),
+        brightness: cfg.brightness.min(100),
+    });
+}
+
+/// The current pattern, or None when `[idle.pattern]` isn't configured
+pub fn settings() -> Option<Settings> {
+    *SETTINGS.lock().unwrap()
+}
+
+pub fn set(settings: Settings) {
+    *SETTINGS.lock().unwrap() = Some(Settings { speed: settings.speed.clamp(1, 100), brightness: settings.brightness.min(100), ..settings });
+}
+
+/// Red to green to blue and back as `hue` goes from 0.0 to 1.0
+fn wheel(hue: f32) -> Rgb {
+    let h = hue.rem_euclid(1.0) * 3.0;
+    let ramp = |x: f32| (x.clamp(0.0, 1.0) * 255.0) as u8;
+    match h as u8 {
+        0 => [ramp(1.0 - h), ramp(h), 0],
+        1 => [0, ramp(2.0 - h), ramp(h - 1.0)],
+        _ => [ramp(h - 2.0), 0, ramp(3.0 - h)],
+    }
+}
+
+fn scale(color: Rgb, level: f32) -> Rgb {
+    color.map(|c| (c as f32 * level.clamp(0.0, 1.0)) as u8)
+}
+
+/// Draws frames for one strip, keeping the sparkle levels between frames
+struct Generator {
+    leds: usize,
+    sparkles: Vec<f32>,
+}
+
+impl Generator {
+    fn new(leds: usize) -> Self {
+        Self { leds, sparkles: vec![0.0; leds] }
+    }
+
+    /// The frame at `elapsed` seconds into the pattern
+    fn frame(&mut self, settings: &Settings, elapsed: f32) -> Vec<Rgb> {
+        let speed = settings.speed as f32 / 20.0;  // At 100 the rainbow scrolls by once a second
+        let pixels: Vec<Rgb> = match settings.style {
+            PatternStyle::Rainbow => (0..self.leds)
+                .map(|idx| wheel(idx as f32 / self.leds.max(1) as f32 + elapsed * speed * 0.2))
+                .collect(),
+            PatternStyle::Breathing => {
+                let level = 0.5 - 0.5 * (elapsed * speed * std::f32::consts::PI * 0.4).cos();
+                vec![scale(settings.color, 0.05 + 0.95 * level); self.leds]
+            }
+            PatternStyle::Sparkle => {
+                let fade = 1.0 - 0.05 * speed.max(0.1);
+                for level in self.sparkles.iter_mut() {
+                    *level *= fade;
+                    if rand::random::<f32>() < 0.01 * speed {
+                        *level = 1.0;
+                    }
+                }
+                self.sparkles.iter().map(|level| scale(settings.color, *level)).collect()
+            }
+        };
+        let brightness = settings.brightness as f32 / 100.0;
+        pixels.into_iter()
+            .map(|pixel| scale(pixel, brightness).map(dimmer::scale))
+            .collect()
+    }
+}
+
+/// Stream the pattern over DDP while the idle look is showing. When an effect ends the idle
+/// time, WLED is told to leave realtime mode so the effect's preset shows straight away.
+pub async fn run(config: Config) {
+    let Some(cfg) = config.idle.as_ref().and_then(|idle| idle.pattern.clone()) else { return };
+    let Some(host) = cfg.host.clone().or_else(|| config.wled.as_ref().map(|w| w.host.clone())) else {
+        eprintln!("Idle pattern needs idle.pattern.host or a [wled] host");
+        return;
+    };
+    let mut ddp = match Ddp::new(&host) {
+        Ok(ddp) => ddp,
+        Err(e) => {
+            eprintln!("Failed to start idle pattern: {:#}", e);
+            return;
+        }
+    };
+
+    println!("Idle pattern on {} (LEDs {}-{})", host, cfg.start, cfg.start + cfg.leds);
+    let mut generator = Generator::new(cfg.leds);
+    let started = Instant::now();
+    let mut streaming = false;
+    let mut failing = false;
+
+    loop {
+        let settings = settings().filter(|settings| settings.enabled && idle::is_resting());
+        match settings {
+            Some(settings) => {
+                match ddp.send_pixels(cfg.start, &generator.frame(&settings, started.elapsed().as_secs_f32())) {
+                    Ok(()) => failing = false,
+                    Err(e) if !failing => {
+                        eprintln!("Failed to send idle pattern: {:#}", e);
+                        failing = true;
+                    }
+                    Err(_) => {}
+                }
+                streaming = true;
+            }
+            None if streaming => {
+                streaming = false;
+                if let Some(wled_cfg) = &config.wled {
+                    if let Err(e) = wled::WLed::set_raw_state(wled_cfg, serde_json::json!({ "live": false })).await {
+                        eprintln!("Failed to end WLED realtime mode: {:#}", e);
+                    }
+                }
+            }
+            None => {}
+        }
+        tokio::time::sleep(FRAME_INTERVAL).await;
+    }
+}
@@ -0,0 +1,79 @@
+//! Boost sources, the effects engine and lighting outputs behind BlinkyBoosts.
+//!
+//! `listeners` ties them together: boosts and zaps from Boostboard, NWC and nostr zap
+//! receipts are counted and turned into effects, with progress reported as `Event`s.
+
+pub mod amount_patterns;
+pub mod amounts;
+pub mod analytics;
+pub mod artnet;
+pub mod artnet_input;
+pub mod audio;
+pub mod backfill;
+pub mod boost_log;
+pub mod boostboard;
+pub mod boosts;
+pub mod bus;
+pub mod bundle;
+pub mod campaigns;
+pub mod capture;
+pub mod clock;
+pub mod config;
+pub mod ddp;
+pub mod dedup;
+pub mod dimmer;
+pub mod effect_log;
+pub mod effects;
+pub mod emoji;
+pub mod episode_lock;
+pub mod fixtures;
+pub mod goal;
+#[cfg(feature = "harness")]
+pub mod harness;
+pub mod health;
+pub mod hold;
+pub mod i18n;
+pub mod idle;
+pub mod idle_pattern;
+pub mod listeners;
+pub mod memo;
+pub mod messages;
+pub mod metrics;
+pub mod mock;
+pub mod moderation;
+pub mod mute;
+pub mod notes;
+pub mod notify;
+pub mod nwc;
+pub mod nwc_setup;
+pub mod osc;
+pub mod outbox;
+pub mod payment_qr;
+pub mod plugins;
+pub mod podcast_index;
+pub mod preview;
+pub mod profiles;
+pub mod rate_limit;
+pub mod reconnect;
+pub mod recovery;
+pub mod rehearsal;
+pub mod sacn;
+pub mod sanitize;
+pub mod sat_tracker;
+pub mod scenes;
+pub mod sequencer;
+pub mod sender_color;
+pub mod show_sync;
+pub mod shutdown;
+pub mod source_labels;
+pub mod streaks;
+pub mod summary;
+pub mod tags;
+pub mod templates;
+pub mod test_run;
+pub mod toggle_stats;
+pub mod total_brackets;
+pub mod wled;
+pub mod wled_discovery;
+pub mod wled_playlist;
+pub mod zaps;
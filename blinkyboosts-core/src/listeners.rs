@@ -0,0 +1,900 @@
+use nostr_sdk::Timestamp;
+use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use anyhow::{Context, Result};
+
+use crate::backfill::Backfill;
+use crate::messages::{ComponentStatus, Event};
+use crate::reconnect::{self, ResumeState};
+use crate::{amounts, boostboard, boosts, bus, campaigns, clock, config, dedup, effects, episode_lock, metrics, moderation, nwc, podcast_index, recovery, sanitize, sat_tracker, sequencer, show_sync, shutdown, streaks, templates, total_brackets, zaps};
+
+const BOOSTBOARD_RESUME_PATH: &str = "./boostboard_resume.json";
+const RELAY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+const RELAY_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+pub type ListenerHandles = Arc<Mutex<HashMap<String, (JoinHandle<()>, CancellationToken)>>>;
+
+// ============================================================================
+// Utility Functions
+// ============================================================================
+
+fn parse_timestamp(s: &str) -> Result<Timestamp> {
+    s.parse::<u64>()
+        .map(Timestamp::from_secs)
+        .context("Failed to parse timestamp as unix seconds")
+}
+
+fn parse_load_since(load_since_str: Option<&String>, default: Timestamp) -> Timestamp {
+    load_since_str
+        .and_then(|s| parse_timestamp(s).ok().inspect(|_| println!("Loading since: {}", s)))
+        .unwrap_or(default)
+}
+
+// ============================================================================
+// Boost Processing
+// ============================================================================
+
+/// A payment as delivered by a listener
+pub struct IncomingBoost {
+    pub source: &'static str,
+    pub sats: i64,
+    pub msats: i64,  // Exact amount; sats is this rounded down
+    pub sender: Option<String>,
+    pub sender_pubkey: Option<String>,
+    pub message: Option<String>,
+    pub episode_guid: Option<String>,
+    pub episode: Option<String>,
+    pub remote: Option<boosts::RemoteItem>,  // Guest feed or song the boost was sent for
+    pub artwork: Option<String>,  // Image for the episode or remote item, from the Podcast Index
+    pub event_ts: Option<Timestamp>,
+}
+
+impl IncomingBoost {
+    pub fn from_boostagram(source: &'static str, boost: &boosts::Boostagram, event_ts: Option<Timestamp>) -> Self {
+        Self {
+            source,
+            sats: boost.sats,
+            msats: if boost.msats > 0 { boost.msats } else { amounts::sats_to_msats(boost.sats) },
+            sender: Some(boost.sender_name.clone()).filter(|name| !name.is_empty()),
+            sender_pubkey: None,
+            message: Some(boost.message.clone()).filter(|message| !message.is_empty()),
+            episode_guid: Some(boost.episode_guid.clone()).filter(|guid| !guid.is_empty()),
+            episode: Some(boost.episode.clone()).filter(|episode| !episode.is_empty()),
+            remote: boost.remote(),
+            artwork: None,
+            event_ts,
+        }
+    }
+}
+
+pub async fn process_boost(
+    boost: IncomingBoost,
+    tx: &tokio::sync::mpsc::Sender<Event>,
+    tracker: &Arc<Mutex<sat_tracker::SatTracker>>,
+    effects: &effects::Effects,
+    trigger_effects_flag: bool
+) {
+    let received = Instant::now();
+    let trigger_effects_flag = trigger_effects_flag && !shutdown::is_shutting_down();
+    episode_lock::observe(boost.episode_guid.as_deref(), boost.episode.as_deref());
+    if !episode_lock::allows(boost.episode_guid.as_deref()) {
+        println!("Ignoring {} sats from {} for another episode than the locked one", boost.sats, boost.source);
+        return;
+    }
+    let boost = enrich(boost);
+
+    let (total, new_session, campaigns) = {
+        let mut tracker = tracker.lock().await;
+        let new_session = tracker.add_to_session(boost.episode_guid.as_deref(), boost.episode.as_deref(), boost.sats);
+        let campaigns = campaigns::count(effects.config(), &mut tracker, boost.message.as_deref(), boost.sats);
+        (tracker.add(boost.source, boost.msats), new_session, campaigns)
+    };
+    println!("{} received: {} sats, total now: {} sats", boost.source, boost.sats, total);
+
+    let _ = tx.send(Event::UpdateSatTotal(total)).await;
+    if trigger_effects_flag {
+        total_brackets::update(effects.config(), total);
+    }
+
+    if let Some(label) = new_session.and_then(|session| session.episode) {
+        start_episode(&label, tx, effects, trigger_effects_flag).await;
+    }
+
+    for (campaign, campaign_total, reached) in campaigns {
+        let _ = tx.send(Event::CampaignTotal(campaign.name.clone(), campaign_total, campaign.target_sats)).await;
+        if reached {
+            println!("Campaign {} reached its goal of {} sats", campaign.name, campaign.target_sats);
+        }
+        if let (Some(toggle), true) = (&campaign.toggle, reached && trigger_effects_flag) {
+            let _in_flight = shutdown::track();
+            match effects.fire_toggle(toggle).await {
+                Some(Ok(effects)) => println!("Campaign toggle fired: {}", metrics::describe(&effects)),
+                Some(Err(e)) => eprintln!("Failed to fire campaign toggle {}: {:#}", toggle, e),
+                None => eprintln!("Campaign toggle {} not found", toggle),
+            }
+        }
+    }
+
+    match moderation::check(&boost, trigger_effects_flag) {
+        moderation::Verdict::Allow => deliver_boost(boost, tx, tracker, effects, trigger_effects_flag, received).await,
+        moderation::Verdict::Blocked => {
+            println!("Sender is blocked, skipping effects and message");
+            let boost = IncomingBoost { message: None, ..boost };
+            deliver_boost(boost, tx, tracker, effects, false, received).await;
+        }
+        moderation::Verdict::Hold => {
+            let (id, dropped) = moderation::hold(boost);
+            println!("Holding boost #{} from a new sender for approval", id);
+            if let Some(dropped) = dropped {
+                println!("Too many boosts held, rejecting the one held longest");
+                let dropped = IncomingBoost { message: None, event_ts: None, ..dropped };
+                deliver_boost(dropped, tx, tracker, effects, false, Instant::now()).await;
+            }
+        }
+    }
+}
+
+/// Fill in the titles a boost arrived without (it only had guids) and artwork from the Podcast
+/// Index. Only cached lookups are used, so effects never wait on the API: the first boost for an
+/// episode or item fetches it in the background for the ones after. Does nothing without a
+/// [podcast_index] section.
+fn enrich(mut boost: IncomingBoost) -> IncomingBoost {
+    if !podcast_index::enabled() {
+        return boost;
+    }
+
+    if let Some(guid) = &boost.episode_guid {
+        if let Some(found) = podcast_index::episode(guid) {
+            boost.episode = boost.episode.or(found.title);
+            boost.artwork = found.artwork;
+        }
+    }
+
+    if let Some(remote) = &mut boost.remote {
+        if let Some(found) = podcast_index::remote(remote) {
+            remote.title = remote.title.take().or(found.title);
+            boost.artwork = found.artwork.or(boost.artwork);
+        }
+    }
+    boost
+}
+
+/// Announce a new episode session, firing the configured toggle for live boosts
+async fn start_episode(label: &str, tx: &tokio::sync::mpsc::Sender<Event>, effects: &effects::Effects, live: bool) {
+    println!("New episode: {}", label);
+    let _ = tx.send(Event::EpisodeStarted(label.to_string())).await;
+
+    let toggle = effects.config().episodes.as_ref().and_then(|cfg| cfg.toggle.as_deref());
+    if let (Some(toggle), true) = (toggle, live) {
+        let _in_flight = shutdown::track();
+        match effects.fire_toggle(toggle).await {
+            Some(Ok(effects)) => println!("New episode toggle fired: {}", metrics::describe(&effects)),
+            Some(Err(e)) => eprintln!("Failed to fire new episode toggle {}: {:#}", toggle, e),
+            None => eprintln!("New episode toggle {} not found", toggle),
+        }
+    }
+}
+
+/// Apply an operator's decision to a held boost; approved boosts fire their effects now.
+/// Returns false if no boost with that id is waiting.
+pub async fn moderate(
+    id: u64,
+    decision: moderation::Decision,
+    tx: &tokio::sync::mpsc::Sender<Event>,
+    tracker: &Arc<Mutex<sat_tracker::SatTracker>>,
+    effects: &effects::Effects
+) -> bool {
+    let Some(boost) = moderation::decide(id, decision) else { return false };
+    println!("Held boost #{}: {:?}", id, decision);
+
+    // Relay delay would include the time spent waiting for approval
+    let boost = IncomingBoost { event_ts: None, ..boost };
+    if decision == moderation::Decision::Approve {
+        deliver_boost(boost, tx, tracker, effects, !shutdown::is_shutting_down(), Instant::now()).await;
+    } else {
+        deliver_boost(IncomingBoost { message: None, ..boost }, tx, tracker, effects, false, Instant::now()).await;
+    }
+    true
+}
+
+/// Fire effects for a counted boost that passed moderation, then announce it on the bus
+async fn deliver_boost(
+    boost: IncomingBoost,
+    tx: &tokio::sync::mpsc::Sender<Event>,
+    tracker: &Arc<Mutex<sat_tracker::SatTracker>>,
+    effects: &effects::Effects,
+    trigger_effects_flag: bool,
+    received: Instant
+) {
+    if trigger_effects_flag {
+        show_sync::publish(&boost);
+    }
+
+    let IncomingBoost { source, sats, msats, sender, sender_pubkey, message, episode_guid, episode, remote, artwork, event_ts, .. } = boost;
+    // History and backfilled boosts were counted when they arrived live
+    let recognition = if trigger_effects_flag {
+        streaks::record(sender_pubkey.as_deref(), sender.as_deref(), episode_guid.as_deref().or(episode.as_deref()))
+    } else {
+        None
+    };
+    let episode = episode.or(episode_guid);
+    let relay_delay = event_ts.map(metrics::relay_delay);
+
+    if let Some(message) = &message {
+        println!("Message: {}", sanitize::clean("console", message));
+    }
+
+    let triggered = if trigger_effects_flag {
+        if let Some(delay) = relay_delay {
+            println!("{} event arrived {} after creation", source, metrics::format_duration(delay));
+        }
+        let _in_flight = shutdown::track();
+        let total = tracker.lock().await.total();
+        let vars = templates::Vars {
+            source: source.to_string(),
+            sats,
+            msats,
+            sender: sender.clone(),
+            sender_pubkey,
+            message: message.clone(),
+            episode: episode.clone(),
+            remote: remote.clone(),
+            artwork: artwork.clone(),
+            ..Default::default()
+        }.with_total(effects.config(), total);
+        if let Some(recognition) = recognition {
+            recognize(&vars, &recognition, tx, effects).await;
+        }
+        effects.trigger_effects(&vars, Some(tracker.clone()), received).await.unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    effects.bus().publish(bus::BoostEvent {
+        id: 0,
+        source: source.to_string(),
+        sats,
+        sender,
+        message,
+        episode,
+        remote_item: remote.map(|r| r.label()),
+        artwork,
+        effects: triggered,
+        relay_delay: if trigger_effects_flag { relay_delay } else { None },
+        live: trigger_effects_flag,
+        received_at: chrono::Utc::now().timestamp(),
+    }).await;
+}
+
+/// Fire the newest boost's effects again, e.g. when the lights missed it. Nothing is counted, so
+/// totals and threshold state stay as they are. Returns false when there's no boost to replay.
+pub async fn replay_last_boost(tracker: &Arc<Mutex<sat_tracker::SatTracker>>, effects: &effects::Effects) -> bool {
+    let (last, total) = {
+        let tracker = tracker.lock().await;
+        (tracker.recent().into_iter().next(), tracker.total())
+    };
+    let Some(last) = last else { return false };
+
+    println!("Replaying {} sats from {}", last.sats, last.sender.as_deref().unwrap_or("anonymous"));
+    let vars = templates::Vars {
+        source: last.source,
+        sats: last.sats,
+        sender: last.sender,
+        message: last.message,
+        episode: last.episode,
+        ..Default::default()
+    }.with_total(effects.config(), total);
+    let _in_flight = shutdown::track();
+    effects.trigger_effects(&vars, None, Instant::now()).await.unwrap_or_default();
+    true
+}
+
+/// Call out a returning booster, firing the `[streaks]` toggle for their streak
+async fn recognize(vars: &templates::Vars, recognition: &streaks::Recognition, tx: &tokio::sync::mpsc::Sender<Event>, effects: &effects::Effects) {
+    let name = vars.sender.clone().unwrap_or_else(|| "anonymous".to_string());
+    match recognition {
+        streaks::Recognition::Milestone(streak) => println!("{} has boosted {} episodes in a row", name, streak),
+        streaks::Recognition::Returning(streak) => println!("Returning booster: {} ({} in a row)", name, streak),
+    }
+    let _ = tx.send(Event::Streak(name, recognition.streak(), recognition.is_milestone())).await;
+
+    if let Some(toggle) = streaks::toggle(effects.config(), recognition) {
+        match effects.fire_toggle(&toggle).await {
+            Some(Ok(effects)) => println!("Streak toggle fired: {}", metrics::describe(&effects)),
+            Some(Err(e)) => eprintln!("Failed to fire streak toggle {}: {:#}", toggle, e),
+            None => eprintln!("Streak toggle {} not found", toggle),
+        }
+    }
+}
+
+async fn sync_threshold_triggers(config: &config::Config, tracker: &Arc<Mutex<sat_tracker::SatTracker>>) {
+    if let Some(toggles) = &config.toggles {
+        let thresholds: Vec<i64> = toggles.iter()
+            .filter(|t| !t.is_default && t.use_total && t.threshold > 0)
+            .map(|t| t.threshold)
+            .collect();
+
+        if let Some(&max_threshold) = thresholds.iter().max() {
+            tracker.lock().await.sync_trigger_state(max_threshold);
+        }
+    }
+}
+
+// ============================================================================
+// History Loading
+// ============================================================================
+
+/// History a listener doesn't need to load itself because `load_history` already counted it
+#[derive(Clone, Copy)]
+struct Preloaded {
+    started: Timestamp,         // When loading began; live boosts from then on come from the subscription
+    latest: Option<Timestamp>,  // Newest boost loaded, where the source resumes from it
+}
+
+static PRELOADED: std::sync::Mutex<BTreeMap<&'static str, Preloaded>> = std::sync::Mutex::new(BTreeMap::new());
+
+fn take_preloaded(source: &str) -> Option<Preloaded> {
+    PRELOADED.lock().unwrap().remove(source)
+}
+
+async fn connect_nwc(cfg: &config::NWC, config: &config::Config) -> Result<nwc::NWC> {
+    let filters = boostboard::BoostFilters::from_config(&cfg.filters);
+    Ok(nwc::NWC::new(&cfg.uri, filters).await
+        .context("Connection error")?
+        .with_plain_payments(config.qr.is_some())
+        .with_memo_boosts(cfg.memo_boosts)
+        .with_seen(dedup::SeenEvents::for_run("nwc")))
+}
+
+/// Stored boosts from the Boostboard API. A page that still fails after `page_retries` ends the
+/// load early, keeping what came before it.
+async fn fetch_boostboard_history(cfg: &config::BoostBoard, tx: &tokio::sync::mpsc::Sender<Event>) -> Vec<boosts::Boostagram> {
+    println!("Loading stored boosts from API...");
+    let backfill = Backfill::start("Boostboard", tx).await;
+    let mut stored_boosts = boostboard::StoredBoosts::new(boostboard::BoostFilters::from_config(&cfg.filters))
+        .with_cancel(backfill.token());
+    if cfg.resume_history {
+        stored_boosts = stored_boosts.with_resume(BOOSTBOARD_RESUME_PATH);
+    }
+
+    let mut loaded = Vec::new();
+    let result = stored_boosts.load_with_errors(|boost: boosts::Boostagram| {
+        let backfill = backfill.clone();
+        let created_at = boost.creation_date;
+        loaded.push(boost);
+        async move { backfill.loaded(created_at).await }
+    }, |_, failures, _| match failures <= cfg.page_retries {
+        true => boostboard::PageAction::Retry,
+        false => boostboard::PageAction::Abort,
+    }).await;
+    backfill.finish().await;
+
+    if let Err(e) = result {
+        eprintln!("Stored boosts load stopped early: {:#}", e);
+    }
+    loaded
+}
+
+async fn fetch_nwc_history(
+    nwc: &nwc::NWC,
+    load_since: Timestamp,
+    tx: &tokio::sync::mpsc::Sender<Event>
+) -> Result<(Vec<boosts::Boostagram>, Option<Timestamp>)> {
+    println!("Loading previous boosts from NWC...");
+    let backfill = Backfill::start("NWC", tx).await;
+    let history = nwc.clone().with_history_cancel(backfill.token());
+
+    let mut loaded = Vec::new();
+    let result = history.load_previous_boosts(Some(load_since), |boost: boosts::Boostagram| {
+        let backfill = backfill.clone();
+        let created_at = boost.creation_date;
+        loaded.push(boost);
+        async move { backfill.loaded(created_at).await }
+    }).await;
+    backfill.finish().await;
+
+    result.map(|latest| (loaded, latest))
+}
+
+/// Count loaded history oldest first, without firing effects, then bring threshold state in line
+async fn apply_history(
+    mut history: Vec<(&'static str, boosts::Boostagram)>,
+    tx: &tokio::sync::mpsc::Sender<Event>,
+    tracker: &Arc<Mutex<sat_tracker::SatTracker>>,
+    effects: &effects::Effects
+) {
+    // Stable, so boosts with the same timestamp keep each source's order
+    history.sort_by_key(|(_, boost)| boost.creation_date);
+
+    for (source, boost) in &history {
+        process_boost(IncomingBoost::from_boostagram(source, boost, None), tx, tracker, effects, false).await;
+    }
+
+    sync_threshold_triggers(effects.config(), tracker).await;
+}
+
+/// Load Boostboard and NWC history at the same time and count it in the order the boosts were
+/// sent, so totals and thresholds come out the same as if they'd arrived live. Listeners started
+/// afterwards skip their own history load; if NWC's failed here, it retries when it starts.
+pub async fn load_history(
+    effects: &effects::Effects,
+    tx: &tokio::sync::mpsc::Sender<Event>,
+    tracker: &Arc<Mutex<sat_tracker::SatTracker>>
+) {
+    let config = effects.config();
+    let started = Timestamp::now();
+
+    let boostboard = async {
+        let cfg = config.boostboard.as_ref()?;
+        Some(fetch_boostboard_history(cfg, tx).await)
+    };
+
+    let nwc = async {
+        let cfg = config.nwc.as_ref()?;
+        let load_since = parse_load_since(cfg.filters.load_since.as_ref(), started);
+        let loaded = match connect_nwc(cfg, config).await {
+            Ok(nwc) => fetch_nwc_history(&nwc, load_since, tx).await,
+            Err(e) => Err(e),
+        };
+        match loaded {
+            Ok(history) => Some(history),
+            Err(e) => {
+                eprintln!("NWC history load failed: {:#}", e);
+                None
+            }
+        }
+    };
+
+    let (boostboard, nwc) = tokio::join!(boostboard, nwc);
+    let mut history = Vec::new();
+
+    if let Some(boosts) = boostboard {
+        PRELOADED.lock().unwrap().insert("Boostboard", Preloaded { started, latest: None });
+        history.extend(boosts.into_iter().map(|boost| ("Boostboard", boost)));
+    }
+
+    if let Some((boosts, latest)) = nwc {
+        PRELOADED.lock().unwrap().insert("NWC", Preloaded { started, latest });
+        history.extend(boosts.into_iter().map(|boost| ("NWC", boost)));
+    }
+
+    apply_history(history, tx, tracker, effects).await;
+}
+
+/// Load one source's history again with `filters` in place of its configured ones, e.g. after
+/// changing load_since, after or before in the GUI, and recount that source's share of the total
+/// from it. Nothing fires, and episode sessions, campaigns and the show recap keep what they
+/// already counted; only the total and threshold state are brought in line.
+pub async fn reload_history(
+    source: &str,
+    filters: &config::BoostFiltersConfig,
+    effects: &effects::Effects,
+    tx: &tokio::sync::mpsc::Sender<Event>,
+    tracker: &Arc<Mutex<sat_tracker::SatTracker>>
+) {
+    let config = effects.config();
+    let (source, history) = match (source, &config.boostboard, &config.nwc) {
+        ("Boostboard", Some(cfg), _) => {
+            // Everything in the window, not just what's newer than the last run's resume point
+            let cfg = config::BoostBoard { filters: filters.clone(), resume_history: false, ..cfg.clone() };
+            ("Boostboard", fetch_boostboard_history(&cfg, tx).await)
+        }
+        ("NWC", _, Some(cfg)) => {
+            let cfg = config::NWC { filters: filters.clone(), ..cfg.clone() };
+            let load_since = parse_load_since(filters.load_since.as_ref(), Timestamp::now());
+            let loaded = match connect_nwc(&cfg, config).await {
+                Ok(nwc) => fetch_nwc_history(&nwc, load_since, tx).await,
+                Err(e) => Err(e),
+            };
+            match loaded {
+                Ok((boosts, _)) => ("NWC", boosts),
+                Err(e) => {
+                    eprintln!("NWC history reload failed: {:#}", e);
+                    return;
+                }
+            }
+        }
+        _ => {
+            eprintln!("Cannot reload history for {}: not configured or no history to load", source);
+            return;
+        }
+    };
+
+    let total = {
+        let mut tracker = tracker.lock().await;
+        tracker.forget_source(source);
+        for boost in &history {
+            tracker.add(source, IncomingBoost::from_boostagram(source, boost, None).msats);
+        }
+        tracker.total()
+    };
+    println!("{} history reloaded: {} boosts, total now: {} sats", source, history.len(), total);
+
+    let _ = tx.send(Event::UpdateSatTotal(total)).await;
+    sync_threshold_triggers(config, tracker).await;
+}
+
+// ============================================================================
+// Listeners
+// ============================================================================
+
+async fn initialize_listener(component_name: &str, tx: &tokio::sync::mpsc::Sender<Event>) {
+    let _ = tx.send(Event::UpdateStatus(component_name.to_string(), ComponentStatus::Running)).await;
+}
+
+async fn listen_for_zaps(
+    effects: effects::Effects,
+    tx: tokio::sync::mpsc::Sender<Event>,
+    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
+    cancel_token: CancellationToken
+) {
+    let resume = ResumeState::for_source("Zaps");
+    let seen = dedup::SeenEvents::open("zaps");
+
+    reconnect::run_with_reconnect("Zaps", &tx, cancel_token, || {
+        zaps_session(effects.clone(), tx.clone(), tracker.clone(), resume.clone(), seen.clone())
+    }).await;
+}
+
+async fn zaps_session(
+    effects: effects::Effects,
+    tx: tokio::sync::mpsc::Sender<Event>,
+    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
+    resume: ResumeState,
+    seen: dedup::SeenEvents
+) -> Result<()> {
+    let cfg = effects.config().zaps.clone().context("Zaps not configured")?;
+
+    let splits = zaps::SplitMode::from_config(&cfg)?;
+    let zap = zaps::Zaps::new(&cfg.relay_addrs, &cfg.naddr, seen).await
+        .context("Connection error")?
+        .with_splits(splits)
+        .with_filters(boostboard::BoostFilters::from_zaps_config(&cfg))
+        .with_live_since(resume.live_since());
+
+    initialize_listener("Zaps", &tx).await;
+    let _monitor = AbortOnDrop(tokio::spawn(monitor_relays("Zaps", zap.client().clone(), tx.clone())));
+    let _gaps = AbortOnDrop(tokio::spawn({
+        let (client, subscription, filter, resume) = (zap.client().clone(), zap.subscription_id(), zap.filter(), resume.clone());
+        async move { reconnect::backfill_gaps("Zaps", client, subscription, filter, resume).await }
+    }));
+
+    let load_since = cfg.load_since.as_ref().and_then(|s| parse_timestamp(s).ok());
+
+    println!("Waiting for Zaps...");
+
+    zap.subscribe_zaps(resume.since(load_since), |zap: zaps::Zap| {
+        let (effects, tx, tracker, resume) = (effects.clone(), tx.clone(), tracker.clone(), resume.clone());
+        async move {
+            println!("Zap: {:#?}", zap);
+            resume.mark_event(zap.created_at);
+            let incoming = IncomingBoost {
+                source: "Zaps",
+                sats: zap.value_msat_total / 1000,
+                msats: zap.value_msat_total,
+                sender: zap.sender_name.clone(),
+                sender_pubkey: Some(zap.sender_pubkey.clone()).filter(|pk| !pk.is_empty()),
+                message: zap.message.clone().filter(|message| !message.is_empty()),
+                episode_guid: None,
+                episode: None,
+                remote: None,
+                artwork: None,
+                event_ts: Some(zap.created_at),
+            };
+            process_boost(incoming, &tx, &tracker, &effects, !zap.is_old).await;
+        }
+    }).await.context("Event error")
+}
+
+async fn listen_for_boostboard(
+    effects: effects::Effects,
+    tx: tokio::sync::mpsc::Sender<Event>,
+    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
+    cancel_token: CancellationToken
+) {
+    let resume = ResumeState::for_source("Boostboard");
+    let seen = dedup::SeenEvents::open("boostboard");
+
+    reconnect::run_with_reconnect("Boostboard", &tx, cancel_token, || {
+        boostboard_session(effects.clone(), tx.clone(), tracker.clone(), resume.clone(), seen.clone())
+    }).await;
+}
+
+async fn boostboard_session(
+    effects: effects::Effects,
+    tx: tokio::sync::mpsc::Sender<Event>,
+    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
+    resume: ResumeState,
+    seen: dedup::SeenEvents
+) -> Result<()> {
+    let cfg = effects.config().boostboard.clone().context("Boostboard not configured")?;
+
+    let filters = boostboard::BoostFilters::from_config(&cfg.filters);
+
+    println!("Boostboard Filters: {:#?}", &filters);
+
+    let board = boostboard::BoostBoard::new(&cfg.relay_addrs, &cfg.pubkey, filters.clone(), seen).await
+        .context("Connection error")?;
+
+    initialize_listener("Boostboard", &tx).await;
+    let _monitor = AbortOnDrop(tokio::spawn(monitor_relays("Boostboard", board.client().clone(), tx.clone())));
+
+    let preloaded = take_preloaded("Boostboard");
+    let default_since = preloaded.map_or_else(Timestamp::now, |p| p.started);
+    let load_since = Some(parse_load_since(cfg.filters.load_since.as_ref(), default_since));
+
+    if !resume.history_loaded() {
+        if preloaded.is_none() {
+            let history = fetch_boostboard_history(&cfg, &tx).await;
+            apply_history(history.into_iter().map(|boost| ("Boostboard", boost)).collect(), &tx, &tracker, &effects).await;
+        }
+        resume.mark_history_loaded();
+    }
+
+    let subscription_id = board.subscribe(resume.since(load_since)).await
+        .context("Subscription error")?;
+
+    println!("Waiting for Boostboard boosts...");
+    let subscription_start_time = resume.live_since();
+    let _gaps = AbortOnDrop(tokio::spawn({
+        let (client, subscription, filter, resume) = (board.client().clone(), subscription_id.clone(), board.filter(), resume.clone());
+        async move { reconnect::backfill_gaps("Boostboard", client, subscription, filter, resume).await }
+    }));
+
+    board.handle_boosts(subscription_id, move |boost: boosts::Boostagram, event_ts: Timestamp| {
+        let (effects, tx, tracker, resume) = (effects.clone(), tx.clone(), tracker.clone(), resume.clone());
+        async move {
+            resume.mark_event(event_ts);
+            println!("Boost: {:#?}", boost);
+            let trigger = !boost.is_old && clock::is_new(event_ts, subscription_start_time);
+            process_boost(IncomingBoost::from_boostagram("Boostboard", &boost, Some(event_ts)), &tx, &tracker, &effects, trigger).await;
+        }
+    }).await.context("Event error")
+}
+
+async fn listen_for_nwc(
+    effects: effects::Effects,
+    tx: tokio::sync::mpsc::Sender<Event>,
+    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
+    cancel_token: CancellationToken
+) {
+    let resume = ResumeState::for_source("NWC");
+
+    reconnect::run_with_reconnect("NWC", &tx, cancel_token, || {
+        nwc_session(effects.clone(), tx.clone(), tracker.clone(), resume.clone())
+    }).await;
+}
+
+async fn nwc_session(
+    effects: effects::Effects,
+    tx: tokio::sync::mpsc::Sender<Event>,
+    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
+    resume: ResumeState
+) -> Result<()> {
+    let cfg = effects.config().nwc.clone().context("NWC not configured")?;
+
+    println!("NWC Filters: {:#?}", boostboard::BoostFilters::from_config(&cfg.filters));
+
+    let nwc = connect_nwc(&cfg, effects.config()).await?;
+
+    initialize_listener("NWC", &tx).await;
+
+    let preloaded = take_preloaded("NWC");
+    let default_since = preloaded.map_or_else(Timestamp::now, |p| p.started);
+    let load_since = parse_load_since(cfg.filters.load_since.as_ref(), default_since);
+
+    if !resume.history_loaded() {
+        let latest_boost_timestamp = match preloaded {
+            Some(preloaded) => preloaded.latest,
+            None => {
+                let (history, latest) = fetch_nwc_history(&nwc, load_since, &tx).await.unwrap_or_default();
+                apply_history(history.into_iter().map(|boost| ("NWC", boost)).collect(), &tx, &tracker, &effects).await;
+                latest
+            }
+        };
+
+        if let Some(ts) = latest_boost_timestamp {
+            resume.mark_event(ts);
+        }
+        resume.mark_history_loaded();
+    }
+
+    let monitor = (cfg.wallet_check_secs > 0).then(|| tokio::spawn(monitor_nwc_wallet(nwc.clone(), cfg.wallet_check_secs, tx.clone())));
+    let _monitor = monitor.map(AbortOnDrop);
+
+    let subscription_start = resume.since(Some(load_since)).unwrap_or(load_since);
+    println!("Waiting for NWC boosts...");
+    let live_since = resume.live_since();
+
+    nwc.subscribe_boosts(subscription_start, |boost: boosts::Boostagram, event_ts: Timestamp| {
+        let (effects, tx, tracker, resume) = (effects.clone(), tx.clone(), tracker.clone(), resume.clone());
+        async move {
+            resume.mark_event(event_ts);
+            println!("NWC Boost: {:#?}", boost);
+            let trigger = clock::is_new(event_ts, live_since);
+            process_boost(IncomingBoost::from_boostagram("NWC", &boost, Some(event_ts)), &tx, &tracker, &effects, trigger).await;
+        }
+    }, |status: nwc::PollStatus| {
+        let status = match status {
+            nwc::PollStatus::Failing { failures, error, retry_in_ms } => ComponentStatus::Error(
+                format!("Wallet not responding ({} failed polls, retrying in {}s): {}", failures, retry_in_ms / 1000, error)
+            ),
+            nwc::PollStatus::Recovered => ComponentStatus::Running,
+        };
+        let _ = tx.try_send(Event::UpdateStatus("NWC".to_string(), status));
+    }).await.context("Event error")
+}
+
+/// Aborts a spawned task when the session that started it ends
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Time the relays' round trip every minute for the GUI's graphs, while the session lasts
+async fn monitor_relays(source: &str, client: nostr_sdk::Client, tx: tokio::sync::mpsc::Sender<Event>) {
+    loop {
+        let round_trip = metrics::relay_round_trip(&client, RELAY_CHECK_TIMEOUT).await;
+        let _ = tx.send(Event::RelayRoundTrip(source.to_string(), round_trip)).await;
+        tokio::time::sleep(RELAY_CHECK_INTERVAL).await;
+    }
+}
+
+/// Report the wallet's balance and round trip, and flag it as failing when it stops answering
+async fn monitor_nwc_wallet(nwc: nwc::NWC, interval_secs: u64, tx: tokio::sync::mpsc::Sender<Event>) {
+    let failing = std::sync::atomic::AtomicBool::new(false);
+    nwc.monitor_wallet(std::time::Duration::from_secs(interval_secs), |status| match status {
+        Ok(status) => {
+            if failing.swap(false, std::sync::atomic::Ordering::SeqCst) {
+                println!("NWC wallet answering again");
+                let _ = tx.try_send(Event::UpdateStatus("NWC".to_string(), ComponentStatus::Running));
+            }
+            let _ = tx.try_send(Event::WalletStatus(status));
+        }
+        Err(e) => {
+            eprintln!("NWC wallet check failed: {:#}", e);
+            failing.store(true, std::sync::atomic::Ordering::SeqCst);
+            let _ = tx.try_send(Event::UpdateStatus("NWC".to_string(), ComponentStatus::Error(format!("Wallet not responding: {:#}", e))));
+        }
+    }).await;
+}
+
+// ============================================================================
+// Listener Management
+// ============================================================================
+
+pub async fn start_listener(
+    name: &str,
+    handles: &ListenerHandles,
+    effects: &effects::Effects,
+    tx: &tokio::sync::mpsc::Sender<Event>,
+    tracker: &Arc<Mutex<sat_tracker::SatTracker>>
+) {
+    stop_listener(name, handles).await;
+
+    let config = effects.config();
+
+    let cancel_token = CancellationToken::new();
+    let cancel_clone = cancel_token.clone();
+
+    let handle = match name {
+        "Zaps" if config.zaps.is_some() => {
+            let (effects, tx, tracker) = (effects.clone(), tx.clone(), tracker.clone());
+            tokio::spawn(async move { listen_for_zaps(effects, tx, tracker, cancel_clone).await })
+        },
+        "Boostboard" if config.boostboard.as_ref().is_some_and(|b| b.relay_addrs.is_empty()) => {
+            eprintln!("Error: No relay addresses specified for boostboard");
+            let _ = tx.send(Event::UpdateStatus("Boostboard".to_string(), ComponentStatus::Error("No relay addresses specified".to_string()))).await;
+            return;
+        },
+        "Boostboard" if config.boostboard.is_some() => {
+            let (effects, tx, tracker) = (effects.clone(), tx.clone(), tracker.clone());
+            tokio::spawn(async move { listen_for_boostboard(effects, tx, tracker, cancel_clone).await })
+        },
+        "NWC" if config.nwc.is_some() => {
+            let (effects, tx, tracker) = (effects.clone(), tx.clone(), tracker.clone());
+            tokio::spawn(async move { listen_for_nwc(effects, tx, tracker, cancel_clone).await })
+        },
+        _ => {
+            eprintln!("Cannot start {}: not configured or unknown", name);
+            return;
+        }
+    };
+
+    handles.lock().await.insert(name.to_string(), (handle, cancel_token));
+}
+
+pub async fn stop_listener(
+    name: &str,
+    handles: &ListenerHandles
+) {
+    if let Some((handle, cancel_token)) = handles.lock().await.remove(name) {
+        println!("Cancelling {} listener...", name);
+        cancel_token.cancel();
+        let _ = tokio::time::timeout(std::time::Duration::from_secs(5), handle).await;
+        println!("{} listener stopped", name);
+    }
+}
+
+/// Restart listener tasks that exited or panicked without being cancelled
+pub async fn supervise_listeners(
+    handles: ListenerHandles,
+    effects: effects::Effects,
+    tx: tokio::sync::mpsc::Sender<Event>,
+    tracker: Arc<Mutex<sat_tracker::SatTracker>>
+) {
+    let policy = effects.config().watchdog.clone().unwrap_or_default();
+    if !policy.enabled {
+        return;
+    }
+
+    let window = std::time::Duration::from_secs(policy.restart_window_secs);
+    let mut restarts: HashMap<String, Vec<Instant>> = HashMap::new();
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(policy.check_interval_secs.max(1)));
+
+    loop {
+        interval.tick().await;
+
+        let exited: Vec<(String, JoinHandle<()>)> = {
+            let mut guard = handles.lock().await;
+            let names: Vec<String> = guard.iter()
+                .filter(|(_, (handle, cancel_token))| handle.is_finished() && !cancel_token.is_cancelled())
+                .map(|(name, _)| name.clone())
+                .collect();
+            names.into_iter()
+                .filter_map(|name| guard.remove(&name).map(|(handle, _)| (name, handle)))
+                .collect()
+        };
+
+        for (name, handle) in exited {
+            let reason = match handle.await {
+                Err(e) if e.is_panic() => "panicked",
+                _ => "exited",
+            };
+            eprintln!("Watchdog: {} listener {} unexpectedly", name, reason);
+
+            let history = restarts.entry(name.clone()).or_default();
+            history.retain(|t| t.elapsed() < window);
+
+            if history.len() >= policy.max_restarts {
+                let error_msg = format!("Listener {}; gave up after {} restarts", reason, history.len());
+                eprintln!("Watchdog: {}: {}", name, error_msg);
+                let _ = tx.send(Event::UpdateStatus(name, ComponentStatus::Error(error_msg))).await;
+                continue;
+            }
+
+            history.push(Instant::now());
+            println!("Watchdog: restarting {} listener (restart {} of {})", name, history.len(), policy.max_restarts);
+            let _ = tx.send(Event::UpdateStatus(name.clone(), ComponentStatus::Error(format!("Listener {}, restarting", reason)))).await;
+            start_listener(&name, &handles, &effects, &tx, &tracker).await;
+        }
+    }
+}
+
+/// Stop new effects, let in-flight ones finish, cancel listeners, then reset outputs
+pub async fn shutdown_gracefully(config: &config::Config, handles: &ListenerHandles) {
+    println!("Shutting down...");
+    shutdown::begin();
+    sequencer::cancel_all();
+
+    let drain_timeout = config.shutdown.clone().unwrap_or_default().drain_timeout_secs;
+    shutdown::drain(std::time::Duration::from_secs(drain_timeout)).await;
+
+    let names: Vec<String> = handles.lock().await.keys().cloned().collect();
+    for name in names {
+        stop_listener(&name, handles).await;
+    }
+
+    shutdown::reset_outputs(config).await;
+    recovery::finish();
+    dedup::flush_all();
+    streaks::flush();
+    println!("Shutdown complete");
+}
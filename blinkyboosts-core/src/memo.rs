@@ -0,0 +1,119 @@
+use serde_json::Value;
+
+use crate::amounts;
+use crate::boosts::Boostagram;
+
+pub const MEMO_BOOST_TYPE: &str = "invoice_memo";
+const RSS_PAYMENT_PREFIX: &str = "rss::payment::";
+
+fn field(json: &Value, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| json.get(*key).and_then(Value::as_str))
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn from_json(json: &Value) -> Option<Boostagram> {
+    json.as_object()?;
+    if !["action", "podcast", "feedID", "message", "app_name"].iter().any(|key| json.get(*key).is_some()) {
+        return None;
+    }
+
+    Some(Boostagram {
+        boost_type: MEMO_BOOST_TYPE.to_string(),
+        action: Some(field(json, &["action"])).filter(|a| !a.is_empty()).unwrap_or_else(|| "boost".to_string()),
+        identifier: field(json, &["url", "boost_link"]),
+        creation_date: 0,
+        sender_name: field(json, &["sender_name", "name", "senderName"]),
+        app_name: field(json, &["app_name", "app"]),
+        podcast: field(json, &["podcast", "feedTitle"]),
+        episode: field(json, &["episode", "itemTitle"]),
+        sats: 0,
+        msats: 0,
+        message: field(json, &["message", "msg", "comment"]),
+        event_guid: field(json, &["eventGuid", "event_guid"]),
+        episode_guid: field(json, &["episode_guid", "itemGuid"]),
+        remote_feed: Some(field(json, &["remote_feed_guid", "remoteFeedGuid"])).filter(|s| !s.is_empty()),
+        remote_item: Some(field(json, &["remote_item_guid", "remoteItemGuid"])).filter(|s| !s.is_empty()),
+        remote_item_title: Some(field(json, &["remote_item_title", "remoteItemTitle"])).filter(|s| !s.is_empty()),
+        is_old: false,
+    })
+}
+
+/// `rss::payment::<action> <url> <message>`, the Podcasting 2.0 invoice memo
+fn from_rss_payment(memo: &str) -> Option<Boostagram> {
+    let rest = memo.strip_prefix(RSS_PAYMENT_PREFIX)?;
+    let (action, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let rest = rest.trim_start();
+    let (url, message) = match rest.split_once(char::is_whitespace) {
+        Some((url, message)) if url.contains("://") => (url, message.trim()),
+        None if rest.contains("://") => (rest, ""),
+        _ => ("", rest.trim()),
+    };
+
+    Some(Boostagram {
+        boost_type: MEMO_BOOST_TYPE.to_string(),
+        action: action.to_lowercase(),
+        identifier: url.to_string(),
+        creation_date: 0,
+        sender_name: String::new(),
+        app_name: String::new(),
+        podcast: String::new(),
+        episode: String::new(),
+        sats: 0,
+        msats: 0,
+        message: message.to_string(),
+        event_guid: String::new(),
+        episode_guid: String::new(),
+        remote_feed: None,
+        remote_item: None,
+        remote_item_title: None,
+        is_old: false,
+    })
+}
+
+/// Boost details from a bolt11 invoice's memo. Apps paying an invoice instead of sending a keysend
+/// can't attach the boostagram TLV record, so they put it in the description instead, either as
+/// boostagram JSON (whole or partial, possibly after a text prefix) or as an `rss::payment::` memo.
+/// The amount always comes from the invoice rather than the memo.
+pub fn parse(description: &str, amount_msat: u64, created_at: i64) -> Option<Boostagram> {
+    let memo = description.trim();
+    let boost = from_rss_payment(memo).or_else(|| {
+        let json: Value = serde_json::from_str(&memo[memo.find('{')?..]).ok()?;
+        from_json(&json)
+    })?;
+
+    Some(Boostagram { sats: (amount_msat / 1000) as i64, msats: amounts::msats_from_u64(amount_msat), creation_date: created_at, ..boost })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rss_payment_memos_split_action_url_and_message() {
+        let boost = parse("rss::payment::Boost https://example.com/ep/1 Great show!", 21_000, 1700000000).unwrap();
+        assert_eq!(boost.action, "boost");
+        assert_eq!(boost.identifier, "https://example.com/ep/1");
+        assert_eq!(boost.message, "Great show!");
+        assert_eq!((boost.sats, boost.creation_date), (21, 1700000000));
+
+        let boost = parse("rss::payment::stream no url here", 1_000, 0).unwrap();
+        assert_eq!((boost.identifier.as_str(), boost.message.as_str()), ("", "no url here"));
+    }
+
+    #[test]
+    fn json_memos_can_follow_a_prefix() {
+        let boost = parse(r#"Boost: {"sender_name":"alice","message":"hi","podcast":"Show","sats":999999}"#, 5_500, 0).unwrap();
+        assert_eq!(boost.action, "boost");
+        assert_eq!((boost.sender_name.as_str(), boost.message.as_str(), boost.podcast.as_str()), ("alice", "hi", "Show"));
+        assert_eq!(boost.sats, 5);  // From the invoice, not the memo
+    }
+
+    #[test]
+    fn other_memos_are_not_boosts() {
+        assert!(parse("Coffee", 1_000, 0).is_none());
+        assert!(parse(r#"{"amount": 5}"#, 1_000, 0).is_none());
+        assert!(parse("{not json", 1_000, 0).is_none());
+    }
+}
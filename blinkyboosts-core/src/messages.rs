@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use crate::boost_log;
+use crate::config;
+use crate::metrics::TriggeredEffect;
+use crate::moderation::Decision;
+use crate::nwc::WalletStatus;
+use crate::nwc_setup::Capabilities;
+use crate::sat_tracker::{BoostRecord, Note};
+use crate::summary::Summary;
+use crate::test_run;
+use crate::wled_discovery;
+
+/// Connection/health state of a listener or output, as shown in the GUI
+#[derive(Clone, Debug, PartialEq)]
+pub enum ComponentStatus {
+    Disabled,
+    Enabled,
+    Running,
+    Reconnecting(u32),
+    Unreachable(String),
+    Error(String),
+}
+
+impl ComponentStatus {
+    pub fn text(&self) -> String {
+        match self {
+            Self::Disabled => "Disabled".to_string(),
+            Self::Enabled => "Enabled".to_string(),
+            Self::Running => "Running".to_string(),
+            Self::Reconnecting(attempt) => format!("Reconnecting (attempt {})", attempt),
+            Self::Unreachable(_) => "Unreachable".to_string(),
+            Self::Error(_) => "Error".to_string(),
+        }
+    }
+
+    /// The status from its `text()` and `detail()`, as reported by another instance
+    pub fn from_text(text: &str, detail: Option<String>) -> Self {
+        let detail = detail.unwrap_or_default();
+        match text {
+            "Disabled" => Self::Disabled,
+            "Enabled" => Self::Enabled,
+            "Running" => Self::Running,
+            "Unreachable" => Self::Unreachable(detail),
+            _ => match text.strip_prefix("Reconnecting (attempt ").and_then(|rest| rest.strip_suffix(')')) {
+                Some(attempt) => Self::Reconnecting(attempt.parse().unwrap_or_default()),
+                None => Self::Error(detail),
+            },
+        }
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Unreachable(msg) | Self::Error(msg) => Some(msg),
+            _ => None,
+        }
+    }
+}
+
+/// What the pipeline reports to the GUI and other front ends
+pub enum Event {
+    UpdateStatus(String, ComponentStatus),
+    BoostReceived(u64, String, i64, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Vec<TriggeredEffect>, Option<Duration>),  // id, source, sats, sender, message (unsanitized), episode, remote item, artwork URL, effects, relay delay
+    EpisodeStarted(String),  // episode title or guid
+    EpisodeLocked(Option<String>),  // title or guid of the episode boosts are limited to, None once unlocked
+    CampaignTotal(String, i64, i64),  // campaign name, total, target
+    BackfillProgress(String, usize, Option<i64>, bool),  // source, boosts loaded, latest boost timestamp, done
+    UpdateSatTotal(i64),
+    EffectFailed(String, String, u32),  // effect description, last error, attempts
+    PaymentQr(String),  // lightning: URI for the audience to pay
+    InvoiceRequested(String, u64),  // lightning: URI of an on-demand invoice, sats
+    InvoiceSettled(u64, bool),  // sats of the requested invoice, whether it was paid before expiring
+    WledEffects(Vec<String>),  // effect names offered by the controller
+    WledControllers(Vec<wled_discovery::Controller>),  // controllers found on the LAN
+    WalletStatus(WalletStatus),  // NWC wallet balance and responsiveness
+    NwcChecked(Result<Capabilities, String>),  // what a pasted wallet connection allows, or why it didn't work
+    RelayRoundTrip(String, Duration),  // source, time for its slowest relay to answer
+    BringToFront,  // another launch found this instance running
+    Streak(String, u32, bool),  // sender, episodes boosted in a row, milestone reached
+    ShowSummary(Summary),  // recap of the show just finished
+    Noted(Note),  // note set on a boost (empty text when cleared) or marker added
+    OlderBoosts(Vec<BoostRecord>, u64),  // a page from the boost log, newest first, and where the next page ends (0 when there are none older)
+    TestRun(test_run::Report),  // pass/fail of each output in a scheduled or requested test run
+}
+
+/// What the GUI, control API and Companion ask the controller task (see `controller.rs` in the app) to do
+#[allow(clippy::large_enum_variant)]
+pub enum Command {
+    TestTrigger(i64),
+    StartListener(String),
+    StopListener(String),
+    CaptureScene(String),  // scene name
+    ModerateBoost(u64, Decision),  // held boost id
+    LoadWledEffects(config::WLed),  // controller to list effects from
+    DiscoverWled,
+    CheckNwc(String),  // pasted wallet connection secret to try
+    ApplyWledSetup(config::WLed),  // presets and playlists to create on the controller
+    ReloadHistory(String, config::BoostFiltersConfig),  // source, filters with the load_since/after/before to load
+    FinishShow,
+    RunTest,  // fire the [test_run] sequence now
+    FireToggle(String),  // toggle name or index
+    LockEpisode(bool),  // true to count only boosts for the latest boost's episode, false to unlock
+    SetTagActive(String, bool),  // tag, whether its toggles may fire
+    RequestInvoice(u64),  // sats to ask the audience for with an on-demand invoice
+    ReplayLastBoost,
+    AnnotateBoost(u64, String),  // boost id, note (empty to clear)
+    AddMarker(String),
+    LoadOlderBoosts(boost_log::Cursor, usize),  // logged boosts before the cursor, at most this many
+}
@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use nostr_sdk::{Client, EventId, EventSource, Filter, Timestamp};
+
+const LATENCY_WINDOW: usize = 50;
+const ACTIVITY_WINDOW: Duration = Duration::from_secs(3600);
+
+/// An effect that was dispatched for a boost, with its timing breakdown
+#[derive(Clone, Debug)]
+pub struct TriggeredEffect {
+    pub description: String,
+    pub output: String,
+    pub queued: Duration,  // Boost received -> output dispatch started
+    pub sent: Duration,    // Output dispatch started -> packet/HTTP request sent
+    pub skipped: bool,     // Muted, gated or rate limited, so it never reached the output
+}
+
+/// The effects a toggle sent, one per output, e.g. "OSC /flash: 1 + WLED: Party"
+pub fn describe(effects: &[TriggeredEffect]) -> String {
+    effects.iter().map(|effect| effect.description.as_str()).collect::<Vec<_>>().join(" + ")
+}
+
+/// How long an event took to reach us after it was created (relay/wallet delivery lag)
+pub fn relay_delay(event_ts: Timestamp) -> Duration {
+    let created = UNIX_EPOCH + Duration::from_secs(event_ts.as_u64());
+    SystemTime::now().duration_since(created).unwrap_or_default()
+}
+
+/// Time for the slowest of a client's relays to answer a request that matches nothing; the
+/// timeout when one doesn't answer at all
+pub async fn relay_round_trip(client: &Client, timeout: Duration) -> Duration {
+    let started = Instant::now();
+    let filter = Filter::new().id(EventId::all_zeros()).limit(1);
+    let _ = client.get_events_of(vec![filter], EventSource::relays(Some(timeout))).await;
+    started.elapsed().min(timeout)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    if d.as_secs() >= 1 {
+        format!("{:.1}s", d.as_secs_f64())
+    } else {
+        format!("{}ms", d.as_millis())
+    }
+}
+
+#[derive(Default)]
+struct Window {
+    samples: VecDeque<Duration>,
+}
+
+impl Window {
+    fn push(&mut self, d: Duration) {
+        if self.samples.len() == LATENCY_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(d);
+    }
+
+    fn average(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<Duration>() / self.samples.len() as u32)
+    }
+
+    fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+}
+
+/// Rolling latency averages per pipeline stage
+#[derive(Default)]
+pub struct LatencyStats {
+    relay: Window,
+    queue: Window,
+    output: HashMap<String, Window>,
+}
+
+impl LatencyStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, relay: Option<Duration>, effects: &[TriggeredEffect]) {
+        if let Some(relay) = relay {
+            self.relay.push(relay);
+        }
+        for effect in effects {
+            self.queue.push(effect.queued);
+            self.output.entry(effect.output.clone()).or_default().push(effect.sent);
+        }
+    }
+
+    /// (stage, average, max) rows for display, relay first, then queue, then each output
+    pub fn summary(&self) -> Vec<(String, Duration, Duration)> {
+        let mut rows = Vec::new();
+
+        for (name, window) in [("Relay", &self.relay), ("Queue", &self.queue)] {
+            if let (Some(avg), Some(max)) = (window.average(), window.max()) {
+                rows.push((name.to_string(), avg, max));
+            }
+        }
+
+        let mut outputs: Vec<_> = self.output.iter().collect();
+        outputs.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, window) in outputs {
+            if let (Some(avg), Some(max)) = (window.average(), window.max()) {
+                rows.push((name.to_uppercase(), avg, max));
+            }
+        }
+
+        rows
+    }
+}
+
+#[derive(Default)]
+struct Activity {
+    events: VecDeque<Instant>,
+    round_trips: VecDeque<(Instant, Duration)>,
+}
+
+impl Activity {
+    fn prune(&mut self, now: Instant) {
+        let recent = |at: &Instant| now.duration_since(*at) < ACTIVITY_WINDOW;
+        while self.events.front().is_some_and(|at| !recent(at)) {
+            self.events.pop_front();
+        }
+        while self.round_trips.front().is_some_and(|(at, _)| !recent(at)) {
+            self.round_trips.pop_front();
+        }
+    }
+}
+
+/// Live events and relay round trips per input over the last hour, for the GUI's sparklines
+#[derive(Default)]
+pub struct InputActivity {
+    inputs: BTreeMap<String, Activity>,
+}
+
+impl InputActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_event(&mut self, input: &str) {
+        let now = Instant::now();
+        let activity = self.inputs.entry(input.to_string()).or_default();
+        activity.prune(now);
+        activity.events.push_back(now);
+    }
+
+    pub fn record_round_trip(&mut self, input: &str, round_trip: Duration) {
+        let now = Instant::now();
+        let activity = self.inputs.entry(input.to_string()).or_default();
+        activity.prune(now);
+        activity.round_trips.push_back((now, round_trip));
+    }
+
+    /// Inputs heard from so far, by name
+    pub fn inputs(&self) -> Vec<&str> {
+        self.inputs.keys().map(String::as_str).collect()
+    }
+
+    /// Events in each of the last 60 minutes, oldest first
+    pub fn events_per_minute(&self, input: &str) -> Vec<u32> {
+        let mut minutes = vec![0; (ACTIVITY_WINDOW.as_secs() / 60) as usize];
+        let now = Instant::now();
+        for at in self.inputs.get(input).map(|a| &a.events).into_iter().flatten() {
+            let ago = (now.duration_since(*at).as_secs() / 60) as usize;
+            if let Some(idx) = minutes.len().checked_sub(ago + 1) {
+                minutes[idx] += 1;
+            }
+        }
+        minutes
+    }
+
+    /// Round trips measured in the last hour, oldest first
+    pub fn round_trips(&self, input: &str) -> Vec<Duration> {
+        let now = Instant::now();
+        self.inputs.get(input).map(|a| &a.round_trips).into_iter().flatten()
+            .filter(|(at, _)| now.duration_since(*at) < ACTIVITY_WINDOW)
+            .map(|(_, round_trip)| *round_trip)
+            .collect()
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Mutex;
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::Config;
+
+/// A packet or HTTP request an output would have sent
+#[derive(Serialize, Debug, Clone)]
+pub struct MockCall {
+    pub time: String,
+    pub output: String,
+    pub target: String,
+    pub payload: Value,
+}
+
+struct MockState {
+    outputs: BTreeSet<&'static str>,
+    file: Option<String>,
+    keep: usize,
+    calls: VecDeque<MockCall>,
+}
+
+static STATE: Mutex<Option<MockState>> = Mutex::new(None);
+
+/// Note which outputs are mocked: "wled", "osc", "artnet", "sacn", "ddp" (the goal display) or
+/// "plugin" (any of them)
+pub fn configure(config: &Config) {
+    let outputs: BTreeSet<&'static str> = [
+        ("wled", config.wled.as_ref().is_some_and(|c| c.mock)),
+        ("osc", config.osc.as_ref().is_some_and(|c| c.mock)),
+        ("artnet", config.artnet.as_ref().is_some_and(|c| c.mock)),
+        ("sacn", config.sacn.as_ref().is_some_and(|c| c.mock)),
+        ("ddp", config.goal.as_ref().is_some_and(|c| c.mock)),
+        ("plugin", config.plugins.as_deref().unwrap_or_default().iter().any(|c| c.mock)),
+    ].into_iter().filter(|(_, mocked)| *mocked).map(|(output, _)| output).collect();
+
+    if outputs.is_empty() {
+        *STATE.lock().unwrap() = None;
+        return;
+    }
+
+    println!("Mocking outputs: {}", outputs.iter().copied().collect::<Vec<_>>().join(", "));
+    let cfg = config.mock.clone().unwrap_or_default();
+    *STATE.lock().unwrap() = Some(MockState { outputs, file: cfg.file, keep: cfg.keep, calls: VecDeque::new() });
+}
+
+pub fn enabled(output: &str) -> bool {
+    STATE.lock().unwrap().as_ref().is_some_and(|state| state.outputs.contains(output))
+}
+
+pub fn record(output: &str, target: impl Into<String>, payload: Value) {
+    let mut state = STATE.lock().unwrap();
+    let Some(state) = state.as_mut() else { return };
+
+    let call = MockCall {
+        time: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        output: output.to_string(),
+        target: target.into(),
+        payload,
+    };
+    println!("[mock {}] {} {}", call.output, call.target, call.payload);
+
+    if let Some(path) = &state.file {
+        let written = OpenOptions::new().create(true).append(true).open(path)
+            .and_then(|mut file| writeln!(file, "{}", serde_json::to_string(&call).unwrap_or_default()));
+        if let Err(e) = written {
+            eprintln!("Failed to write mock log {}: {}", path, e);
+        }
+    }
+
+    state.calls.push_back(call);
+    while state.calls.len() > state.keep {
+        state.calls.pop_front();
+    }
+}
+
+/// DMX frame payload listing only the lit channels (1-based), since mostly-zero frames are noise
+pub fn dmx_payload(universe: u16, data: &[u8]) -> Value {
+    let channels: serde_json::Map<String, Value> = data.iter().enumerate()
+        .filter(|(_, value)| **value > 0)
+        .map(|(idx, value)| ((idx + 1).to_string(), Value::from(*value)))
+        .collect();
+    serde_json::json!({ "universe": universe, "channels": channels })
+}
+
+/// Recorded calls, oldest first
+pub fn recent() -> Vec<MockCall> {
+    STATE.lock().unwrap().as_ref().map(|state| state.calls.iter().cloned().collect()).unwrap_or_default()
+}
@@ -0,0 +1,207 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::fs;
+use std::sync::Mutex;
+use nostr_sdk::PublicKey;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, Config};
+use crate::listeners::IncomingBoost;
+
+const MODERATION_PATH: &str = "./moderation.json";
+const MAX_HELD: usize = 200;  // Past this the longest held boost is rejected to make room
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    Allow,
+    Hold,     // First-time sender: wait for an operator
+    Blocked,  // Count the sats, but no effects or message
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Decision {
+    Approve,  // Fire the effects now and trust the sender
+    Reject,   // Count the sats without effects or message
+    Block,    // Reject and block the sender from then on
+}
+
+impl Decision {
+    pub fn parse(decision: &str) -> Option<Self> {
+        match decision.to_lowercase().as_str() {
+            "approve" => Some(Self::Approve),
+            "reject" => Some(Self::Reject),
+            "block" => Some(Self::Block),
+            _ => None,
+        }
+    }
+}
+
+/// A held boost as listed for the operator
+#[derive(Serialize, Debug, Clone)]
+pub struct PendingBoost {
+    pub id: u64,
+    pub source: String,
+    pub sats: i64,
+    pub sender: Option<String>,
+    pub message: Option<String>,  // Unsanitized
+    pub held_at: i64,
+}
+
+/// Senders trusted or blocked from the GUI or API, kept across restarts
+#[derive(Serialize, Deserialize, Default)]
+struct Saved {
+    trusted: BTreeSet<String>,
+    blocked: BTreeSet<String>,
+}
+
+struct Held {
+    id: u64,
+    held_at: i64,
+    boost: IncomingBoost,
+}
+
+struct State {
+    cfg: config::Moderation,
+    trusted: HashSet<String>,
+    blocked: HashSet<String>,
+    known: HashSet<String>,  // Senders of boosts loaded from history
+    saved: Saved,
+    queue: VecDeque<Held>,
+    next_id: u64,
+}
+
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+/// Pubkeys compare as hex so npub and hex entries match; names compare case-insensitively
+pub(crate) fn normalize(identity: &str) -> String {
+    PublicKey::parse(identity.trim())
+        .map(|pk| pk.to_hex())
+        .unwrap_or_else(|_| identity.trim().to_lowercase())
+}
+
+fn identities(boost: &IncomingBoost) -> Vec<String> {
+    [boost.sender_pubkey.as_deref(), boost.sender.as_deref()].into_iter()
+        .flatten()
+        .filter(|identity| !identity.trim().is_empty())
+        .map(normalize)
+        .collect()
+}
+
+/// Load the trust and block lists; every boost is allowed without a [moderation] section
+pub fn configure(config: &Config) {
+    let Some(cfg) = config.moderation.clone() else {
+        *STATE.lock().unwrap() = None;
+        return;
+    };
+
+    let saved: Saved = fs::read_to_string(MODERATION_PATH).ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let trusted = cfg.trusted.iter().chain(&saved.trusted).map(|s| normalize(s)).collect();
+    let blocked = cfg.blocked.iter().chain(&saved.blocked).map(|s| normalize(s)).collect();
+
+    *STATE.lock().unwrap() = Some(State {
+        cfg, trusted, blocked, known: HashSet::new(), saved, queue: VecDeque::new(), next_id: 1,
+    });
+}
+
+/// Decide what to do with a boost. Only `live` boosts (ones that would fire effects) are held;
+/// senders of earlier boosts are remembered as known when `trust_history` is set.
+pub fn check(boost: &IncomingBoost, live: bool) -> Verdict {
+    let mut state = STATE.lock().unwrap();
+    let Some(state) = state.as_mut() else { return Verdict::Allow };
+    let identities = identities(boost);
+
+    if identities.iter().any(|id| state.blocked.contains(id)) {
+        return Verdict::Blocked;
+    }
+
+    if !live {
+        if state.cfg.trust_history {
+            state.known.extend(identities);
+        }
+        return Verdict::Allow;
+    }
+
+    if !state.cfg.hold_unknown || identities.iter().any(|id| state.trusted.contains(id) || state.known.contains(id)) {
+        return Verdict::Allow;
+    }
+
+    if identities.is_empty() && !state.cfg.hold_anonymous {
+        return Verdict::Allow;
+    }
+
+    Verdict::Hold
+}
+
+/// Queue a boost for approval, returning its id and, when the queue was full, the longest held
+/// boost taken off it to make room (to be rejected)
+pub fn hold(boost: IncomingBoost) -> (u64, Option<IncomingBoost>) {
+    let mut state = STATE.lock().unwrap();
+    let Some(state) = state.as_mut() else { return (0, None) };
+
+    let id = state.next_id;
+    state.next_id += 1;
+    state.queue.push_back(Held { id, held_at: chrono::Utc::now().timestamp(), boost });
+    let dropped = match state.queue.len() > MAX_HELD {
+        true => state.queue.pop_front().map(|held| held.boost),
+        false => None,
+    };
+    (id, dropped)
+}
+
+/// Boosts waiting for approval, oldest first
+pub fn pending() -> Vec<PendingBoost> {
+    STATE.lock().unwrap().as_ref().map(|state| {
+        state.queue.iter().map(|held| PendingBoost {
+            id: held.id,
+            source: held.boost.source.to_string(),
+            sats: held.boost.sats,
+            sender: held.boost.sender.clone(),
+            message: held.boost.message.clone(),
+            held_at: held.held_at,
+        }).collect()
+    }).unwrap_or_default()
+}
+
+pub fn is_pending(id: u64) -> bool {
+    STATE.lock().unwrap().as_ref().is_some_and(|state| state.queue.iter().any(|held| held.id == id))
+}
+
+/// Take a held boost off the queue, trusting or blocking its sender as decided
+pub fn decide(id: u64, decision: Decision) -> Option<IncomingBoost> {
+    let mut state = STATE.lock().unwrap();
+    let state = state.as_mut()?;
+
+    let idx = state.queue.iter().position(|held| held.id == id)?;
+    let boost = state.queue.remove(idx)?.boost;
+    let raw: Vec<String> = [boost.sender_pubkey.clone(), boost.sender.clone()].into_iter().flatten()
+        .filter(|identity| !identity.trim().is_empty())
+        .collect();
+
+    let changed = match decision {
+        Decision::Approve if state.cfg.trust_on_approve && !raw.is_empty() => {
+            // Anyone can send under a name, so it's only trusted for senders without a pubkey
+            let trusted = raw[0].clone();
+            state.trusted.insert(normalize(&trusted));
+            state.saved.trusted.insert(trusted);
+            true
+        }
+        Decision::Block if !raw.is_empty() => {
+            state.blocked.extend(raw.iter().map(|s| normalize(s)));
+            state.saved.blocked.extend(raw);
+            true
+        }
+        _ => false,
+    };
+
+    if changed {
+        let written = serde_json::to_string_pretty(&state.saved).map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(MODERATION_PATH, json).map_err(anyhow::Error::from));
+        if let Err(e) = written {
+            eprintln!("Failed to save {}: {:#}", MODERATION_PATH, e);
+        }
+    }
+
+    Some(boost)
+}
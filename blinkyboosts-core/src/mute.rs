@@ -0,0 +1,33 @@
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use crate::config::Config;
+use crate::effects;
+
+static MUTED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Output types the configured toggles send to, e.g. "osc" or "wled"
+pub fn outputs(config: &Config) -> Vec<String> {
+    config.toggles.as_deref().unwrap_or_default().iter()
+        .flat_map(effects::outputs_of)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+pub fn is_muted(output: &str) -> bool {
+    MUTED.lock().unwrap().contains(&output.to_lowercase())
+}
+
+/// Silence an output type until unmuted; its toggles are skipped rather than queued for retry
+pub fn set_muted(output: &str, muted: bool) {
+    let output = output.to_lowercase();
+    let mut state = MUTED.lock().unwrap();
+    let changed = match muted {
+        true => state.insert(output.clone()),
+        false => state.remove(&output),
+    };
+    if changed {
+        println!("Output {} {}", output, if muted { "muted" } else { "unmuted" });
+    }
+}
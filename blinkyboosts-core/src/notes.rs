@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use anyhow::{Context, Result};
+
+use crate::sat_tracker::Note;
+
+const NOTES_PATH: &str = "./notes.jsonl";
+
+/// Keep a note or marker for `export`, one JSON object per line. A cleared note is kept as an
+/// empty one, so it overrides the boost's earlier note.
+pub fn append(note: &Note) {
+    let written = OpenOptions::new().create(true).append(true).open(NOTES_PATH)
+        .and_then(|mut file| writeln!(file, "{}", serde_json::to_string(note).unwrap_or_default()));
+    if let Err(e) = written {
+        eprintln!("Failed to write {}: {}", NOTES_PATH, e);
+    }
+}
+
+/// Every note and marker kept, oldest first: markers, and the latest note on each boost unless
+/// it was cleared. Lines that can't be read are skipped.
+pub fn load() -> Result<Vec<Note>> {
+    let text = match std::fs::read_to_string(NOTES_PATH) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).context(format!("Failed to read {}", NOTES_PATH)),
+    };
+    let kept: Vec<Note> = text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+
+    // Boost ids start over every run, so a boost is told apart by when it came in too
+    let latest: BTreeMap<(u64, i64), usize> = kept.iter().enumerate()
+        .filter_map(|(idx, note)| note.boost_id.map(|id| ((id, note.at), idx)))
+        .collect();
+    let mut notes: Vec<Note> = kept.into_iter().enumerate()
+        .filter(|(idx, note)| note.boost_id.is_none_or(|id| latest.get(&(id, note.at)) == Some(idx)))
+        .map(|(_, note)| note)
+        .filter(|note| !note.text.is_empty())
+        .collect();
+    notes.sort_by_key(|note| note.at);
+    Ok(notes)
+}
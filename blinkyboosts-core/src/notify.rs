@@ -0,0 +1,68 @@
+use std::time::Duration;
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use crate::bus::{BoostEvent, Bus};
+use crate::config::{self, Config, NotifyService};
+use crate::{amounts, i18n, outbox, sanitize, source_labels};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Push a notification for each live boost of at least `[notify] min_sats`
+pub fn attach(bus: &Bus, config: &Config) {
+    let Some(cfg) = config.notify.clone() else { return };
+    let config = config.clone();
+    bus.consume("notify", move |event| {
+        let config = config.clone();
+        let big = event.live && event.sats >= cfg.min_sats;
+        async move {
+            if big {
+                let (title, message) = describe(&event);
+                outbox::send(&config, outbox::Post::Notification { title, message }).await;
+            }
+        }
+    });
+}
+
+/// The notification's title and text: who sent how much, then their message (or the episode)
+fn describe(event: &BoostEvent) -> (String, String) {
+    let title = i18n::tr("held-boost", &[
+        ("sats", amounts::format_sats(event.sats)),
+        ("sender", event.sender.clone().unwrap_or_else(|| i18n::t("anonymous"))),
+        ("source", source_labels::label(&event.source)),
+    ]);
+    let message = event.message.as_deref().map(|m| sanitize::clean("notify", m))
+        .filter(|m| !m.trim().is_empty())
+        .or(event.episode.clone())
+        .unwrap_or_else(|| title.clone());
+    (title, message)
+}
+
+/// Send one notification through the configured service
+pub async fn send(cfg: &config::Notify, title: &str, message: &str) -> Result<()> {
+    let client = reqwest::Client::builder().timeout(TIMEOUT).build()?;
+    let server = cfg.server.trim_end_matches('/');
+    let request = match cfg.service {
+        // JSON publishing, so titles with emoji or accents don't need header encoding
+        NotifyService::Ntfy => {
+            let topic = cfg.topic.as_deref().context("notify.topic is needed for ntfy")?;
+            let request = client.post(server)
+                .json(&json!({ "topic": topic, "title": title, "message": message, "priority": cfg.priority, "tags": ["zap"] }));
+            match &cfg.token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            }
+        }
+        NotifyService::Gotify => {
+            let token = cfg.token.as_deref().context("notify.token is needed for Gotify")?;
+            client.post(format!("{}/message", server))
+                .header("X-Gotify-Key", token)
+                .json(&json!({ "title": title, "message": message, "priority": cfg.priority }))
+        }
+    };
+    request.send().await
+        .context(format!("Failed to reach {}", server))?
+        .error_for_status()
+        .context("Notification refused")?;
+    Ok(())
+}
@@ -0,0 +1,485 @@
+use crate::boosts::Boostagram;
+use crate::boostboard::BoostFilters;
+use crate::dedup::{Seen, SeenEvents};
+use crate::{amounts, clock, memo, payment_qr};
+use anyhow::{Context, Result};
+use nostr_sdk::{Client, Filter, Keys, Kind, NWC as NostrWC, RelayPoolNotification, Timestamp};
+use nostr_sdk::nips::{nip04, nip47};
+use serde::Deserialize;
+use serde_json::Value;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct NWC {
+    client: Client,
+    uri: nip47::NostrWalletConnectURI,
+    filters: BoostFilters,
+    plain_payments: bool,
+    memo_boosts: bool,
+    history_cancel: Option<CancellationToken>,
+    seen: Option<SeenEvents>,  // Payment hashes already counted, shared between history and polls
+    notifications: Arc<OnceLock<bool>>,  // Whether the wallet sends payment notifications, once subscribed
+    invoicing: Arc<OnceLock<NostrWC>>,  // Opened by the first `make_invoice` and kept for the rest
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GetInfoResult {
+    #[serde(default)]
+    pub alias: String,
+    #[serde(default)]
+    pub methods: Vec<String>,
+    #[serde(default)]
+    pub notifications: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PayNotification {
+    pub invoice: Option<String>,
+    pub amount: Option<u64>,
+    pub description: Option<String>,
+    pub metadata: Option<PayNotificationMetadata>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PayNotificationMetadata {
+    pub tlv_records: Vec<TlvRecord>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TlvRecord {
+    pub r#type: u64,
+    pub value: String,
+}
+
+const BOOST_TLV_TYPE: u64 = 7629169;
+const PLAIN_PAYMENT_TYPE: &str = "payment";
+const POLL_INTERVAL_MS: u64 = 5000;
+const MAX_POLL_INTERVAL_MS: u64 = 60_000;
+const POLL_FAILURE_THRESHOLD: u32 = 3;  // Consecutive failures before the wallet is reported as failing
+
+/// Wallet details from a periodic check
+#[derive(Debug, Clone)]
+pub struct WalletStatus {
+    pub alias: String,
+    pub balance_msats: Option<u64>,  // None when the connection isn't allowed to read the balance
+    pub latency: Duration,           // Round trip of get_info through the relay
+    pub notifications: Option<bool>, // Whether boosts arrive as notifications rather than by polling
+}
+
+/// Wallet health changes seen while polling for transactions
+#[derive(Debug, Clone)]
+pub enum PollStatus {
+    Failing { failures: u32, error: String, retry_in_ms: u64 },
+    Recovered,
+}
+
+fn poll_interval_ms(failures: u32) -> u64 {
+    POLL_INTERVAL_MS.saturating_mul(1 << failures.min(4)).min(MAX_POLL_INTERVAL_MS)
+}
+
+impl NWC {
+    pub async fn new(uri: &str, filters: BoostFilters) -> Result<Self> {
+        let uri = nip47::NostrWalletConnectURI::from_str(uri)
+            .context("Failed to parse NWC URI")?;
+
+        let client = Client::default();
+        client.add_relay(uri.relay_url.clone()).await
+            .context("Failed to add relay")?;
+
+        client.connect().await;
+        println!("Connected to NWC relay {}", &uri.relay_url);
+
+        Ok(Self {
+            client, uri, filters, plain_payments: false, memo_boosts: false, history_cancel: None, seen: None,
+            notifications: Arc::new(OnceLock::new()), invoicing: Arc::new(OnceLock::new()),
+        })
+    }
+
+    /// Also treat incoming payments without boostagram metadata (e.g. paid from the
+    /// on-screen QR code) as boosts
+    pub fn with_plain_payments(mut self, enabled: bool) -> Self {
+        self.plain_payments = enabled;
+        self
+    }
+
+    /// Look for boost details in the memo of invoice payments without boostagram metadata
+    /// (see `memo::parse`)
+    pub fn with_memo_boosts(mut self, enabled: bool) -> Self {
+        self.memo_boosts = enabled;
+        self
+    }
+
+    /// Stop `load_previous_boosts` (keeping what was loaded so far) once `token` is cancelled
+    pub fn with_history_cancel(mut self, token: CancellationToken) -> Self {
+        self.history_cancel = Some(token);
+        self
+    }
+
+    /// Remember loaded and polled payments here, so a poll that starts at the second of the
+    /// last one counted (or overlaps the previous poll) skips what was already counted
+    pub fn with_seen(mut self, seen: SeenEvents) -> Self {
+        self.seen = Some(seen);
+        self
+    }
+
+    /// Close the relay connection, e.g. once a one-off request is done
+    pub async fn disconnect(&self) {
+        if let Err(e) = self.client.disconnect().await {
+            eprintln!("Failed to disconnect from NWC relay {}: {}", self.uri.relay_url, e);
+        }
+        if let Some(wallet) = self.invoicing.get() {
+            if let Err(e) = wallet.clone().shutdown().await {
+                eprintln!("Failed to disconnect from NWC relay {}: {}", self.uri.relay_url, e);
+            }
+        }
+    }
+
+    /// Ask the wallet for a fresh invoice, returning the bolt11 string
+    pub async fn make_invoice(&self, amount_msat: u64, description: &str, expiry_secs: u64) -> Result<String> {
+        let params = nip47::MakeInvoiceRequestParams {
+            amount: amount_msat,
+            description: Some(description.to_string()),
+            description_hash: None,
+            expiry: Some(expiry_secs),
+        };
+
+        let wallet = self.invoicing.get_or_init(|| NostrWC::new(self.uri.clone()));
+        let result = wallet.make_invoice(params).await
+            .context("Wallet failed to create invoice")?;
+        Ok(result.invoice)
+    }
+
+    fn accepts(&self, boost: &Boostagram, created_at: Timestamp) -> bool {
+        self.filters.matches_timestamp(created_at.as_u64() as i64)
+            && self.filters.matches_sats(boost.sats)
+            && (boost.boost_type == PLAIN_PAYMENT_TYPE || self.filters.matches_boost(boost))
+    }
+
+    fn memo_boost(&self, amount_msat: u64, description: Option<&str>, created_at: Timestamp) -> Option<Boostagram> {
+        if !self.memo_boosts || amount_msat == 0 {
+            return None;
+        }
+        memo::parse(description?, amount_msat, created_at.as_u64() as i64)
+    }
+
+    /// A payment without boost details as a boost, when plain payments are on or it paid the
+    /// invoice requested on demand (see `payment_qr::request`)
+    fn plain_payment(&self, amount_msat: u64, description: Option<String>, created_at: Timestamp, requested: bool) -> Option<Boostagram> {
+        if !(self.plain_payments || requested) || amount_msat == 0 {
+            return None;
+        }
+
+        Some(Boostagram {
+            boost_type: PLAIN_PAYMENT_TYPE.to_string(),
+            action: "boost".to_string(),
+            identifier: String::new(),
+            creation_date: created_at.as_u64() as i64,
+            sender_name: String::new(),
+            app_name: "Lightning".to_string(),
+            podcast: String::new(),
+            episode: String::new(),
+            sats: (amount_msat / 1000) as i64,
+            msats: amounts::msats_from_u64(amount_msat),
+            message: description.unwrap_or_default(),
+            event_guid: String::new(),
+            episode_guid: String::new(),
+            remote_feed: None,
+            remote_item: None,
+            remote_item_title: None,
+            is_old: false,
+        })
+    }
+
+    pub async fn get_info(&self) -> Result<Option<GetInfoResult>> {
+        let req = nip47::Request::get_info();
+        let req_event = req.to_event(&self.uri)?;
+
+        let subscription = Filter::new()
+            .author(self.uri.public_key)
+            .kind(Kind::WalletConnectResponse)
+            .event(req_event.id)
+            .since(Timestamp::now());
+
+        self.client.subscribe(vec![subscription], None).await?;
+        self.client.send_event(req_event).await?;
+
+        let mut notifications = self.client.notifications();
+        if let Ok(RelayPoolNotification::Event { event, .. }) = notifications.recv().await {
+            if event.kind == Kind::WalletConnectResponse {
+                let decrypted = nip04::decrypt(&self.uri.secret, &event.pubkey, &event.content)?;
+                let parsed: Value = serde_json::from_str(&decrypted)?;
+
+                if let Some(result) = parsed.get("result") {
+                    return Ok(Some(serde_json::from_value(result.clone())?));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Ask the wallet for its info and balance, timing the round trip
+    pub async fn wallet_status(&self, wallet: &NostrWC) -> Result<WalletStatus> {
+        let started = Instant::now();
+        let info = wallet.get_info().await.context("Wallet didn't answer get_info")?;
+        let latency = started.elapsed();
+
+        let balance_msats = if info.methods.iter().any(|method| method == "get_balance") {
+            Some(wallet.get_balance().await.context("Wallet didn't answer get_balance")?)
+        } else {
+            None
+        };
+
+        Ok(WalletStatus { alias: info.alias, balance_msats, latency, notifications: self.notifications.get().copied() })
+    }
+
+    /// Check the wallet every `interval`, passing each result to `on_status`, so a wallet that
+    /// stops answering is noticed even while boosts arrive as notifications
+    pub async fn monitor_wallet<S>(&self, interval: Duration, on_status: S)
+    where
+        S: Fn(Result<WalletStatus>),
+    {
+        let wallet = NostrWC::new(self.uri.clone());
+        loop {
+            on_status(self.wallet_status(&wallet).await);
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    pub async fn subscribe_boosts<F, Fut, S>(&self, timestamp: Timestamp, func: F, on_poll_status: S) -> Result<()>
+    where
+        F: Fn(Boostagram, Timestamp) -> Fut,
+        Fut: Future<Output = ()>,
+        S: Fn(PollStatus),
+    {
+        let info = self.get_info().await?
+            .ok_or_else(|| anyhow::anyhow!("No info returned from NWC"))?;
+
+        let notifications = info.notifications.contains(&"payment_received".to_string());
+        let _ = self.notifications.set(notifications);
+        if notifications {
+            println!("NWC listening for boosts");
+            self.listen_for_boosts(func).await
+        } else {
+            println!("NWC polling for boosts");
+            self.poll_boosts(timestamp, func, on_poll_status).await
+        }
+    }
+
+    async fn listen_for_boosts<F, Fut>(&self, func: F) -> Result<()>
+    where
+        F: Fn(Boostagram, Timestamp) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let keys = Keys::new(self.uri.secret.clone());
+        let subscription = Filter::new()
+            .author(self.uri.public_key)
+            .pubkey(keys.public_key())
+            .kind(Kind::Custom(23196));
+
+        self.client.subscribe(vec![subscription], None).await?;
+        let mut notifications = self.client.notifications();
+
+        while let Ok(notification) = notifications.recv().await {
+            if let RelayPoolNotification::Event { event, .. } = notification {
+                if event.kind == Kind::WalletConnectResponse {
+                    if let Some(boost) = self.extract_boost_from_notification(&event).await? {
+                        if self.accepts(&boost, event.created_at) {
+                            println!("boost: {:#?}", boost);
+                            func(boost, event.created_at).await;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn extract_boost_from_notification(&self, event: &nostr_sdk::Event) -> Result<Option<Boostagram>> {
+        let decrypted = nip04::decrypt(&self.uri.secret, &event.pubkey, &event.content)?;
+        let parsed: Value = serde_json::from_str(&decrypted)?;
+
+        if parsed.get("notification_type").and_then(|v| v.as_str()) == Some("payment_received") {
+            if let Some(notification) = parsed.get("notification") {
+                let pay_notif: PayNotification = serde_json::from_value(notification.clone())?;
+                let requested = pay_notif.invoice.as_deref().is_some_and(payment_qr::settle);
+
+                if let Some(meta) = &pay_notif.metadata {
+                    for tlv in &meta.tlv_records {
+                        if tlv.r#type == BOOST_TLV_TYPE {
+                            if let Ok(bytes) = hex::decode(&tlv.value) {
+                                if let Ok(boost) = serde_json::from_slice::<Boostagram>(&bytes) {
+                                    return Ok(Some(boost));
+                                }
+                            }
+                        }
+                    }
+                }
+
+                let amount = pay_notif.amount.unwrap_or_default();
+                return Ok(self.memo_boost(amount, pay_notif.description.as_deref(), event.created_at)
+                    .or_else(|| self.plain_payment(amount, pay_notif.description, event.created_at, requested)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn poll_boosts<F, Fut, S>(&self, timestamp: Timestamp, func: F, on_status: S) -> Result<()>
+    where
+        F: Fn(Boostagram, Timestamp) -> Fut,
+        Fut: Future<Output = ()>,
+        S: Fn(PollStatus),
+    {
+        let mut last_created_at = timestamp;
+        let seen = self.seen.clone().unwrap_or_else(SeenEvents::in_memory);  // Polls overlap by a second
+        let mut failures: u32 = 0;
+        let nwc = NostrWC::new(self.uri.clone());
+
+        loop {
+            let params = nip47::ListTransactionsRequestParams {
+                from: Some(last_created_at),
+                until: None,
+                limit: None,
+                offset: None,
+                unpaid: Some(false),
+                transaction_type: Some(nip47::TransactionType::Incoming),
+            };
+
+            match nwc.list_transactions(params).await {
+                Ok(transactions) => {
+                    if failures >= POLL_FAILURE_THRESHOLD {
+                        println!("NWC wallet responding again after {} failed polls", failures);
+                        on_status(PollStatus::Recovered);
+                    }
+                    failures = 0;
+
+                    for tran in transactions {
+                        if seen.check_key(&tran.payment_hash) == Seen::Duplicate {
+                            continue;
+                        }
+
+                        if let Some(boost) = self.extract_boost_from_transaction(&tran) {
+                            if self.accepts(&boost, tran.created_at) {
+                                println!("boost: {:#?}", boost);
+                                func(boost, tran.created_at).await;
+                            }
+                        }
+
+                        // Poll again from the same second so payments settled later within it aren't missed
+                        last_created_at = last_created_at.max(clock::clamp(tran.created_at));
+                    }
+                }
+                Err(err) => {
+                    failures += 1;
+                    let retry_in_ms = poll_interval_ms(failures);
+                    eprintln!("Error polling transactions ({} consecutive, retrying in {}ms): {:#?}", failures, retry_in_ms, err);
+
+                    if failures >= POLL_FAILURE_THRESHOLD {
+                        on_status(PollStatus::Failing { failures, error: err.to_string(), retry_in_ms });
+                    }
+                }
+            }
+
+            tokio::time::sleep(tokio::time::Duration::from_millis(poll_interval_ms(failures))).await;
+        }
+    }
+
+    fn extract_boost_from_transaction(&self, tran: &nip47::LookupInvoiceResponseResult) -> Option<Boostagram> {
+        let requested = tran.invoice.as_deref().is_some_and(payment_qr::settle);
+        self.boost_tlv_from_transaction(tran)
+            .or_else(|| self.memo_boost(tran.amount, tran.description.as_deref(), tran.created_at))
+            .or_else(|| self.plain_payment(tran.amount, tran.description.clone(), tran.created_at, requested))
+    }
+
+    fn boost_tlv_from_transaction(&self, tran: &nip47::LookupInvoiceResponseResult) -> Option<Boostagram> {
+        let metadata = tran.metadata.as_ref()?;
+        let tlvs = metadata.get("tlv_records")?.as_array()?;
+
+        for tlv in tlvs {
+            let tlv_type = tlv.get("type")?.as_i64()?;
+            let tlv_value = tlv.get("value")?.as_str()?;
+
+            if tlv_type == BOOST_TLV_TYPE as i64 {
+                let bytes = hex::decode(tlv_value).ok()?;
+                return serde_json::from_slice::<Boostagram>(&bytes).ok();
+            }
+        }
+
+        None
+    }
+
+    pub async fn load_previous_boosts<F, Fut>(&self, from: Option<Timestamp>, mut callback: F) -> Result<Option<Timestamp>>
+    where
+        F: FnMut(Boostagram) -> Fut + Send,
+        Fut: Future<Output = ()> + Send,
+    {
+        let nwc = NostrWC::new(self.uri.clone());
+
+        // Use the maximum of from and filters.after to start from the earliest relevant timestamp
+        let from_timestamp = match (from, self.filters.after) {
+            (Some(f), Some(a)) => f.max(a),
+            (Some(f), None) => f,
+            (None, Some(a)) => a,
+            (None, None) => Timestamp::from_secs(0),
+        };
+
+        let until_timestamp = self.filters.before;
+
+        let params = nip47::ListTransactionsRequestParams {
+            from: Some(from_timestamp),
+            until: until_timestamp,
+            limit: None,
+            offset: None,
+            unpaid: Some(false),
+            transaction_type: Some(nip47::TransactionType::Incoming),
+        };
+
+        let mut last_boost_at = from;
+
+        let cancel = self.history_cancel.clone().unwrap_or_default();
+        let listed = tokio::select! {
+            listed = nwc.list_transactions(params) => listed,
+            _ = cancel.cancelled() => return Ok(last_boost_at),
+        };
+
+        match listed {
+            Ok(transactions) => {
+                // Sort transactions by created_at to process in chronological order
+                let mut sorted_transactions: Vec<_> = transactions.into_iter().collect();
+                sorted_transactions.sort_by_key(|t| t.created_at);
+
+                for tran in sorted_transactions {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+                    // Counted here even if seen before, since a reload recounts its window
+                    if let Some(seen) = &self.seen {
+                        seen.check_key(&tran.payment_hash);
+                    }
+                    if let Some(boost) = self.extract_boost_from_transaction(&tran) {
+                        if self.accepts(&boost, tran.created_at) {
+                            callback(boost).await;
+
+                            if last_boost_at.is_none_or(|last| tran.created_at > last) {
+                                last_boost_at = Some(tran.created_at);
+                            }
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                eprintln!("Error loading previous transactions from NWC: {:#?}", err);
+                return Err(anyhow::anyhow!("Failed to load previous transactions: {:#}", err));
+            }
+        }
+
+        Ok(last_boost_at)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,95 @@
+use std::str::FromStr;
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use nostr_sdk::nips::nip47::NostrWalletConnectURI;
+
+use crate::boostboard::BoostFilters;
+use crate::nwc::NWC;
+
+pub const ALBY_HUB: &str = "https://my.albyhub.com";
+const APP_NAME: &str = "BlinkyBoosts";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Methods to ask for: get_info and list_transactions to find boosts, make_invoice for the
+/// on-screen QR and requested boosts, get_balance for the wallet panel
+const METHODS: [&str; 4] = ["get_info", "list_transactions", "make_invoice", "get_balance"];
+
+/// Link that opens Alby Hub (or a self-hosted hub at `hub`) on a new app connection with the
+/// permissions and payment notifications BlinkyBoosts uses already ticked
+pub fn new_connection_link(hub: &str) -> String {
+    format!("{}/apps/new?name={}&request_methods={}&notification_types=payment_received",
+        hub.trim_end_matches('/'), APP_NAME, METHODS.join("%20"))
+}
+
+/// A pasted connection secret, tidied and parsed, with hints for the usual mistakes
+pub fn parse(uri: &str) -> Result<NostrWalletConnectURI> {
+    let uri = uri.trim().trim_matches(|c| c == '"' || c == '\'');
+    if uri.is_empty() {
+        bail!("Paste the connection secret from your wallet");
+    }
+    if uri.starts_with("nostr+walletauth") {
+        bail!("That's a request for a connection, not one - paste the secret the wallet shows after you approve it");
+    }
+    if !uri.starts_with("nostr+walletconnect://") {
+        bail!("A connection secret starts with nostr+walletconnect://");
+    }
+    NostrWalletConnectURI::from_str(uri).context("The connection secret is incomplete, copy it again")
+}
+
+/// What a wallet connection lets BlinkyBoosts do, from the wallet's get_info
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    pub uri: String,  // Tidied connection secret, to save in [nwc] uri
+    pub alias: String,
+    pub methods: Vec<String>,
+    pub notifications: Vec<String>,
+}
+
+impl Capabilities {
+    fn has(&self, method: &str) -> bool {
+        self.methods.iter().any(|m| m == method)
+    }
+
+    /// Boosts arrive as payment notifications
+    pub fn notifies(&self) -> bool {
+        self.notifications.iter().any(|n| n == "payment_received")
+    }
+
+    /// Boosts can be found at all: from notifications, or by polling the transaction list
+    pub fn receives_boosts(&self) -> bool {
+        self.notifies() || self.has("list_transactions")
+    }
+
+    /// Past boosts can be loaded at startup and from the history window
+    pub fn loads_history(&self) -> bool {
+        self.has("list_transactions")
+    }
+
+    pub fn makes_invoices(&self) -> bool {
+        self.has("make_invoice")
+    }
+
+    pub fn reads_balance(&self) -> bool {
+        self.has("get_balance")
+    }
+}
+
+/// Connect with a pasted secret and ask the wallet what the connection may do
+pub async fn check(uri: &str) -> Result<Capabilities> {
+    let parsed = parse(uri)?;
+    let uri = parsed.to_string();
+
+    // The connection is closed however the check ends, even when it runs out of time
+    let mut wallet = None;
+    let info = tokio::time::timeout(CHECK_TIMEOUT, async {
+        let wallet = wallet.insert(NWC::new(&uri, BoostFilters::default()).await?);
+        wallet.get_info().await
+    }).await;
+    if let Some(wallet) = wallet {
+        wallet.disconnect().await;
+    }
+
+    let info = info.context(format!("The wallet didn't answer within {}s, is it online?", CHECK_TIMEOUT.as_secs()))??
+        .context("The wallet answered without its info")?;
+    Ok(Capabilities { uri, alias: info.alias, methods: info.methods, notifications: info.notifications })
+}
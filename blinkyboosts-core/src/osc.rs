@@ -0,0 +1,287 @@
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use rosc::{OscMessage, OscPacket, OscType, decoder, encoder};
+use anyhow::{Context, Result, anyhow, bail};
+
+use std::sync::Arc;
+use tokio::sync::{oneshot, Mutex};
+
+use crate::config::{self, Config};
+use crate::sat_tracker::SatTracker;
+use crate::{mock, templates};
+
+pub struct Osc {
+    sock: UdpSocket,
+    to_addr: SocketAddrV4,
+    ack_timeout: Option<Duration>,
+    reply_address: Option<String>,  // Where replies arrive, when not back at the sending socket
+}
+
+/// A trigger waiting for the reply to its path
+struct Waiter {
+    id: u64,
+    path: String,
+    reply: oneshot::Sender<OscMessage>,
+}
+
+/// A socket kept open for the life of the process, handing each reply that arrives on it to the
+/// oldest trigger waiting for that path
+struct ReplyListener {
+    sock: Arc<tokio::net::UdpSocket>,
+    waiters: std::sync::Mutex<Vec<Waiter>>,
+    next_id: AtomicU64,
+}
+
+/// Reply listeners by the address they're bound to ("" for the shared sending socket)
+static LISTENERS: std::sync::Mutex<BTreeMap<String, Arc<ReplyListener>>> = std::sync::Mutex::new(BTreeMap::new());
+
+impl Osc {
+    pub fn new(address: &str) -> Result<Self> {
+        let sock = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))
+            .context("Unable to bind to host address")?;
+
+        sock.set_broadcast(true)
+            .context("Unable to enable broadcast")?;
+
+        let to_addr = address.parse()
+            .with_context(|| format!("Unable to parse OSC address: {}", address))?;
+
+        Ok(Self { sock, to_addr, ack_timeout: None, reply_address: None })
+    }
+
+    /// An OSC target from `[osc]`, waiting for a reply to each cue when `ack_timeout_ms` is set
+    pub fn from_config(cfg: &config::OSC) -> Result<Self> {
+        let mut osc = Self::new(&cfg.address)?;
+        osc.ack_timeout = cfg.ack_timeout_ms.map(Duration::from_millis);
+        osc.reply_address = cfg.reply_address.clone();
+        Ok(osc)
+    }
+
+    /// Probe the target with an empty datagram. UDP has no handshake, so this can only detect
+    /// an ICMP "port unreachable" (nothing listening); silence is treated as reachable.
+    pub fn ping(&self, timeout: Duration) -> Result<()> {
+        if mock::enabled("osc") {
+            return Ok(());
+        }
+
+        let sock = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))
+            .context("Unable to bind to host address")?;
+        sock.connect(self.to_addr)
+            .with_context(|| format!("Unable to reach OSC target {}", self.to_addr))?;
+        sock.set_read_timeout(Some(timeout))?;
+        sock.send(&[])?;
+
+        let mut buf = [0u8; 64];
+        match sock.recv(&mut buf) {
+            Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+                Err(anyhow!("Nothing listening at {}", self.to_addr))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn trigger_path(&self, path: &str, args: Vec<OscType>) -> Result<()> {
+        println!("Triggering OSC path with args: {} {:?}", path, args);
+        match self.ack_timeout {
+            Some(timeout) if !mock::enabled("osc") => self.send_awaiting_reply(path, args, timeout).await,
+            _ => self.send(path, args),
+        }
+    }
+
+    fn send(&self, path: &str, args: Vec<OscType>) -> Result<()> {
+        if mock::enabled("osc") {
+            mock::record("osc", self.to_addr.to_string(), serde_json::json!({ "path": path, "args": format!("{:?}", args) }));
+            return Ok(());
+        }
+
+        self.sock.send_to(&encode(path, args)?, self.to_addr)
+            .with_context(|| format!("Failed to send OSC message to {}", self.to_addr))?;
+        Ok(())
+    }
+
+    /// Send `path` and wait for the target to answer it, either QLab style on `/reply<path>`
+    /// with a JSON status, or by echoing the path back as Resolume does. Without a
+    /// `reply_address` the message goes out from the shared listener so the reply comes back to it.
+    async fn send_awaiting_reply(&self, path: &str, args: Vec<OscType>, timeout: Duration) -> Result<()> {
+        let listener = ReplyListener::for_address(self.reply_address.as_deref().unwrap_or_default())?;
+        let (id, reply) = listener.wait_for(path);
+
+        let sent = match self.reply_address {
+            Some(_) => self.send(path, args),
+            None => match encode(path, args) {
+                Ok(msg) => listener.sock.send_to(&msg, self.to_addr).await.map(|_| ())
+                    .with_context(|| format!("Failed to send OSC message to {}", self.to_addr)),
+                Err(e) => Err(e),
+            },
+        };
+        if let Err(e) = sent {
+            listener.forget(id);
+            return Err(e);
+        }
+
+        match tokio::time::timeout(timeout, reply).await {
+            Ok(Ok(msg)) if msg.addr == path => Ok(()),
+            Ok(Ok(msg)) => check_status(&msg),
+            Ok(Err(_)) => bail!("Stopped listening for OSC replies to {}", path),
+            Err(_) => {
+                listener.forget(id);
+                bail!("No reply from {} to {} within {}ms", self.to_addr, path, timeout.as_millis())
+            }
+        }
+    }
+
+    /// Send the toggle's path, with `{{...}}` placeholders in a string arg filled in from `vars`
+    pub async fn trigger_toggle(&self, toggle: &crate::config::Toggle, vars: &templates::Vars) -> Result<()> {
+        let osc_config = toggle.osc.as_ref()
+            .ok_or_else(|| anyhow!("OSC toggle missing 'osc' configuration"))?;
+
+        let arg = match &osc_config.arg_value {
+            crate::config::OscArgValue::String(s) => OscType::String(templates::render(s, vars, "osc")),
+            crate::config::OscArgValue::Int(i) => OscType::Int(*i as i32),
+            crate::config::OscArgValue::Float(f) => OscType::Float(*f as f32),
+        };
+
+        self.trigger_path(&osc_config.path, vec![arg]).await
+    }
+}
+
+impl ReplyListener {
+    /// The listener bound to `address`, or to an ephemeral port for "", started on first use
+    fn for_address(address: &str) -> Result<Arc<Self>> {
+        let mut listeners = LISTENERS.lock().unwrap();
+        if let Some(listener) = listeners.get(address) {
+            return Ok(listener.clone());
+        }
+
+        let sock = if address.is_empty() {
+            let sock = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))
+                .context("Unable to bind to host address")?;
+            sock.set_broadcast(true).context("Unable to enable broadcast")?;
+            sock
+        } else {
+            UdpSocket::bind(address).with_context(|| format!("Unable to listen for OSC replies on {}", address))?
+        };
+        sock.set_nonblocking(true)?;
+        let listener = Arc::new(Self {
+            sock: Arc::new(tokio::net::UdpSocket::from_std(sock)?),
+            waiters: std::sync::Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(0),
+        });
+        tokio::spawn(listener.clone().route_replies());
+        listeners.insert(address.to_string(), listener.clone());
+        Ok(listener)
+    }
+
+    fn wait_for(&self, path: &str) -> (u64, oneshot::Receiver<OscMessage>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (reply, rx) = oneshot::channel();
+        self.waiters.lock().unwrap().push(Waiter { id, path: path.to_string(), reply });
+        (id, rx)
+    }
+
+    fn forget(&self, id: u64) {
+        self.waiters.lock().unwrap().retain(|waiter| waiter.id != id);
+    }
+
+    async fn route_replies(self: Arc<Self>) {
+        let mut buf = [0u8; decoder::MTU];
+        loop {
+            let len = match self.sock.recv(&mut buf).await {
+                Ok(len) => len,
+                // An ICMP "port unreachable" for an earlier send; the waiter times out
+                Err(e) if e.kind() == ErrorKind::ConnectionRefused => continue,
+                Err(e) => {
+                    eprintln!("Stopped listening for OSC replies: {}", e);
+                    self.waiters.lock().unwrap().clear();
+                    return;
+                }
+            };
+            let Ok((_, packet)) = decoder::decode_udp(&buf[..len]) else { continue };
+
+            for msg in messages(packet) {
+                let mut waiters = self.waiters.lock().unwrap();
+                let answered = waiters.iter()
+                    .position(|waiter| msg.addr == waiter.path || msg.addr == format!("/reply{}", waiter.path));
+                if let Some(idx) = answered {
+                    let _ = waiters.remove(idx).reply.send(msg);
+                }
+            }
+        }
+    }
+}
+
+fn encode(path: &str, args: Vec<OscType>) -> Result<Vec<u8>> {
+    encoder::encode(&OscPacket::Message(OscMessage {
+        addr: path.to_string(),
+        args,
+    }))
+    .with_context(|| format!("Failed to encode OSC message for path: {}", path))
+}
+
+/// Send the running totals every `[osc.totals] interval_ms` as `<prefix>/total`,
+/// `<prefix>/last_boost` and, with a `[goal]`, `<prefix>/goal_pct`, so VJ software and media
+/// servers can bind visuals to them whatever the toggles do
+pub async fn broadcast_totals(config: Config, tracker: Arc<Mutex<SatTracker>>) {
+    let Some(osc_cfg) = &config.osc else { return };
+    let Some(cfg) = &osc_cfg.totals else { return };
+    let address = cfg.address.as_deref().unwrap_or(&osc_cfg.address);
+
+    let osc = match Osc::new(address) {
+        Ok(osc) => osc,
+        Err(e) => {
+            eprintln!("Failed to start OSC totals broadcast: {:#}", e);
+            return;
+        }
+    };
+
+    println!("Broadcasting sat totals over OSC to {} every {}ms", address, cfg.interval_ms);
+    let mut interval = tokio::time::interval(Duration::from_millis(cfg.interval_ms.max(50)));
+
+    loop {
+        interval.tick().await;
+
+        let (total, last_boost) = {
+            let tracker = tracker.lock().await;
+            (tracker.total(), tracker.recent().first().map(|boost| boost.sats))
+        };
+
+        let mut messages = vec![(format!("{}/total", cfg.prefix), OscType::Int(clamp_i32(total)))];
+        if let Some(sats) = last_boost {
+            messages.push((format!("{}/last_boost", cfg.prefix), OscType::Int(clamp_i32(sats))));
+        }
+        if let Some(goal) = config.goal.as_ref().filter(|goal| goal.target_sats > 0) {
+            let pct = total as f64 * 100.0 / goal.target_sats as f64;
+            messages.push((format!("{}/goal_pct", cfg.prefix), OscType::Float(pct as f32)));
+        }
+
+        for (path, arg) in messages {
+            if let Err(e) = osc.send(&path, vec![arg]) {
+                eprintln!("Failed to broadcast {}: {:#}", path, e);
+            }
+        }
+    }
+}
+
+fn clamp_i32(sats: i64) -> i32 {
+    sats.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+fn messages(packet: OscPacket) -> Vec<OscMessage> {
+    match packet {
+        OscPacket::Message(msg) => vec![msg],
+        OscPacket::Bundle(bundle) => bundle.content.into_iter().flat_map(messages).collect(),
+    }
+}
+
+/// A QLab reply's `status`, which is "ok" unless the cue was refused
+fn check_status(reply: &OscMessage) -> Result<()> {
+    let Some(OscType::String(json)) = reply.args.first() else { return Ok(()) };
+    let reply: serde_json::Value = serde_json::from_str(json).unwrap_or_default();
+    match reply.get("status").and_then(|status| status.as_str()) {
+        Some("ok") | None => Ok(()),
+        Some(status) => bail!("{} replied with status \"{}\"", reply.get("address").and_then(|a| a.as_str()).unwrap_or("target"), status),
+    }
+}
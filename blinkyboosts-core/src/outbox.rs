@@ -0,0 +1,149 @@
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, Config};
+use crate::{notify, summary};
+
+const OUTBOX_PATH: &str = "./outbox.json";
+
+/// A post that can go out late, unlike light cues. Where it goes comes from the config when
+/// it's sent, so no webhooks or keys are written to the outbox.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "to", rename_all = "snake_case")]
+pub enum Post {
+    Discord { content: String },  // To `[summary] discord_webhook`
+    Nostr { content: String },    // A note from `[summary] nostr`
+    Notification { title: String, message: String },  // Through `[notify]`
+}
+
+impl Post {
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Discord { .. } => "Discord",
+            Self::Nostr { .. } => "nostr",
+            Self::Notification { .. } => "notification",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Queued {
+    #[serde(skip)]
+    id: u64,  // Numbered as queued or loaded, so a flush finds its posts again
+    post: Post,
+    queued_at: i64,  // Unix seconds
+    attempts: u32,
+}
+
+// Loaded from ./outbox.json on first use. Posts stay queued until they're sent.
+static QUEUE: Mutex<Option<Vec<Queued>>> = Mutex::new(None);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+fn load() -> Vec<Queued> {
+    let mut queue: Vec<Queued> = fs::read_to_string(OUTBOX_PATH).ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    for item in &mut queue {
+        item.id = next_id();
+    }
+    queue
+}
+
+fn save(queue: &[Queued]) {
+    let written = serde_json::to_string_pretty(queue).map_err(anyhow::Error::from)
+        .and_then(|json| fs::write(OUTBOX_PATH, json).map_err(anyhow::Error::from));
+    if let Err(e) = written {
+        eprintln!("Failed to save {}: {:#}", OUTBOX_PATH, e);
+    }
+}
+
+async fn deliver(config: &Config, post: &Post) -> Result<()> {
+    let cfg = config.summary.clone().unwrap_or_default();
+    match post {
+        Post::Discord { content } => {
+            let webhook = cfg.discord_webhook.context("summary.discord_webhook is no longer set")?;
+            summary::post_discord(&webhook, content).await
+        }
+        Post::Nostr { content } => {
+            let nostr = cfg.nostr.context("summary.nostr is no longer set")?;
+            summary::post_nostr(&nostr, content).await
+        }
+        Post::Notification { title, message } => {
+            let notify = config.notify.as_ref().context("notify is no longer set")?;
+            notify::send(notify, title, message).await
+        }
+    }
+}
+
+/// Send a post now. When that fails and there's an `[outbox]`, it's kept in ./outbox.json and
+/// sent once the service can be reached again, even after a restart.
+pub async fn send(config: &Config, post: Post) {
+    let Err(e) = deliver(config, &post).await else { return };
+    eprintln!("Failed to post to {}: {:#}", post.describe(), e);
+    if config.outbox.is_none() {
+        return;
+    }
+
+    println!("Keeping the {} post in {} to send later", post.describe(), OUTBOX_PATH);
+    let mut queue = QUEUE.lock().unwrap();
+    let queue = queue.get_or_insert_with(load);
+    queue.push(Queued { id: next_id(), post, queued_at: chrono::Utc::now().timestamp(), attempts: 1 });
+    save(queue);
+}
+
+/// Take a post out of the queue once it's sent or given up on
+fn remove(id: u64) {
+    let mut queue = QUEUE.lock().unwrap();
+    let queue = queue.get_or_insert_with(load);
+    queue.retain(|item| item.id != id);
+    save(queue);
+}
+
+/// Try the queued posts in order, keeping the ones that fail again until they're too old. Each
+/// stays in the queue, and ./outbox.json, until it's sent, so posts queued meanwhile or a crash
+/// partway through lose nothing.
+async fn flush(config: &Config, cfg: &config::Outbox) {
+    let queued = QUEUE.lock().unwrap().get_or_insert_with(load).clone();
+
+    let now = chrono::Utc::now().timestamp();
+    let max_age = cfg.max_age_hours.saturating_mul(3600) as i64;
+    for item in queued {
+        if now.saturating_sub(item.queued_at) > max_age {
+            eprintln!("Dropping the {} post after {} attempts: queued over {}h ago", item.post.describe(), item.attempts, cfg.max_age_hours);
+            remove(item.id);
+            continue;
+        }
+        match deliver(config, &item.post).await {
+            Ok(()) => {
+                println!("Sent the queued {} post", item.post.describe());
+                remove(item.id);
+            }
+            Err(e) => {
+                let mut queue = QUEUE.lock().unwrap();
+                let queue = queue.get_or_insert_with(load);
+                if let Some(queued) = queue.iter_mut().find(|queued| queued.id == item.id) {
+                    queued.attempts += 1;
+                    eprintln!("Queued {} post still can't be sent (attempt {}): {:#}", item.post.describe(), queued.attempts, e);
+                }
+                save(queue);
+            }
+        }
+    }
+}
+
+/// Send queued posts, including ones left by a previous run, every `retry_secs`
+pub async fn run(config: Config) {
+    let Some(cfg) = config.outbox.clone() else { return };
+    loop {
+        flush(&config, &cfg).await;
+        tokio::time::sleep(Duration::from_secs(cfg.retry_secs.max(1))).await;
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use qrcode::render::svg;
+use qrcode::{Color, QrCode};
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+use crate::boostboard::BoostFilters;
+use crate::config::Config;
+use crate::messages::Event;
+use crate::nwc;
+
+const REQUEST_EXPIRY_SECS: u64 = 300;
+
+static CURRENT: Mutex<Option<String>> = Mutex::new(None);
+// Oldest first; each stays payable until paid or expired, even once a newer one is shown
+static REQUESTED: Mutex<Vec<Requested>> = Mutex::new(Vec::new());
+// One wallet connection for every invoice, opened when the first is needed
+static WALLET: tokio::sync::Mutex<Option<nwc::NWC>> = tokio::sync::Mutex::const_new(None);
+
+/// An invoice asked for on demand, shown in place of the standing QR until paid or expired
+struct Requested {
+    invoice: String,  // Lowercase bolt11
+    paid: oneshot::Sender<()>,
+}
+
+/// The `lightning:` URI currently on display, if any: the newest requested invoice while one is
+/// open, otherwise the standing code
+pub fn current() -> Option<String> {
+    if let Some(requested) = REQUESTED.lock().unwrap().last() {
+        return Some(format!("lightning:{}", requested.invoice.to_uppercase()));
+    }
+    CURRENT.lock().unwrap().clone()
+}
+
+/// QR modules for `data` as (width, dark flags in row-major order)
+pub fn modules(data: &str) -> Result<(usize, Vec<bool>)> {
+    let code = QrCode::new(data.as_bytes()).context("Failed to encode QR code")?;
+    let dark = code.to_colors().into_iter().map(|c| c == Color::Dark).collect();
+    Ok((code.width(), dark))
+}
+
+pub fn svg(data: &str) -> Result<String> {
+    let code = QrCode::new(data.as_bytes()).context("Failed to encode QR code")?;
+    Ok(code.render::<svg::Color>().min_dimensions(256, 256).build())
+}
+
+async fn wallet(config: &Config) -> Result<nwc::NWC> {
+    let Some(nwc_cfg) = &config.nwc else {
+        bail!("Configure NWC to generate invoices");
+    };
+    let mut wallet = WALLET.lock().await;
+    if let Some(wallet) = wallet.as_ref() {
+        return Ok(wallet.clone());
+    }
+    let connected = nwc::NWC::new(&nwc_cfg.uri, BoostFilters::from_config(&nwc_cfg.filters)).await?;
+    Ok(wallet.insert(connected).clone())
+}
+
+async fn payment_uri(config: &Config) -> Result<String> {
+    let cfg = config.qr.clone().context("QR code not configured")?;
+
+    if let Some(address) = &cfg.lightning_address {
+        return Ok(format!("lightning:{}", address));
+    }
+    if let Some(lnurl) = &cfg.lnurl {
+        return Ok(format!("lightning:{}", lnurl.to_uppercase()));
+    }
+
+    if config.nwc.is_none() {
+        bail!("Set qr.lightning_address or qr.lnurl, or configure NWC to generate invoices");
+    }
+    let wallet = wallet(config).await?;
+    let invoice = wallet.make_invoice(cfg.invoice_sats.saturating_mul(1000), &cfg.invoice_description, cfg.invoice_expiry_secs).await?;
+    Ok(format!("lightning:{}", invoice.to_uppercase()))
+}
+
+/// Publish the payment QR to the GUI, refreshing NWC invoices before they expire
+pub async fn run(config: Config, tx: Sender<Event>) {
+    let Some(cfg) = config.qr.clone() else { return };
+    let static_code = cfg.lightning_address.is_some() || cfg.lnurl.is_some();
+
+    loop {
+        let retry_in = match payment_uri(&config).await {
+            Ok(uri) => {
+                println!("Payment QR: {}", uri);
+                *CURRENT.lock().unwrap() = Some(uri.clone());
+                let _ = tx.send(Event::PaymentQr(uri)).await;
+                if static_code {
+                    return;
+                }
+                Duration::from_secs(cfg.invoice_expiry_secs.saturating_sub(30).max(30))
+            }
+            Err(e) => {
+                eprintln!("Failed to create payment QR: {:#}", e);
+                Duration::from_secs(30)
+            }
+        };
+
+        tokio::time::sleep(retry_in).await;
+    }
+}
+
+/// Create an invoice for `sats` through NWC and show it until it's paid or expires, reporting
+/// either outcome on `tx`. Returns the bolt11 invoice.
+pub async fn request(config: &Config, sats: u64, tx: Sender<Event>) -> Result<String> {
+    if sats == 0 {
+        bail!("sats must be positive");
+    }
+    if config.nwc.is_none() {
+        bail!("Configure NWC to request invoices");
+    }
+    let wallet = wallet(config).await?;
+    let description = config.qr.as_ref().map_or("Boost the show", |qr| qr.invoice_description.as_str());
+    let invoice = wallet.make_invoice(sats.saturating_mul(1000), description, REQUEST_EXPIRY_SECS).await?;
+
+    let (paid_tx, paid_rx) = oneshot::channel();
+    REQUESTED.lock().unwrap().push(Requested { invoice: invoice.to_lowercase(), paid: paid_tx });
+    println!("Requested {} sats: {}", sats, invoice);
+    let _ = tx.send(Event::InvoiceRequested(format!("lightning:{}", invoice.to_uppercase()), sats)).await;
+
+    let key = invoice.to_lowercase();
+    tokio::spawn(async move {
+        let paid = match tokio::time::timeout(Duration::from_secs(REQUEST_EXPIRY_SECS), paid_rx).await {
+            Ok(paid) => paid.is_ok(),
+            Err(_) => {
+                REQUESTED.lock().unwrap().retain(|r| r.invoice != key);
+                println!("Requested invoice for {} sats expired unpaid", sats);
+                false
+            }
+        };
+        let _ = tx.send(Event::InvoiceSettled(sats, paid)).await;
+    });
+
+    Ok(invoice)
+}
+
+/// Mark `invoice` paid if it's one of the open requested invoices. Returns whether it was, so the
+/// payment counts as a boost even without `[qr]`.
+pub fn settle(invoice: &str) -> bool {
+    let mut requested = REQUESTED.lock().unwrap();
+    let Some(index) = requested.iter().position(|r| r.invoice.eq_ignore_ascii_case(invoice)) else {
+        return false;
+    };
+    let _ = requested.remove(index).paid.send(());
+    true
+}
@@ -0,0 +1,195 @@
+use std::collections::{BTreeMap, HashMap};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+use crate::config;
+use crate::messages::{ComponentStatus, Event};
+use crate::mock;
+
+type Pending = Arc<std::sync::Mutex<BTreeMap<u64, oneshot::Sender<Result<()>>>>>;  // By trigger id
+
+/// A line written by a plugin on stdout
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PluginReply {
+    Result { id: Option<u64>, ok: bool, error: Option<String> },  // No id: answers the oldest trigger
+    Status { status: String, message: Option<String> },
+}
+
+struct PluginProcess {
+    child: Child,
+    stdin: ChildStdin,
+    pending: Pending,
+}
+
+/// External output plugins: long-running executables that receive one JSON trigger per line
+/// on stdin and answer each with a result line on stdout, e.g.
+///
+/// - in:  `{"type": "trigger", "id": 7, "toggle": "confetti", "params": {...}}`
+/// - out: `{"type": "result", "id": 7, "ok": true}` or `{"type": "result", "id": 7, "ok": false, "error": "..."}`
+/// - out (any time): `{"type": "status", "status": "running" | "error", "message": "..."}`
+#[derive(Clone, Default)]
+pub struct Plugins {
+    configs: Vec<config::Plugin>,
+    processes: Arc<Mutex<HashMap<String, PluginProcess>>>,
+    status_tx: Arc<std::sync::Mutex<Option<mpsc::Sender<Event>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+pub fn component_name(plugin: &str) -> String {
+    format!("Plugin: {}", plugin)
+}
+
+impl Plugins {
+    pub fn new(configs: Vec<config::Plugin>) -> Self {
+        Self { configs, ..Default::default() }
+    }
+
+    /// Launch every configured plugin, reporting their status to `tx` from now on
+    pub async fn start(&self, tx: mpsc::Sender<Event>) {
+        *self.status_tx.lock().unwrap() = Some(tx);
+
+        let mut processes = self.processes.lock().await;
+        for cfg in self.configs.iter().filter(|cfg| !cfg.mock) {
+            if let Err(e) = self.ensure_running(&mut processes, cfg) {
+                eprintln!("Failed to start plugin {}: {:#}", cfg.name, e);
+                self.report(&cfg.name, ComponentStatus::Error(format!("{:#}", e)));
+            }
+        }
+    }
+
+    /// Send a trigger to a plugin and wait for its result. A mocked plugin isn't started; the
+    /// trigger is recorded and succeeds.
+    pub async fn trigger(&self, toggle: &config::Toggle) -> Result<()> {
+        let plugin = toggle.plugin.as_ref().context("Toggle has no plugin settings")?;
+        let cfg = self.configs.iter().find(|c| c.name == plugin.name)
+            .with_context(|| format!("Plugin {} not configured", plugin.name))?;
+        if cfg.mock {
+            mock::record("plugin", cfg.name.clone(), json!({
+                "toggle": toggle.name,
+                "params": plugin.params.clone().unwrap_or(Value::Null),
+            }));
+            return Ok(());
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let line = json!({
+            "type": "trigger",
+            "id": id,
+            "toggle": toggle.name,
+            "params": plugin.params.clone().unwrap_or(Value::Null),
+        }).to_string();
+
+        let (result_tx, result_rx) = oneshot::channel();
+        let pending = {
+            let mut processes = self.processes.lock().await;
+            self.ensure_running(&mut processes, cfg)?;
+            let process = processes.get_mut(&cfg.name).expect("plugin process just started");
+
+            process.pending.lock().unwrap().insert(id, result_tx);
+            let sent = async {
+                process.stdin.write_all(line.as_bytes()).await?;
+                process.stdin.write_all(b"\n").await?;
+                process.stdin.flush().await
+            }.await;
+
+            if let Err(e) = sent {
+                process.pending.lock().unwrap().remove(&id);
+                processes.remove(&cfg.name);
+                bail!("Failed to write to plugin {}: {}", cfg.name, e);
+            }
+            process.pending.clone()
+        };
+
+        match tokio::time::timeout(Duration::from_millis(cfg.timeout_ms), result_rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => bail!("Plugin {} exited before replying", cfg.name),
+            Err(_) => {
+                // A late reply must not be taken for the next trigger's
+                pending.lock().unwrap().remove(&id);
+                bail!("Plugin {} did not reply within {}ms", cfg.name, cfg.timeout_ms)
+            }
+        }
+    }
+
+    /// Spawn the plugin if it isn't running (or has exited since)
+    fn ensure_running(&self, processes: &mut HashMap<String, PluginProcess>, cfg: &config::Plugin) -> Result<()> {
+        if let Some(process) = processes.get_mut(&cfg.name) {
+            if matches!(process.child.try_wait(), Ok(None)) {
+                return Ok(());
+            }
+            processes.remove(&cfg.name);
+        }
+
+        println!("Starting plugin {}: {} {}", cfg.name, cfg.command, cfg.args.join(" "));
+        let mut child = Command::new(&cfg.command)
+            .args(&cfg.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to run {}", cfg.command))?;
+
+        let stdin = child.stdin.take().context("Plugin stdin unavailable")?;
+        let stdout = child.stdout.take().context("Plugin stdout unavailable")?;
+        let pending = Pending::default();
+
+        tokio::spawn(self.clone().read_replies(cfg.name.clone(), stdout, pending.clone()));
+        processes.insert(cfg.name.clone(), PluginProcess { child, stdin, pending });
+        self.report(&cfg.name, ComponentStatus::Running);
+        Ok(())
+    }
+
+    async fn read_replies(self, name: String, stdout: ChildStdout, pending: Pending) {
+        let mut lines = BufReader::new(stdout).lines();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+            match serde_json::from_str::<PluginReply>(&line) {
+                Ok(PluginReply::Result { id, ok, error }) => {
+                    let result = if ok {
+                        Ok(())
+                    } else {
+                        Err(anyhow!(error.unwrap_or_else(|| "plugin reported failure".to_string())))
+                    };
+                    let waiting = {
+                        let mut pending = pending.lock().unwrap();
+                        match id {
+                            Some(id) => pending.remove(&id),
+                            None => pending.pop_first().map(|(_, waiting)| waiting),
+                        }
+                    };
+                    match waiting {
+                        Some(waiting) => { let _ = waiting.send(result); }
+                        None => eprintln!("Plugin {} replied to a trigger that already timed out", name),
+                    }
+                }
+                Ok(PluginReply::Status { status, message }) => {
+                    let status = match status.as_str() {
+                        "error" => ComponentStatus::Error(message.unwrap_or_default()),
+                        _ => ComponentStatus::Running,
+                    };
+                    self.report(&name, status);
+                }
+                Err(_) => println!("[{}] {}", name, line),
+            }
+        }
+
+        eprintln!("Plugin {} exited", name);
+        pending.lock().unwrap().clear();
+        self.report(&name, ComponentStatus::Error("Plugin exited".to_string()));
+    }
+
+    fn report(&self, name: &str, status: ComponentStatus) {
+        if let Some(tx) = self.status_tx.lock().unwrap().as_ref() {
+            let _ = tx.try_send(Event::UpdateStatus(component_name(name), status));
+        }
+    }
+}
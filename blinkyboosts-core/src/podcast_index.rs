@@ -0,0 +1,167 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use nostr_sdk::hashes::{sha1, Hash};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::boosts::RemoteItem;
+use crate::config::{self, Config};
+
+const API_URL: &str = "https://api.podcastindex.org/api/1.0";
+const BACKOFF: Duration = Duration::from_secs(60);
+const CACHE_CAPACITY: usize = 2000;  // Lookups remembered; the oldest make room
+
+/// What the Podcast Index knows about an episode, feed or song
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub artwork: Option<String>,  // Image URL: the item's own, else its feed's
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Episode {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    feed_image: String,
+}
+
+#[derive(Deserialize)]
+struct Feed {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    artwork: String,
+    #[serde(default)]
+    image: String,
+}
+
+/// Answers keyed by lookup path and guids; None when the index doesn't know it, so it isn't
+/// asked again
+struct Cache {
+    answers: BTreeMap<String, Option<Metadata>>,
+    order: VecDeque<String>,  // Oldest answer first
+    pending: BTreeSet<String>,  // Being looked up in the background
+}
+
+impl Cache {
+    fn insert(&mut self, key: String, answer: Option<Metadata>) {
+        if self.answers.insert(key.clone(), answer).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.answers.remove(&oldest);
+            }
+        }
+    }
+}
+
+static CONFIG: Mutex<Option<config::PodcastIndex>> = Mutex::new(None);
+static CACHE: Mutex<Cache> = Mutex::new(Cache { answers: BTreeMap::new(), order: VecDeque::new(), pending: BTreeSet::new() });
+// After a failed request, lookups are skipped until then so loading history doesn't wait on each boost
+static BACKING_OFF: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub fn configure(config: &Config) {
+    *CONFIG.lock().unwrap() = config.podcast_index.clone();
+}
+
+pub fn enabled() -> bool {
+    CONFIG.lock().unwrap().is_some()
+}
+
+fn non_empty(text: String) -> Option<String> {
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// GET an API endpoint, signed with the key, secret and time as the Podcast Index requires
+async fn get(cfg: &config::PodcastIndex, path: &str, query: &[(&str, &str)]) -> Result<Value> {
+    let date = chrono::Utc::now().timestamp().to_string();
+    let authorization = sha1::Hash::hash(format!("{}{}{}", cfg.api_key, cfg.api_secret, date).as_bytes()).to_string();
+
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+        .get(format!("{}/{}", API_URL, path))
+        .query(query)
+        .header("User-Agent", "BlinkyBoosts")
+        .header("X-Auth-Key", &cfg.api_key)
+        .header("X-Auth-Date", &date)
+        .header("Authorization", authorization)
+        .timeout(Duration::from_millis(cfg.timeout_ms))
+        .send().await?
+        .error_for_status()?
+        .json().await
+        .context("Unreadable Podcast Index response")
+}
+
+/// The cached answer for a lookup, without waiting on the API: on a miss it's looked up in the
+/// background for the next boost that needs it, and None is returned for now. Failed requests
+/// aren't cached, but the API is left alone for a minute after one.
+fn cached(path: &'static str, query: &[(&'static str, &str)], parse: fn(&Value) -> Option<Metadata>) -> Option<Metadata> {
+    let cfg = CONFIG.lock().unwrap().clone()?;
+    let runtime = tokio::runtime::Handle::try_current().ok()?;
+    let key = std::iter::once(path).chain(query.iter().map(|(_, value)| *value)).collect::<Vec<_>>().join("/");
+    {
+        let mut cache = CACHE.lock().unwrap();
+        if let Some(cached) = cache.answers.get(&key) {
+            return cached.clone();
+        }
+        if cache.pending.contains(&key) || BACKING_OFF.lock().unwrap().is_some_and(|until| Instant::now() < until) {
+            return None;
+        }
+        cache.pending.insert(key.clone());
+    }
+
+    let query: Vec<(&'static str, String)> = query.iter().map(|(name, value)| (*name, value.to_string())).collect();
+    runtime.spawn(async move {
+        let query: Vec<(&str, &str)> = query.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        let result = get(&cfg, path, &query).await;
+        let mut cache = CACHE.lock().unwrap();
+        cache.pending.remove(&key);
+        match result {
+            Ok(response) => cache.insert(key, parse(&response)),
+            Err(e) => {
+                eprintln!("Podcast Index lookup of {} failed: {:#}", key, e);
+                *BACKING_OFF.lock().unwrap() = Some(Instant::now() + BACKOFF);
+            }
+        }
+    });
+    None
+}
+
+// Unknown guids come back as an empty list rather than a missing field
+fn parse_episode(response: &Value) -> Option<Metadata> {
+    let episode: Episode = serde_json::from_value(response.get("episode")?.clone()).ok()?;
+    Some(Metadata {
+        title: non_empty(episode.title),
+        artwork: non_empty(episode.image).or_else(|| non_empty(episode.feed_image)),
+    })
+}
+
+fn parse_feed(response: &Value) -> Option<Metadata> {
+    let feed: Feed = serde_json::from_value(response.get("feed")?.clone()).ok()?;
+    Some(Metadata {
+        title: non_empty(feed.title),
+        artwork: non_empty(feed.artwork).or_else(|| non_empty(feed.image)),
+    })
+}
+
+/// One of the show's own episodes, by its guid; needs `[podcast_index] feed_guid`
+pub fn episode(guid: &str) -> Option<Metadata> {
+    let feed_guid = CONFIG.lock().unwrap().as_ref()?.feed_guid.clone()?;
+    cached("episodes/byguid", &[("guid", guid), ("feedguid", &feed_guid)], parse_episode)
+}
+
+/// The song or guest episode a boost was sent for, or its feed when the boost names no item
+pub fn remote(remote: &RemoteItem) -> Option<Metadata> {
+    let feed_guid = remote.feed_guid.as_deref()?;
+    match remote.item_guid.as_deref() {
+        Some(item_guid) => cached("episodes/byguid", &[("guid", item_guid), ("feedguid", feed_guid)], parse_episode),
+        None => cached("podcasts/byguid", &[("guid", feed_guid)], parse_feed),
+    }
+}
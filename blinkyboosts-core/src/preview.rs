@@ -0,0 +1,173 @@
+use serde_json::Value;
+
+use crate::config::{self, Config};
+use crate::sender_color::Rgb;
+use crate::{dimmer, effects, fixtures, scenes};
+
+pub struct PreviewSegment {
+    pub name: String,
+    pub start: usize,
+    pub stop: usize,
+    pub effect: Option<String>,
+}
+
+pub struct PreviewFixture {
+    pub name: String,
+    pub color: Rgb,
+}
+
+/// What the rig would look like after a toggle fires, without sending anything
+pub struct Preview {
+    pub leds: Vec<Rgb>,
+    pub segments: Vec<PreviewSegment>,
+    pub fixtures: Vec<PreviewFixture>,
+    pub note: Option<String>,
+}
+
+fn scale(color: Rgb, amount: u64) -> Rgb {
+    color.map(|c| (c as u64 * amount / 255) as u8)
+}
+
+fn rgb(values: &[u64]) -> Rgb {
+    [0, 1, 2].map(|i| values.get(i).copied().unwrap_or(0).min(255) as u8)
+}
+
+impl Preview {
+    /// The configured strip and fixtures, all dark
+    pub fn blank(config: &Config) -> Self {
+        let segments: Vec<PreviewSegment> = config.wled.as_ref()
+            .and_then(|w| w.segments.as_ref())
+            .map(|segments| segments.iter()
+                .map(|s| PreviewSegment { name: s.name.clone(), start: s.start as usize, stop: s.stop as usize, effect: None })
+                .collect())
+            .unwrap_or_default();
+        let leds = segments.iter().map(|s| s.stop).max().unwrap_or(0);
+
+        Self {
+            leds: vec![[0, 0, 0]; leds],
+            segments,
+            fixtures: config.fixtures.as_deref().unwrap_or_default().iter()
+                .map(|f| PreviewFixture { name: f.name.clone(), color: [0, 0, 0] })
+                .collect(),
+            note: None,
+        }
+    }
+
+    /// Simulate `toggle`, coloring it for `sender` when it has `sender_color` set
+    pub fn for_toggle(config: &Config, toggle: &config::Toggle, sender: Option<Rgb>) -> Self {
+        let mut preview = Self::blank(config);
+        let sender = sender.filter(|_| toggle.sender_color);
+        for branch in effects::fan_out(toggle) {
+            preview.apply_toggle(config, &branch, sender);
+        }
+        preview
+    }
+
+    /// Draw one output of a toggle over what's already shown
+    fn apply_toggle(&mut self, config: &Config, toggle: &config::Toggle, sender: Option<Rgb>) {
+        match toggle.output.to_lowercase().as_str() {
+            "wled" => {
+                let Some(toggle_cfg) = &toggle.wled else { return };
+                self.apply_wled_preset(config, &toggle_cfg.preset);
+                if let Some(color) = sender {
+                    let ids: Vec<usize> = match &toggle_cfg.color_segments {
+                        Some(ids) => ids.iter().map(|id| *id as usize).collect(),
+                        None => (0..self.segments.len()).collect(),
+                    };
+                    for id in ids {
+                        self.fill_segment(id, color);
+                    }
+                }
+            }
+            "fixture" => {
+                let Some(toggle_cfg) = &toggle.fixture else { return };
+                if let Some(fixture) = fixtures::find(config, &toggle_cfg.name) {
+                    if let Ok(values) = fixtures::channel_values(fixture, toggle_cfg, sender) {
+                        self.set_fixture(fixture, &values);
+                    }
+                }
+            }
+            "scene" => {
+                let Some(scene) = toggle.scene.as_deref().and_then(scenes::get) else {
+                    self.note = Some("Scene not captured yet".to_string());
+                    return;
+                };
+                self.apply_scene(config, &scene);
+            }
+            other => self.note = Some(format!("No preview for {} output", other)),
+        }
+    }
+
+    fn fill_segment(&mut self, id: usize, color: Rgb) {
+        let Some(segment) = self.segments.get(id) else { return };
+        let stop = segment.stop.min(self.leds.len());
+        let start = segment.start.min(stop);
+        self.leds[start..stop].fill(color.map(dimmer::scale));
+    }
+
+    /// Color each segment with its preset color; playlists show their first preset
+    fn apply_wled_preset(&mut self, config: &Config, name: &str) {
+        let Some(wled_cfg) = &config.wled else { return };
+        let presets = wled_cfg.presets.as_deref().unwrap_or_default();
+
+        let preset = presets.iter().find(|p| p.name == name).or_else(|| {
+            let playlist = wled_cfg.playlists.as_deref().unwrap_or_default().iter().find(|p| p.name == name)?;
+            presets.iter().find(|p| Some(&p.name) == playlist.presets.first())
+        });
+
+        let Some(preset) = preset else {
+            self.note = Some(format!("Preset {} is not defined in config.toml", name));
+            return;
+        };
+
+        for id in 0..self.segments.len() {
+            let color = preset.colors.get(id).map_or([0, 0, 0], |c| rgb(c));
+            self.segments[id].effect = preset.effects.get(id).cloned();
+            self.fill_segment(id, scale(color, wled_cfg.brightness));
+        }
+    }
+
+    fn apply_scene(&mut self, config: &Config, scene: &scenes::Scene) {
+        let brightness = scene.wled.as_ref().and_then(|s| s["bri"].as_u64()).unwrap_or(255);
+        let segments = scene.wled.as_ref().and_then(|s| s["seg"].as_array().cloned()).unwrap_or_default();
+
+        for (idx, segment) in segments.iter().enumerate() {
+            let id = segment["id"].as_u64().map_or(idx, |id| id as usize);
+            let color = segment["col"][0].as_array()
+                .map(|c| rgb(&c.iter().filter_map(Value::as_u64).collect::<Vec<_>>()))
+                .unwrap_or([0, 0, 0]);
+            self.fill_segment(id, scale(color, brightness));
+        }
+
+        for fixture in config.fixtures.as_deref().unwrap_or_default() {
+            let Ok(universe) = fixtures::universe(config, fixture) else { continue };
+            let output = fixture.output.to_lowercase();
+            let Some(frame) = scene.dmx.iter().find(|f| f.output == output && f.universe == universe) else { continue };
+
+            // Channels are 1-based; `validate` flags fixtures starting at 0 or running past 512
+            let values: Vec<(u16, u8)> = (0..fixture.channels.len() as u16)
+                .filter_map(|offset| fixture.start_channel.checked_add(offset))
+                .filter_map(|channel| frame.values.get((channel as usize).checked_sub(1)?).map(|v| (channel, *v)))
+                .collect();
+            self.set_fixture(fixture, &values);
+        }
+    }
+
+    /// Show a fixture's RGB channels, scaled by its dimmer channel if it has one
+    fn set_fixture(&mut self, fixture: &config::Fixture, values: &[(u16, u8)]) {
+        let role_value = |role: &str| {
+            let offset = fixture.channels.iter().position(|c| c.eq_ignore_ascii_case(role))?;
+            let channel = fixture.start_channel.checked_add(offset as u16)?;
+            values.iter().find(|(c, _)| *c == channel).map(|(_, v)| *v)
+        };
+
+        let mut color = [role_value("red"), role_value("green"), role_value("blue")].map(|v| v.unwrap_or(0));
+        if fixture.channels.iter().any(|c| c.eq_ignore_ascii_case("dimmer")) {
+            color = scale(color, role_value("dimmer").unwrap_or(0) as u64);
+        }
+
+        if let Some(preview) = self.fixtures.iter_mut().find(|f| f.name == fixture.name) {
+            preview.color = color.map(dimmer::scale);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use nostr_sdk::{Client, PublicKey};
+
+const LOOKUP_TIMEOUT_SECS: u64 = 2;
+
+/// Display details from a pubkey's kind-0 metadata
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub display_name: Option<String>,
+}
+
+/// Cache of kind-0 metadata by pubkey hex, shared for the life of the process so each
+/// sender is only looked up once (misses are cached too)
+#[derive(Clone, Debug)]
+pub struct Profiles {
+    cache: Arc<Mutex<HashMap<String, Profile>>>,
+}
+
+impl Profiles {
+    pub fn shared() -> Self {
+        static PROFILES: OnceLock<Profiles> = OnceLock::new();
+        PROFILES.get_or_init(|| Self { cache: Arc::default() }).clone()
+    }
+
+    /// The sender's cached profile, without waiting on relays: on a miss it's fetched in the
+    /// background for their next zap, and an empty profile is returned for now
+    pub fn lookup(&self, client: &Client, pubkey: &str) -> Profile {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(profile) = cache.get(pubkey) {
+            return profile.clone();
+        }
+        let Ok(pk) = PublicKey::parse(pubkey) else { return Profile::default() };
+        // Empty until the fetch answers, so each sender is only fetched once
+        cache.insert(pubkey.to_string(), Profile::default());
+
+        let (cache, client, pubkey) = (self.cache.clone(), client.clone(), pubkey.to_string());
+        tokio::spawn(async move {
+            match client.fetch_metadata(pk, Some(Duration::from_secs(LOOKUP_TIMEOUT_SECS))).await {
+                Ok(metadata) => {
+                    let display_name = metadata.display_name.filter(|n| !n.is_empty())
+                        .or(metadata.name.filter(|n| !n.is_empty()));
+                    cache.lock().unwrap().insert(pubkey, Profile { display_name });
+                }
+                Err(e) => eprintln!("No profile found for {}: {}", pubkey, e),
+            }
+        });
+        Profile::default()
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::{self, Config};
+
+struct Limiter {
+    limit: config::RateLimit,
+    sent: VecDeque<Instant>,  // Within the last `per_ms`, oldest first
+}
+
+static LIMITERS: Mutex<Option<HashMap<String, Limiter>>> = Mutex::new(None);
+
+/// Set up the `[rate_limits]` for each output, by output name
+pub fn configure(config: &Config) {
+    *LIMITERS.lock().unwrap() = config.rate_limits.as_ref().map(|limits| {
+        limits.iter()
+            .map(|(output, limit)| (output.to_lowercase(), Limiter { limit: limit.clone(), sent: VecDeque::new() }))
+            .collect()
+    });
+}
+
+/// Whether a toggle may be sent to `output` now, counting it toward the limit when it may
+pub fn allow(output: &str) -> bool {
+    let mut limiters = LIMITERS.lock().unwrap();
+    let Some(limiter) = limiters.as_mut().and_then(|limiters| limiters.get_mut(&output.to_lowercase())) else { return true };
+
+    let window = Duration::from_millis(limiter.limit.per_ms);
+    while limiter.sent.front().is_some_and(|at| at.elapsed() >= window) {
+        limiter.sent.pop_front();
+    }
+    if limiter.sent.len() >= limiter.limit.max as usize {
+        return false;
+    }
+    limiter.sent.push_back(Instant::now());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allow_counts_toward_each_outputs_limit() {
+        let config: Config = toml::from_str(r#"
+            [rate_limits]
+            WLED = { max = 2, per_ms = 60000 }
+            osc = { max = 1, per_ms = 0 }
+        "#).unwrap();
+        configure(&config);
+
+        assert!(allow("wled"));
+        assert!(allow("Wled"));
+        assert!(!allow("wled"));
+        assert!(allow("osc"));
+        assert!(allow("osc"));  // Nothing stays in a zero-length window
+        assert!(allow("artnet"));
+    }
+}
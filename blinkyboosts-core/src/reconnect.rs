@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use nostr_sdk::{Client, Filter, RelayPoolNotification, RelayStatus, SubscriptionId, Timestamp};
+use tokio::sync::mpsc::Sender;
+use tokio_util::sync::CancellationToken;
+use anyhow::Result;
+
+use crate::clock;
+use crate::messages::{ComponentStatus, Event};
+
+const BASE_DELAY_SECS: u64 = 1;
+const MAX_DELAY_SECS: u64 = 60;
+const STABLE_CONNECTION_SECS: u64 = 60;  // A session that lasted this long resets the backoff
+
+/// Where a listener left off, shared across reconnects so history isn't reloaded
+/// and live events aren't counted twice
+#[derive(Clone, Default)]
+pub struct ResumeState {
+    history_loaded: Arc<AtomicBool>,
+    last_event: Arc<AtomicU64>,
+    live_since: Arc<AtomicU64>,
+}
+
+static RESUME_STATES: Mutex<BTreeMap<&'static str, ResumeState>> = Mutex::new(BTreeMap::new());
+
+impl ResumeState {
+    /// The state for `source`, kept for the life of the process so a listener the watchdog (or
+    /// the operator) restarts resumes from where it was instead of loading history again
+    pub fn for_source(source: &'static str) -> Self {
+        RESUME_STATES.lock().unwrap().entry(source).or_default().clone()
+    }
+
+    pub fn history_loaded(&self) -> bool {
+        self.history_loaded.load(Ordering::SeqCst)
+    }
+
+    pub fn mark_history_loaded(&self) {
+        self.history_loaded.store(true, Ordering::SeqCst);
+    }
+
+    pub fn mark_event(&self, ts: Timestamp) {
+        self.last_event.fetch_max(clock::clamp(ts).as_u64(), Ordering::SeqCst);
+    }
+
+    /// Subscription start: the second of the last processed event, so others from that second
+    /// not yet delivered aren't missed (dedup drops the ones that were), or `default` if nothing
+    /// was seen yet
+    pub fn since(&self, default: Option<Timestamp>) -> Option<Timestamp> {
+        match self.last_event.load(Ordering::SeqCst) {
+            0 => default,
+            last => Some(Timestamp::from_secs(last).max(default.unwrap_or_else(|| Timestamp::from_secs(0)))),
+        }
+    }
+
+    /// Where to look for events missed while a relay was down: the last processed event, which
+    /// dedup drops when it comes again, or when live listening started
+    pub fn gap_start(&self) -> Timestamp {
+        match self.last_event.load(Ordering::SeqCst) {
+            0 => self.live_since(),
+            last => Timestamp::from_secs(last),
+        }
+    }
+
+    /// Time the first live subscription started; events from before it don't trigger effects
+    pub fn live_since(&self) -> Timestamp {
+        let now = Timestamp::now().as_u64();
+        match self.live_since.compare_exchange(0, now, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => Timestamp::from_secs(now),
+            Err(existing) => Timestamp::from_secs(existing),
+        }
+    }
+}
+
+/// Watch `client`'s relays for the session and, when one comes back after dropping, ask it again
+/// for `subscription`'s events (`filter`) since `resume.gap_start()`. The answers arrive on the
+/// subscription as usual, so boosts sent during the blip are counted and duplicates dropped.
+pub async fn backfill_gaps(source: &str, client: Client, subscription: SubscriptionId, filter: Filter, resume: ResumeState) {
+    let mut notifications = client.notifications();
+    let mut dropped = HashSet::new();
+
+    while let Ok(notification) = notifications.recv().await {
+        let RelayPoolNotification::RelayStatus { relay_url, status } = notification else { continue };
+        match status {
+            RelayStatus::Disconnected | RelayStatus::Terminated => {
+                dropped.insert(relay_url);
+            }
+            RelayStatus::Connected if dropped.remove(&relay_url) => {
+                let since = resume.gap_start();
+                println!("{} relay {} reconnected, fetching events since {}", source, relay_url, since);
+                let filter = filter.clone().since(since);
+                if let Err(e) = client.subscribe_with_id_to([relay_url.clone()], subscription.clone(), vec![filter], None).await {
+                    eprintln!("Failed to backfill {} from {}: {:#}", source, relay_url, e);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let secs = BASE_DELAY_SECS.saturating_mul(1 << attempt.saturating_sub(1).min(6));
+    Duration::from_secs(secs.min(MAX_DELAY_SECS))
+}
+
+/// Run a listener session, reconnecting with exponential backoff whenever it errors or ends,
+/// until cancelled
+pub async fn run_with_reconnect<F, Fut>(
+    component: &str,
+    tx: &Sender<Event>,
+    cancel_token: CancellationToken,
+    mut session: F
+)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        let started = Instant::now();
+
+        let result = tokio::select! {
+            result = session() => result,
+            _ = cancel_token.cancelled() => break,
+        };
+
+        match result {
+            Ok(()) => eprintln!("{} connection closed", component),
+            Err(e) => eprintln!("{} connection error: {:#}", component, e),
+        }
+
+        if started.elapsed() >= Duration::from_secs(STABLE_CONNECTION_SECS) {
+            attempt = 0;
+        }
+        attempt += 1;
+
+        let delay = backoff_delay(attempt);
+        println!("Reconnecting {} in {}s (attempt {})", component, delay.as_secs(), attempt);
+        let _ = tx.send(Event::UpdateStatus(component.to_string(), ComponentStatus::Reconnecting(attempt))).await;
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {},
+            _ = cancel_token.cancelled() => break,
+        }
+    }
+
+    println!("{} listener cancelled", component);
+    let _ = tx.send(Event::UpdateStatus(component.to_string(), ComponentStatus::Disabled)).await;
+}
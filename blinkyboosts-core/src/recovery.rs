@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::sender_color::Rgb;
+use crate::templates;
+
+const RECOVERY_PATH: &str = "./recovery.json";
+
+/// An effect still to be sent: waiting out its toggle's `delay_secs`, or for a retry
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pending {
+    pub toggle: config::Toggle,  // Sends to a single output
+    pub color: Option<Rgb>,      // Retries only; delayed effects pick theirs when they fire
+    pub vars: templates::Vars,
+    pub due: i64,                // Unix milliseconds
+    pub attempt: u32,            // 0 for a delayed effect, otherwise the retry it's waiting for
+}
+
+impl Pending {
+    /// Time left until it's due; none once it's overdue
+    pub fn remaining(&self) -> Duration {
+        Duration::from_millis(self.due.saturating_sub(chrono::Utc::now().timestamp_millis()).max(0) as u64)
+    }
+}
+
+/// Unix milliseconds `delay` from now, for `Pending::due`
+pub fn due_in(delay: Duration) -> i64 {
+    chrono::Utc::now().timestamp_millis().saturating_add(delay.as_millis() as i64)
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Saved {
+    running: bool,  // Still set when the app starts if the last run didn't shut down cleanly
+    pending: BTreeMap<u64, Pending>,
+}
+
+// None until `start`, so the CLI and test harness leave the file alone
+static SAVED: Mutex<Option<Saved>> = Mutex::new(None);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+fn write(saved: &Saved) {
+    let written = serde_json::to_string_pretty(saved).map_err(anyhow::Error::from)
+        .and_then(|json| fs::write(RECOVERY_PATH, json).map_err(anyhow::Error::from));
+    if let Err(e) = written {
+        eprintln!("Failed to save {}: {:#}", RECOVERY_PATH, e);
+    }
+}
+
+/// Start keeping waiting effects on disk. Returns the ones the last run left behind, and
+/// whether it ended without a clean shutdown (a crash or kill, rather than quitting).
+pub fn start() -> (Vec<Pending>, bool) {
+    let previous: Saved = fs::read_to_string(RECOVERY_PATH).ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let saved = Saved { running: true, pending: BTreeMap::new() };
+    write(&saved);
+    *SAVED.lock().unwrap() = Some(saved);
+
+    (previous.pending.into_values().collect(), previous.running)
+}
+
+/// Keep an effect until it's `forget`-ten; the id to forget it by, or None when not keeping any
+pub fn remember(pending: Pending) -> Option<u64> {
+    let mut saved = SAVED.lock().unwrap();
+    let saved = saved.as_mut()?;
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    saved.pending.insert(id, pending);
+    write(saved);
+    Some(id)
+}
+
+/// Drop an effect that's about to be sent, or was cancelled. Done before sending, so an effect
+/// that was going out as the app died isn't sent again.
+pub fn forget(id: Option<u64>) {
+    let Some(id) = id else { return };
+    let mut saved = SAVED.lock().unwrap();
+    let Some(saved) = saved.as_mut() else { return };
+    if saved.pending.remove(&id).is_some() {
+        write(saved);
+    }
+}
+
+/// Record a clean shutdown: waiting effects were dropped on purpose and the outputs reset
+pub fn finish() {
+    if SAVED.lock().unwrap().take().is_some() {
+        write(&Saved::default());
+    }
+}
@@ -0,0 +1,126 @@
+use std::time::Duration;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::boosts::Boostagram;
+use crate::sat_tracker::BoostRecord;
+
+/// A boost in a rehearsal timeline, `at` seconds into the show
+#[derive(Deserialize, Debug, Clone)]
+pub struct Cue {
+    pub at: f64,
+    pub sats: i64,
+    #[serde(default)]
+    pub sender: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub episode: Option<String>,
+}
+
+impl Cue {
+    pub fn offset(&self) -> Duration {
+        Duration::from_secs_f64(self.at.max(0.0))
+    }
+}
+
+/// Read a timeline: a boost log (./boost_log.jsonl), boosts saved by `export`, or a script of
+/// cues like `[{"at": 0, "sats": 1000}, {"at": 90.5, "sats": 21, "sender": "Alice"}]`. Recorded
+/// boosts are timed from the first one. Returns the cues in order.
+pub fn load(path: &str) -> Result<Vec<Cue>> {
+    let text = std::fs::read_to_string(path).context(format!("Failed to read {}", path))?;
+    let mut cues = parse(&text).context(format!("{} is not a boost log, export or cue script", path))?;
+    cues.sort_by(|a, b| a.at.total_cmp(&b.at));
+    Ok(cues)
+}
+
+/// An `export` file; its notes don't make cues
+#[derive(Deserialize)]
+struct Export {
+    boosts: Vec<Boostagram>,
+}
+
+fn parse(text: &str) -> Result<Vec<Cue>> {
+    if let Ok(cues) = serde_json::from_str::<Vec<Cue>>(text) {
+        return Ok(cues);
+    }
+    let exported = serde_json::from_str::<Export>(text).map(|export| export.boosts)
+        .or_else(|_| serde_json::from_str::<Vec<Boostagram>>(text));
+    if let Ok(boosts) = exported {
+        return Ok(from_recorded(boosts.into_iter().map(|boost| (boost.creation_date, Cue {
+            at: 0.0,
+            sats: boost.sats,
+            sender: Some(boost.sender_name).filter(|sender| !sender.is_empty()),
+            message: Some(boost.message).filter(|message| !message.is_empty()),
+            episode: Some(boost.episode).filter(|episode| !episode.is_empty()),
+        }))));
+    }
+
+    let records = text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str::<BoostRecord>)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(from_recorded(records.into_iter().map(|record| (record.received_at, Cue {
+        at: 0.0,
+        sats: record.sats,
+        sender: record.sender,
+        message: record.message,
+        episode: record.episode,
+    }))))
+}
+
+/// Time recorded boosts (with their Unix timestamps) from the earliest
+fn from_recorded(boosts: impl Iterator<Item = (i64, Cue)>) -> Vec<Cue> {
+    let boosts: Vec<_> = boosts.collect();
+    let start = boosts.iter().map(|(at, _)| *at).min().unwrap_or_default();
+    boosts.into_iter().map(|(at, cue)| Cue { at: (at - start) as f64, ..cue }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boost(creation_date: i64, sats: i64, sender: &str) -> Boostagram {
+        Boostagram {
+            boost_type: "boost".to_string(), action: "boost".to_string(), identifier: String::new(), creation_date,
+            sender_name: sender.to_string(), app_name: String::new(), podcast: String::new(), episode: String::new(),
+            sats, msats: 0, message: String::new(), event_guid: String::new(), episode_guid: String::new(),
+            remote_feed: None, remote_item: None, remote_item_title: None, is_old: false,
+        }
+    }
+
+    fn timed(cues: &[Cue]) -> Vec<(f64, i64)> {
+        cues.iter().map(|cue| (cue.at, cue.sats)).collect()
+    }
+
+    #[test]
+    fn cue_scripts_are_read_as_written() {
+        let cues = parse(r#"[{"at": 90.5, "sats": 21, "sender": "Alice"}, {"at": 0, "sats": 1000}]"#).unwrap();
+        assert_eq!(timed(&cues), [(90.5, 21), (0.0, 1000)]);
+        assert_eq!(cues[0].sender.as_deref(), Some("Alice"));
+    }
+
+    #[test]
+    fn exports_are_timed_from_the_first_boost() {
+        let boosts = vec![boost(1_000_060, 500, "bob"), boost(1_000_000, 100, "")];
+        let export = serde_json::json!({ "boosts": boosts, "notes": [] }).to_string();
+        let cues = parse(&export).unwrap();
+        assert_eq!(timed(&cues), [(60.0, 500), (0.0, 100)]);
+        assert_eq!((cues[0].sender.as_deref(), cues[1].sender.as_deref()), (Some("bob"), None));
+
+        let bare = serde_json::to_string(&boosts).unwrap();
+        assert_eq!(timed(&parse(&bare).unwrap()), [(60.0, 500), (0.0, 100)]);
+    }
+
+    #[test]
+    fn boost_logs_are_timed_from_the_first_boost() {
+        let log = [(7, 1_000_030, 300), (8, 1_000_010, 200)].map(|(id, received_at, sats)| {
+            serde_json::to_string(&BoostRecord {
+                id, source: "Zaps".to_string(), sats, sender: None, message: None, episode: None, remote_item: None,
+                received_at, effects: Vec::new(), note: None,
+            }).unwrap()
+        }).join("\n");
+        assert_eq!(timed(&parse(&format!("{}\n\n", log)).unwrap()), [(20.0, 300), (0.0, 200)]);
+        assert!(parse("not a timeline").is_err());
+    }
+}
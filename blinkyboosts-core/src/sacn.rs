@@ -0,0 +1,132 @@
+use std::net::{IpAddr, SocketAddr};
+use anyhow::Result;
+use sacn::source::SacnSource;
+use sacn::packet::ACN_SDT_MULTICAST_PORT;
+
+use crate::{capture, dimmer, fixtures, mock};
+use crate::sender_color::{self, Rgb};
+
+pub struct Sacn {
+    source: Option<SacnSource>,  // None when mocked
+    universe: u16,
+    priority: u8,
+}
+
+impl Sacn {
+    pub fn new(_broadcast_address: String, universe: Option<u16>) -> Result<Self> {
+        let universe = universe.unwrap_or(1);
+
+        if mock::enabled("sacn") {
+            return Ok(Self { source: None, universe, priority: 100 });
+        }
+
+        // Create local address for the sACN source
+        // Use a port offset from the multicast port to avoid conflicts
+        // The sacn crate handles multicast/broadcast automatically
+        let local_addr = SocketAddr::new(
+            IpAddr::V4("0.0.0.0".parse().map_err(|e| anyhow::anyhow!("Failed to parse IP address: {}", e))?),
+            ACN_SDT_MULTICAST_PORT + 1
+        );
+
+        // Create a new sACN source
+        let mut source = SacnSource::with_ip("BlinkyBoosts", local_addr)
+            .map_err(|e| anyhow::anyhow!("Failed to create sACN source: {}", e))?;
+
+        // Register the universe
+        source.register_universe(universe)
+            .map_err(|e| anyhow::anyhow!("Failed to register universe {}: {}", universe, e))?;
+
+        Ok(Self {
+            source: Some(source),
+            universe,
+            priority: 100, // Default priority
+        })
+    }
+
+    /// Send packets at this priority (0-200) instead of the default 100
+    pub fn with_priority(self, priority: u8) -> Self {
+        Self { priority, ..self }
+    }
+
+    pub fn send_dmx(&mut self, data: &[u8]) -> Result<()> {
+        anyhow::ensure!(data.len() <= 513, "DMX data cannot exceed 513 bytes (including start code)");
+
+        // Data should already include start code as first byte
+        // If data doesn't start with 0, prepend start code
+        let mut dmx_data = if data.is_empty() || data[0] != 0 {
+            let mut with_start_code = vec![0u8; data.len() + 1];
+            with_start_code[0] = 0; // Start code
+            with_start_code[1..].copy_from_slice(data);
+            with_start_code
+        } else {
+            data.to_vec()
+        };
+
+        // Scale by the master dimmer (the start code is 0 so stays put)
+        for value in dmx_data.iter_mut() {
+            *value = dimmer::scale(*value);
+        }
+
+        let Some(source) = self.source.as_mut() else {
+            mock::record("sacn", format!("universe {}", self.universe), mock::dmx_payload(self.universe, &dmx_data[1..]));
+            return Ok(());
+        };
+
+        // Send the DMX data to the universe
+        // Using None for dst_ip means multicast, None for sync_uni means no synchronization delay
+        source.send(&[self.universe], &dmx_data, Some(self.priority), None, None)
+            .map_err(|e| anyhow::anyhow!("Failed to send sACN data: {}", e))?;
+        capture::record("sacn", "multicast", self.universe, &dmx_data[1..]);
+
+        Ok(())
+    }
+
+    pub fn trigger_channel(&mut self, channel: u16, value: u8) -> Result<()> {
+        self.trigger_channels(&[(channel, value)])
+    }
+
+    /// Send one frame with each (channel, value) set and every other channel at zero
+    pub fn trigger_channels(&mut self, values: &[(u16, u8)]) -> Result<()> {
+        let mut data = Vec::new();
+        for &(channel, value) in values {
+            anyhow::ensure!(channel > 0 && channel <= 512, "Channel must be between 1 and 512");
+            if data.len() < channel as usize {
+                data.resize(channel as usize, 0u8);
+            }
+            data[(channel - 1) as usize] = value;
+        }
+
+        self.send_dmx(&data)
+    }
+
+    /// Zero every channel, then tell receivers this source is going away
+    pub fn blackout(&mut self) -> Result<()> {
+        self.send_dmx(&[0u8; 512])?;
+        let Some(source) = self.source.as_mut() else { return Ok(()) };
+        source.terminate_stream(self.universe, 0)
+            .map_err(|e| anyhow::anyhow!("Failed to terminate sACN stream on universe {}: {}", self.universe, e))
+    }
+
+    /// The (channel, value) pairs an sACN toggle sets, with `color` on its RGB channels
+    pub fn toggle_values(sacn_config: &crate::config::ToggleSacn, color: Option<Rgb>) -> Vec<(u16, u8)> {
+        let values = fixtures::toggle_values(sacn_config.channel, sacn_config.value, sacn_config.count, &sacn_config.channels);
+        sender_color::with_rgb(values, sacn_config.rgb_channel, color)
+    }
+
+    /// Set the toggle's channels on top of the remembered frames, sending every universe they span at once
+    pub fn trigger_toggle(config: &crate::config::Config, toggle: &crate::config::Toggle, color: Option<Rgb>) -> Result<()> {
+        let sacn_config = toggle.sacn.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("sACN toggle missing 'sacn' configuration"))?;
+        let default_universe = config.sacn.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("sACN not configured"))?
+            .universe.unwrap_or(1);
+
+        let values = Self::toggle_values(sacn_config, color);
+        anyhow::ensure!(!values.is_empty(), "sACN toggle sets no channels");
+        let layer = fixtures::Layer {
+            merge: sacn_config.merge.unwrap_or(fixtures::Layer::output_default(config, "sacn").merge),
+            priority: sacn_config.priority,
+        };
+        fixtures::send_layer(config, "sacn", sacn_config.universe.unwrap_or(default_universe), &values, layer)
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use anyhow::{bail, Result};
+
+use crate::config::{self, Config};
+
+/// Outputs that show boost messages, each with its own level
+pub const OUTPUTS: [&str; 8] = ["console", "gui", "events", "api", "export", "notify", "osc", "mqtt"];
+
+const DEFAULT_WORDS: [&str; 20] = [
+    "fuck*", "motherfuck*", "shit*", "bullshit*", "cunt*", "bitch*", "asshole*", "bastard*",
+    "dick", "dickhead*", "cock", "cocksuck*", "pussy", "pussies", "slut*", "whore*",
+    "twat*", "wanker*", "prick", "piss*",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Level {
+    Off,
+    Light,   // Mask words and limit length
+    Strict,  // Also strip URLs and emoji
+}
+
+impl Level {
+    pub fn parse(level: &str) -> Result<Self> {
+        match level.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "light" => Ok(Self::Light),
+            "strict" => Ok(Self::Strict),
+            other => bail!("unknown level \"{}\" (expected off, light or strict)", other),
+        }
+    }
+}
+
+struct Rules {
+    level: Level,
+    outputs: BTreeMap<String, Level>,
+    words: Vec<String>,
+    max_length: usize,
+}
+
+static RULES: Mutex<Option<Rules>> = Mutex::new(None);
+
+/// Check levels and output names without applying them
+pub fn validate(cfg: &config::Sanitize) -> Result<()> {
+    Level::parse(&cfg.level)?;
+    for (output, level) in &cfg.outputs {
+        if !OUTPUTS.contains(&output.as_str()) {
+            bail!("unknown output \"{}\" (expected one of {})", output, OUTPUTS.join(", "));
+        }
+        Level::parse(level)?;
+    }
+    Ok(())
+}
+
+/// Load the sanitization rules; messages pass through untouched without a [sanitize] section
+pub fn configure(config: &Config) {
+    let Some(cfg) = &config.sanitize else {
+        *RULES.lock().unwrap() = None;
+        return;
+    };
+
+    let parse = |level: &str| Level::parse(level)
+        .inspect_err(|e| eprintln!("Ignoring sanitize level: {:#}", e))
+        .unwrap_or(Level::Light);
+
+    let defaults = DEFAULT_WORDS.iter().filter(|_| cfg.default_words).map(|w| w.to_string());
+    *RULES.lock().unwrap() = Some(Rules {
+        level: parse(&cfg.level),
+        outputs: cfg.outputs.iter().map(|(output, level)| (output.clone(), parse(level))).collect(),
+        words: defaults.chain(cfg.words.iter().map(|w| w.to_lowercase())).collect(),
+        max_length: cfg.max_length,
+    });
+}
+
+/// Clean a message for `output` at that output's level
+pub fn clean(output: &str, message: &str) -> String {
+    let rules = RULES.lock().unwrap();
+    let Some(rules) = rules.as_ref() else { return message.to_string() };
+    let level = rules.outputs.get(output).copied().unwrap_or(rules.level);
+
+    if level == Level::Off {
+        return message.to_string();
+    }
+
+    let mut text = message.to_string();
+    if level == Level::Strict {
+        text = strip_emoji(&strip_urls(&text));
+    }
+    text = mask_words(&text, &rules.words);
+    truncate(&text, rules.max_length)
+}
+
+fn word_matches(word: &str, pattern: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(stem) => word.starts_with(stem),
+        None => word == pattern,
+    }
+}
+
+/// Replace all but the first letter of each listed word with asterisks
+fn mask_words(text: &str, words: &[String]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, result: &mut String| {
+        let lower = word.to_lowercase();
+        if words.iter().any(|pattern| word_matches(&lower, pattern)) {
+            let mut chars = word.chars();
+            result.extend(chars.next());
+            result.extend(chars.map(|_| '*'));
+        } else {
+            result.push_str(word);
+        }
+        word.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '\'' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut result);
+            result.push(c);
+        }
+    }
+    flush(&mut word, &mut result);
+    result
+}
+
+fn strip_urls(text: &str) -> String {
+    text.split_whitespace()
+        .filter(|token| {
+            let lower = token.to_lowercase();
+            !(lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("www."))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF   // Pictographs, emoticons, transport, flags, skin tones
+        | 0x2600..=0x27BF   // Misc symbols and dingbats
+        | 0x2B00..=0x2BFF   // Arrows and stars
+        | 0xFE00..=0xFE0F   // Variation selectors
+        | 0x200D            // Zero width joiner
+        | 0xE0020..=0xE007F // Tag sequences
+    )
+}
+
+fn strip_emoji(text: &str) -> String {
+    let stripped: String = text.chars().filter(|c| !is_emoji(*c)).collect();
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncate(text: &str, max_length: usize) -> String {
+    if max_length == 0 || text.chars().count() <= max_length {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_length.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_are_masked_after_the_first_letter() {
+        let words = vec!["darn".to_string(), "heck*".to_string()];
+        assert_eq!(mask_words("Darn it, what the heckity heck", &words), "D*** it, what the h****** h***");
+        assert_eq!(mask_words("darning", &words), "darning");
+    }
+
+    #[test]
+    fn strict_strips_urls_and_emoji() {
+        assert_eq!(strip_urls("see https://example.com and www.example.com now"), "see and now");
+        assert_eq!(strip_emoji("great 🎉 show 👍🏽"), "great show");
+    }
+
+    #[test]
+    fn truncate_counts_characters() {
+        assert_eq!(truncate("héllo wörld", 6), "héllo…");
+        assert_eq!(truncate("short", 6), "short");
+        assert_eq!(truncate("anything", 0), "anything");
+    }
+
+    #[test]
+    fn clean_uses_each_outputs_level() {
+        let config: Config = toml::from_str(r#"
+            [sanitize]
+            level = "strict"
+            outputs = { console = "off", gui = "light" }
+            default_words = false
+            words = ["darn"]
+            max_length = 40
+        "#).unwrap();
+        configure(&config);
+
+        let message = "darn good show 🎉 https://example.com";
+        assert_eq!(clean("console", message), message);
+        assert_eq!(clean("gui", message), "d*** good show 🎉 https://example.com");
+        assert_eq!(clean("api", message), "d*** good show");
+    }
+}
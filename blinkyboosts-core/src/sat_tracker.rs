@@ -0,0 +1,292 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use crate::{boost_log, notes};
+
+use crate::amounts;
+
+const RECENT_CAPACITY: usize = 100;
+
+/// A processed boost, kept for the control API
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BoostRecord {
+    pub id: u64,  // See `BoostEvent::id`
+    pub source: String,
+    pub sats: i64,
+    pub sender: Option<String>,
+    pub message: Option<String>,  // Sanitized for the "api" output
+    pub episode: Option<String>,
+    pub remote_item: Option<String>,  // Title or guid of the guest feed or song boosted
+    pub received_at: i64,
+    pub effects: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,  // The operator's
+}
+
+/// An operator's note on a boost, or a marker they put in the show's timeline (`boost_id` None),
+/// for reference in post-production
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Note {
+    pub at: i64,  // Unix seconds; when the boost came in for a note on one
+    pub text: String,
+    pub boost_id: Option<u64>,
+    pub sats: Option<i64>,
+    pub sender: Option<String>,
+}
+
+/// Sats received for one episode, detected from boost metadata
+#[derive(Clone, Debug, Serialize)]
+pub struct Session {
+    pub episode: Option<String>,  // Title, or guid when the title is missing; None until an episode is seen
+    pub episode_guid: Option<String>,
+    pub started_at: i64,
+    pub total: i64,
+    pub boosts: usize,
+}
+
+impl Session {
+    fn new(episode_guid: Option<&str>, episode: Option<&str>) -> Self {
+        Self {
+            episode: episode.or(episode_guid).map(str::to_string),
+            episode_guid: episode_guid.map(str::to_string),
+            started_at: chrono::Utc::now().timestamp(),
+            total: 0,
+            boosts: 0,
+        }
+    }
+
+    fn matches(&self, episode_guid: Option<&str>, episode: Option<&str>) -> bool {
+        match (episode_guid, &self.episode_guid) {
+            (Some(guid), Some(own)) => guid == own,
+            _ => episode.is_some() && self.episode.as_deref() == episode,
+        }
+    }
+}
+
+/// Figures for the end-of-show recap, from every recorded boost since startup or the last
+/// `finish_show`
+#[derive(Clone, Debug, Default)]
+pub struct ShowStats {
+    pub started_at: Option<i64>,  // First boost
+    pub boosts: usize,
+    pub total: i64,
+    pub by_source: BTreeMap<String, i64>,
+    pub by_sender: HashMap<String, i64>,  // Named senders only
+    pub by_episode: BTreeMap<String, usize>,  // Boosts, for those with episode metadata
+    pub biggest: Option<BoostRecord>,
+    pub notes: Vec<Note>,  // In the order they were made
+}
+
+impl ShowStats {
+    fn add(&mut self, record: &BoostRecord) {
+        self.started_at.get_or_insert(record.received_at);
+        self.boosts += 1;
+        self.total = self.total.saturating_add(record.sats);
+        let source = self.by_source.entry(record.source.clone()).or_insert(0);
+        *source = source.saturating_add(record.sats);
+        if let Some(sender) = record.sender.as_ref().filter(|s| !s.trim().is_empty()) {
+            let sender = self.by_sender.entry(sender.clone()).or_insert(0);
+            *sender = sender.saturating_add(record.sats);
+        }
+        if let Some(episode) = &record.episode {
+            *self.by_episode.entry(episode.clone()).or_insert(0) += 1;
+        }
+        if self.biggest.as_ref().is_none_or(|biggest| record.sats > biggest.sats) {
+            self.biggest = Some(record.clone());
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SatTracker {
+    total_msats: i64,  // Kept in msats so sub-sat remainders carry over instead of being dropped
+    by_source_msats: HashMap<String, i64>,
+    cycle_msats: i64,  // Progress towards the largest threshold, in msats like the total
+    recent: VecDeque<BoostRecord>,
+    sessions: Vec<Session>,
+    campaigns: HashMap<String, i64>,
+    show: ShowStats,
+}
+
+impl SatTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a payment of `msats`, returning the new total in whole sats
+    pub fn add(&mut self, source: &str, msats: i64) -> i64 {
+        self.total_msats = self.total_msats.saturating_add(msats);
+        let source = self.by_source_msats.entry(source.to_string()).or_insert(0);
+        *source = source.saturating_add(msats);
+        self.total()
+    }
+
+    /// Take a source's payments out of the total, e.g. before counting its history again
+    pub fn forget_source(&mut self, source: &str) {
+        if let Some(msats) = self.by_source_msats.remove(source) {
+            self.total_msats = self.total_msats.saturating_sub(msats);
+        }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.total_msats / 1000
+    }
+
+    pub fn total_msats(&self) -> i64 {
+        self.total_msats
+    }
+
+    /// Whole sats per source
+    pub fn by_source(&self) -> HashMap<String, i64> {
+        self.by_source_msats.iter().map(|(source, msats)| (source.clone(), msats / 1000)).collect()
+    }
+
+    /// Count a boost toward its episode's session. The first boost for an episode not seen
+    /// before starts a new session; late boosts for an earlier episode count toward that one,
+    /// and boosts without episode metadata count toward the current session. Returns the new
+    /// session when it replaced one for a different episode.
+    pub fn add_to_session(&mut self, episode_guid: Option<&str>, episode: Option<&str>, sats: i64) -> Option<Session> {
+        let mut started = false;
+        let idx = match self.sessions.iter().rposition(|s| s.matches(episode_guid, episode)) {
+            Some(idx) => idx,
+            None => {
+                match self.sessions.last_mut() {
+                    // Boosts seen before any episode metadata belong to the first episode
+                    Some(current) if current.episode.is_none() => {
+                        current.episode = episode.or(episode_guid).map(str::to_string);
+                        current.episode_guid = episode_guid.map(str::to_string);
+                    }
+                    Some(_) if episode_guid.is_none() && episode.is_none() => {}
+                    previous => {
+                        started = previous.is_some();
+                        self.sessions.push(Session::new(episode_guid, episode));
+                    }
+                }
+                self.sessions.len() - 1
+            }
+        };
+
+        let session = &mut self.sessions[idx];
+        session.total = session.total.saturating_add(sats);
+        session.boosts += 1;
+        started.then(|| session.clone())
+    }
+
+    pub fn add_to_campaign(&mut self, name: &str, sats: i64) -> i64 {
+        let total = self.campaigns.entry(name.to_string()).or_insert(0);
+        *total = total.saturating_add(sats);
+        *total
+    }
+
+    pub fn campaign_total(&self, name: &str) -> i64 {
+        self.campaigns.get(name).copied().unwrap_or(0)
+    }
+
+    /// Episode sessions, oldest first
+    pub fn sessions(&self) -> &[Session] {
+        &self.sessions
+    }
+
+    pub fn record(&mut self, record: BoostRecord) {
+        self.show.add(&record);
+        if self.recent.len() >= RECENT_CAPACITY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(record);
+    }
+
+    pub fn show_stats(&self) -> &ShowStats {
+        &self.show
+    }
+
+    /// End the show, returning its figures and starting the next one's from nothing. Totals
+    /// for thresholds, sessions and campaigns carry on.
+    pub fn finish_show(&mut self) -> ShowStats {
+        std::mem::take(&mut self.show)
+    }
+
+    /// Set or, with empty `text`, clear the note on a boost from this run, kept in ./notes.jsonl for
+    /// `export`. Boosts older than the recent history are found in the boost log. Returns the
+    /// note (empty when cleared), or None if the boost is in neither.
+    pub fn annotate(&mut self, id: u64, text: &str) -> Option<Note> {
+        let text = text.trim();
+        let record = match self.recent.iter_mut().find(|record| record.id == id) {
+            Some(record) => {
+                record.note = Some(text.to_string()).filter(|text| !text.is_empty());
+                record.clone()
+            }
+            None => boost_log::find(id)?,
+        };
+        let note = Note {
+            at: record.received_at,
+            text: text.to_string(),
+            boost_id: Some(id),
+            sats: Some(record.sats),
+            sender: record.sender.clone(),
+        };
+        self.show.notes.retain(|note| note.boost_id != Some(id));
+        if !text.is_empty() {
+            self.show.notes.push(note.clone());
+        }
+        notes::append(&note);
+        Some(note)
+    }
+
+    /// Put a timestamped marker in the show, e.g. "started segment 2"
+    pub fn add_marker(&mut self, text: &str) -> Note {
+        let note = Note { at: chrono::Utc::now().timestamp(), text: text.trim().to_string(), boost_id: None, sats: None, sender: None };
+        self.show.notes.push(note.clone());
+        notes::append(&note);
+        note
+    }
+
+    /// Recently processed boosts, newest first
+    pub fn recent(&self) -> Vec<BoostRecord> {
+        self.recent.iter().rev().cloned().collect()
+    }
+
+    /// Check which thresholds (in sats) are crossed by a boost of `boost_msats`
+    pub fn get_thresholds_to_trigger(
+        &mut self,
+        boost_msats: i64,
+        all_thresholds: &[i64],
+        max_threshold: i64
+    ) -> Vec<i64> {
+        let max_msats = amounts::sats_to_msats(max_threshold);
+        let old_cycle = self.cycle_msats;
+        let new_cycle = old_cycle.saturating_add(boost_msats);
+        
+        let mut triggered = Vec::new();
+
+        // Handle max threshold crossing with wraparound
+        if new_cycle >= max_msats {
+            triggered.push(max_threshold);
+            self.cycle_msats = new_cycle - max_msats;
+            
+            // After reset, check if other thresholds are met
+            for &threshold in all_thresholds {
+                if threshold != max_threshold && self.cycle_msats >= amounts::sats_to_msats(threshold) {
+                    triggered.push(threshold);
+                }
+            }
+        } else {
+            self.cycle_msats = new_cycle;
+            
+            // Check normal threshold crossings
+            for &threshold in all_thresholds {
+                let threshold_msats = amounts::sats_to_msats(threshold);
+                if old_cycle < threshold_msats && new_cycle >= threshold_msats {
+                    triggered.push(threshold);
+                }
+            }
+        }
+
+        triggered.sort_unstable();
+        triggered
+    }
+
+    /// Sync cycle position based on total (call after loading historical data)
+    pub fn sync_trigger_state(&mut self, max_threshold: i64) {
+        self.cycle_msats = self.total_msats % amounts::sats_to_msats(max_threshold);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Mutex;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::Config;
+use crate::{dimmer, fixtures, wled};
+
+const SCENES_PATH: &str = "./scenes.json";
+
+/// WLED state keys restored by a scene; preset/playlist ids are left out so applying
+/// a scene doesn't restart whatever was running when it was captured
+const WLED_KEYS: [&str; 5] = ["on", "bri", "transition", "mainseg", "seg"];
+
+static SCENES: Mutex<Option<BTreeMap<String, Scene>>> = Mutex::new(None);
+
+/// A snapshot of the live outputs that can be restored later
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Scene {
+    pub wled: Option<Value>,
+    #[serde(default)]
+    pub dmx: Vec<SceneFrame>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SceneFrame {
+    pub output: String,
+    pub universe: u16,
+    pub values: Vec<u8>,
+}
+
+fn with_scenes<T>(f: impl FnOnce(&mut BTreeMap<String, Scene>) -> T) -> T {
+    let mut scenes = SCENES.lock().unwrap();
+    let scenes = scenes.get_or_insert_with(|| {
+        fs::read_to_string(SCENES_PATH).ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    });
+    f(scenes)
+}
+
+pub fn names() -> Vec<String> {
+    with_scenes(|scenes| scenes.keys().cloned().collect())
+}
+
+pub fn get(name: &str) -> Option<Scene> {
+    with_scenes(|scenes| scenes.get(name).cloned())
+}
+
+/// Read the current WLED state and latched DMX frames and save them as `name`
+pub async fn capture(config: &Config, name: &str) -> Result<Scene> {
+    let wled = match &config.wled {
+        Some(wled_cfg) => {
+            let state = wled::WLed::get_state(wled_cfg).await
+                .context("Failed to read WLED state")?;
+            let kept: Map<String, Value> = WLED_KEYS.iter()
+                .filter_map(|key| state.get(*key).map(|v| (key.to_string(), v.clone())))
+                .collect();
+            Some(Value::Object(kept))
+        }
+        None => None,
+    };
+
+    let dmx = fixtures::frames().into_iter()
+        .map(|(output, universe, values)| SceneFrame { output, universe, values })
+        .collect();
+
+    let scene = Scene { wled, dmx };
+    let json = with_scenes(|scenes| {
+        scenes.insert(name.to_string(), scene.clone());
+        serde_json::to_string_pretty(scenes)
+    })?;
+    fs::write(SCENES_PATH, json).context(format!("Failed to write {}", SCENES_PATH))?;
+
+    println!("Captured scene {}", name);
+    Ok(scene)
+}
+
+/// Send a saved scene to the outputs. WLED brightness is scaled by the master dimmer.
+pub async fn apply(config: &Config, name: &str) -> Result<()> {
+    let scene = get(name).with_context(|| format!("Scene {} not found", name))?;
+
+    if let (Some(wled_cfg), Some(mut state)) = (&config.wled, scene.wled) {
+        if let Some(bri) = state.get("bri").and_then(Value::as_u64) {
+            state["bri"] = Value::from(bri * dimmer::level() as u64 / 100);
+        }
+        wled::WLed::set_raw_state(wled_cfg, state).await
+            .context("Failed to apply scene to WLED")?;
+    }
+
+    for frame in &scene.dmx {
+        fixtures::send_frame(config, &frame.output, frame.universe, &frame.values)?;
+        fixtures::latch(&frame.output, frame.universe, &frame.values);
+    }
+
+    Ok(())
+}
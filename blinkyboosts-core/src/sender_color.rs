@@ -0,0 +1,42 @@
+use nostr_sdk::hashes::{sha256, Hash};
+
+pub type Rgb = [u8; 3];
+
+/// A fully saturated color picked from a hash of the sender's pubkey or name, so the
+/// same person always gets the same color
+pub fn derive(identity: &str) -> Rgb {
+    let normalized = identity.trim().to_lowercase();
+    let hash = sha256::Hash::hash(normalized.as_bytes()).to_byte_array();
+    let hue = u16::from_be_bytes([hash[0], hash[1]]) as f32 / u16::MAX as f32 * 360.0;
+    hsv_to_rgb(hue, 1.0, 1.0)
+}
+
+pub fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// DMX channel values for a toggle, with the color written to `rgb_channel`..`rgb_channel + 2`
+/// when both are set. Channels past 65535 are left off (`validate` flags them).
+pub fn with_rgb(mut values: Vec<(u16, u8)>, rgb_channel: Option<u16>, color: Option<Rgb>) -> Vec<(u16, u8)> {
+    if let (Some(start), Some(color)) = (rgb_channel, color) {
+        values.extend((0..3).filter_map(|offset| start.checked_add(offset)).zip(color));
+    }
+    values
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> Rgb {
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = value - chroma;
+
+    let (r, g, b) = match hue as u32 {
+        0..=59 => (chroma, x, 0.0),
+        60..=119 => (x, chroma, 0.0),
+        120..=179 => (0.0, chroma, x),
+        180..=239 => (0.0, x, chroma),
+        240..=299 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    [r, g, b].map(|c| ((c + m) * 255.0).round() as u8)
+}
@@ -0,0 +1,84 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tokio::task::AbortHandle;
+
+use crate::recovery;
+
+/// An effect waiting out its toggle's `delay_secs`, as listed for the operator
+#[derive(Serialize, Debug, Clone)]
+pub struct Scheduled {
+    pub id: u64,
+    pub description: String,
+    pub sats: i64,
+    pub sender: Option<String>,
+    pub remaining_secs: u64,
+}
+
+struct Entry {
+    id: u64,
+    description: String,
+    sats: i64,
+    sender: Option<String>,
+    due: Instant,
+    task: AbortHandle,
+    saved: Option<u64>,  // Recovery id, see `recovery::remember`
+}
+
+static PENDING: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Run `fire` after `delay` unless it's cancelled first; returns the id to cancel it by. `saved`
+/// is forgotten from the recovery file once the effect fires or is cancelled.
+pub fn schedule<F>(delay: Duration, description: String, sats: i64, sender: Option<String>, saved: Option<u64>, fire: F) -> u64
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let mut pending = PENDING.lock().unwrap();
+
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        PENDING.lock().unwrap().retain(|entry| entry.id != id);
+        recovery::forget(saved);
+        fire.await;
+    });
+
+    pending.push(Entry { id, description, sats, sender, due: Instant::now() + delay, task: task.abort_handle(), saved });
+    id
+}
+
+/// Effects still waiting to fire, soonest first
+pub fn pending() -> Vec<Scheduled> {
+    let mut pending: Vec<_> = PENDING.lock().unwrap().iter()
+        .map(|entry| Scheduled {
+            id: entry.id,
+            description: entry.description.clone(),
+            sats: entry.sats,
+            sender: entry.sender.clone(),
+            remaining_secs: entry.due.saturating_duration_since(Instant::now()).as_secs_f64().ceil() as u64,
+        })
+        .collect();
+    pending.sort_by_key(|scheduled| scheduled.remaining_secs);
+    pending
+}
+
+/// Drop a waiting effect; false when it already fired or was cancelled
+pub fn cancel(id: u64) -> bool {
+    let mut pending = PENDING.lock().unwrap();
+    let Some(idx) = pending.iter().position(|entry| entry.id == id) else { return false };
+    let entry = pending.remove(idx);
+    entry.task.abort();
+    recovery::forget(entry.saved);
+    println!("Cancelled {} ({} sats)", entry.description, entry.sats);
+    true
+}
+
+/// Drop every waiting effect, e.g. when shutting down
+pub fn cancel_all() {
+    for entry in PENDING.lock().unwrap().drain(..) {
+        entry.task.abort();
+    }
+}
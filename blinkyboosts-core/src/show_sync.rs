@@ -0,0 +1,221 @@
+use std::sync::{Arc, OnceLock};
+use nostr_sdk::nips::nip44;
+use nostr_sdk::prelude::Output;
+use nostr_sdk::{Client, Event, EventBuilder, Filter, Keys, Kind, PublicKey, RelayPoolNotification, Tag, Timestamp};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
+use anyhow::{bail, Context, Result};
+
+use crate::boosts::RemoteItem;
+use crate::config::{self, Config};
+use crate::dedup::{Seen, SeenEvents};
+use crate::listeners::{process_boost, IncomingBoost};
+use crate::messages::{self, ComponentStatus};
+use crate::{amounts, effects, reconnect, sat_tracker};
+
+/// Ephemeral kind, so relays pass sync events on without storing them
+const SYNC_KIND: u16 = 25420;
+
+/// Source name for boosts mirrored from another instance
+pub const SOURCE: &str = "Sync";
+
+static OUTBOX: OnceLock<mpsc::UnboundedSender<SyncedBoost>> = OnceLock::new();
+
+/// A boost as sent between instances
+#[derive(Serialize, Deserialize, Debug)]
+struct SyncedBoost {
+    source: String,
+    sats: i64,
+    #[serde(default)]
+    msats: i64,
+    sender: Option<String>,
+    sender_pubkey: Option<String>,
+    message: Option<String>,
+    episode_guid: Option<String>,
+    episode: Option<String>,
+    #[serde(default)]
+    remote: Option<RemoteItem>,
+}
+
+enum Role {
+    Publish,
+    Mirror,
+}
+
+fn role(cfg: &config::ShowSync) -> Result<Role> {
+    match cfg.role.to_lowercase().as_str() {
+        "publish" => Ok(Role::Publish),
+        "mirror" => Ok(Role::Mirror),
+        other => bail!("Unknown sync role \"{}\" (expected \"publish\" or \"mirror\")", other),
+    }
+}
+
+fn peers(cfg: &config::ShowSync) -> Result<Vec<PublicKey>> {
+    cfg.peers.iter()
+        .map(|peer| PublicKey::parse(peer).context(format!("Invalid sync peer: {}", peer)))
+        .collect()
+}
+
+/// Check the role, key and peers without connecting
+pub fn validate(cfg: &config::ShowSync) -> Result<()> {
+    role(cfg)?;
+    Keys::parse(&cfg.secret_key).context("Invalid sync secret_key")?;
+    if peers(cfg)?.is_empty() {
+        bail!("No peers specified");
+    }
+    Ok(())
+}
+
+/// Send a live boost to the mirroring instances. Does nothing unless this instance publishes.
+pub fn publish(boost: &IncomingBoost) {
+    if boost.source == SOURCE {
+        return;
+    }
+    if let Some(outbox) = OUTBOX.get() {
+        let _ = outbox.send(SyncedBoost {
+            source: boost.source.to_string(),
+            sats: boost.sats,
+            msats: boost.msats,
+            sender: boost.sender.clone(),
+            sender_pubkey: boost.sender_pubkey.clone(),
+            message: boost.message.clone(),
+            episode_guid: boost.episode_guid.clone(),
+            episode: boost.episode.clone(),
+            remote: boost.remote.clone(),
+        });
+    }
+}
+
+async fn connect(cfg: &config::ShowSync, keys: &Keys) -> Result<Client> {
+    let client = Client::new(keys.clone());
+    for addr in &cfg.relay_addrs {
+        client.add_relay(addr).await
+            .context(format!("Failed to add relay: {}", addr))?;
+    }
+    client.connect().await;
+    Ok(client)
+}
+
+/// Publish this instance's live boosts, or mirror another's, as set by `[sync] role`
+pub async fn run(
+    config: Config,
+    effects: effects::Effects,
+    tx: mpsc::Sender<messages::Event>,
+    tracker: Arc<Mutex<sat_tracker::SatTracker>>
+) {
+    let Some(cfg) = config.sync.clone() else { return };
+
+    let setup = validate(&cfg).and_then(|_| Ok((role(&cfg)?, Keys::parse(&cfg.secret_key)?, peers(&cfg)?)));
+    let (role, keys, peers) = match setup {
+        Ok(setup) => setup,
+        Err(e) => {
+            eprintln!("Show sync disabled: {:#}", e);
+            let _ = tx.send(messages::Event::UpdateStatus(SOURCE.to_string(), ComponentStatus::Error(format!("{:#}", e)))).await;
+            return;
+        }
+    };
+
+    match role {
+        Role::Publish => {
+            let (outbox, inbox) = mpsc::unbounded_channel();
+            if OUTBOX.set(outbox).is_err() {
+                return;
+            }
+            if let Err(e) = publish_boosts(&cfg, &keys, &peers, inbox, &tx).await {
+                eprintln!("Show sync stopped: {:#}", e);
+                let _ = tx.send(messages::Event::UpdateStatus(SOURCE.to_string(), ComponentStatus::Error(format!("{:#}", e)))).await;
+            }
+        }
+        Role::Mirror => {
+            let seen = SeenEvents::open("sync");
+            reconnect::run_with_reconnect(SOURCE, &tx, CancellationToken::new(), || {
+                mirror_session(&cfg, &keys, &peers, &effects, &tx, &tracker, seen.clone())
+            }).await;
+        }
+    }
+}
+
+async fn publish_boosts(
+    cfg: &config::ShowSync,
+    keys: &Keys,
+    peers: &[PublicKey],
+    mut inbox: mpsc::UnboundedReceiver<SyncedBoost>,
+    tx: &mpsc::Sender<messages::Event>
+) -> Result<()> {
+    let client = connect(cfg, keys).await?;
+    let _ = tx.send(messages::Event::UpdateStatus(SOURCE.to_string(), ComponentStatus::Running)).await;
+    println!("Publishing boosts to {} sync peer(s)", peers.len());
+
+    while let Some(boost) = inbox.recv().await {
+        let json = serde_json::to_string(&boost)?;
+        for peer in peers {
+            let content = nip44::encrypt(keys.secret_key(), peer, &json, nip44::Version::V2)
+                .context("Failed to encrypt sync event")?;
+            let builder = EventBuilder::new(Kind::Custom(SYNC_KIND), content, [Tag::public_key(*peer)]);
+            if let Err(e) = client.send_event_builder(builder).await {
+                eprintln!("Failed to publish sync event to {}: {:#}", peer, e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn decrypt(keys: &Keys, event: &Event) -> Result<SyncedBoost> {
+    let json = nip44::decrypt(keys.secret_key(), &event.pubkey, &event.content)
+        .context("Failed to decrypt sync event")?;
+    serde_json::from_str(&json).context("Failed to parse sync event")
+}
+
+async fn mirror_session(
+    cfg: &config::ShowSync,
+    keys: &Keys,
+    peers: &[PublicKey],
+    effects: &effects::Effects,
+    tx: &mpsc::Sender<messages::Event>,
+    tracker: &Arc<Mutex<sat_tracker::SatTracker>>,
+    seen: SeenEvents
+) -> Result<()> {
+    let client = connect(cfg, keys).await?;
+
+    let filter = Filter::new()
+        .kind(Kind::Custom(SYNC_KIND))
+        .authors(peers.to_vec())
+        .pubkey(keys.public_key())
+        .since(Timestamp::now());
+    let Output { val: sub_id, .. } = client.subscribe(vec![filter], None).await
+        .context("Failed to subscribe to sync events")?;
+
+    let _ = tx.send(messages::Event::UpdateStatus(SOURCE.to_string(), ComponentStatus::Running)).await;
+    println!("Mirroring boosts from {} sync peer(s)...", peers.len());
+
+    client.handle_notifications(|notification| async {
+        let RelayPoolNotification::Event { subscription_id, event, .. } = notification else { return Ok(false) };
+        if subscription_id != sub_id || seen.check(&event.id) != Seen::New {
+            return Ok(false);
+        }
+
+        match decrypt(keys, &event) {
+            Ok(boost) => {
+                println!("Sync boost from {}: {} sats", boost.source, boost.sats);
+                let incoming = IncomingBoost {
+                    source: SOURCE,
+                    sats: boost.sats,
+                    msats: if boost.msats > 0 { boost.msats } else { amounts::sats_to_msats(boost.sats) },
+                    sender: boost.sender,
+                    sender_pubkey: boost.sender_pubkey,
+                    message: boost.message,
+                    episode_guid: boost.episode_guid,
+                    episode: boost.episode,
+                    remote: boost.remote,
+                    artwork: None,
+                    event_ts: Some(event.created_at),
+                };
+                process_boost(incoming, tx, tracker, effects, true).await;
+            }
+            Err(e) => eprintln!("Ignoring sync event {}: {:#}", event.id, e),
+        }
+        Ok(false)
+    }).await.context("Failed to handle sync events")
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::{artnet, fixtures, sacn, wled};
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// Held while effects for a boost are being sent; shutdown waits for these to drop
+pub struct InFlight;
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn track() -> InFlight {
+    IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+    InFlight
+}
+
+/// Stop new effects from starting
+pub fn begin() {
+    SHUTTING_DOWN.store(true, Ordering::SeqCst);
+}
+
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Wait for in-flight effects to finish sending, up to `timeout`
+pub async fn drain(timeout: Duration) {
+    let started = Instant::now();
+
+    while IN_FLIGHT.load(Ordering::SeqCst) > 0 {
+        if started.elapsed() >= timeout {
+            eprintln!("Gave up waiting for {} in-flight effects", IN_FLIGHT.load(Ordering::SeqCst));
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Send the configured idle/blackout state to every configured output
+pub async fn reset_outputs(config: &Config) {
+    let cfg = config.shutdown.clone().unwrap_or_default();
+    if !cfg.reset_outputs {
+        return;
+    }
+
+    if let Some(wled_cfg) = &config.wled {
+        println!("Resetting WLED...");
+        if let Err(e) = wled::WLed::reset(wled_cfg, cfg.wled_preset.as_deref()).await {
+            eprintln!("Failed to reset WLED: {:#}", e);
+        }
+    }
+
+    if !cfg.dmx_blackout {
+        return;
+    }
+
+    let toggles = config.toggles.as_deref().unwrap_or_default();
+    let fixture_universes = |output: &str| -> Vec<u16> {
+        config.fixtures.as_deref().unwrap_or_default().iter()
+            .filter(|f| f.output.eq_ignore_ascii_case(output))
+            .filter_map(|f| fixtures::universe(config, f).ok())
+            .collect()
+    };
+    fixtures::clear_frames();
+
+    if let Some(artnet_cfg) = &config.artnet {
+        let default_universe = artnet_cfg.universe.unwrap_or(0);
+        let universes: BTreeSet<u16> = toggles.iter()
+            .filter_map(|t| t.artnet.as_ref())
+            .filter_map(|a| fixtures::span(a.universe.unwrap_or(default_universe), &artnet::ArtNet::toggle_values(a, Some([0; 3]))).ok())
+            .flat_map(|spanned| spanned.into_keys())
+            .chain(fixture_universes("artnet"))
+            .chain([default_universe])
+            .collect();
+
+        for universe in universes {
+            println!("Blacking out Art-Net universe {}", universe);
+            let result = artnet::ArtNet::new(artnet_cfg.broadcast_address.clone(), artnet_cfg.local_address.clone(), Some(universe))
+                .and_then(|a| a.blackout());
+            if let Err(e) = result {
+                eprintln!("Failed to black out Art-Net universe {}: {:#}", universe, e);
+            }
+        }
+    }
+
+    if let Some(sacn_cfg) = &config.sacn {
+        let default_universe = sacn_cfg.universe.unwrap_or(1);
+        let universes: BTreeSet<u16> = toggles.iter()
+            .filter_map(|t| t.sacn.as_ref())
+            .filter_map(|s| fixtures::span(s.universe.unwrap_or(default_universe), &sacn::Sacn::toggle_values(s, Some([0; 3]))).ok())
+            .flat_map(|spanned| spanned.into_keys())
+            .chain(fixture_universes("sacn"))
+            .chain([default_universe])
+            .collect();
+
+        for universe in universes {
+            println!("Blacking out sACN universe {}", universe);
+            let result = sacn::Sacn::new(sacn_cfg.broadcast_address.clone(), Some(universe))
+                .and_then(|mut s| s.blackout());
+            if let Err(e) = result {
+                eprintln!("Failed to black out sACN universe {}: {:#}", universe, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::config::{self, Config};
+use crate::sender_color::{self, Rgb};
+
+static LABELS: Mutex<Option<HashMap<String, config::SourceLabel>>> = Mutex::new(None);
+
+/// Set up the `[sources]` labels, by input name
+pub fn configure(config: &Config) {
+    *LABELS.lock().unwrap() = config.sources.as_ref().map(|sources| {
+        sources.iter()
+            .map(|(source, label)| (source.to_lowercase(), label.clone()))
+            .collect()
+    });
+}
+
+fn get(source: &str) -> Option<config::SourceLabel> {
+    LABELS.lock().unwrap().as_ref()?.get(&source.to_lowercase()).cloned()
+}
+
+pub fn color(source: &str) -> Option<Rgb> {
+    get(source)?.color
+}
+
+/// The color as "#rrggbb", for the event stream
+pub fn hex(source: &str) -> Option<String> {
+    color(source).map(sender_color::hex)
+}
+
+pub fn icon(source: &str) -> Option<String> {
+    get(source)?.icon.filter(|icon| !icon.trim().is_empty())
+}
+
+/// The source's name with its icon in front, e.g. "⚡ Zaps"
+pub fn label(source: &str) -> String {
+    match icon(source) {
+        Some(icon) => format!("{} {}", icon.trim(), source),
+        None => source.to_string(),
+    }
+}
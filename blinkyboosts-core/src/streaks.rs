@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, Config};
+use crate::moderation;
+
+const STREAKS_PATH: &str = "./streaks.json";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);  // Changes saved at most this often
+
+/// Why a sender's boost is worth calling out
+#[derive(Clone, Debug, PartialEq)]
+pub enum Recognition {
+    Returning(u32),  // Episodes boosted in a row, counting this one
+    Milestone(u32),  // The streak reached one of `[streaks] milestones`
+}
+
+impl Recognition {
+    pub fn streak(&self) -> u32 {
+        match self {
+            Self::Returning(streak) | Self::Milestone(streak) => *streak,
+        }
+    }
+
+    pub fn is_milestone(&self) -> bool {
+        matches!(self, Self::Milestone(_))
+    }
+}
+
+/// A sender's boosting record, kept across restarts
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct Sender {
+    name: Option<String>,
+    last_episode: usize,  // Index into `Saved::episodes`
+    streak: u32,
+    episodes: u32,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Saved {
+    episodes: Vec<String>,  // Episode guids (or titles) in the order their first boost arrived
+    senders: BTreeMap<String, Sender>,
+}
+
+struct State {
+    cfg: config::Streaks,
+    saved: Saved,
+    dirty: bool,  // Changed since last saved
+}
+
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+static WRITING: Mutex<()> = Mutex::new(());  // Held from snapshot to write, so an older snapshot never lands last
+
+/// Load the saved streaks; nothing is tracked without a [streaks] section
+pub fn configure(config: &Config) {
+    let Some(cfg) = config.streaks.clone() else {
+        *STATE.lock().unwrap() = None;
+        return;
+    };
+
+    let saved = fs::read_to_string(STREAKS_PATH).ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    *STATE.lock().unwrap() = Some(State { cfg, saved, dirty: false });
+}
+
+/// Count a live boost toward its sender's streak. Only a sender's first boost in an episode
+/// counts: it continues the streak when they boosted the episode before, otherwise starts a new
+/// one. Returns how to recognize the sender when this boost makes them a returning booster.
+/// Saved in the background every few seconds, and by `flush` on shutdown.
+pub fn record(sender_pubkey: Option<&str>, sender: Option<&str>, episode: Option<&str>) -> Option<Recognition> {
+    let identity = sender_pubkey.or(sender).filter(|identity| !identity.trim().is_empty())?;
+    let episode = episode.filter(|episode| !episode.is_empty())?;
+
+    let mut state = STATE.lock().unwrap();
+    let state = state.as_mut()?;
+    let saved = &mut state.saved;
+
+    let episode_idx = match saved.episodes.iter().position(|e| e == episode) {
+        Some(idx) => idx,
+        None => {
+            saved.episodes.push(episode.to_string());
+            saved.episodes.len() - 1
+        }
+    };
+
+    let record = saved.senders.entry(moderation::normalize(identity)).or_default();
+    if record.episodes > 0 && record.last_episode == episode_idx {
+        return None;
+    }
+
+    let returning = record.episodes > 0;
+    record.streak = if returning && record.last_episode + 1 == episode_idx { record.streak + 1 } else { 1 };
+    record.episodes += 1;
+    record.last_episode = episode_idx;
+    if let Some(name) = sender {
+        record.name = Some(name.to_string());
+    }
+    let streak = record.streak;
+    state.dirty = true;
+    flush_periodically();
+
+    if state.cfg.milestones.contains(&streak) {
+        Some(Recognition::Milestone(streak))
+    } else if returning {
+        Some(Recognition::Returning(streak))
+    } else {
+        None
+    }
+}
+
+/// Save the streaks if they changed since they were last saved, e.g. on shutdown
+pub fn flush() {
+    let _writing = WRITING.lock().unwrap();
+    let json = {
+        let mut state = STATE.lock().unwrap();
+        let Some(state) = state.as_mut().filter(|state| state.dirty) else { return };
+        state.dirty = false;
+        serde_json::to_string_pretty(&state.saved)
+    };
+    let written = json.map_err(anyhow::Error::from)
+        .and_then(|json| fs::write(STREAKS_PATH, json).map_err(anyhow::Error::from));
+    if let Err(e) = written {
+        eprintln!("Failed to save {}: {:#}", STREAKS_PATH, e);
+    }
+}
+
+/// Flush every `FLUSH_INTERVAL` off the async threads, once the first change is made
+fn flush_periodically() {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else { return };
+    if FLUSHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    runtime.spawn(async {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let _ = tokio::task::spawn_blocking(flush).await;
+        }
+    });
+}
+
+/// The toggle to fire for a recognition, if one is configured
+pub fn toggle(config: &Config, recognition: &Recognition) -> Option<String> {
+    let cfg = config.streaks.as_ref()?;
+    match recognition {
+        Recognition::Milestone(_) => cfg.milestone_toggle.clone().or_else(|| cfg.returning_toggle.clone()),
+        Recognition::Returning(_) => cfg.returning_toggle.clone(),
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use nostr_sdk::{Client, EventBuilder, Keys};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::amounts::format_sats;
+use crate::config::{self, Config};
+use crate::{i18n, outbox, source_labels};
+use crate::sat_tracker::{Note, SatTracker, ShowStats};
+
+const SUMMARIES_DIR: &str = "./summaries";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Booster {
+    pub sender: String,
+    pub sats: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BiggestBoost {
+    pub sender: Option<String>,
+    pub sats: i64,
+    pub source: String,
+    pub message: Option<String>,  // Sanitized for the "api" output
+}
+
+/// Recap of a finished show
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Summary {
+    pub started_at: Option<i64>,
+    pub finished_at: i64,
+    pub total_sats: i64,
+    pub boosts: usize,
+    pub top_boosters: Vec<Booster>,
+    pub biggest: Option<BiggestBoost>,
+    pub by_source: BTreeMap<String, i64>,
+    #[serde(default)]
+    pub by_episode: BTreeMap<String, usize>,  // Boosts per episode
+    #[serde(default)]
+    pub notes: Vec<Note>,  // The operator's notes and markers, in show order
+    pub saved_to: Vec<String>,  // Markdown and JSON copies
+}
+
+/// A note or marker as a line of the recap, e.g. "20:41 5,000 sats from alice: read on air"
+pub fn note_line(note: &Note) -> String {
+    let time = chrono::DateTime::from_timestamp(note.at, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+        .unwrap_or_default();
+    match note.sats {
+        Some(sats) => format!("{} {}", time, i18n::tr("recap-note-boost", &[
+            ("sats", format_sats(sats)),
+            ("sender", note.sender.clone().unwrap_or_else(|| i18n::t("anonymous"))),
+            ("note", note.text.clone()),
+        ])),
+        None => format!("{} {}", time, note.text),
+    }
+}
+
+impl Summary {
+    pub fn from_stats(stats: &ShowStats, top: usize) -> Self {
+        let mut notes = stats.notes.clone();
+        notes.sort_by_key(|note| note.at);
+
+        let mut boosters: Vec<_> = stats.by_sender.iter()
+            .map(|(sender, sats)| Booster { sender: sender.clone(), sats: *sats })
+            .collect();
+        boosters.sort_by(|a, b| b.sats.cmp(&a.sats).then_with(|| a.sender.cmp(&b.sender)));
+        boosters.truncate(top);
+
+        Self {
+            started_at: stats.started_at,
+            finished_at: chrono::Utc::now().timestamp(),
+            total_sats: stats.total,
+            boosts: stats.boosts,
+            top_boosters: boosters,
+            biggest: stats.biggest.as_ref().map(|boost| BiggestBoost {
+                sender: boost.sender.clone(),
+                sats: boost.sats,
+                source: boost.source.clone(),
+                message: boost.message.clone(),
+            }),
+            by_source: stats.by_source.clone(),
+            by_episode: stats.by_episode.clone(),
+            notes,
+            saved_to: Vec::new(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let date = |ts: i64| chrono::DateTime::from_timestamp(ts, 0)
+            .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+
+        let mut md = format!("# {}\n\n", i18n::t("recap-title"));
+        if let Some(started) = self.started_at {
+            md += &format!("{}\n\n", i18n::tr("recap-dates", &[("start", date(started)), ("end", date(self.finished_at))]));
+        }
+        md += &format!("**{}**\n", i18n::tr("recap-total", &[("sats", format_sats(self.total_sats)), ("boosts", self.boosts.to_string())]));
+
+        if let Some(biggest) = &self.biggest {
+            md += &format!("\n## {}\n\n{}\n", i18n::t("recap-biggest-heading"), i18n::tr("held-boost", &[
+                ("sats", format_sats(biggest.sats)),
+                ("sender", biggest.sender.clone().unwrap_or_else(|| i18n::t("anonymous"))),
+                ("source", source_labels::label(&biggest.source)),
+            ]));
+            if let Some(message) = &biggest.message {
+                md += &format!("\n> {}\n", message);
+            }
+        }
+
+        if !self.top_boosters.is_empty() {
+            md += &format!("\n## {}\n\n", i18n::t("top-boosters"));
+            for (idx, booster) in self.top_boosters.iter().enumerate() {
+                md += &format!("{}. {}: {}\n", idx + 1, booster.sender, i18n::tr("amount-sats", &[("sats", format_sats(booster.sats))]));
+            }
+        }
+
+        if !self.by_source.is_empty() {
+            md += &format!("\n## {}\n\n", i18n::t("by-source"));
+            for (source, sats) in &self.by_source {
+                md += &format!("- {}: {}\n", source_labels::label(source), i18n::tr("amount-sats", &[("sats", format_sats(*sats))]));
+            }
+        }
+
+        if !self.notes.is_empty() {
+            md += &format!("\n## {}\n\n", i18n::t("recap-notes"));
+            for note in &self.notes {
+                md += &format!("- {}\n", note_line(note));
+            }
+        }
+        md
+    }
+
+    /// Write Markdown and JSON copies to ./summaries
+    fn save(&mut self) -> Result<()> {
+        fs::create_dir_all(SUMMARIES_DIR).context(format!("Failed to create {}", SUMMARIES_DIR))?;
+        let stamp = chrono::DateTime::from_timestamp(self.finished_at, 0)
+            .map(|t| t.with_timezone(&chrono::Local).format("%Y%m%d-%H%M%S").to_string())
+            .unwrap_or_default();
+        let md_path = PathBuf::from(SUMMARIES_DIR).join(format!("show-{}.md", stamp));
+        let json_path = PathBuf::from(SUMMARIES_DIR).join(format!("show-{}.json", stamp));
+        self.saved_to = vec![md_path.display().to_string(), json_path.display().to_string()];
+
+        fs::write(&md_path, self.to_markdown()).context(format!("Failed to write {}", md_path.display()))?;
+        fs::write(&json_path, serde_json::to_string_pretty(self)?).context(format!("Failed to write {}", json_path.display()))?;
+        Ok(())
+    }
+}
+
+/// Every recap saved to ./summaries, oldest first. Files that can't be read are skipped.
+pub fn load_saved() -> Vec<Summary> {
+    let Ok(entries) = fs::read_dir(SUMMARIES_DIR) else { return Vec::new() };
+    let mut summaries: Vec<Summary> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| fs::read_to_string(&path).ok()
+            .and_then(|json| serde_json::from_str(&json)
+                .inspect_err(|e| eprintln!("Skipping show recap {}: {}", path.display(), e))
+                .ok()))
+        .collect();
+    summaries.sort_by_key(|summary| summary.finished_at);
+    summaries
+}
+
+/// Post a recap to a Discord webhook
+pub async fn post_discord(webhook: &str, content: &str) -> Result<()> {
+    reqwest::Client::new().post(webhook)
+        .json(&serde_json::json!({ "content": content }))
+        .send().await
+        .context("Failed to reach Discord")?
+        .error_for_status()
+        .context("Discord refused the recap")?;
+    Ok(())
+}
+
+/// Publish a recap as a note from the show's account
+pub async fn post_nostr(cfg: &config::SummaryNostr, content: &str) -> Result<()> {
+    let keys = Keys::parse(&cfg.secret_key).context("Invalid summary.nostr secret_key")?;
+    let client = Client::new(keys);
+    for addr in &cfg.relay_addrs {
+        client.add_relay(addr).await
+            .context(format!("Failed to add relay: {}", addr))?;
+    }
+    client.connect().await;
+    let result = client.send_event_builder(EventBuilder::text_note(content, [])).await;
+    client.disconnect().await?;
+    result.context("Failed to publish the recap")?;
+    Ok(())
+}
+
+/// Finish the show: recap everything since the last finish, save it and post it to the
+/// configured outputs. Failures to save or post are logged, and posts kept in the `[outbox]` when
+/// there is one; the recap is returned regardless.
+pub async fn finish(config: &Config, tracker: &Arc<Mutex<SatTracker>>) -> Summary {
+    let cfg = config.summary.clone().unwrap_or_default();
+    let stats = tracker.lock().await.finish_show();
+    let mut summary = Summary::from_stats(&stats, cfg.top_boosters);
+    println!("Show finished: {} sats from {} boosts", format_sats(summary.total_sats), summary.boosts);
+
+    if let Err(e) = summary.save() {
+        eprintln!("Failed to save show recap: {:#}", e);
+        summary.saved_to.clear();
+    }
+    if cfg.discord_webhook.is_some() {
+        outbox::send(config, outbox::Post::Discord { content: summary.to_markdown() }).await;
+    }
+    if cfg.nostr.is_some() {
+        outbox::send(config, outbox::Post::Nostr { content: summary.to_markdown() }).await;
+    }
+    summary
+}
@@ -0,0 +1,46 @@
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use crate::config::{self, Config};
+
+// Lowercased; None while every tag is active
+static ACTIVE: Mutex<Option<BTreeSet<String>>> = Mutex::new(None);
+
+/// Start from `active_tags`, or with every tag active when it isn't set
+pub fn configure(config: &Config) {
+    *ACTIVE.lock().unwrap() = config.active_tags.as_ref()
+        .map(|tags| tags.iter().map(|tag| tag.to_lowercase()).collect());
+}
+
+/// Every tag used by the configured toggles
+pub fn all(config: &Config) -> Vec<String> {
+    config.toggles.as_deref().unwrap_or_default().iter()
+        .flat_map(|toggle| toggle.tags.iter().map(|tag| tag.to_lowercase()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+pub fn is_active(tag: &str) -> bool {
+    ACTIVE.lock().unwrap().as_ref().is_none_or(|active| active.contains(&tag.to_lowercase()))
+}
+
+/// Switch every toggle with this tag on or off, until changed again or restarted
+pub fn set_active(config: &Config, tag: &str, active: bool) {
+    let tag = tag.to_lowercase();
+    let mut state = ACTIVE.lock().unwrap();
+    let tags = state.get_or_insert_with(|| all(config).into_iter().collect());
+    let changed = match active {
+        true => tags.insert(tag.clone()),
+        false => tags.remove(&tag),
+    };
+    if changed {
+        println!("Toggles tagged {} {}", tag, if active { "enabled" } else { "disabled" });
+    }
+}
+
+/// Whether a toggle can fire for boosts: untagged ones always can, tagged ones while one of
+/// their tags is active
+pub fn allows(toggle: &config::Toggle) -> bool {
+    toggle.tags.is_empty() || toggle.tags.iter().any(|tag| is_active(tag))
+}
@@ -0,0 +1,182 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::amounts;
+use crate::boosts::RemoteItem;
+use crate::config::Config;
+use crate::sanitize;
+
+const VARIABLES: &[&str] = &["sats", "sender", "message", "total", "goal_pct", "source", "episode", "remote_item", "artwork"];
+
+/// The boost a text output is describing, plus the running totals
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Vars {
+    pub source: String,
+    pub sats: i64,
+    #[serde(default)]
+    pub msats: i64,                     // Exact amount when known; 0 when it's a whole `sats`
+    pub sender: Option<String>,         // Display name
+    pub sender_pubkey: Option<String>,
+    pub message: Option<String>,        // Unsanitized; cleaned for the output when rendered
+    pub episode: Option<String>,
+    pub total: i64,
+    pub goal_pct: Option<i64>,          // Total as a percentage of `[goal] target_sats`
+    pub remote: Option<RemoteItem>,     // Guest feed or song the boost was sent for
+    #[serde(default)]
+    pub artwork: Option<String>,        // Episode or remote item image URL, from the Podcast Index
+}
+
+impl Vars {
+    /// Fill in the totals, with the goal percentage when a `[goal]` is configured
+    pub fn with_total(self, config: &Config, total: i64) -> Self {
+        let goal_pct = config.goal.as_ref()
+            .filter(|goal| goal.target_sats > 0)
+            .map(|goal| amounts::percent(total, goal.target_sats));
+        Self { total, goal_pct, ..self }
+    }
+
+    /// Exact amount in msats
+    pub fn msats(&self) -> i64 {
+        if self.msats > 0 { self.msats } else { amounts::sats_to_msats(self.sats) }
+    }
+
+    /// Pubkey, or the sender's name when there isn't one, e.g. to pick a sender color
+    pub fn identity(&self) -> Option<&str> {
+        self.sender_pubkey.as_deref().or(self.sender.as_deref())
+    }
+
+    fn get(&self, name: &str, output: &str) -> Option<String> {
+        Some(match name {
+            "sats" => self.sats.to_string(),
+            "sender" => self.sender.clone().or_else(|| self.sender_pubkey.clone()).unwrap_or_default(),
+            "message" => self.message.as_deref().map(|m| sanitize::clean(output, m)).unwrap_or_default(),
+            "total" => self.total.to_string(),
+            "goal_pct" => self.goal_pct.map(|pct| pct.to_string()).unwrap_or_default(),
+            "source" => self.source.clone(),
+            "episode" => self.episode.clone().unwrap_or_default(),
+            "remote_item" => self.remote.as_ref().map(RemoteItem::label).unwrap_or_default(),
+            "artwork" => self.artwork.clone().unwrap_or_default(),
+            _ => return None,
+        })
+    }
+}
+
+fn apply(filter: &str, value: String) -> Result<String> {
+    let (name, arg) = filter.split_once(':').map_or((filter, None), |(name, arg)| (name.trim(), Some(arg.trim())));
+    Ok(match (name, arg) {
+        ("upper", None) => value.to_uppercase(),
+        ("lower", None) => value.to_lowercase(),
+        ("separated", None) => value.parse().map(amounts::separated).unwrap_or(value),
+        ("abbreviated", None) => value.parse().map(amounts::abbreviated).unwrap_or(value),
+        ("default", Some(fallback)) => if value.is_empty() { fallback.to_string() } else { value },
+        ("truncate", Some(len)) => {
+            let Ok(len) = len.parse::<usize>() else { bail!("truncate needs a length, got \"{}\"", len) };
+            match value.char_indices().nth(len) {
+                Some((cut, _)) => format!("{}…", &value[..cut]),
+                None => value,
+            }
+        }
+        _ => bail!("Unknown filter \"{}\"", filter),
+    })
+}
+
+/// Expand one `name|filter|filter:arg` placeholder, or None if it doesn't name a variable
+fn expand(expr: &str, vars: &Vars, output: &str) -> Result<Option<String>> {
+    let mut parts = expr.split('|').map(str::trim);
+    let Some(mut value) = vars.get(parts.next().unwrap_or_default(), output) else { return Ok(None) };
+    for filter in parts {
+        value = apply(filter, value)?;
+    }
+    Ok(Some(value))
+}
+
+/// Split a template into literal text and placeholder expressions
+fn parts(template: &str) -> Vec<(&str, Option<&str>)> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else { break };
+        parts.push((&rest[..start], Some(rest[start + 2..start + 2 + len].trim())));
+        rest = &rest[start + 4 + len..];
+    }
+    parts.push((rest, None));
+    parts
+}
+
+/// Fill in `{{sats}}`, `{{sender}}`, `{{message}}`, `{{total}}`, `{{goal_pct}}`, `{{source}}`
+/// and `{{episode}}`, each optionally piped through filters: `{{message|truncate:40}}`,
+/// `{{sender|default:Anonymous}}`, `{{sender|upper}}`, `{{sender|lower}}`, and for amounts
+/// `{{total|separated}}` (1,234,567) or `{{total|abbreviated}}` (1.2M). The message is
+/// cleaned for `output` as set in `[sanitize]`. Placeholders that don't parse are left as written.
+pub fn render(template: &str, vars: &Vars, output: &str) -> String {
+    let mut text = String::new();
+    for (literal, expr) in parts(template) {
+        text.push_str(literal);
+        if let Some(expr) = expr {
+            match expand(expr, vars, output) {
+                Ok(Some(value)) => text.push_str(&value),
+                _ => text.push_str(&format!("{{{{{}}}}}", expr)),
+            }
+        }
+    }
+    text
+}
+
+/// Check every placeholder names a known variable and filter
+pub fn validate(template: &str) -> Result<()> {
+    for expr in parts(template).into_iter().filter_map(|(_, expr)| expr) {
+        let name = expr.split('|').next().unwrap_or_default().trim();
+        if !VARIABLES.contains(&name) {
+            bail!("Unknown variable \"{}\" (expected one of {})", name, VARIABLES.join(", "));
+        }
+        expand(expr, &Vars::default(), "")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> Vars {
+        Vars {
+            source: "NWC".to_string(),
+            sats: 1234567,
+            sender: Some("alice".to_string()),
+            message: Some("Nice one".to_string()),
+            total: 5000,
+            ..Vars::default()
+        }
+    }
+
+    #[test]
+    fn render_fills_in_variables_and_filters() {
+        let vars = vars();
+        assert_eq!(render("{{sender}} sent {{sats|separated}} via {{source}}", &vars, "osc"), "alice sent 1,234,567 via NWC");
+        assert_eq!(render("{{ sats | abbreviated }} {{sender|upper}}", &vars, "osc"), "1.2M ALICE");
+        assert_eq!(render("{{message|truncate:4}}", &vars, "osc"), "Nice…");
+        assert_eq!(render("{{episode|default:No episode}}", &vars, "osc"), "No episode");
+        assert_eq!(render("{{goal_pct}}%", &vars, "osc"), "%");
+    }
+
+    #[test]
+    fn render_leaves_unknown_placeholders_as_written() {
+        let vars = vars();
+        assert_eq!(render("{{nope}} {{sats|sparkle}}", &vars, "osc"), "{{nope}} {{sats|sparkle}}");
+        assert_eq!(render("{{sats", &vars, "osc"), "{{sats");
+    }
+
+    #[test]
+    fn sender_falls_back_to_the_pubkey() {
+        let vars = Vars { sender: None, sender_pubkey: Some("abc123".to_string()), ..vars() };
+        assert_eq!(render("{{sender}}", &vars, "osc"), "abc123");
+    }
+
+    #[test]
+    fn validate_checks_variables_and_filters() {
+        assert!(validate("{{total|separated}} {{message|truncate:40}}").is_ok());
+        assert!(validate("{{nope}}").is_err());
+        assert!(validate("{{sats|sparkle}}").is_err());
+        assert!(validate("{{message|truncate:many}}").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::config::{self, Config};
+use crate::effects::{self, Effects};
+use crate::messages::Event;
+use crate::{health, summary};
+
+/// One thing a test run checked: an output answering, or a toggle reaching one of its outputs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub error: Option<String>,  // None if it passed
+}
+
+/// The results of one run of the `[test_run]` sequence
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Report {
+    pub at: i64,  // Unix seconds
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.error.is_none())
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let at = chrono::DateTime::from_timestamp(self.at, 0)
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let mut md = format!("**Test run {}: {}**\n", at, if self.passed() { "passed" } else { "FAILED" });
+        for check in &self.checks {
+            match &check.error {
+                None => md += &format!("- ✅ {}\n", check.name),
+                Some(error) => md += &format!("- ❌ {}: {}\n", check.name, error),
+            }
+        }
+        md
+    }
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").context(format!("Invalid time \"{}\" (expected HH:MM)", time))
+}
+
+/// Check the run times and that every toggle in the sequence exists
+pub fn validate(config: &Config, cfg: &config::TestRun) -> Result<()> {
+    for time in &cfg.times {
+        parse_time(time)?;
+    }
+    for name in &cfg.toggles {
+        effects::find_toggle(config, name).context(format!("No toggle named \"{}\"", name))?;
+    }
+    Ok(())
+}
+
+/// Probe each output, then fire the sequence's toggles `step_ms` apart, noting which of their
+/// outputs each reached
+pub async fn execute(effects: &Effects, cfg: &config::TestRun) -> Report {
+    let config = effects.config();
+    let timeout = Duration::from_millis(config.health.clone().unwrap_or_default().timeout_ms);
+    let mut checks: Vec<Check> = health::check_outputs(config, timeout).await.into_iter()
+        .map(|(name, result)| Check { name: name.to_string(), error: result.err().map(|e| format!("{:#}", e)) })
+        .collect();
+
+    for (idx, name) in cfg.toggles.iter().enumerate() {
+        if idx > 0 {
+            tokio::time::sleep(Duration::from_millis(cfg.step_ms)).await;
+        }
+        let Some(toggle) = effects::find_toggle(config, name) else {
+            checks.push(Check { name: name.clone(), error: Some("no such toggle".to_string()) });
+            continue;
+        };
+        let outputs = effects::outputs_of(toggle);
+        let (reached, error) = match effects.fire_toggle(name).await {
+            Some(Ok(triggered)) => (triggered.into_iter().map(|effect| effect.output).collect(), None),
+            Some(Err(e)) => (BTreeSet::new(), Some(format!("{:#}", e))),
+            None => (BTreeSet::new(), Some("no such toggle".to_string())),
+        };
+        for output in outputs {
+            let error = match reached.contains(&output) {
+                true => None,
+                false => Some(error.clone().unwrap_or_else(|| "not sent".to_string())),
+            };
+            checks.push(Check { name: format!("{} → {}", name, output), error });
+        }
+    }
+
+    Report { at: chrono::Utc::now().timestamp(), checks }
+}
+
+/// Run the sequence and report it to the GUI, and to Discord when `discord` is set
+pub async fn run_and_report(effects: &Effects, cfg: &config::TestRun, tx: &Sender<Event>) {
+    println!("Starting test run");
+    let report = execute(effects, cfg).await;
+    println!("{}", report.to_markdown());
+
+    if cfg.discord {
+        match effects.config().summary.as_ref().and_then(|summary| summary.discord_webhook.as_ref()) {
+            Some(webhook) => {
+                if let Err(e) = summary::post_discord(webhook, &report.to_markdown()).await {
+                    eprintln!("Failed to post test run to Discord: {:#}", e);
+                }
+            }
+            None => eprintln!("test_run.discord is set but [summary] has no discord_webhook"),
+        }
+    }
+    let _ = tx.send(Event::TestRun(report)).await;
+}
+
+/// Fire the `[test_run]` sequence at each of its times every day
+pub async fn run(effects: Effects, tx: Sender<Event>) {
+    let Some(cfg) = effects.config().test_run.clone() else { return };
+    let times: Vec<NaiveTime> = cfg.times.iter()
+        .filter_map(|time| parse_time(time).inspect_err(|e| eprintln!("Ignoring test run time: {:#}", e)).ok())
+        .collect();
+    if times.is_empty() {
+        return;
+    }
+
+    loop {
+        let now = Local::now().naive_local();
+        let next = times.iter()
+            .map(|time| {
+                let today = now.date().and_time(*time);
+                if today > now { today } else { today + chrono::Duration::days(1) }
+            })
+            .min()
+            .unwrap_or(now);
+        tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+        run_and_report(&effects, &cfg, &tx).await;
+    }
+}
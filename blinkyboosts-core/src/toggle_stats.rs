@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use serde::Serialize;
+
+use crate::config::{self, Config};
+use crate::effects::format_toggle_description;
+use crate::tags;
+
+/// How a toggle has been used since startup
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct Stats {
+    pub fired: u64,               // Times it was sent (once per output), including manual fires and successful retries
+    pub matched_sats: i64,        // Sats of the boosts that matched it
+    pub last_fired: Option<i64>,  // Unix seconds
+}
+
+/// A configured toggle with its stats, for the API
+#[derive(Serialize, Debug, Clone)]
+pub struct ToggleSummary {
+    pub index: usize,
+    pub name: Option<String>,
+    pub description: String,
+    pub threshold: i64,
+    pub tags: Vec<String>,
+    pub active: bool,  // False while all its tags are switched off
+    pub stats: Stats,
+}
+
+// Keyed by position in the toggle list
+static STATS: Mutex<BTreeMap<usize, Stats>> = Mutex::new(BTreeMap::new());
+
+/// Position of a toggle borrowed from the config's toggle list
+pub fn index_of(config: &Config, toggle: &config::Toggle) -> Option<usize> {
+    config.toggles.as_deref().unwrap_or_default().iter().position(|t| std::ptr::eq(t, toggle))
+}
+
+pub fn matched(idx: usize, sats: i64) {
+    STATS.lock().unwrap().entry(idx).or_default().matched_sats += sats;
+}
+
+pub fn fired(idx: usize) {
+    let mut stats = STATS.lock().unwrap();
+    let entry = stats.entry(idx).or_default();
+    entry.fired += 1;
+    entry.last_fired = Some(chrono::Utc::now().timestamp());
+}
+
+pub fn get(idx: usize) -> Stats {
+    STATS.lock().unwrap().get(&idx).cloned().unwrap_or_default()
+}
+
+pub fn summary(config: &Config) -> Vec<ToggleSummary> {
+    config.toggles.as_deref().unwrap_or_default().iter().enumerate()
+        .map(|(index, toggle)| ToggleSummary {
+            index,
+            name: toggle.name.clone(),
+            description: format_toggle_description(toggle),
+            threshold: toggle.threshold,
+            tags: toggle.tags.clone(),
+            active: tags::allows(toggle),
+            stats: get(index),
+        })
+        .collect()
+}
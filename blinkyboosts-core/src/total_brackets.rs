@@ -0,0 +1,45 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use crate::config::Config;
+use crate::wled;
+
+/// Show total the current bracket was picked from
+static TOTAL: AtomicI64 = AtomicI64::new(0);
+
+/// The `[[total_brackets]]` preset for a total: the bracket with the highest `from_sats` reached
+pub fn preset_for(config: &Config, total: i64) -> Option<&str> {
+    config.total_brackets.as_deref().unwrap_or_default().iter()
+        .filter(|bracket| total >= bracket.from_sats)
+        .max_by_key(|bracket| bracket.from_sats)
+        .map(|bracket| bracket.wled_preset.as_str())
+}
+
+/// The preset for the show total as last seen by `update`
+pub fn current(config: &Config) -> Option<&str> {
+    preset_for(config, TOTAL.load(Ordering::SeqCst))
+}
+
+/// Record the new show total, switching the ambient look when it moves into another bracket.
+/// With `[idle]` configured the switch waits for the idle look to be restored so a running
+/// boost effect isn't cut short; otherwise the preset is sent straight away.
+pub fn update(config: &Config, total: i64) {
+    let previous = TOTAL.swap(total, Ordering::SeqCst);
+    let Some(preset) = preset_for(config, total) else { return };
+    if preset_for(config, previous) == Some(preset) {
+        return;
+    }
+
+    println!("Total of {} sats moved into the {} bracket", total, preset);
+    if config.idle.is_none() {
+        let config = config.clone();
+        tokio::spawn(async move { apply(&config).await });
+    }
+}
+
+/// Send the current bracket's preset to WLED
+pub async fn apply(config: &Config) {
+    let (Some(wled_cfg), Some(preset)) = (&config.wled, current(config)) else { return };
+    if let Err(e) = wled::WLed::reset(wled_cfg, Some(preset)).await {
+        eprintln!("Failed to send WLED preset {} for the total: {:#}", preset, e);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::config;
-use reqwest;
+use crate::dimmer;
+use crate::mock;
+use crate::sender_color::Rgb;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::value::Value;
@@ -90,26 +92,82 @@ struct JsonPlaylist {
     pub r: u64,
 }
 
+const RETRY_DELAY_MS: u64 = 250;
+
+/// HTTP client for a WLED controller with connect/request timeouts and limited retries
+#[derive(Debug, Clone)]
+struct Http {
+    client: reqwest::Client,
+    retries: u32,
+}
+
+impl Http {
+    fn new(config: &config::WLed) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_millis(config.connect_timeout_ms))
+            .timeout(Duration::from_millis(config.request_timeout_ms))
+            .build()
+            .context("Failed to build WLED HTTP client")?;
+
+        Ok(Self { client, retries: config.retries })
+    }
+
+    /// Send a request, retrying connection failures, timeouts and 5xx responses
+    async fn send<F>(&self, build: F) -> Result<reqwest::Response>
+    where
+        F: Fn(&reqwest::Client) -> reqwest::RequestBuilder,
+    {
+        let mut attempt = 0;
+
+        loop {
+            let retryable = match build(&self.client).send().await {
+                Ok(res) if res.status().is_server_error() => anyhow::anyhow!("HTTP {}", res.status()),
+                Ok(res) => return Ok(res),
+                Err(e) if e.is_connect() || e.is_timeout() => anyhow::Error::new(e),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= self.retries {
+                return Err(retryable.context(format!("WLED unreachable after {} attempts", attempt + 1)));
+            }
+
+            attempt += 1;
+            eprintln!("WLED request failed ({:#}), retrying ({}/{})", retryable, attempt, self.retries);
+            sleep(Duration::from_millis(RETRY_DELAY_MS * attempt as u64)).await;
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct WLed {
     host: String,
+    brightness: u64,
+    http: Http,
     presets: Vec<Preset>,
     effects: Vec<Effect>,
     raw_presets: HashMap<u64, JsonPreset>,
+    mock_presets: Vec<Preset>,  // Stand-in for presets.json when mocked, using the ids setup would store
 }
 
 impl WLed {
-    pub fn new() -> Self {
-        Self {
-            host: "".to_string(),
+    pub fn new(config: &config::WLed) -> Result<Self> {
+        let presets = config.presets.as_deref().unwrap_or_default().iter().enumerate()
+            .map(|(idx, ps)| Preset { id: (idx + 1) as u64, name: ps.name.clone() });
+        let playlists = config.playlists.as_deref().unwrap_or_default().iter().enumerate()
+            .map(|(idx, pl)| Preset { id: (idx + 100) as u64, name: pl.name.clone() });
+
+        Ok(Self {
+            host: config.host.clone(),
+            brightness: config.brightness,
+            http: Http::new(config)?,
             presets: vec![],
             effects: vec![],
             raw_presets: HashMap::new(),
-        }
+            mock_presets: presets.chain(playlists).collect(),
+        })
     }
 
-    pub async fn load(&mut self, host: &str) -> Result<()> {
-        self.host = String::from(host);
+    pub async fn load(&mut self) -> Result<()> {
         self.load_effects().await
             .context("Failed to load WLED effects")?;
         self.load_presets().await
@@ -118,31 +176,41 @@ impl WLed {
     }
 
     pub async fn load_effects(&mut self) -> Result<()> {
-        self.effects = get_effects(&self.host).await
+        self.effects = get_effects(&self.http, &self.host).await
             .context("Failed to get WLED effects")?;
         Ok(())
     }
 
     pub async fn load_presets(&mut self) -> Result<()> {
-        self.presets = get_presets(&self.host).await
+        if mock::enabled("wled") {
+            self.presets = self.mock_presets.clone();
+            return Ok(());
+        }
+
+        self.raw_presets = get_raw_presets(&self.http, &self.host).await
             .context("Failed to get WLED presets")?;
-        self.raw_presets = get_raw_presets(&self.host).await
-            .context("Failed to get raw WLED presets")?;
+        self.presets = self.raw_presets.iter()
+            .map(|(id, preset)| Preset { id: *id, name: preset.n.clone() })
+            .collect();
         Ok(())
     }
 
+    /// Names of the effects the controller offers, in id order
+    pub async fn effect_names(config: &config::WLed) -> Result<Vec<String>> {
+        let mut wled = Self::new(config)?;
+        wled.load_effects().await?;
+        Ok(wled.effects.into_iter().map(|eff| eff.name).collect())
+    }
+
     pub fn get_effect(&self, name: &str) -> Option<Effect> {
         self.effects.clone().into_iter().find(|eff| eff.name == name)
     }
 
     pub fn get_effect_id(&self, name: &str) -> u64 {
-
-        let effect_id = match self.get_effect(name) {
+        match self.get_effect(name) {
             Some(eff) => eff.id,
             _ => 0,
-        };
-
-        effect_id
+        }
     }
 
     pub fn get_preset(&self, name: &str) -> Option<Preset> {
@@ -166,8 +234,7 @@ impl WLed {
 
         let black = vec![0, 0, 0];
         for s in 0..32 {
-            if s < preset.colors.len() {
-                let segment = &segments[s];
+            if let (Some(color), Some(segment)) = (preset.colors.get(s), segments.get(s)) {
                 let get_color = |colors: &Option<Vec<Vec<u64>>>| {
                     colors.as_ref().and_then(|c| c.get(s).cloned()).unwrap_or_else(|| black.clone())
                 };
@@ -179,7 +246,7 @@ impl WLed {
                     grp: segment.grouping.unwrap_or(1),
                     bri: config.brightness,
                     n: segment.name.clone(),
-                    col: vec![preset.colors[s].clone(), get_color(&preset.colors2), get_color(&preset.colors3)],
+                    col: vec![color.clone(), get_color(&preset.colors2), get_color(&preset.colors3)],
                     fx: self.get_effect_id(&preset.effects[s]),
                     sx: preset.speed.unwrap_or(128),
                     ix: preset.intensity.unwrap_or(128),
@@ -205,9 +272,6 @@ impl WLed {
         }
 
         if changed || config.force {
-            let url = format!("http://{}/json/state", self.host);
-            let client = reqwest::Client::new();
-
             let json = json!({
                 "on": true,
                 "bri": config.brightness,
@@ -218,16 +282,9 @@ impl WLed {
                 "seg": json_preset.seg,
             });
 
-            let res = client.post(&url)
-                .json(&json)
-                .send()
-                .await
+            set_state(&self.http, &self.host, json).await
                 .context("Failed to send preset to WLED")?;
 
-            if !res.status().is_success() {
-                return Err(anyhow::anyhow!("Failed to set preset: HTTP {}", res.status()));
-            }
-
             sleep(Duration::from_millis(500)).await;
 
             self.load_presets().await
@@ -282,7 +339,7 @@ impl WLed {
             "playlist": json.playlist,
         });
 
-        if let Ok(()) = set_state(&self.host, state).await {
+        if let Ok(()) = set_state(&self.http, &self.host, state).await {
             self.load_presets().await?;
         }
 
@@ -295,30 +352,114 @@ impl WLed {
             .context(format!("Failed to run preset: {}", preset.name))
     }
 
+    /// Run a preset, then scale its brightness by the master dimmer
     pub async fn run_preset_id(&self, preset_id: u64) -> Result<()> {
-        set_state(&self.host, json!({"ps": preset_id})).await
+        set_state(&self.http, &self.host, json!({"ps": preset_id})).await?;
+
+        let level = dimmer::level();
+        if level < 100 {
+            set_state(&self.http, &self.host, json!({"bri": self.brightness * level as u64 / 100})).await?;
+        }
+        Ok(())
+    }
+
+    /// The controller's current /json/state
+    pub async fn get_state(config: &config::WLed) -> Result<Value> {
+        let wled = WLed::new(config)?;
+        if mock::enabled("wled") {
+            return Ok(json!({"on": true, "bri": wled.brightness, "seg": []}));
+        }
+
+        let addr = format!("http://{}/json/state", wled.host);
+        wled.http.send(|client| client.get(&addr)).await
+            .context(format!("Failed to connect to WLED at {}", addr))?
+            .json::<Value>()
+            .await
+            .context("Failed to parse WLED state")
     }
 
-    pub async fn trigger_toggle(toggle: &crate::config::Toggle, host: &str) -> Result<()> {
+    pub async fn set_raw_state(config: &config::WLed, state: Value) -> Result<()> {
+        let wled = WLed::new(config)?;
+        set_state(&wled.http, &wled.host, state).await
+    }
+
+    pub async fn set_brightness(config: &config::WLed, brightness: u64) -> Result<()> {
+        let wled = WLed::new(config)?;
+        set_state(&wled.http, &wled.host, json!({"bri": brightness})).await
+    }
+
+    /// Check the controller answers /json/info
+    pub async fn ping(config: &config::WLed) -> Result<()> {
+        let wled = WLed::new(config)?;
+        if mock::enabled("wled") {
+            return Ok(());
+        }
+
+        let addr = format!("http://{}/json/info", wled.host);
+
+        let res = wled.http.send(|client| client.get(&addr)).await
+            .context(format!("WLED unreachable at {}", wled.host))?;
+
+        if !res.status().is_success() {
+            return Err(anyhow::anyhow!("WLED returned HTTP {}", res.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Return the controller to an idle look: run the named preset, or switch off if none is given
+    pub async fn reset(config: &config::WLed, preset: Option<&str>) -> Result<()> {
+        let mut wled = WLed::new(config)?;
+
+        let Some(name) = preset else {
+            return set_state(&wled.http, &wled.host, json!({"on": false})).await;
+        };
+
+        wled.load().await
+            .context("Failed to load WLED for reset")?;
+
+        let preset = wled.get_preset(name)
+            .ok_or_else(|| anyhow::anyhow!("WLED preset not found: {}", name))?;
+        wled.run_preset(preset).await
+    }
+
+    /// Set the primary color of the given segments, or of the selected segments if none are given
+    pub async fn set_segment_color(&self, segments: Option<&[u64]>, color: Rgb) -> Result<()> {
+        let seg = match segments {
+            Some(ids) => json!(ids.iter().map(|id| json!({"id": id, "col": [color]})).collect::<Vec<_>>()),
+            None => json!({"col": [color]}),
+        };
+        set_state(&self.http, &self.host, json!({"seg": seg})).await
+    }
+
+    pub async fn trigger_toggle(toggle: &crate::config::Toggle, config: &config::WLed, color: Option<Rgb>) -> Result<()> {
         let wled_config = toggle.wled.as_ref()
             .ok_or_else(|| anyhow::anyhow!("WLED toggle missing 'wled' configuration"))?;
 
-        let mut wled = WLed::new();
-        wled.load(host).await
+        let mut wled = WLed::new(config)?;
+        wled.load().await
             .context("Failed to load WLED for toggle")?;
 
-        if let Some(preset) = wled.get_preset(&wled_config.preset) {
-            wled.run_preset(preset).await
-                .context(format!("Failed to run WLED preset: {}", wled_config.preset))
-        } else {
-            Err(anyhow::anyhow!("WLED preset not found: {}", wled_config.preset))
+        let preset = wled.get_preset(&wled_config.preset)
+            .ok_or_else(|| anyhow::anyhow!("WLED preset not found: {}", wled_config.preset))?;
+        wled.run_preset(preset).await
+            .context(format!("Failed to run WLED preset: {}", wled_config.preset))?;
+
+        if let Some(color) = color {
+            wled.set_segment_color(wled_config.color_segments.as_deref(), color).await
+                .context("Failed to set WLED sender color")?;
         }
+        Ok(())
     }
 }
 
-async fn get_effects(host: &str) -> Result<Vec<Effect>> {
+async fn get_effects(http: &Http, host: &str) -> Result<Vec<Effect>> {
+    if mock::enabled("wled") {
+        return Ok(vec![]);
+    }
+
     let addr = format!("http://{}/json/effects", host);
-    let resp = reqwest::get(&addr).await
+    let resp = http.send(|client| client.get(&addr)).await
         .context(format!("Failed to connect to WLED at {}", addr))?
         .json::<Value>()
         .await
@@ -342,9 +483,9 @@ async fn get_effects(host: &str) -> Result<Vec<Effect>> {
     Ok(effects)
 }
 
-async fn get_raw_presets(host: &str) -> Result<HashMap<u64, JsonPreset>> {
+async fn get_raw_presets(http: &Http, host: &str) -> Result<HashMap<u64, JsonPreset>> {
     let addr = format!("http://{}/presets.json", host);
-    let resp = reqwest::get(&addr).await
+    let resp = http.send(|client| client.get(&addr)).await
         .context(format!("Failed to connect to WLED at {}", addr))?
         .json::<HashMap<u64, Value>>()
         .await
@@ -362,30 +503,18 @@ async fn get_raw_presets(host: &str) -> Result<HashMap<u64, JsonPreset>> {
     Ok(result)
 }
 
-async fn get_presets(host: &str) -> Result<Vec<Preset>> {
-    let map = get_raw_presets(host).await
-        .context("Failed to get raw presets")?;
-
-    let pls = map.into_iter().map(
-        |(id, preset)| Preset {
-            id,
-            name: preset.n,
-        }
-    ).collect();
-
-    Ok(pls)
-}
-
-async fn set_state(host: &str, json: Value) -> Result<()> {
+async fn set_state(http: &Http, host: &str, json: Value) -> Result<()> {
     let addr = format!("http://{}/json/state", host);
     let json_str = json.to_string();
 
+    if mock::enabled("wled") {
+        mock::record("wled", addr, json);
+        return Ok(());
+    }
+
     println!("{} {}", addr, json_str);
 
-    let client = reqwest::Client::new();
-    let res = client.post(&addr)
-        .body(json_str)
-        .send()
+    let res = http.send(|client| client.post(&addr).body(json_str.clone()))
         .await
         .context(format!("Failed to send state to WLED at {}", addr))?;
 
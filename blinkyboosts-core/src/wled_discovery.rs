@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tokio::net::UdpSocket;
+use tokio::time::{timeout_at, Duration, Instant};
+
+const MDNS_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::new(224, 0, 0, 251), 5353);
+const SERVICE: [&str; 3] = ["_wled", "_tcp", "local"];
+const TYPE_PTR: u16 = 12;
+const CLASS_IN_UNICAST: u16 = 0x8001;  // IN with the "unicast response" bit set
+const FLAG_RESPONSE: u16 = 0x8000;
+
+/// A WLED controller that answered on the LAN
+#[derive(Debug, Clone)]
+pub struct Controller {
+    pub host: String,
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Deserialize)]
+struct JsonInfo {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    ver: String,
+}
+
+/// Ask the LAN for `_wled._tcp` over mDNS, wait `wait` for answers and read each
+/// controller's name and version from its JSON API, so a host doesn't have to be typed in
+pub async fn discover(wait: Duration) -> Result<Vec<Controller>> {
+    let sock = UdpSocket::bind("0.0.0.0:0").await.context("Failed to bind mDNS socket")?;
+    sock.send_to(&query(), MDNS_ADDR).await.context("Failed to send mDNS query")?;
+
+    // Responders answer a query from a port other than 5353 directly, so the answer's
+    // source address is the controller's
+    let deadline = Instant::now() + wait;
+    let mut hosts = BTreeSet::new();
+    let mut buf = [0u8; 1500];
+    while let Ok(received) = timeout_at(deadline, sock.recv_from(&mut buf)).await {
+        let (len, from) = received.context("Failed to read mDNS answer")?;
+        if is_answer(&buf[..len]) {
+            hosts.insert(from.ip());
+        }
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(2))
+        .build()?;
+    let mut controllers = Vec::new();
+    for host in hosts {
+        match info(&client, host).await {
+            Ok(info) => controllers.push(Controller {
+                host: host.to_string(),
+                name: if info.name.is_empty() { host.to_string() } else { info.name },
+                version: info.ver,
+            }),
+            Err(e) => eprintln!("Ignoring mDNS answer from {}: {:#}", host, e),
+        }
+    }
+    Ok(controllers)
+}
+
+async fn info(client: &reqwest::Client, host: IpAddr) -> Result<JsonInfo> {
+    client.get(format!("http://{}/json/info", host))
+        .send().await?
+        .error_for_status()?
+        .json::<JsonInfo>().await
+        .context("Not a WLED controller")
+}
+
+/// A PTR question for `_wled._tcp.local`
+fn query() -> Vec<u8> {
+    let mut packet = vec![0u8; 12];
+    packet[5] = 1;  // One question
+    for label in SERVICE {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN_UNICAST.to_be_bytes());
+    packet
+}
+
+/// A response carrying at least one answer record
+fn is_answer(packet: &[u8]) -> bool {
+    packet.len() >= 12
+        && u16::from_be_bytes([packet[2], packet[3]]) & FLAG_RESPONSE != 0
+        && u16::from_be_bytes([packet[6], packet[7]]) > 0
+}
@@ -0,0 +1,190 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::{self, WhilePlaying};
+use crate::{clock, mock, wled};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_OVERRUN: Duration = Duration::from_secs(30);  // Stop waiting on a playlist this long past its expected end
+const DEFERRED_PRESET: Duration = Duration::from_secs(5);  // Shown before the next deferred effect runs
+
+/// When a WLED effect for a boost may run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slot {
+    Now,
+    After(Duration),  // Once the playlists ahead of it have ended
+    Coalesced,  // An effect already waiting to run covers this one
+}
+
+struct State {
+    free_at: Option<Instant>,  // When the last playlist started or waiting to start is due to end
+    deferred_at: Option<Instant>,  // When the latest deferred effect runs
+    overrunning: bool,  // The controller is still playing past the expected end
+}
+
+static STATE: Mutex<State> = Mutex::new(State { free_at: None, deferred_at: None, overrunning: false });
+
+/// How long a configured playlist plays for, or None for a preset or a playlist that never ends.
+/// WLED durations are in tenths of a second, and the last one is reused for presets without one.
+pub fn length(cfg: &config::WLed, name: &str) -> Option<Duration> {
+    let playlist = cfg.playlists.as_deref().unwrap_or_default().iter().find(|pl| pl.name == name)?;
+    if playlist.repeat == 0 {
+        return None;
+    }
+    let mut tenths = 0;
+    for idx in 0..playlist.presets.len() {
+        let dur = *playlist.durations.get(idx).or(playlist.durations.last())?;
+        if dur == 0 {
+            return None;  // Holds that preset until something else runs
+        }
+        tenths += dur;
+    }
+    Some(Duration::from_millis(tenths * playlist.repeat * 100))
+}
+
+/// Book a slot for a boost's WLED effect running `preset`, which keeps the controller busy until
+/// it ends if it's a playlist. Later effects are lined up behind it. A plain preset that has to
+/// wait is given a few seconds of its own, so effects deferred together still run in turn.
+pub fn claim(cfg: &config::WLed, preset: &str) -> Slot {
+    let now = Instant::now();
+    let length = length(cfg, preset).map(waited);
+    let plain = length.is_none() && playlist_id(cfg, preset).is_none();
+    let mut state = STATE.lock().unwrap();
+    let busy_until = state.free_at.filter(|at| *at > now);
+
+    let start = match (busy_until, cfg.while_playing) {
+        (Some(_), WhilePlaying::Coalesce) if state.deferred_at.is_some_and(|at| at > now) => return Slot::Coalesced,
+        (Some(at), WhilePlaying::Defer | WhilePlaying::Coalesce) => at,
+        _ => now,
+    };
+    let length = match plain && start > now {
+        true => Some(clock::scaled(DEFERRED_PRESET)),
+        false => length,
+    };
+    state.free_at = Some(start + length.unwrap_or_default()).filter(|at| *at > now);
+    match start > now {
+        true => {
+            state.deferred_at = Some(start);
+            Slot::After(start - now)
+        }
+        false => Slot::Now,
+    }
+}
+
+/// Wait while the controller is still playing a playlist that ran past its expected end
+pub async fn settled() {
+    while STATE.lock().unwrap().overrunning {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// After `preset` has been sent, check the controller's state once the playlist is due to end and
+/// hold later effects back until it reports the playlist finished (or moved on to its end preset)
+pub fn started(cfg: &config::WLed, preset: &str) {
+    let Some(length) = length(cfg, preset).map(waited) else { return };
+    let Some(id) = playlist_id(cfg, preset) else { return };
+    let cfg = cfg.clone();
+
+    tokio::spawn(async move {
+        let expected_end = Instant::now() + length;
+        tokio::time::sleep(length).await;
+        while playing(&cfg, id).await {
+            if expected_end.elapsed() > MAX_OVERRUN {
+                eprintln!("WLED playlist {} still running {}s past its end, no longer waiting for it", id, MAX_OVERRUN.as_secs());
+                break;
+            }
+            {
+                let mut state = STATE.lock().unwrap();
+                state.overrunning = true;
+                state.free_at = state.free_at.max(Some(Instant::now() + POLL_INTERVAL));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        STATE.lock().unwrap().overrunning = false;
+    });
+}
+
+/// How long a playlist of `length` keeps the controller busy: sped up like the show's timers when
+/// WLED is mocked, since nothing really plays it
+fn waited(length: Duration) -> Duration {
+    match mock::enabled("wled") {
+        true => clock::scaled(length),
+        false => length,
+    }
+}
+
+/// The id the playlist is stored under (see `WLed::set_playlist`)
+fn playlist_id(cfg: &config::WLed, name: &str) -> Option<u64> {
+    cfg.playlists.as_deref().unwrap_or_default().iter()
+        .position(|pl| pl.name == name)
+        .map(|idx| (idx + 100) as u64)
+}
+
+/// Whether the controller reports playlist `id` as running; an unreachable one counts as done
+async fn playing(cfg: &config::WLed, id: u64) -> bool {
+    match wled::WLed::get_state(cfg).await {
+        Ok(state) => state.get("pl").and_then(|pl| pl.as_i64()) == Some(id as i64),
+        Err(e) => {
+            eprintln!("Failed to check WLED playlist state: {:#}", e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wled(while_playing: &str) -> config::WLed {
+        toml::from_str(&format!(r#"
+            host = "127.0.0.1"
+            boost_playlist = "party"
+            brightness = 128
+            setup = false
+            force = false
+            while_playing = "{}"
+
+            [[playlists]]
+            name = "party"
+            presets = ["red", "blue"]
+            durations = [20]
+            transitions = [0]
+            repeat = 1
+            end = "red"
+
+            [[playlists]]
+            name = "forever"
+            presets = ["red", "blue"]
+            durations = [20, 0]
+            transitions = [0]
+            repeat = 1
+            end = "red"
+        "#, while_playing)).unwrap()
+    }
+
+    fn after(slot: Slot, secs: u64) -> bool {
+        matches!(slot, Slot::After(wait) if wait <= Duration::from_secs(secs) && wait > Duration::from_secs(secs) - Duration::from_millis(500))
+    }
+
+    #[test]
+    fn length_adds_up_the_durations() {
+        let cfg = wled("defer");
+        assert_eq!(length(&cfg, "party"), Some(Duration::from_secs(4)));
+        assert_eq!(length(&cfg, "forever"), None);
+        assert_eq!(length(&cfg, "red"), None);
+    }
+
+    #[test]
+    fn claim_lines_effects_up_behind_playlists() {
+        let (defer, coalesce, interrupt) = (wled("defer"), wled("coalesce"), wled("interrupt"));
+
+        assert_eq!(claim(&defer, "party"), Slot::Now);
+        assert!(after(claim(&defer, "party"), 4));
+        assert!(after(claim(&defer, "red"), 8));
+        assert!(after(claim(&defer, "party"), 13));  // The deferred preset gets a few seconds of its own
+        assert_eq!(claim(&coalesce, "party"), Slot::Coalesced);
+
+        assert_eq!(claim(&interrupt, "red"), Slot::Now);
+        assert_eq!(claim(&defer, "red"), Slot::Now);  // Nothing's left playing once a preset cut in
+    }
+}
@@ -0,0 +1,373 @@
+use lightning_invoice::{Bolt11Invoice, Bolt11InvoiceDescription};
+
+use nostr_sdk::hashes::{sha256, Hash};
+use nostr_sdk::nips::nip01::Coordinate;
+use nostr_sdk::{Alphabet, Timestamp, Client, Options, Filter, Kind, PublicKey, SingleLetterTag, SubscriptionId, RelayPoolNotification, TagKind};
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use std::future::Future;
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::boostboard::BoostFilters;
+use crate::{amounts, clock, config};
+use crate::dedup::{Seen, SeenEvents};
+use crate::profiles::Profiles;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Zap {
+    pub sender_pubkey:    String,
+    pub sender_name:      Option<String>,
+    pub recipient_pubkey: Option<String>,
+    pub message:          Option<String>,
+    pub value_msat_total: i64,
+    pub created_at:       Timestamp,
+    pub is_old:           bool,
+}
+
+/// How receipts for a zap split between several recipients (NIP-57 zap splits) are counted.
+/// The sender's wallet pays each recipient separately, so one zap produces a receipt per share.
+#[derive(Debug, Clone)]
+pub enum SplitMode {
+    /// Count every receipt as its own zap
+    Separate,
+    /// Sum receipts from the same sender and message arriving within the window into one zap
+    Aggregate(Duration),
+    /// Only count receipts paid to one of these pubkeys (hex)
+    Local(Vec<String>),
+}
+
+impl SplitMode {
+    pub fn from_config(cfg: &config::Zaps) -> Result<Self> {
+        match cfg.splits.to_lowercase().as_str() {
+            "separate" => Ok(Self::Separate),
+            "aggregate" => Ok(Self::Aggregate(Duration::from_secs(cfg.split_window_secs))),
+            "local" => {
+                if cfg.local_pubkeys.is_empty() {
+                    bail!("\"local\" requires local_pubkeys");
+                }
+                let pubkeys = cfg.local_pubkeys.iter()
+                    .map(|pk| PublicKey::parse(pk)
+                        .map(|pk| pk.to_hex())
+                        .context(format!("Invalid local pubkey: {}", pk)))
+                    .collect::<Result<_>>()?;
+                Ok(Self::Local(pubkeys))
+            }
+            other => bail!("Unknown split mode \"{}\" (expected separate, aggregate or local)", other),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Zaps {
+    client: Client,
+    naddr: Coordinate,
+    seen: SeenEvents,
+    profiles: Profiles,
+    splits: SplitMode,
+    filters: BoostFilters,
+    live_since: Option<Timestamp>,
+    subscription: SubscriptionId,
+}
+
+impl Zaps {
+    pub async fn new(relay_addrs: &Vec<String>, naddr: &str, seen: SeenEvents) -> Result<Self> {
+        let opts = Options::new().wait_for_send(false);
+        let client = Client::builder().opts(opts).build();
+
+        for relay_addr in relay_addrs {
+            client.add_relay(relay_addr).await
+                .context(format!("Failed to add relay: {}", relay_addr))?;
+        }
+
+        client.connect().await;
+
+        let naddr: Coordinate = Coordinate::parse(naddr)
+            .context(format!("Failed to parse naddr: {}", naddr))?;
+
+        Ok(Self {
+            client,
+            naddr,
+            seen,
+            profiles: Profiles::shared(),
+            splits: SplitMode::Separate,
+            filters: BoostFilters::default(),
+            live_since: None,
+            subscription: SubscriptionId::generate(),
+        })
+    }
+
+    pub fn with_splits(mut self, splits: SplitMode) -> Self {
+        self.splits = splits;
+        self
+    }
+
+    pub fn with_filters(mut self, filters: BoostFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// When live listening first started, so zaps sent while reconnecting still count as new
+    pub fn with_live_since(mut self, live_since: Timestamp) -> Self {
+        self.live_since = Some(live_since);
+        self
+    }
+
+    /// The relay connection, e.g. to time its round trips
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Zap receipts for the naddr, without a start time
+    pub fn filter(&self) -> Filter {
+        Filter::new()
+            .coordinate(&self.naddr)
+            .kind(Kind::ZapReceipt)
+    }
+
+    /// The id `subscribe` uses, so events asked for again arrive on the same subscription
+    pub fn subscription_id(&self) -> SubscriptionId {
+        self.subscription.clone()
+    }
+
+    pub async fn subscribe(&self, since: Option<Timestamp>) -> Result<SubscriptionId> {
+        let filter = self.filter().since(since.unwrap_or_else(|| Timestamp::from_secs(0)));
+
+        self.client.subscribe_with_id(self.subscription.clone(), vec![filter], None).await
+            .context("Failed to subscribe to zaps")?;
+
+        Ok(self.subscription.clone())
+    }
+
+    pub async fn subscribe_zaps<F, Fut>(&self, since: Option<Timestamp>, func: F) -> Result<()>
+    where
+     F: Fn(Zap) -> Fut,
+     Fut: Future<Output = ()>,
+    {
+        // Amounts are checked once split shares have been merged, so a zap's total is compared
+        let func = |zap: Zap| {
+            let counted = self.filters.matches_sats(zap.value_msat_total / 1000);
+            if !counted {
+                println!("Zap doesn't match filters: {:#?}", zap);
+            }
+            let handled = counted.then(|| func(zap));
+            async move {
+                if let Some(handled) = handled {
+                    handled.await;
+                }
+            }
+        };
+
+        match &self.splits {
+            SplitMode::Separate => self.receive_zaps(since, func).await,
+            SplitMode::Local(pubkeys) => {
+                self.receive_zaps(since, |zap| {
+                    let local = zap.recipient_pubkey.as_ref().is_some_and(|pk| pubkeys.contains(pk));
+                    if !local {
+                        println!("Ignoring zap split share paid to {}", zap.recipient_pubkey.as_deref().unwrap_or("unknown"));
+                    }
+                    let handled = local.then(|| func(zap));
+                    async move {
+                        if let Some(handled) = handled {
+                            handled.await;
+                        }
+                    }
+                }).await
+            }
+            SplitMode::Aggregate(window) => {
+                let (zap_tx, zap_rx) = mpsc::unbounded_channel();
+                let receive = async move {
+                    let result = self.receive_zaps(since, |zap| {
+                        let _ = zap_tx.send(zap);
+                        async {}
+                    }).await;
+                    drop(zap_tx);
+                    result
+                };
+                let (result, ()) = tokio::join!(receive, aggregate_splits(zap_rx, *window, func));
+                result
+            }
+        }
+    }
+
+    async fn receive_zaps<F, Fut>(&self, since: Option<Timestamp>, func: F) -> Result<()>
+    where
+     F: Fn(Zap) -> Fut,
+     Fut: Future<Output = ()>,
+    {
+        let sub_id = self.subscribe(since).await
+            .context("Failed to subscribe to zaps")?;
+
+        let now = self.live_since.unwrap_or_else(Timestamp::now);
+
+        // Handle subscription notifications with `handle_notifications` method
+        self.client.handle_notifications(|notification| async {
+            if let RelayPoolNotification::Event {
+                subscription_id,
+                event,
+                ..
+            } = notification
+            {
+                // Check subscription ID
+                if subscription_id != sub_id {
+                    return Ok(false);
+                }
+
+                let seen_state = self.seen.check(&event.id);
+                if seen_state == Seen::Duplicate {
+                    println!("Duplicate zap receipt dropped: {}", event.id);
+                    return Ok(false);
+                }
+
+                let mut description = String::new();
+                let mut bolt11 = String::new();
+                let mut recipient = None;
+
+                for tag in &event.tags {
+                    let content = tag.content().unwrap_or_default().to_string();
+
+                    if tag.kind() == TagKind::Description {
+                        description = content;
+                    }
+                    else if tag.kind() == TagKind::Bolt11 {
+                        bolt11 = content;
+                    }
+                    else if tag.kind() == TagKind::SingleLetter(SingleLetterTag::lowercase(Alphabet::P)) {
+                        recipient = Some(content);
+                    }
+                }
+
+                let value_msat_total = match zap_amount_msat(&bolt11, &description) {
+                    Ok(msat) => msat,
+                    Err(e) => {
+                        eprintln!("Rejected zap receipt {}: {:#}", event.id, e);
+                        return Ok(false);
+                    }
+                };
+
+                let mut pubkey = String::new();
+
+                if let Ok(Value::Object(req)) = serde_json::from_str(&description) {
+                    if let Value::String(pk) = &req["pubkey"] {
+                        pubkey = pk.clone();
+                    }
+                }
+
+                let profile = self.profiles.lookup(&self.client, &pubkey);
+
+                let result =  Zap {
+                    sender_name: Some(profile.display_name.unwrap_or_else(|| pubkey.clone())),
+                    sender_pubkey: pubkey,
+                    recipient_pubkey: recipient,
+                    message: Some(event.content),
+                    value_msat_total,
+                    created_at: event.created_at,
+                    is_old: !clock::is_new(event.created_at, now) || seen_state == Seen::PreviousRun
+                };
+
+                if self.filters.excludes_sender(&[&result.sender_pubkey, result.sender_name.as_deref().unwrap_or_default()]) {
+                    println!("Zap doesn't match filters: {:#?}", result);
+                    return Ok(false);
+                }
+
+                func(result).await;
+            }
+            Ok(false) // Set to true to exit from the loop
+        })
+        .await
+        .context("Failed to handle zap notifications")?;
+
+        Ok(())
+    }
+}
+
+/// Merge receipts for the shares of a split zap, passing each merged zap on once no further
+/// shares have arrived for `window`. Pending zaps are flushed when the receiver closes.
+async fn aggregate_splits<F, Fut>(mut rx: mpsc::UnboundedReceiver<Zap>, window: Duration, func: F)
+where
+ F: Fn(Zap) -> Fut,
+ Fut: Future<Output = ()>,
+{
+    // (merged zap, recipients paid so far, when to pass it on)
+    let mut pending: Vec<(Zap, Vec<Option<String>>, Instant)> = Vec::new();
+
+    loop {
+        let next_due = pending.iter().map(|(_, _, due)| *due).min();
+
+        tokio::select! {
+            zap = rx.recv() => match zap {
+                Some(zap) => {
+                    let sibling = pending.iter_mut().find(|(merged, recipients, _)| {
+                        merged.sender_pubkey == zap.sender_pubkey
+                            && merged.message == zap.message
+                            && merged.created_at.as_u64().abs_diff(zap.created_at.as_u64()) <= window.as_secs()
+                            && !recipients.contains(&zap.recipient_pubkey)
+                    });
+
+                    match sibling {
+                        Some((merged, recipients, due)) => {
+                            println!("Merging zap split share of {} msat", zap.value_msat_total);
+                            merged.value_msat_total = merged.value_msat_total.saturating_add(zap.value_msat_total);
+                            merged.is_old &= zap.is_old;
+                            recipients.push(zap.recipient_pubkey);
+                            *due = Instant::now() + window;
+                        }
+                        None => {
+                            let recipients = vec![zap.recipient_pubkey.clone()];
+                            pending.push((zap, recipients, Instant::now() + window));
+                        }
+                    }
+                }
+                None => {
+                    for (zap, _, _) in pending.drain(..) {
+                        func(zap).await;
+                    }
+                    return;
+                }
+            },
+            _ = tokio::time::sleep_until(next_due.unwrap_or_else(Instant::now)), if next_due.is_some() => {
+                let now = Instant::now();
+                let (due, waiting) = pending.into_iter().partition(|(_, _, due)| *due <= now);
+                pending = waiting;
+                for (zap, _, _) in due {
+                    func(zap).await;
+                }
+            }
+        }
+    }
+}
+
+/// Amount of a zap receipt in msats. Uses the bolt11 amount when present, falling back to the
+/// amount tag of the embedded zap request. Per NIP-57 the invoice's description hash must match
+/// the zap request, otherwise the receipt is treated as spoofed.
+fn zap_amount_msat(bolt11: &str, description: &str) -> Result<i64> {
+    let request_amount = serde_json::from_str::<Value>(description).ok()
+        .and_then(|req| req["tags"].as_array().cloned())
+        .and_then(|tags| tags.iter().find_map(|tag| match tag.as_array()?.as_slice() {
+            [Value::String(name), Value::String(amount), ..] if name == "amount" => amount.parse::<i64>().ok(),
+            _ => None,
+        }));
+
+    let invoice = match bolt11.parse::<Bolt11Invoice>() {
+        Ok(invoice) => invoice,
+        Err(_) => return Ok(request_amount.unwrap_or_default()),
+    };
+
+    if let Bolt11InvoiceDescription::Hash(hash) = invoice.description() {
+        if hash.0 != sha256::Hash::hash(description.as_bytes()) {
+            bail!("description hash does not match zap request");
+        }
+    }
+
+    match (invoice.amount_milli_satoshis(), request_amount) {
+        (Some(invoice_msat), Some(request_msat)) if amounts::msats_from_u64(invoice_msat) != request_msat => {
+            bail!("invoice amount {} msat does not match zap request amount {} msat", invoice_msat, request_msat)
+        }
+        (Some(invoice_msat), _) => Ok(amounts::msats_from_u64(invoice_msat)),
+        (None, request_msat) => Ok(request_msat.unwrap_or_default()),
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use blinkyboosts_core::config::Config;
+use blinkyboosts_core::harness::Harness;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn config(extra: &str) -> Config {
+    toml::from_str(&format!(r#"
+        [wled]
+        host = "unused"
+        boost_playlist = "BOOST"
+        brightness = 128
+        setup = false
+        force = false
+
+        [[toggles]]
+        threshold = 100
+        use_total = true
+        output = "wled"
+        wled = {{ preset = "BOOST-100" }}
+
+        [[toggles]]
+        threshold = 1000
+        use_total = true
+        output = "wled"
+        wled = {{ preset = "BOOST-1000" }}
+        {}
+    "#, extra)).unwrap()
+}
+
+#[tokio::test]
+async fn boost_runs_the_matching_wled_preset() {
+    let mut harness = Harness::start(config("")).await.unwrap();
+
+    harness.boost(150, "alice", "Great show!").unwrap();
+    let delivered = harness.delivered(TIMEOUT).await.unwrap();
+
+    assert_eq!(delivered.source, "Boostboard");
+    assert_eq!(delivered.sats, 150);
+    assert_eq!(delivered.sender.as_deref(), Some("alice"));
+    assert_eq!(delivered.effects.len(), 1);
+    assert_eq!(delivered.effects[0].description, "WLED: BOOST-100");
+    assert_eq!(harness.wled.presets_run(), vec!["BOOST-100"]);
+    assert_eq!(harness.total().await, 150);
+}
+
+#[tokio::test]
+async fn excluded_sender_is_not_counted() {
+    let mut harness = Harness::start(config(r#"
+        [boostboard]
+        pubkey = "unused"
+        exclude_senders = ["spammer"]
+    "#)).await.unwrap();
+
+    harness.boost(5000, "spammer", "buy my coin").unwrap();
+    harness.boost(200, "bob", "hi").unwrap();
+    let delivered = harness.delivered(TIMEOUT).await.unwrap();
+
+    assert_eq!(delivered.sender.as_deref(), Some("bob"));
+    assert_eq!(harness.total().await, 200);
+    assert!(!harness.wled.presets_run().contains(&"BOOST-1000".to_string()));
+}
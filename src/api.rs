@@ -0,0 +1,358 @@
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Extension, Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::{broadcast, mpsc, Mutex};
+
+use blinkyboosts_core::{backfill, campaigns, config, metrics, mock, payment_qr, sanitize, scenes, summary, toggle_stats};
+use blinkyboosts_core::config::ApiRole;
+use blinkyboosts_core::moderation::{self, Decision};
+use blinkyboosts_core::effects::Effects;
+use blinkyboosts_core::messages::{Command, Event};
+use blinkyboosts_core::sat_tracker::SatTracker;
+
+use crate::event_stream::{EventStream, StreamEvent};
+use crate::remote::AgentCommand;
+
+pub const LISTENERS: [&str; 3] = ["Zaps", "Boostboard", "NWC"];
+
+#[derive(Clone)]
+struct ApiState {
+    tx: mpsc::Sender<Command>,
+    event_tx: mpsc::Sender<Event>,  // For the recap when the API finishes the show
+    tracker: Arc<Mutex<SatTracker>>,
+    effects: Effects,
+    events: EventStream,
+}
+
+#[derive(Deserialize)]
+struct TriggerRequest {
+    sats: i64,
+}
+
+#[derive(Deserialize)]
+struct NoteRequest {
+    text: String,
+}
+
+/// Serve the control API until the process exits
+pub async fn serve(
+    cfg: config::Api,
+    tx: mpsc::Sender<Command>,
+    event_tx: mpsc::Sender<Event>,
+    tracker: Arc<Mutex<SatTracker>>,
+    effects: Effects,
+    events: EventStream
+) -> Result<()> {
+    if cfg.tokens.is_empty() {
+        println!("Control API has no [[api.tokens]]: anyone who can reach {} can fire effects", cfg.bind);
+    }
+    let tokens = Arc::new(cfg.tokens.clone());
+
+    // Totals and history, safe for an overlay URL
+    let viewer = Router::new()
+        .route("/events", get(event_socket))
+        .route("/total", get(get_total))
+        .route("/qr.svg", get(get_qr))
+        .route("/boosts", get(get_boosts))
+        .route("/sessions", get(get_sessions))
+        .route("/campaigns", get(get_campaigns))
+        .route("/mock", get(get_mock))
+        .route("/summary", get(get_summary))
+        .route("/toggles", get(get_toggles))
+        .route("/scenes", get(get_scenes))
+        .route_layer(middleware::from_fn_with_state((tokens.clone(), ApiRole::Viewer), require_role));
+
+    // Anything that fires effects, changes listeners or shows held messages
+    let operator = Router::new()
+        .route("/moderation", get(get_moderation))
+        .route("/moderation/:id/:decision", post(moderate_boost))
+        .route("/summary/finish", post(finish_show))
+        .route("/trigger", post(post_trigger))
+        .route("/invoice", post(post_invoice))
+        .route("/boosts/:id/note", post(note_boost))
+        .route("/markers", post(add_marker))
+        .route("/toggles/:name/fire", post(fire_toggle))
+        .route("/listeners/:name/start", post(start_listener))
+        .route("/listeners/:name/stop", post(stop_listener))
+        .route("/listeners/:name/backfill/cancel", post(cancel_backfill))
+        .route("/scenes/:name/capture", post(capture_scene))
+        .route("/scenes/:name/apply", post(apply_scene))
+        .route_layer(middleware::from_fn_with_state((tokens, ApiRole::Operator), require_role));
+
+    let app = viewer.merge(operator)
+        .with_state(ApiState { tx, event_tx, tracker, effects, events });
+
+    let listener = tokio::net::TcpListener::bind(&cfg.bind).await
+        .context(format!("Failed to bind control API to {}", cfg.bind))?;
+
+    println!("Control API listening on http://{}", cfg.bind);
+    axum::serve(listener, app).await.context("Control API server error")
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "error": message.into() }))).into_response()
+}
+
+/// The token sent with a request: the `Authorization: Bearer` header, or a `token` query
+/// parameter for browser WebSockets and overlay URLs, which can't set headers
+fn request_token(request: &Request) -> Option<&str> {
+    let bearer = request.headers().get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    bearer.or_else(|| {
+        request.uri().query()?.split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    }).map(str::trim)
+}
+
+// Compares every byte so the time taken doesn't give away how much of a guess was right
+fn same_token(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Let a request through when no tokens are configured, or its token's role is at least `needed`.
+/// The role it was let through with is passed on to the handler.
+async fn require_role(
+    State((tokens, needed)): State<(Arc<Vec<config::ApiToken>>, ApiRole)>,
+    mut request: Request,
+    next: Next
+) -> Response {
+    if tokens.is_empty() {
+        request.extensions_mut().insert(ApiRole::Operator);
+        return next.run(request).await;
+    }
+
+    let Some(sent) = request_token(&request) else {
+        return error(StatusCode::UNAUTHORIZED, "API token required");
+    };
+    let Some(token) = tokens.iter().find(|token| same_token(&token.token, sent)) else {
+        return error(StatusCode::UNAUTHORIZED, "Unknown API token");
+    };
+    if token.role < needed {
+        eprintln!("API token \"{}\" refused {} {}: needs the operator role", token.name, request.method(), request.uri().path());
+        return error(StatusCode::FORBIDDEN, "This token is view-only");
+    }
+    request.extensions_mut().insert(token.role);
+    next.run(request).await
+}
+
+/// WebSocket stream of boosts, totals, effects and status changes (see `StreamEvent`)
+/// Operators can also send `AgentCommand`s on it, which is how `blinkyboosts connect` controls
+/// a headless agent.
+async fn event_socket(State(state): State<ApiState>, Extension(role): Extension<ApiRole>, ws: WebSocketUpgrade) -> Response {
+    let events = state.events.subscribe();
+    ws.on_upgrade(move |socket| forward_events(socket, events, role, state.tx))
+}
+
+async fn forward_events(mut socket: WebSocket, mut events: broadcast::Receiver<StreamEvent>, role: ApiRole, commands: mpsc::Sender<Command>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(json) = serde_json::to_string(&event) else { continue };
+                    if socket.send(Message::Text(json)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!("WebSocket client lagging, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(Message::Text(json))) => match serde_json::from_str::<AgentCommand>(&json) {
+                    Ok(_) if role < ApiRole::Operator => eprintln!("Ignoring command from a view-only WebSocket client"),
+                    Ok(command) => { let _ = commands.send(command.into_command()).await; }
+                    Err(e) => eprintln!("Ignoring WebSocket message: {}", e),
+                },
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn get_total(State(state): State<ApiState>) -> Response {
+    let tracker = state.tracker.lock().await;
+    Json(json!({ "total": tracker.total(), "by_source": tracker.by_source() })).into_response()
+}
+
+async fn get_qr() -> Response {
+    let Some(uri) = payment_qr::current() else {
+        return error(StatusCode::NOT_FOUND, "No payment QR configured");
+    };
+
+    match payment_qr::svg(&uri) {
+        Ok(svg) => ([(header::CONTENT_TYPE, "image/svg+xml")], svg).into_response(),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)),
+    }
+}
+
+async fn get_boosts(State(state): State<ApiState>) -> Response {
+    Json(state.tracker.lock().await.recent()).into_response()
+}
+
+/// Sats per episode, oldest first
+async fn get_sessions(State(state): State<ApiState>) -> Response {
+    Json(state.tracker.lock().await.sessions()).into_response()
+}
+
+/// Campaign totals, with the one in the rotating display slot marked `showing`
+async fn get_campaigns(State(state): State<ApiState>) -> Response {
+    Json(campaigns::progress(state.effects.config(), &*state.tracker.lock().await)).into_response()
+}
+
+/// Recap of the show so far, without finishing it
+async fn get_summary(State(state): State<ApiState>) -> Response {
+    let top = state.effects.config().summary.clone().unwrap_or_default().top_boosters;
+    Json(summary::Summary::from_stats(state.tracker.lock().await.show_stats(), top)).into_response()
+}
+
+/// Finish the show, saving and posting its recap
+async fn finish_show(State(state): State<ApiState>) -> Response {
+    let recap = summary::finish(state.effects.config(), &state.tracker).await;
+    let _ = state.event_tx.send(Event::ShowSummary(recap.clone())).await;
+    Json(recap).into_response()
+}
+
+/// Calls recorded for outputs with `mock = true`, oldest first
+async fn get_mock() -> Response {
+    Json(mock::recent()).into_response()
+}
+
+/// Boosts from new senders waiting for approval, oldest first
+async fn get_moderation() -> Response {
+    let pending: Vec<_> = moderation::pending().into_iter()
+        .map(|boost| moderation::PendingBoost { message: boost.message.map(|m| sanitize::clean("api", &m)), ..boost })
+        .collect();
+    Json(pending).into_response()
+}
+
+async fn moderate_boost(State(state): State<ApiState>, Path((id, decision)): Path<(u64, String)>) -> Response {
+    let Some(decision) = Decision::parse(&decision) else {
+        return error(StatusCode::NOT_FOUND, format!("Unknown decision {} (expected approve, reject or block)", decision));
+    };
+    if !moderation::is_pending(id) {
+        return error(StatusCode::NOT_FOUND, format!("No held boost #{}", id));
+    }
+
+    match state.tx.send(Command::ModerateBoost(id, decision)).await {
+        Ok(()) => (StatusCode::ACCEPTED, Json(json!({ "id": id, "decision": format!("{:?}", decision).to_lowercase() }))).into_response(),
+        Err(_) => error(StatusCode::SERVICE_UNAVAILABLE, "Controller is not running"),
+    }
+}
+
+async fn post_trigger(State(state): State<ApiState>, Json(req): Json<TriggerRequest>) -> Response {
+    if req.sats <= 0 {
+        return error(StatusCode::BAD_REQUEST, "sats must be positive");
+    }
+
+    match state.tx.send(Command::TestTrigger(req.sats)).await {
+        Ok(()) => (StatusCode::ACCEPTED, Json(json!({ "sats": req.sats }))).into_response(),
+        Err(_) => error(StatusCode::SERVICE_UNAVAILABLE, "Controller is not running"),
+    }
+}
+
+/// Show an on-demand invoice for `sats` on the GUI and overlay; paying it fires effects like
+/// any boost
+async fn post_invoice(State(state): State<ApiState>, Json(req): Json<TriggerRequest>) -> Response {
+    if req.sats <= 0 {
+        return error(StatusCode::BAD_REQUEST, "sats must be positive");
+    }
+
+    match payment_qr::request(state.effects.config(), req.sats as u64, state.event_tx.clone()).await {
+        Ok(invoice) => Json(json!({ "sats": req.sats, "invoice": invoice })).into_response(),
+        Err(e) => error(StatusCode::BAD_GATEWAY, format!("{:#}", e)),
+    }
+}
+
+/// Set the operator's note on a boost from this run; an empty note clears it
+async fn note_boost(State(state): State<ApiState>, Path(id): Path<u64>, Json(req): Json<NoteRequest>) -> Response {
+    let Some(note) = state.tracker.lock().await.annotate(id, &req.text) else {
+        return error(StatusCode::NOT_FOUND, format!("Boost {} is no longer in the recent history or boost log", id));
+    };
+    let _ = state.event_tx.send(Event::Noted(note.clone())).await;
+    Json(note).into_response()
+}
+
+async fn add_marker(State(state): State<ApiState>, Json(req): Json<NoteRequest>) -> Response {
+    if req.text.trim().is_empty() {
+        return error(StatusCode::BAD_REQUEST, "text must not be empty");
+    }
+    let note = state.tracker.lock().await.add_marker(&req.text);
+    let _ = state.event_tx.send(Event::Noted(note.clone())).await;
+    Json(note).into_response()
+}
+
+/// Each configured toggle with how often it fired and the sats that matched it
+async fn get_toggles(State(state): State<ApiState>) -> Response {
+    Json(toggle_stats::summary(state.effects.config())).into_response()
+}
+
+async fn fire_toggle(State(state): State<ApiState>, Path(name): Path<String>) -> Response {
+    match state.effects.fire_toggle(&name).await {
+        Some(Ok(effects)) => Json(json!({ "fired": metrics::describe(&effects) })).into_response(),
+        Some(Err(e)) => error(StatusCode::BAD_GATEWAY, format!("{:#}", e)),
+        None => error(StatusCode::NOT_FOUND, format!("No toggle named {}", name)),
+    }
+}
+
+async fn get_scenes() -> Response {
+    Json(scenes::names()).into_response()
+}
+
+async fn capture_scene(State(state): State<ApiState>, Path(name): Path<String>) -> Response {
+    match scenes::capture(state.effects.config(), &name).await {
+        Ok(scene) => Json(json!({ "captured": name, "scene": scene })).into_response(),
+        Err(e) => error(StatusCode::BAD_GATEWAY, format!("{:#}", e)),
+    }
+}
+
+async fn apply_scene(State(state): State<ApiState>, Path(name): Path<String>) -> Response {
+    if scenes::get(&name).is_none() {
+        return error(StatusCode::NOT_FOUND, format!("No scene named {}", name));
+    }
+
+    match scenes::apply(state.effects.config(), &name).await {
+        Ok(()) => Json(json!({ "applied": name })).into_response(),
+        Err(e) => error(StatusCode::BAD_GATEWAY, format!("{:#}", e)),
+    }
+}
+
+async fn start_listener(State(state): State<ApiState>, Path(name): Path<String>) -> Response {
+    send_listener_message(&state, &name, Command::StartListener).await
+}
+
+async fn stop_listener(State(state): State<ApiState>, Path(name): Path<String>) -> Response {
+    send_listener_message(&state, &name, Command::StopListener).await
+}
+
+async fn cancel_backfill(Path(name): Path<String>) -> Response {
+    let Some(listener) = LISTENERS.iter().find(|l| l.eq_ignore_ascii_case(&name)) else {
+        return error(StatusCode::NOT_FOUND, format!("Unknown listener {}", name));
+    };
+
+    match backfill::cancel(listener) {
+        true => Json(json!({ "cancelled": listener })).into_response(),
+        false => error(StatusCode::NOT_FOUND, format!("{} is not loading history", listener)),
+    }
+}
+
+async fn send_listener_message(state: &ApiState, name: &str, message: fn(String) -> Command) -> Response {
+    let Some(listener) = LISTENERS.iter().find(|l| l.eq_ignore_ascii_case(name)) else {
+        return error(StatusCode::NOT_FOUND, format!("Unknown listener {}", name));
+    };
+
+    match state.tx.send(message(listener.to_string())).await {
+        Ok(()) => (StatusCode::ACCEPTED, Json(json!({ "listener": listener }))).into_response(),
+        Err(_) => error(StatusCode::SERVICE_UNAVAILABLE, "Controller is not running"),
+    }
+}
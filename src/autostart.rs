@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+use anyhow::{Context, Result};
+
+const NAME: &str = "BlinkyBoosts";
+
+/// Where the login item lives for this platform
+fn item_path() -> Result<PathBuf> {
+    let var = |name: &str| std::env::var(name).context(format!("{} is not set", name));
+    Ok(if cfg!(windows) {
+        PathBuf::from(var("APPDATA")?).join(r"Microsoft\Windows\Start Menu\Programs\Startup").join(format!("{}.cmd", NAME))
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(var("HOME")?).join("Library/LaunchAgents/com.blinkyboosts.plist")
+    } else {
+        PathBuf::from(var("HOME")?).join(".config/autostart/blinkyboosts.desktop")
+    })
+}
+
+/// Login item contents starting `exe` from `dir`, where config.toml is read from
+fn item(exe: &str, dir: &str) -> String {
+    if cfg!(windows) {
+        format!("@echo off\r\ncd /d \"{}\"\r\nstart \"\" \"{}\"\r\n", dir, exe)
+    } else if cfg!(target_os = "macos") {
+        format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.blinkyboosts</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>WorkingDirectory</key>
+    <string>{}</string>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#, exe, dir)
+    } else {
+        format!("[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\"\nPath={}\nX-GNOME-Autostart-enabled=true\n", NAME, exe, dir)
+    }
+}
+
+pub fn is_installed() -> bool {
+    item_path().is_ok_and(|path| path.exists())
+}
+
+/// Start this executable at login, from the current directory so it finds the same config.toml
+pub fn install() -> Result<PathBuf> {
+    let exe = std::env::current_exe().context("Can't find the running executable")?;
+    let dir = std::env::current_dir().context("Can't read the current directory")?;
+    let path = item_path()?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, item(&exe.to_string_lossy(), &dir.to_string_lossy()))
+        .context(format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+pub fn remove() -> Result<()> {
+    let path = item_path()?;
+    if path.exists() {
+        std::fs::remove_file(&path).context(format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,682 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use nostr_sdk::nips::nip01::Coordinate;
+use nostr_sdk::{Keys, PublicKey, Timestamp};
+
+use crate::{autostart, gui};
+use blinkyboosts_core::boosts::Boostagram;
+use blinkyboosts_core::config::{self, Config};
+use blinkyboosts_core::{amount_patterns, amounts, artnet, boostboard, bundle, capture, clock, dimmer, effects, emoji, fixtures, i18n, mock, notes, nwc, nwc_setup, rehearsal, sacn, sanitize, sat_tracker, show_sync, templates, test_run, total_brackets, zaps};
+
+#[derive(Parser)]
+#[command(name = "blinkyboosts", about = "Trigger lighting effects from podcast boosts and zaps")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Check config.toml for errors without connecting to anything
+    Validate,
+    /// Create the configured WLED presets and playlists, then exit
+    SetupWled,
+    /// Trigger effects for a test boost, then exit
+    Test {
+        #[arg(long)]
+        sats: i64,
+        /// Sender name or pubkey, for previewing the color of toggles with sender_color set
+        #[arg(long)]
+        sender: Option<String>,
+    },
+    /// Listen for boosts and trigger effects (the default when no command is given)
+    Listen {
+        /// Run without the GUI, logging to the console
+        #[arg(long)]
+        headless: bool,
+        /// Run as one of another instance's [[shows]], reporting events on stdout
+        #[arg(long, hide = true)]
+        show: Option<String>,
+    },
+    /// Run the GUI as a controller for the headless agent at [remote] url, without listening or
+    /// driving any outputs here
+    Connect,
+    /// Send boosts through the whole pipeline using a mock relay and WLED controller, then exit
+    #[cfg(feature = "harness")]
+    Simulate {
+        /// Boost amounts, sent in order
+        #[arg(long, num_args = 1.., required = true)]
+        sats: Vec<i64>,
+        #[arg(long, default_value = "Simulator")]
+        sender: String,
+        #[arg(long, default_value = "")]
+        message: String,
+    },
+    /// Start BlinkyBoosts at login, from the current directory, then exit
+    Autostart {
+        /// Remove the login item instead
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Load historical boosts from Boostboard and NWC and print them as JSON, with the operator's
+    /// notes and markers
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Write the show's setup (config, scenes, moderation lists, translations) to one bundle file
+    ExportBundle {
+        #[arg(long, default_value = "blinkyboosts-bundle.json")]
+        output: String,
+    },
+    /// Set this directory up from a bundle file, keeping replaced files as .bak
+    ImportBundle {
+        file: String,
+    },
+    /// Print the DMX frames recorded by [capture] with their timing, then exit
+    Playback {
+        /// Capture file to read instead of `[capture] file`
+        #[arg(long)]
+        file: Option<String>,
+        /// Also send the frames again, at their recorded pace, to the configured Art-Net and sACN outputs
+        #[arg(long)]
+        send: bool,
+    },
+    /// Replay a boost timeline through the effects on a simulated clock, to rehearse a show's cues
+    Rehearse {
+        /// Boost log (boost_log.jsonl), file written by `export`, or a JSON list of {at, sats, sender, message} cues
+        file: String,
+        /// How many times faster than real time to run; below 1 runs slower
+        #[arg(long, default_value_t = 10.0)]
+        speed: f64,
+        /// Record what every output would send instead of sending it (see [mock])
+        #[arg(long)]
+        mock: bool,
+    },
+}
+
+/// Run a one-shot command. Listening is handled by main.
+pub async fn run(command: &Command, config: Config) -> Result<()> {
+    match command {
+        Command::Validate => validate(&config),
+        Command::SetupWled => setup_wled(config).await,
+        Command::Test { sats, sender } => test(config, *sats, sender.as_deref()).await,
+        Command::Export { output } => export(&config, output.as_deref()).await,
+        Command::Autostart { remove } => autostart(*remove),
+        Command::ExportBundle { output } => export_bundle(output),
+        Command::ImportBundle { file } => import_bundle(file),
+        Command::Playback { file, send } => playback(&config, file.as_deref(), *send).await,
+        Command::Rehearse { file, speed, mock } => rehearse(config, file, *speed, *mock).await,
+        #[cfg(feature = "harness")]
+        Command::Simulate { sats, sender, message } => simulate(config, sats, sender, message).await,
+        Command::Listen { .. } | Command::Connect => Ok(()),
+    }
+}
+
+fn validate(config: &Config) -> Result<()> {
+    let mut problems = Vec::new();
+
+    if let Some(nwc_cfg) = &config.nwc {
+        if let Err(e) = nwc_setup::parse(&nwc_cfg.uri) {
+            problems.push(format!("nwc.uri: {:#}", e));
+        }
+    }
+
+    if let Some(board_cfg) = &config.boostboard {
+        if board_cfg.relay_addrs.is_empty() {
+            problems.push("boostboard.relay_addrs: no relays specified".to_string());
+        }
+        if let Err(e) = PublicKey::from_hex(&board_cfg.pubkey) {
+            problems.push(format!("boostboard.pubkey: {}", e));
+        }
+    }
+
+    if let Some(zaps_cfg) = &config.zaps {
+        if zaps_cfg.relay_addrs.is_empty() {
+            problems.push("zaps.relay_addrs: no relays specified".to_string());
+        }
+        if let Err(e) = Coordinate::parse(&zaps_cfg.naddr) {
+            problems.push(format!("zaps.naddr: {}", e));
+        }
+        if let Err(e) = zaps::SplitMode::from_config(zaps_cfg) {
+            problems.push(format!("zaps.splits: {:#}", e));
+        }
+    }
+
+    let sats_ranges = [
+        ("boostboard", config.boostboard.as_ref().map(|c| (c.filters.min_sats, c.filters.max_sats))),
+        ("nwc", config.nwc.as_ref().map(|c| (c.filters.min_sats, c.filters.max_sats))),
+        ("zaps", config.zaps.as_ref().map(|c| (c.min_sats, c.max_sats))),
+    ];
+    for (source, range) in sats_ranges {
+        if let Some((Some(min), Some(max))) = range.filter(|(min, max)| min > max) {
+            problems.push(format!("{}: min_sats {} is over max_sats {}", source, min, max));
+        }
+    }
+
+    if let Some(sync_cfg) = &config.sync {
+        if sync_cfg.relay_addrs.is_empty() {
+            problems.push("sync.relay_addrs: no relays specified".to_string());
+        }
+        if let Err(e) = show_sync::validate(sync_cfg) {
+            problems.push(format!("sync: {:#}", e));
+        }
+    }
+
+    if let Some(input_cfg) = &config.artnet_input {
+        let channels = input_cfg.gates.iter().map(|gate| gate.channel).chain(input_cfg.intensity_channel);
+        for channel in channels.filter(|channel| *channel == 0 || *channel > 512) {
+            problems.push(format!("artnet_input: channel {} is outside 1-512", channel));
+        }
+    }
+
+    if let Some(sacn_cfg) = config.sacn.as_ref().filter(|sacn_cfg| sacn_cfg.priority > 200) {
+        problems.push(format!("sacn: priority {} is over 200", sacn_cfg.priority));
+    }
+
+    if let Some(template) = config.mqtt.as_ref().and_then(|mqtt| mqtt.boost_template.as_ref()) {
+        if let Err(e) = templates::validate(template) {
+            problems.push(format!("mqtt.boost_template: {:#}", e));
+        }
+    }
+
+    if let Some(dimmer_cfg) = &config.dimmer {
+        if dimmer_cfg.master > 100 {
+            problems.push(format!("dimmer.master: {} is over 100%", dimmer_cfg.master));
+        }
+        if let Err(e) = dimmer::validate(dimmer_cfg) {
+            problems.push(format!("dimmer.schedule: {:#}", e));
+        }
+    }
+
+    if let Some(sanitize_cfg) = &config.sanitize {
+        if let Err(e) = sanitize::validate(sanitize_cfg) {
+            problems.push(format!("sanitize: {:#}", e));
+        }
+    }
+
+    if let Some(goal_cfg) = &config.goal {
+        if goal_cfg.target_sats <= 0 || goal_cfg.leds == 0 {
+            problems.push("goal: target_sats and leds must be greater than zero".to_string());
+        }
+        if goal_cfg.host.is_none() && config.wled.is_none() {
+            problems.push("goal.host: no host set and no [wled] section to default to".to_string());
+        }
+    }
+
+    let campaigns = config.campaigns.as_deref().unwrap_or_default();
+    for campaign in campaigns {
+        if campaign.target_sats <= 0 {
+            problems.push(format!("campaign {}: target_sats must be greater than zero", campaign.name));
+        }
+    }
+    let split: u32 = campaigns.iter().map(|c| c.split_percent as u32).sum();
+    if split > 100 {
+        problems.push(format!("campaigns: split_percent adds up to {}%, over 100%", split));
+    }
+
+    for bracket in config.total_brackets.as_deref().unwrap_or_default() {
+        if config.wled.is_none() {
+            problems.push(format!("total bracket {}: [wled] not configured", bracket.wled_preset));
+        }
+        if bracket.from_sats < 0 {
+            problems.push(format!("total bracket {}: from_sats can't be negative", bracket.wled_preset));
+        }
+    }
+
+    if let Some(nostr) = config.summary.as_ref().and_then(|summary| summary.nostr.as_ref()) {
+        if let Err(e) = Keys::parse(&nostr.secret_key) {
+            problems.push(format!("summary.nostr.secret_key: {}", e));
+        }
+        if nostr.relay_addrs.is_empty() {
+            problems.push("summary.nostr.relay_addrs: no relays specified".to_string());
+        }
+    }
+
+    if let Some(notify) = &config.notify {
+        match notify.service {
+            config::NotifyService::Ntfy if notify.topic.is_none() => problems.push("notify.topic: needed for ntfy".to_string()),
+            config::NotifyService::Gotify if notify.token.is_none() => problems.push("notify.token: needed for Gotify".to_string()),
+            _ => {}
+        }
+    }
+
+    if let Some(shortcuts) = &config.shortcuts {
+        for (action, shortcut) in [
+            ("pause_effects", &shortcuts.pause_effects),
+            ("fire_test", &shortcuts.fire_test),
+            ("dismiss_dialogs", &shortcuts.dismiss_dialogs),
+            ("next_tab", &shortcuts.next_tab),
+            ("replay_last_boost", &shortcuts.replay_last_boost),
+        ] {
+            if !shortcut.trim().is_empty() && gui::parse_shortcut(shortcut).is_none() {
+                problems.push(format!("shortcuts.{}: \"{}\" is not a key or modifier+key", action, shortcut));
+            }
+        }
+    }
+
+    if let Some(test_cfg) = &config.test_run {
+        if let Err(e) = test_run::validate(config, test_cfg) {
+            problems.push(format!("test_run: {:#}", e));
+        }
+    }
+
+    for (emoji, name) in emoji::unknown_toggles(config) {
+        problems.push(format!("emoji.{}: no toggle named \"{}\"", emoji, name));
+    }
+
+    for (output, limit) in config.rate_limits.iter().flatten() {
+        if limit.max == 0 || limit.per_ms == 0 {
+            problems.push(format!("rate_limits.{}: max and per_ms must be above 0", output));
+        }
+    }
+
+    for output in config.latency_ms.iter().flatten().map(|(output, _)| output) {
+        if !["wled", "osc", "artnet", "sacn", "plugin", "fixture", "scene"].contains(&output.to_lowercase().as_str()) {
+            problems.push(format!("latency_ms.{}: not an output", output));
+        }
+    }
+
+    if let Some(locale) = config.display.as_ref().and_then(|display| display.locale.as_deref()) {
+        if let Err(e) = i18n::validate(locale) {
+            problems.push(format!("display.locale: {}", e));
+        }
+    }
+
+    if config.outbox.as_ref().is_some_and(|outbox| outbox.retry_secs == 0 || outbox.max_age_hours == 0) {
+        problems.push("outbox: retry_secs and max_age_hours must be above 0".to_string());
+    }
+
+    if let Some(audio) = &config.audio {
+        if audio.command.trim().is_empty() {
+            problems.push("audio.command: no capture command set".to_string());
+        }
+        if audio.sample_rate == 0 || audio.update_ms == 0 {
+            problems.push("audio: sample_rate and update_ms must be above 0".to_string());
+        }
+        if audio.dmx_channel.is_some_and(|channel| channel == 0 || channel > 512) {
+            problems.push("audio.dmx_channel: must be between 1 and 512".to_string());
+        }
+        if !audio.wled_intensity && audio.dmx_channel.is_none() {
+            problems.push("audio: set wled_intensity or dmx_channel for the level to drive".to_string());
+        }
+    }
+
+    if let Some(index) = &config.podcast_index {
+        if index.api_key.trim().is_empty() || index.api_secret.trim().is_empty() {
+            problems.push("podcast_index: api_key and api_secret are required".to_string());
+        }
+    }
+
+    if let Some(api) = &config.api {
+        for (idx, token) in api.tokens.iter().enumerate() {
+            if token.token.trim().is_empty() {
+                problems.push(format!("api.tokens {}: token is empty", token.name));
+            } else if api.tokens[..idx].iter().any(|other| other.token == token.token) {
+                problems.push(format!("api.tokens {}: token used more than once", token.name));
+            }
+        }
+    }
+
+    let shows = config.shows.as_deref().unwrap_or_default();
+    for (idx, show) in shows.iter().enumerate() {
+        if shows[..idx].iter().any(|other| other.name == show.name) {
+            problems.push(format!("show {}: name used more than once", show.name));
+        }
+        let config_path = std::path::Path::new(&show.dir).join("config.toml");
+        if !config_path.is_file() {
+            problems.push(format!("show {}: no config.toml in {}", show.name, show.dir));
+        }
+    }
+
+    for fixture in config.fixtures.as_deref().unwrap_or_default() {
+        match fixture.output.to_lowercase().as_str() {
+            "artnet" if config.artnet.is_none() => problems.push(format!("fixture {}: [artnet] not configured", fixture.name)),
+            "sacn" if config.sacn.is_none() => problems.push(format!("fixture {}: [sacn] not configured", fixture.name)),
+            "artnet" | "sacn" => {}
+            other => problems.push(format!("fixture {}: unknown output \"{}\"", fixture.name, other)),
+        }
+        let last = fixture.start_channel as usize + fixture.channels.len().saturating_sub(1);
+        if fixture.start_channel == 0 || last > 512 {
+            problems.push(format!("fixture {}: channels {}-{} are outside 1-512", fixture.name, fixture.start_channel, last));
+        }
+    }
+
+    for (idx, toggle) in config.toggles.as_deref().unwrap_or_default().iter().enumerate() {
+        let label = toggle.name.clone().unwrap_or_else(|| format!("toggles[{}]", idx));
+        let branches = effects::fan_out(toggle);
+        if branches.is_empty() {
+            problems.push(format!("{}: no output or outputs set", label));
+        }
+        for branch in &branches {
+            validate_output(config, &label, branch, &mut problems);
+        }
+        if let Some(priority) = toggle.sacn.as_ref().and_then(|s| s.priority).filter(|priority| *priority > 200) {
+            problems.push(format!("{}: sacn priority {} is over 200", label, priority));
+        }
+        if let Some(config::OscArgValue::String(template)) = toggle.osc.as_ref().map(|osc| &osc.arg_value) {
+            if let Err(e) = templates::validate(template) {
+                problems.push(format!("{}: osc arg_value: {:#}", label, e));
+            }
+        }
+        if let Some((start, end)) = toggle.endswith_range {
+            if start > end || end > 9 {
+                problems.push(format!("{}: endswith_range ({}, {}) is not a digit range", label, start, end));
+            }
+        }
+        for source in &toggle.sources {
+            if !["Boostboard", "NWC", "Zaps", show_sync::SOURCE, "Test"].iter().any(|known| known.eq_ignore_ascii_case(source)) {
+                problems.push(format!("{}: unknown source \"{}\" (expected Boostboard, NWC, Zaps, {} or Test)", label, source, show_sync::SOURCE));
+            }
+        }
+        if let Some(chance) = toggle.chance.filter(|chance| !(0.0..=1.0).contains(chance)) {
+            problems.push(format!("{}: chance {} is not between 0.0 and 1.0", label, chance));
+        }
+        if let Some(hold) = &toggle.hold {
+            if hold.sats_per_sec == 0 {
+                problems.push(format!("{}: hold sats_per_sec must be over 0", label));
+            }
+            if hold.min_secs > hold.max_secs {
+                problems.push(format!("{}: hold min_secs {} is over max_secs {}", label, hold.min_secs, hold.max_secs));
+            }
+        }
+        if let Err(e) = amount_patterns::validate(toggle) {
+            problems.push(format!("{}: amount_patterns: {:#}", label, e));
+        }
+    }
+
+    if problems.is_empty() {
+        println!("config.toml is valid");
+        return Ok(());
+    }
+
+    for problem in &problems {
+        eprintln!("  {}", problem);
+    }
+    bail!("config.toml has {} problem(s)", problems.len())
+}
+
+/// Check one output of a toggle (see `effects::fan_out`) has what it needs to send
+fn validate_output(config: &Config, label: &str, toggle: &config::Toggle, problems: &mut Vec<String>) {
+    let configured = match toggle.output.as_str() {
+        "osc" => config.osc.is_some() && toggle.osc.is_some(),
+        "artnet" => config.artnet.is_some() && toggle.artnet.is_some(),
+        "sacn" => config.sacn.is_some() && toggle.sacn.is_some(),
+        "wled" => config.wled.is_some() && toggle.wled.is_some(),
+        "scene" => toggle.scene.is_some(),
+        "fixture" => toggle.fixture.as_ref().is_some_and(|f| fixtures::find(config, &f.name).is_some()),
+        "plugin" => toggle.plugin.as_ref().is_some_and(|p| {
+            config.plugins.as_deref().unwrap_or_default().iter().any(|c| c.name == p.name)
+        }),
+        other => {
+            problems.push(format!("{}: unknown output \"{}\"", label, other));
+            return;
+        }
+    };
+    if !configured {
+        problems.push(format!("{}: {} output or toggle settings not configured", label, toggle.output));
+    }
+    let rgb_channel = match toggle.output.as_str() {
+        "artnet" => toggle.artnet.as_ref().and_then(|a| a.rgb_channel),
+        "sacn" => toggle.sacn.as_ref().and_then(|s| s.rgb_channel),
+        _ => None,
+    };
+    if let Some(channel) = rgb_channel.filter(|channel| *channel == 0 || *channel > u16::MAX - 2) {
+        problems.push(format!("{}: rgb_channel {} is outside 1-{}", label, channel, u16::MAX - 2));
+    }
+    let dmx_values = match toggle.output.as_str() {
+        "artnet" => toggle.artnet.as_ref().map(|a| (
+            a.universe.or(config.artnet.as_ref().and_then(|c| c.universe)).unwrap_or(0),
+            artnet::ArtNet::toggle_values(a, Some([0; 3]))
+        )),
+        "sacn" => toggle.sacn.as_ref().map(|s| (
+            s.universe.or(config.sacn.as_ref().and_then(|c| c.universe)).unwrap_or(1),
+            sacn::Sacn::toggle_values(s, Some([0; 3]))
+        )),
+        _ => None,
+    };
+    if let Some((universe, values)) = dmx_values {
+        if values.is_empty() {
+            problems.push(format!("{}: no DMX channels set", label));
+        } else if let Err(e) = fixtures::span(universe, &values) {
+            problems.push(format!("{}: {:#}", label, e));
+        }
+    }
+}
+
+async fn setup_wled(config: Config) -> Result<()> {
+    if config.wled.as_ref().is_none_or(|w| !w.setup) {
+        bail!("WLED setup is not enabled in config.toml (wled.setup = true)");
+    }
+
+    effects::setup_effects(config).await?;
+    println!("WLED presets and playlists created");
+    Ok(())
+}
+
+fn autostart(remove: bool) -> Result<()> {
+    if remove {
+        autostart::remove()?;
+        println!("BlinkyBoosts will no longer start at login");
+    } else {
+        let path = autostart::install()?;
+        println!("BlinkyBoosts will start at login ({})", path.display());
+    }
+    Ok(())
+}
+
+fn export_bundle(output: &str) -> Result<()> {
+    let bundle = bundle::export(std::path::Path::new("."))?;
+    std::fs::write(output, serde_json::to_string_pretty(&bundle)?).context(format!("Failed to write {}", output))?;
+    println!("Exported {} files to {}: {}", bundle.files.len(), output, bundle.files.keys().cloned().collect::<Vec<_>>().join(", "));
+    Ok(())
+}
+
+/// Needs no config.toml, so a new machine can be set up from a bundle
+pub fn import_bundle(file: &str) -> Result<()> {
+    let json = std::fs::read_to_string(file).context(format!("Failed to read {}", file))?;
+    let bundle: bundle::Bundle = serde_json::from_str(&json).context(format!("{} is not a BlinkyBoosts bundle", file))?;
+    let written = bundle::import(std::path::Path::new("."), &bundle)?;
+    println!("Imported {}", written.join(", "));
+    println!("Run `blinkyboosts validate` to check the config on this machine");
+    Ok(())
+}
+
+async fn playback(config: &Config, file: Option<&str>, send: bool) -> Result<()> {
+    let path = file.or(config.capture.as_ref().map(|cfg| cfg.file.as_str()))
+        .context("No --file given and [capture] not configured")?;
+    let frames = capture::load(path)?;
+    let Some(first) = frames.first() else {
+        println!("No frames in {}", path);
+        return Ok(());
+    };
+
+    let start = first.at()?;
+    let started = Instant::now();
+    for frame in &frames {
+        let offset = (frame.at()? - start).to_std().unwrap_or_default();
+        let values = frame.values()?;
+        if send {
+            tokio::time::sleep(offset.saturating_sub(started.elapsed())).await;
+            fixtures::send_frame(config, &frame.output, frame.universe, &values)
+                .context(format!("Failed to send frame captured at {}", frame.time))?;
+        }
+
+        let lit: Vec<String> = values.iter().enumerate()
+            .filter(|(_, value)| **value > 0)
+            .map(|(idx, value)| format!("{}={}", idx + 1, value))
+            .collect();
+        let lit = if lit.is_empty() { "all zero".to_string() } else { lit.join(" ") };
+        println!("+{:.3}s {} {} universe {}: {}", offset.as_secs_f64(), frame.output, frame.target, frame.universe, lit);
+    }
+    Ok(())
+}
+
+/// Cues fire through the effects like live boosts and count toward the totals, but nothing is
+/// kept: no history, streaks or boost log.
+async fn rehearse(mut config: Config, file: &str, speed: f64, mock: bool) -> Result<()> {
+    if !speed.is_finite() || speed <= 0.0 {
+        bail!("--speed must be above 0");
+    }
+    let cues = rehearsal::load(file)?;
+    let Some(last) = cues.last() else {
+        println!("No boosts in {}", file);
+        return Ok(());
+    };
+    if mock {
+        config.wled.iter_mut().for_each(|cfg| cfg.mock = true);
+        config.osc.iter_mut().for_each(|cfg| cfg.mock = true);
+        config.artnet.iter_mut().for_each(|cfg| cfg.mock = true);
+        config.sacn.iter_mut().for_each(|cfg| cfg.mock = true);
+        config.goal.iter_mut().for_each(|cfg| cfg.mock = true);
+        config.plugins.iter_mut().flatten().for_each(|cfg| cfg.mock = true);
+    }
+    println!("Rehearsing {} boosts over {} at {}x, taking {}", cues.len(), clock_time(last.offset()),
+        speed, clock_time(last.offset().div_f64(speed)));
+
+    clock::set_speed(speed);
+    let (effects, _retry_rx) = effects::Effects::new(config);
+    let tracker = Arc::new(tokio::sync::Mutex::new(sat_tracker::SatTracker::new()));
+    let started = Instant::now();
+    for cue in &cues {
+        tokio::time::sleep(clock::scaled(cue.offset()).saturating_sub(started.elapsed())).await;
+        let total = tracker.lock().await.add("Rehearsal", amounts::sats_to_msats(cue.sats));
+        total_brackets::update(effects.config(), total);
+
+        let vars = templates::Vars {
+            source: "Rehearsal".to_string(),
+            sats: cue.sats,
+            sender: cue.sender.clone(),
+            message: cue.message.clone(),
+            episode: cue.episode.clone(),
+            ..Default::default()
+        }.with_total(effects.config(), total);
+        let triggered = effects.trigger_effects(&vars, Some(tracker.clone()), Instant::now()).await?;
+        let from = cue.sender.as_deref().map(|sender| format!(" from {}", sender)).unwrap_or_default();
+        let fired = match triggered.is_empty() {
+            true => "no effects".to_string(),
+            false => triggered.iter().map(|effect| effect.description.as_str()).collect::<Vec<_>>().join(", "),
+        };
+        println!("[{}] {} sats{}: {}", clock_time(cue.offset()), cue.sats, from, fired);
+    }
+
+    // Let delayed toggles and holds from the last cues play out
+    let trailing = effects.config().toggles.as_deref().unwrap_or_default().iter()
+        .map(|toggle| toggle.delay_secs + toggle.hold.as_ref().map_or(0, |hold| hold.max_secs.max(hold.min_secs)))
+        .max().unwrap_or_default();
+    tokio::time::sleep(clock::scaled(std::time::Duration::from_secs(trailing))).await;
+    println!("Rehearsal done, total {} sats", tracker.lock().await.total());
+    Ok(())
+}
+
+/// A show time offset as h:mm:ss
+fn clock_time(offset: std::time::Duration) -> String {
+    let secs = offset.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+async fn test(config: Config, sats: i64, sender: Option<&str>) -> Result<()> {
+    let (effects, _retry_rx) = effects::Effects::new(config);
+    let tracker = Arc::new(tokio::sync::Mutex::new(sat_tracker::SatTracker::new()));
+
+    let vars = templates::Vars {
+        source: "Test".to_string(),
+        sats,
+        sender: sender.map(str::to_string),
+        ..Default::default()
+    }.with_total(effects.config(), sats);
+    let triggered = effects.trigger_effects(&vars, Some(tracker), Instant::now()).await?;
+    if triggered.is_empty() {
+        println!("No effects triggered for {} sats", sats);
+    }
+    for effect in triggered {
+        println!("Triggered {}", effect.description);
+    }
+
+    let mocked = mock::recent();
+    if !mocked.is_empty() {
+        println!("Recorded {} mock calls:", mocked.len());
+        for call in mocked {
+            println!("  {} {} {} {}", call.time, call.output, call.target, call.payload);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "harness")]
+async fn simulate(config: Config, amounts: &[i64], sender: &str, message: &str) -> Result<()> {
+    use blinkyboosts_core::harness::Harness;
+
+    let mut harness = Harness::start(config).await?;
+    println!("Mock relay at {}, mock WLED at {}", harness.relay.url(), harness.wled.host());
+
+    for &sats in amounts {
+        let states = harness.wled.states().len();
+        harness.boost(sats, sender, message)?;
+        match harness.delivered(std::time::Duration::from_secs(5)).await {
+            Ok(delivered) if delivered.effects.is_empty() => println!("{} sats: counted, no effects", sats),
+            Ok(delivered) => {
+                let effects: Vec<_> = delivered.effects.iter().map(|e| e.description.as_str()).collect();
+                println!("{} sats: {}", sats, effects.join(", "));
+            }
+            Err(_) => println!("{} sats: not delivered (filtered out?)", sats),
+        }
+        for state in &harness.wled.states()[states..] {
+            println!("  WLED <- {}", state);
+        }
+    }
+
+    println!("Total: {} sats", harness.total().await);
+    Ok(())
+}
+
+async fn export(config: &Config, output: Option<&str>) -> Result<()> {
+    let boosts = Arc::new(Mutex::new(Vec::<Boostagram>::new()));
+
+    if let Some(board_cfg) = &config.boostboard {
+        eprintln!("Loading stored boosts from Boostboard...");
+        let stored = boostboard::StoredBoosts::new(boostboard::BoostFilters::from_config(&board_cfg.filters));
+        stored.load_with_errors(|boost| {
+            boosts.lock().unwrap().push(boost);
+            async {}
+        }, |_, failures, _| match failures <= board_cfg.page_retries {
+            true => boostboard::PageAction::Retry,
+            false => boostboard::PageAction::Abort,
+        }).await.context("Failed to load Boostboard boosts")?;
+    }
+
+    if let Some(nwc_cfg) = &config.nwc {
+        eprintln!("Loading previous boosts from NWC...");
+        let wallet = nwc::NWC::new(&nwc_cfg.uri, boostboard::BoostFilters::from_config(&nwc_cfg.filters)).await?
+            .with_memo_boosts(nwc_cfg.memo_boosts);
+        let since = nwc_cfg.filters.load_since.as_ref()
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(Timestamp::from_secs);
+        wallet.load_previous_boosts(since, |boost| {
+            boosts.lock().unwrap().push(boost);
+            async {}
+        }).await.context("Failed to load NWC boosts")?;
+    }
+
+    let mut boosts = std::mem::take(&mut *boosts.lock().unwrap());
+    boosts.sort_by_key(|b| b.creation_date);
+
+    sanitize::configure(config);
+    for boost in boosts.iter_mut() {
+        boost.message = sanitize::clean("export", &boost.message);
+    }
+    let notes = notes::load()?;
+    let json = serde_json::to_string_pretty(&serde_json::json!({ "boosts": boosts, "notes": notes }))?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, json).context(format!("Failed to write {}", path))?;
+            eprintln!("Exported {} boosts and {} notes to {}", boosts.len(), notes.len(), path);
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
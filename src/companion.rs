@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, Mutex};
+
+use blinkyboosts_core::{config, metrics};
+use blinkyboosts_core::effects::Effects;
+use blinkyboosts_core::messages::Command;
+use blinkyboosts_core::sat_tracker::SatTracker;
+
+use crate::api::LISTENERS;
+use crate::event_stream::{EventStream, StreamEvent};
+
+type Variables = Arc<std::sync::Mutex<BTreeMap<String, String>>>;
+
+#[derive(Clone)]
+struct Server {
+    tx: mpsc::Sender<Command>,
+    effects: Effects,
+    variables: Variables,
+    changes: broadcast::Sender<(String, String)>,
+}
+
+/// Serve the Companion protocol until the process exits.
+///
+/// Clients send one command per line and get `OK ...` or `ERR ...` back:
+/// `FIRE <toggle>`, `TRIGGER <sats>`, `START <listener>`, `STOP <listener>`, `VARS`.
+/// Feedback variables are pushed as `VAR <name> <value>` on connect and whenever they change:
+/// `total`, `last_boost_sats`, `last_boost_source`, `last_effect`, `episode`, `wallet_balance_sats`
+/// and `status_<component>` (e.g. `status_nwc`, `status_wled`).
+pub async fn serve(
+    cfg: config::Companion,
+    tx: mpsc::Sender<Command>,
+    tracker: Arc<Mutex<SatTracker>>,
+    effects: Effects,
+    events: EventStream
+) -> Result<()> {
+    let listener = TcpListener::bind(&cfg.bind).await
+        .context(format!("Failed to bind Companion server to {}", cfg.bind))?;
+    println!("Companion server listening on {}", cfg.bind);
+
+    let variables = Variables::default();
+    let total = tracker.lock().await.total();
+    variables.lock().unwrap().insert("total".to_string(), total.to_string());
+
+    let (changes, _) = broadcast::channel(256);
+    tokio::spawn(track_variables(events, variables.clone(), changes.clone()));
+
+    let server = Server { tx, effects, variables, changes };
+
+    loop {
+        let (stream, addr) = listener.accept().await.context("Companion accept failed")?;
+        println!("Companion client connected from {}", addr);
+        tokio::spawn(server.clone().handle_client(stream));
+    }
+}
+
+fn variable_name(component: &str) -> String {
+    let id: String = component.to_lowercase().chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("status_{}", id.trim_matches('_'))
+}
+
+async fn track_variables(events: EventStream, variables: Variables, changes: broadcast::Sender<(String, String)>) {
+    let mut events = events.subscribe();
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+
+        let updates = match event {
+            StreamEvent::Total { total } => vec![("total".to_string(), total.to_string())],
+            StreamEvent::Boost { source, sats, .. } => vec![
+                ("last_boost_sats".to_string(), sats.to_string()),
+                ("last_boost_source".to_string(), source),
+            ],
+            StreamEvent::Episode { episode } => vec![("episode".to_string(), episode)],
+            StreamEvent::Effect { description, .. } => vec![("last_effect".to_string(), description)],
+            StreamEvent::Status { component, status, .. } => vec![(variable_name(&component), status)],
+            StreamEvent::Wallet { balance_msats: Some(msats), .. } => vec![("wallet_balance_sats".to_string(), (msats / 1000).to_string())],
+            StreamEvent::EffectFailed { .. } | StreamEvent::PaymentQr { .. } | StreamEvent::Campaign { .. }
+                | StreamEvent::InvoiceRequested { .. } | StreamEvent::InvoiceSettled { .. } | StreamEvent::EpisodeLock { .. }
+                | StreamEvent::Backfill { .. } | StreamEvent::Wallet { .. } | StreamEvent::Streak { .. }
+                | StreamEvent::Summary { .. } | StreamEvent::Note { .. } | StreamEvent::Relay { .. } | StreamEvent::TestRun { .. } => Vec::new(),
+        };
+
+        for (name, value) in updates {
+            variables.lock().unwrap().insert(name.clone(), value.clone());
+            let _ = changes.send((name, value));
+        }
+    }
+}
+
+impl Server {
+    async fn handle_client(self, stream: TcpStream) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut changes = self.changes.subscribe();
+
+        if writer.write_all(self.all_variables().as_bytes()).await.is_err() {
+            return;
+        }
+
+        loop {
+            let output = tokio::select! {
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) if line.trim().is_empty() => continue,
+                    Ok(Some(line)) => self.handle_command(line.trim()).await,
+                    _ => break,
+                },
+                change = changes.recv() => match change {
+                    Ok((name, value)) => format!("VAR {} {}\n", name, value),
+                    Err(broadcast::error::RecvError::Lagged(_)) => self.all_variables(),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+
+            if writer.write_all(output.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+
+        println!("Companion client disconnected");
+    }
+
+    fn all_variables(&self) -> String {
+        self.variables.lock().unwrap().iter()
+            .map(|(name, value)| format!("VAR {} {}\n", name, value))
+            .collect()
+    }
+
+    async fn handle_command(&self, line: &str) -> String {
+        let (command, arg) = line.split_once(' ').map_or((line, ""), |(c, a)| (c, a.trim()));
+
+        match command.to_uppercase().as_str() {
+            "VARS" => format!("{}OK VARS\n", self.all_variables()),
+            "FIRE" => match self.effects.fire_toggle(arg).await {
+                Some(Ok(effects)) => format!("OK FIRE {}\n", metrics::describe(&effects)),
+                Some(Err(e)) => format!("ERR FIRE {:#}\n", e),
+                None => format!("ERR FIRE no toggle named {}\n", arg),
+            },
+            "TRIGGER" => match arg.parse::<i64>() {
+                Ok(sats) if sats > 0 => self.send(Command::TestTrigger(sats), format!("TRIGGER {}", sats)).await,
+                _ => "ERR TRIGGER sats must be a positive number\n".to_string(),
+            },
+            "START" | "STOP" => {
+                let Some(listener) = LISTENERS.iter().find(|l| l.eq_ignore_ascii_case(arg)) else {
+                    return format!("ERR {} unknown listener {}\n", command.to_uppercase(), arg);
+                };
+                let msg = if command.eq_ignore_ascii_case("START") {
+                    Command::StartListener(listener.to_string())
+                } else {
+                    Command::StopListener(listener.to_string())
+                };
+                self.send(msg, format!("{} {}", command.to_uppercase(), listener)).await
+            }
+            _ => format!("ERR unknown command {}\n", command),
+        }
+    }
+
+    async fn send(&self, msg: Command, ack: String) -> String {
+        match self.tx.send(msg).await {
+            Ok(()) => format!("OK {}\n", ack),
+            Err(_) => "ERR controller is not running\n".to_string(),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+use blinkyboosts_core::effects::{self, Effects};
+use blinkyboosts_core::listeners::{
+    moderate, process_boost, reload_history, replay_last_boost, start_listener, stop_listener,
+    IncomingBoost, ListenerHandles,
+};
+use blinkyboosts_core::messages::{Command, ComponentStatus, Event};
+use blinkyboosts_core::sat_tracker::SatTracker;
+use blinkyboosts_core::{amounts, boost_log, episode_lock, metrics, nwc_setup, payment_qr, scenes, summary, tags, test_run, wled, wled_discovery};
+use tokio::time::Duration;
+
+/// Carry out commands from the GUI, control API and Companion until every sender is gone,
+/// reporting results on `events` like the rest of the pipeline
+pub async fn run(
+    mut commands: mpsc::Receiver<Command>,
+    events: mpsc::Sender<Event>,
+    effects: Effects,
+    tracker: Arc<Mutex<SatTracker>>,
+    handles: ListenerHandles
+) {
+    while let Some(command) = commands.recv().await {
+        match command {
+            Command::TestTrigger(sats) => {
+                println!("Test trigger received for {} sats", sats);
+                let incoming = IncomingBoost {
+                    source: "Test", sats, msats: amounts::sats_to_msats(sats), sender: None, sender_pubkey: None, message: None,
+                    episode_guid: None, episode: None, remote: None, artwork: None, event_ts: None,
+                };
+                process_boost(incoming, &events, &tracker, &effects, true).await;
+            },
+            Command::StartListener(name) => {
+                println!("Starting listener: {}", name);
+                start_listener(&name, &handles, &effects, &events, &tracker).await;
+            },
+            Command::StopListener(name) => {
+                println!("Stopping listener: {}", name);
+                stop_listener(&name, &handles).await;
+            },
+            Command::ModerateBoost(id, decision) => {
+                if !moderate(id, decision, &events, &tracker, &effects).await {
+                    eprintln!("No held boost #{}", id);
+                }
+            },
+            Command::ReplayLastBoost => {
+                if !replay_last_boost(&tracker, &effects).await {
+                    println!("No boost to replay yet");
+                }
+            },
+            Command::RunTest => {
+                let Some(cfg) = effects.config().test_run.clone() else {
+                    eprintln!("No [test_run] configured");
+                    continue;
+                };
+                let (effects, events) = (effects.clone(), events.clone());
+                tokio::spawn(async move { test_run::run_and_report(&effects, &cfg, &events).await });
+            },
+            Command::FireToggle(name) => {
+                match effects.fire_toggle(&name).await {
+                    Some(Ok(triggered)) => println!("Fired toggle {}: {}", name, metrics::describe(&triggered)),
+                    Some(Err(e)) => eprintln!("Failed to fire toggle {}: {:#}", name, e),
+                    None => eprintln!("No toggle named {}", name),
+                }
+            },
+            Command::LockEpisode(lock) => {
+                let locked = match lock {
+                    true => episode_lock::lock_to_latest(),
+                    false => {
+                        episode_lock::unlock();
+                        None
+                    }
+                };
+                if lock && locked.is_none() {
+                    eprintln!("No boost has named an episode yet");
+                }
+                let _ = events.send(Event::EpisodeLocked(locked.map(|episode| episode.label))).await;
+            },
+            Command::SetTagActive(tag, active) => tags::set_active(effects.config(), &tag, active),
+            Command::RequestInvoice(sats) => {
+                let (config, events) = (effects.config().clone(), events.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = payment_qr::request(&config, sats, events).await {
+                        eprintln!("Failed to request invoice: {:#}", e);
+                    }
+                });
+            },
+            Command::AnnotateBoost(id, text) => match tracker.lock().await.annotate(id, &text) {
+                Some(note) => { let _ = events.send(Event::Noted(note)).await; }
+                None => eprintln!("Boost {} is no longer in the recent history or boost log", id),
+            },
+            Command::AddMarker(text) => {
+                let note = tracker.lock().await.add_marker(&text);
+                let _ = events.send(Event::Noted(note)).await;
+            },
+            Command::FinishShow => {
+                let summary = summary::finish(effects.config(), &tracker).await;
+                let _ = events.send(Event::ShowSummary(summary)).await;
+            },
+            Command::CaptureScene(name) => {
+                if let Err(e) = scenes::capture(effects.config(), &name).await {
+                    eprintln!("Failed to capture scene {}: {:#}", name, e);
+                }
+            },
+            Command::LoadWledEffects(wled_cfg) => {
+                match wled::WLed::effect_names(&wled_cfg).await {
+                    Ok(names) => { let _ = events.send(Event::WledEffects(names)).await; }
+                    Err(e) => eprintln!("Failed to load WLED effects: {:#}", e),
+                }
+            },
+            Command::DiscoverWled => {
+                let events = events.clone();
+                tokio::spawn(async move {
+                    match wled_discovery::discover(Duration::from_secs(3)).await {
+                        Ok(controllers) => { let _ = events.send(Event::WledControllers(controllers)).await; }
+                        Err(e) => {
+                            eprintln!("Failed to look for WLED controllers: {:#}", e);
+                            let _ = events.send(Event::WledControllers(Vec::new())).await;
+                        }
+                    }
+                });
+            },
+            Command::CheckNwc(uri) => {
+                let events = events.clone();
+                tokio::spawn(async move {
+                    let checked = nwc_setup::check(&uri).await.map_err(|e| format!("{:#}", e));
+                    let _ = events.send(Event::NwcChecked(checked)).await;
+                });
+            },
+            Command::ApplyWledSetup(wled_cfg) => {
+                println!("Applying WLED presets and playlists to {}", wled_cfg.host);
+                let status = match effects::apply_wled_setup(&wled_cfg).await {
+                    Ok(()) => ComponentStatus::Running,
+                    Err(e) => {
+                        eprintln!("Failed to apply WLED setup: {:#}", e);
+                        ComponentStatus::Error(format!("Setup failed: {:#}", e))
+                    }
+                };
+                let _ = events.send(Event::UpdateStatus("WLED".to_string(), status)).await;
+            },
+            Command::LoadOlderBoosts(cursor, limit) => match boost_log::page(cursor, limit) {
+                Ok((page, next)) => { let _ = events.send(Event::OlderBoosts(page, next)).await; }
+                Err(e) => {
+                    eprintln!("Failed to load older boosts: {:#}", e);
+                    let _ = events.send(Event::OlderBoosts(Vec::new(), 0)).await;
+                }
+            },
+            Command::ReloadHistory(source, filters) => {
+                println!("Reloading {} history", source);
+                let (effects, events, tracker) = (effects.clone(), events.clone(), tracker.clone());
+                tokio::spawn(async move { reload_history(&source, &filters, &effects, &events, &tracker).await });
+            },
+        }
+    }
+}
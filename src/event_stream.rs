@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use blinkyboosts_core::messages::Event;
+use blinkyboosts_core::{sanitize, source_labels};
+use blinkyboosts_core::sat_tracker::Note;
+use blinkyboosts_core::summary::Summary;
+use blinkyboosts_core::test_run::Report;
+
+const CAPACITY: usize = 256;
+
+/// Events pushed to WebSocket clients as JSON, one object per message, tagged by `type`:
+///
+/// - `{"type": "boost", "id": 7, "source": "Zaps", "source_color": "#a050ff", "source_icon": "⚡", "sats": 100, "sender": "alice", "message": "Great show!", "episode": "Episode 42", "effects": ["WLED: BOOST"], "relay_delay_ms": 850}`
+/// - `{"type": "episode", "episode": "Episode 43"}`
+/// - `{"type": "episode_lock", "episode": "Episode 43"}` (`null` once unlocked)
+/// - `{"type": "backfill", "source": "NWC", "loaded": 120, "at": 1736553600, "done": false}`
+/// - `{"type": "campaign", "name": "New mic fund", "total": 21000, "target_sats": 500000}`
+/// - `{"type": "effect", "description": "WLED: BOOST", "output": "wled", "queued_ms": 2, "sent_ms": 40}`
+/// - `{"type": "effect_failed", "description": "WLED: BOOST", "error": "...", "attempts": 3}`
+/// - `{"type": "total", "total": 12345}`
+/// - `{"type": "payment_qr", "uri": "lightning:..."}`
+/// - `{"type": "invoice_requested", "uri": "lightning:...", "sats": 5000}`
+/// - `{"type": "invoice_settled", "sats": 5000, "paid": true}`
+/// - `{"type": "status", "component": "NWC", "status": "Running", "detail": null}`
+/// - `{"type": "summary", "total_sats": 52100, "boosts": 48, "top_boosters": [{"sender": "alice", "sats": 21000}], ...}`
+/// - `{"type": "note", "at": 1736553600, "text": "Read on air", "boost_id": 7, "sats": 5000, "sender": "alice"}` (`boost_id` null for a marker)
+/// - `{"type": "streak", "sender": "alice", "streak": 5, "milestone": true}`
+/// - `{"type": "wallet", "alias": "My node", "balance_msats": 21000000, "latency_ms": 320, "notifications": true}`
+/// - `{"type": "relay", "source": "Zaps", "round_trip_ms": 180}`
+/// - `{"type": "test_run", "at": 1736553600, "checks": [{"name": "WLED", "error": null}]}`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    Boost {
+        #[serde(default)]
+        id: u64,
+        source: String,
+        source_color: Option<String>,  // "#rrggbb" from [sources]
+        source_icon: Option<String>,
+        sats: i64,
+        sender: Option<String>,
+        message: Option<String>,  // Sanitized for the "events" output
+        episode: Option<String>,
+        remote_item: Option<String>,  // Title or guid of the guest feed or song boosted
+        artwork: Option<String>,  // Image URL, when [podcast_index] found one
+        effects: Vec<String>,
+        relay_delay_ms: Option<u128>,
+    },
+    Effect {
+        description: String,
+        output: String,
+        queued_ms: u128,
+        sent_ms: u128,
+    },
+    EffectFailed {
+        description: String,
+        error: String,
+        attempts: u32,
+    },
+    Episode {
+        episode: String,
+    },
+    EpisodeLock {
+        episode: Option<String>,
+    },
+    Backfill {
+        source: String,
+        loaded: usize,
+        at: Option<i64>,
+        done: bool,
+    },
+    Campaign {
+        name: String,
+        total: i64,
+        target_sats: i64,
+    },
+    Total {
+        total: i64,
+    },
+    Summary {
+        #[serde(flatten)]
+        summary: Summary,
+    },
+    Note {
+        #[serde(flatten)]
+        note: Note,
+    },
+    TestRun {
+        #[serde(flatten)]
+        report: Report,
+    },
+    Streak {
+        sender: String,
+        streak: u32,
+        milestone: bool,
+    },
+    Wallet {
+        alias: String,
+        balance_msats: Option<u64>,
+        latency_ms: u128,
+        notifications: Option<bool>,
+    },
+    Relay {
+        source: String,
+        round_trip_ms: u128,  // Slowest relay's answer to a request; the 10s timeout if one didn't answer
+    },
+    Status {
+        component: String,
+        status: String,
+        detail: Option<String>,
+    },
+    PaymentQr {
+        uri: String,
+    },
+    InvoiceRequested {
+        uri: String,
+        sats: u64,
+    },
+    InvoiceSettled {
+        sats: u64,
+        paid: bool,  // false if it expired
+    },
+}
+
+impl StreamEvent {
+    fn from_message(msg: &Event) -> Vec<Self> {
+        match msg {
+            Event::BoostReceived(id, source, sats, sender, message, episode, remote_item, artwork, effects, relay_delay) => {
+                let boost = Self::Boost {
+                    id: *id,
+                    source: source.clone(),
+                    source_color: source_labels::hex(source),
+                    source_icon: source_labels::icon(source),
+                    sats: *sats,
+                    sender: sender.clone(),
+                    message: message.as_deref().map(|m| sanitize::clean("events", m)),
+                    episode: episode.clone(),
+                    remote_item: remote_item.clone(),
+                    artwork: artwork.clone(),
+                    effects: effects.iter().map(|e| e.description.clone()).collect(),
+                    relay_delay_ms: relay_delay.map(|d| d.as_millis()),
+                };
+                std::iter::once(boost)
+                    .chain(effects.iter().map(|e| Self::Effect {
+                        description: e.description.clone(),
+                        output: e.output.clone(),
+                        queued_ms: e.queued.as_millis(),
+                        sent_ms: e.sent.as_millis(),
+                    }))
+                    .collect()
+            }
+            Event::EffectFailed(description, error, attempts) => vec![Self::EffectFailed {
+                description: description.clone(),
+                error: error.clone(),
+                attempts: *attempts,
+            }],
+            Event::UpdateSatTotal(total) => vec![Self::Total { total: *total }],
+            Event::UpdateStatus(component, status) => vec![Self::Status {
+                component: component.clone(),
+                status: status.text(),
+                detail: status.detail().map(str::to_string),
+            }],
+            Event::PaymentQr(uri) => vec![Self::PaymentQr { uri: uri.clone() }],
+            Event::InvoiceRequested(uri, sats) => vec![Self::InvoiceRequested { uri: uri.clone(), sats: *sats }],
+            Event::InvoiceSettled(sats, paid) => vec![Self::InvoiceSettled { sats: *sats, paid: *paid }],
+            Event::WalletStatus(wallet) => vec![Self::Wallet {
+                alias: wallet.alias.clone(),
+                balance_msats: wallet.balance_msats,
+                latency_ms: wallet.latency.as_millis(),
+                notifications: wallet.notifications,
+            }],
+            Event::RelayRoundTrip(source, round_trip) => vec![Self::Relay {
+                source: source.clone(),
+                round_trip_ms: round_trip.as_millis(),
+            }],
+            Event::EpisodeStarted(episode) => vec![Self::Episode { episode: episode.clone() }],
+            Event::EpisodeLocked(episode) => vec![Self::EpisodeLock { episode: episode.clone() }],
+            Event::ShowSummary(summary) => vec![Self::Summary { summary: summary.clone() }],
+            Event::Noted(note) => vec![Self::Note { note: note.clone() }],
+            Event::OlderBoosts(..) => vec![],
+            Event::Streak(sender, streak, milestone) => vec![Self::Streak {
+                sender: sender.clone(),
+                streak: *streak,
+                milestone: *milestone,
+            }],
+            Event::BackfillProgress(source, loaded, at, done) => vec![Self::Backfill {
+                source: source.clone(),
+                loaded: *loaded,
+                at: *at,
+                done: *done,
+            }],
+            Event::CampaignTotal(name, total, target_sats) => vec![Self::Campaign {
+                name: name.clone(),
+                total: *total,
+                target_sats: *target_sats,
+            }],
+            Event::WledEffects(_) | Event::WledControllers(_) | Event::NwcChecked(_) | Event::BringToFront => Vec::new(),
+            Event::TestRun(report) => vec![Self::TestRun { report: report.clone() }],
+        }
+    }
+}
+
+/// Fan-out of everything sent to the GUI, for external consumers
+#[derive(Clone)]
+pub struct EventStream {
+    tx: broadcast::Sender<StreamEvent>,
+}
+
+impl EventStream {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(CAPACITY);
+        Self { tx }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StreamEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Mirror a GUI message to subscribers; a no-op when nobody is listening
+    pub fn publish(&self, msg: &Event) {
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+
+        for event in StreamEvent::from_message(msg) {
+            let _ = self.tx.send(event);
+        }
+    }
+}
@@ -1,4 +1,13 @@
-use crate::config::{Config, BoostBoard, NWC, OSC, ArtNet, Sacn, WLed, Zaps, BoostFiltersConfig};
+use blinkyboosts_core::config::{self, Config, BoostBoard, NWC, OSC, ArtNet, Sacn, WLed, Zaps, BoostFiltersConfig};
+use blinkyboosts_core::messages::{Command, ComponentStatus, Event};
+use blinkyboosts_core::metrics::{self, InputActivity, LatencyStats, TriggeredEffect};
+use blinkyboosts_core::{amounts, analytics, artnet_input, backfill, boost_log, campaigns, dimmer, effect_log, effects, i18n, idle_pattern, mute, nwc, nwc_setup, payment_qr, plugins, sanitize, scenes, sender_color, sequencer, source_labels, tags, test_run, toggle_stats, wled_discovery};
+use blinkyboosts_core::moderation::{self, Decision};
+use blinkyboosts_core::preview::Preview;
+use blinkyboosts_core::sat_tracker::{BoostRecord, Note};
+use blinkyboosts_core::summary::{self, Summary};
+use crate::autostart;
+use crate::shows::Shows;
 use eframe::egui;
 use egui::{Color32, RichText, Ui, ViewportBuilder};
 use std::sync::{Arc, Mutex};
@@ -6,59 +15,150 @@ use std::time::Duration;
 use chrono::Local;
 use tokio::sync::mpsc;
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum ComponentStatus {
-    Disabled,
-    Enabled,
-    Running,
-    Error(String),
-}
+const STREAK_CALLOUT_SECS: i64 = 30;
+const OLDER_BOOSTS_PAGE: usize = 50;
 
-impl ComponentStatus {
-    pub fn color(&self) -> Color32 {
-        match self {
-            Self::Disabled => Color32::GRAY,
-            Self::Enabled => Color32::GREEN,
-            Self::Running => Color32::LIGHT_BLUE,
-            Self::Error(_) => Color32::RED,
+/// Render a QR code as a texture with a 4 module quiet zone
+fn qr_texture(ctx: &egui::Context, data: &str) -> Option<egui::TextureHandle> {
+    let (width, dark) = match payment_qr::modules(data) {
+        Ok(modules) => modules,
+        Err(e) => {
+            eprintln!("Failed to render payment QR: {:#}", e);
+            return None;
         }
+    };
+
+    let size = width + 8;
+    let mut image = egui::ColorImage::new([size, size], Color32::WHITE);
+    for (idx, _) in dark.iter().enumerate().filter(|(_, d)| **d) {
+        image[(idx % width + 4, idx / width + 4)] = Color32::BLACK;
     }
+    Some(ctx.load_texture("payment_qr", image, egui::TextureOptions::NEAREST))
+}
 
-    pub fn text(&self) -> &str {
-        match self {
-            Self::Disabled => "Disabled",
-            Self::Enabled => "Enabled",
-            Self::Running => "Running",
-            Self::Error(_) => "Error",
-        }
+fn status_color(status: &ComponentStatus) -> Color32 {
+    match status {
+        ComponentStatus::Disabled => Color32::GRAY,
+        ComponentStatus::Enabled => Color32::GREEN,
+        ComponentStatus::Running => Color32::LIGHT_BLUE,
+        ComponentStatus::Reconnecting(_) => Color32::YELLOW,
+        ComponentStatus::Unreachable(_) => Color32::from_rgb(255, 140, 0),
+        ComponentStatus::Error(_) => Color32::RED,
+    }
+}
+
+fn status_text(status: &ComponentStatus) -> String {
+    match status {
+        ComponentStatus::Disabled => i18n::t("status-disabled"),
+        ComponentStatus::Enabled => i18n::t("status-enabled"),
+        ComponentStatus::Running => i18n::t("status-running"),
+        ComponentStatus::Reconnecting(attempt) => i18n::tr("status-reconnecting", &[("attempt", attempt.to_string())]),
+        ComponentStatus::Unreachable(_) => i18n::t("status-unreachable"),
+        ComponentStatus::Error(_) => i18n::t("status-error"),
     }
 }
 
-pub enum GuiMessage {
-    UpdateStatus(String, ComponentStatus),
-    BoostReceived(String, i64, Vec<String>),
-    TestTrigger(i64),
-    UpdateSatTotal(i64),
-    StartListener(String),
-    StopListener(String),
+/// Parse a shortcut like "Ctrl+Shift+R", "Alt+F2" or "Escape"
+pub fn parse_shortcut(text: &str) -> Option<egui::KeyboardShortcut> {
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let key = egui::Key::from_name(parts.pop()?)?;
+    let mut modifiers = egui::Modifiers::NONE;
+    for part in parts {
+        modifiers = modifiers | match part.to_lowercase().as_str() {
+            "ctrl" | "control" => egui::Modifiers::CTRL,
+            "cmd" | "command" => egui::Modifiers::COMMAND,
+            "alt" | "option" => egui::Modifiers::ALT,
+            "shift" => egui::Modifiers::SHIFT,
+            _ => return None,
+        };
+    }
+    Some(egui::KeyboardShortcut::new(modifiers, key))
+}
+
+struct FailedEffect {
+    description: String,
+    error: String,
+    attempts: u32,
+    time: chrono::DateTime<Local>,
+}
+
+struct RecentBoost {
+    id: u64,
+    source: String,
+    sats: i64,
+    sender: Option<String>,
+    message: Option<String>,
+    episode: Option<String>,
+    remote_item: Option<String>,
+    artwork: Option<String>,
+    effects: Vec<TriggeredEffect>,
+    relay_delay: Option<Duration>,
+    time: chrono::DateTime<Local>,
+    note: Option<String>,
 }
 
 pub struct BlinkyBoostsApp {
     config: Config,
     modified_config: Config,
     statuses: std::collections::HashMap<String, ComponentStatus>,
-    recent_boosts: Vec<(String, i64, Vec<String>, chrono::DateTime<Local>)>,
-    tx: mpsc::Sender<GuiMessage>,
-    rx: Arc<Mutex<mpsc::Receiver<GuiMessage>>>,
+    recent_boosts: Vec<RecentBoost>,
+    older_boosts: Vec<BoostRecord>,  // Paged in from the boost log, newest first
+    older_cursor: Option<u64>,  // Where the next page of the boost log ends; None until a page is loaded
+    no_older_boosts: bool,  // The last page reached the start of the log
+    loading_older: bool,  // A page was asked for and hasn't arrived yet
+    markers: Vec<Note>,  // Oldest first
+    marker_text: String,
+    editing_note: Option<(u64, String)>,  // Boost id and the note being typed
+    failed_effects: Vec<FailedEffect>,
+    latency: LatencyStats,
+    activity: InputActivity,
+    tx: mpsc::Sender<Command>,
+    rx: Arc<Mutex<mpsc::Receiver<Event>>>,
     show_save_dialog: bool,
     save_error: Option<String>,
     expanded: std::collections::HashMap<String, bool>,
     test_amount: String,
     sat_total: i64,
+    episode: Option<String>,
+    locked_episode: Option<String>,  // Only boosts for this episode count, until unlocked
+    campaign_totals: std::collections::HashMap<String, i64>,
+    backfills: std::collections::BTreeMap<String, (usize, Option<i64>, bool)>,  // loaded, latest timestamp, done
+    payment_qr: Option<(String, Option<egui::TextureHandle>)>,
+    request_amount: String,
+    requested_invoice: Option<(String, u64, Option<egui::TextureHandle>)>,  // lightning: URI, sats, QR
+    invoice_outcome: Option<(u64, bool)>,  // Sats and whether the last requested invoice was paid
+    scene_name: String,
+    preview_toggle: Option<usize>,
+    preview_sender: String,
+    history_filter: String,
+    history_failures_only: bool,
+    wled_effects: Vec<String>,  // Effect names read from the controller, for the preset editor
+    wled_controllers: Option<Vec<wled_discovery::Controller>>,  // Controllers found on the LAN, None while looking
+    wled_discovering: bool,
+    wallet: Option<(nwc::WalletStatus, chrono::DateTime<Local>)>,  // Last NWC wallet check and when it answered
+    nwc_secret: String,  // Connection secret pasted into the setup steps, not yet saved
+    nwc_checking: bool,
+    nwc_check: Option<Result<nwc_setup::Capabilities, String>>,  // What the pasted connection allows
+    nwc_link_qr: Option<egui::TextureHandle>,  // The Alby Hub link, for a hub on another device
+    bring_to_front: bool,
+    autostart: bool,
+    autostart_error: Option<String>,
+    confirm_finish: bool,
+    summary: Option<Summary>,  // Recap of the last finished show, until closed
+    test_report: Option<test_run::Report>,  // Last scheduled or requested test run
+    test_running: bool,
+    streak_callout: Option<(String, chrono::DateTime<Local>)>,  // Latest returning booster and when they boosted
+    shows: Shows,
+    remote: bool,  // Controlling an agent (`connect`), so actions on this machine's own pipeline aren't available
+    show_tab: Option<String>,  // One of the [[shows]], or None for this instance's own
+    analytics_tab: bool,  // Shown in place of the show tabs
+    recaps: Vec<Summary>,  // Saved show recaps, read when the Analytics tab is opened
+    analytics_from: String,
+    analytics_to: String,
 }
 
 impl BlinkyBoostsApp {
-    pub fn new(config: Config, tx: mpsc::Sender<GuiMessage>, rx: mpsc::Receiver<GuiMessage>) -> Self {
+    pub fn new(config: Config, tx: mpsc::Sender<Command>, rx: mpsc::Receiver<Event>, shows: Shows, remote: bool) -> Self {
         let mut statuses = std::collections::HashMap::new();
         for (name, enabled) in [
             ("NWC", config.nwc.is_some()),
@@ -68,6 +168,7 @@ impl BlinkyBoostsApp {
             ("OSC", config.osc.is_some()),
             ("Art-Net", config.artnet.is_some()),
             ("sACN", config.sacn.is_some()),
+            ("Console", config.artnet_input.is_some()),
         ] {
             statuses.insert(
                 name.to_string(),
@@ -80,6 +181,16 @@ impl BlinkyBoostsApp {
             modified_config: config,
             statuses,
             recent_boosts: Vec::new(),
+            older_boosts: Vec::new(),
+            older_cursor: None,
+            no_older_boosts: false,
+            loading_older: false,
+            markers: Vec::new(),
+            marker_text: String::new(),
+            editing_note: None,
+            failed_effects: Vec::new(),
+            latency: LatencyStats::new(),
+            activity: InputActivity::new(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
             show_save_dialog: false,
@@ -87,6 +198,42 @@ impl BlinkyBoostsApp {
             expanded: std::collections::HashMap::new(),
             test_amount: "100".to_string(),
             sat_total: 0,
+            episode: None,
+            locked_episode: None,
+            campaign_totals: std::collections::HashMap::new(),
+            backfills: std::collections::BTreeMap::new(),
+            payment_qr: None,
+            request_amount: "5000".to_string(),
+            requested_invoice: None,
+            invoice_outcome: None,
+            scene_name: String::new(),
+            preview_toggle: None,
+            preview_sender: String::new(),
+            history_filter: String::new(),
+            history_failures_only: false,
+            wled_effects: Vec::new(),
+            wled_controllers: None,
+            wled_discovering: false,
+            nwc_secret: String::new(),
+            nwc_checking: false,
+            nwc_check: None,
+            nwc_link_qr: None,
+            wallet: None,
+            bring_to_front: false,
+            autostart: autostart::is_installed(),
+            autostart_error: None,
+            confirm_finish: false,
+            summary: None,
+            test_report: None,
+            test_running: false,
+            streak_callout: None,
+            shows,
+            remote,
+            show_tab: None,
+            analytics_tab: false,
+            recaps: Vec::new(),
+            analytics_from: String::new(),
+            analytics_to: String::new(),
         }
     }
 
@@ -106,26 +253,204 @@ impl BlinkyBoostsApp {
         }
     }
 
+    fn fire_test(&self) {
+        if let Ok(sats) = self.test_amount.parse::<i64>() {
+            if sats > 0 {
+                let _ = self.tx.try_send(Command::TestTrigger(sats));
+            }
+        }
+    }
+
+    /// Close the open dialogs as if cancelled; unsaved settings are discarded
+    fn dismiss_dialogs(&mut self) {
+        self.confirm_finish = false;
+        self.summary = None;
+        if self.show_save_dialog {
+            self.modified_config = self.config.clone();
+            self.show_save_dialog = false;
+            self.save_error = None;
+        }
+    }
+
+    /// Run the operator actions whose `[shortcuts]` were pressed. While a text field has focus,
+    /// only shortcuts with Ctrl, Cmd or Alt count, so typing doesn't trigger anything.
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let shortcuts = self.config.shortcuts.clone().unwrap_or_default();
+        let typing = ctx.wants_keyboard_input();
+        let pressed = |text: &str| parse_shortcut(text)
+            .filter(|shortcut| !typing || shortcut.modifiers.ctrl || shortcut.modifiers.command || shortcut.modifiers.alt)
+            .is_some_and(|shortcut| ctx.input_mut(|i| i.consume_shortcut(&shortcut)));
+
+        if pressed(&shortcuts.pause_effects) {
+            let outputs = mute::outputs(&self.config);
+            let mute_all = !outputs.iter().all(|output| mute::is_muted(output));
+            for output in &outputs {
+                mute::set_muted(output, mute_all);
+            }
+        }
+        if pressed(&shortcuts.fire_test) {
+            self.fire_test();
+        }
+        if pressed(&shortcuts.dismiss_dialogs) {
+            self.dismiss_dialogs();
+        }
+        if pressed(&shortcuts.next_tab) {
+            let names = self.shows.names();
+            self.analytics_tab = false;
+            self.show_tab = match &self.show_tab {
+                None => names.first().cloned(),
+                Some(current) => names.iter().skip_while(|name| *name != current).nth(1).cloned(),
+            };
+        }
+        if pressed(&shortcuts.replay_last_boost) {
+            let _ = self.tx.try_send(Command::ReplayLastBoost);
+        }
+    }
+
+    /// Editor for the `[shortcuts]`; changes apply once saved
+    fn render_shortcuts(&mut self, ui: &mut Ui) {
+        let mut shortcuts = self.modified_config.shortcuts.clone().unwrap_or_default();
+        let mut changed = false;
+        for (label, value) in [
+            (i18n::t("shortcut-pause-effects"), &mut shortcuts.pause_effects),
+            (i18n::t("shortcut-fire-test"), &mut shortcuts.fire_test),
+            (i18n::t("shortcut-dismiss-dialogs"), &mut shortcuts.dismiss_dialogs),
+            (i18n::t("shortcut-next-tab"), &mut shortcuts.next_tab),
+            (i18n::t("shortcut-replay-last-boost"), &mut shortcuts.replay_last_boost),
+        ] {
+            ui.horizontal(|ui| {
+                ui.label(label);
+                changed |= ui.text_edit_singleline(value).changed();
+                if !value.trim().is_empty() && parse_shortcut(value).is_none() {
+                    ui.colored_label(Color32::RED, i18n::t("not-a-shortcut"));
+                }
+            });
+        }
+        if changed {
+            self.modified_config.shortcuts = Some(shortcuts);
+            self.show_save_dialog = true;
+        }
+    }
+
     fn process_messages(&mut self) {
         if let Ok(mut rx) = self.rx.try_lock() {
             while let Ok(msg) = rx.try_recv() {
                 match msg {
-                    GuiMessage::UpdateStatus(comp, status) => {
+                    Event::UpdateStatus(comp, status) => {
                         self.statuses.insert(comp, status);
                     }
-                    GuiMessage::BoostReceived(src, amt, fx) => {
-                        self.recent_boosts.push((src, amt, fx, Local::now()));
+                    Event::BoostReceived(id, source, sats, sender, message, episode, remote_item, artwork, effects, relay_delay) => {
+                        self.latency.record(relay_delay, &effects);
+                        // Only live boosts have a relay delay; history and test boosts would skew the rate
+                        if relay_delay.is_some() {
+                            self.activity.record_event(&source);
+                        }
+                        let message = message.map(|m| sanitize::clean("gui", &m));
+                        self.recent_boosts.push(RecentBoost { id, source, sats, sender, message, episode, remote_item, artwork, effects, relay_delay, time: Local::now(), note: None });
                     }
-                    GuiMessage::TestTrigger(_) => {}
-                    GuiMessage::UpdateSatTotal(total) => {
+                    Event::OlderBoosts(page, next) => {
+                        self.loading_older = false;
+                        self.no_older_boosts = next == 0;
+                        self.older_cursor = Some(next);
+                        self.older_boosts.extend(page);
+                    }
+                    Event::Noted(note) => match note.boost_id {
+                        Some(id) => {
+                            if let Some(boost) = self.recent_boosts.iter_mut().find(|boost| boost.id == id) {
+                                boost.note = Some(note.text).filter(|text| !text.is_empty());
+                            }
+                        }
+                        None => self.markers.push(note),
+                    },
+                    Event::EpisodeStarted(episode) => {
+                        self.episode = Some(episode);
+                    }
+                    Event::EpisodeLocked(episode) => {
+                        self.locked_episode = episode;
+                    }
+                    Event::BackfillProgress(source, loaded, at, done) => {
+                        let entry = self.backfills.entry(source).or_insert((0, None, false));
+                        *entry = (loaded, at.or(entry.1), done);
+                    }
+                    Event::CampaignTotal(name, total, _) => {
+                        self.campaign_totals.insert(name, total);
+                    }
+                    Event::EffectFailed(description, error, attempts) => {
+                        self.failed_effects.push(FailedEffect { description, error, attempts, time: Local::now() });
+                    }
+                    Event::PaymentQr(uri) => {
+                        self.payment_qr = Some((uri, None));
+                    }
+                    Event::InvoiceRequested(uri, sats) => {
+                        self.requested_invoice = Some((uri, sats, None));
+                        self.invoice_outcome = None;
+                    }
+                    Event::InvoiceSettled(sats, paid) => {
+                        self.requested_invoice = None;
+                        self.invoice_outcome = Some((sats, paid));
+                    }
+                    Event::UpdateSatTotal(total) => {
                         self.sat_total = total;
                     }
-                    GuiMessage::StartListener(_) | GuiMessage::StopListener(_) => {
-                        // These are handled by main.rs, not by the GUI
+                    Event::WledEffects(names) => {
+                        self.wled_effects = names;
+                    }
+                    Event::WledControllers(controllers) => {
+                        self.wled_controllers = Some(controllers);
+                        self.wled_discovering = false;
+                    }
+                    Event::NwcChecked(checked) => {
+                        self.nwc_check = Some(checked);
+                        self.nwc_checking = false;
+                    }
+                    Event::WalletStatus(status) => {
+                        self.activity.record_round_trip("NWC", status.latency);
+                        self.wallet = Some((status, Local::now()));
+                    }
+                    Event::RelayRoundTrip(source, round_trip) => {
+                        self.activity.record_round_trip(&source, round_trip);
+                    }
+                    Event::BringToFront => {
+                        self.bring_to_front = true;
+                    }
+                    Event::ShowSummary(summary) => {
+                        self.summary = Some(summary);
+                    }
+                    Event::TestRun(report) => {
+                        self.test_report = Some(report);
+                        self.test_running = false;
+                    }
+                    Event::Streak(sender, streak, milestone) => {
+                        let callout = match milestone {
+                            true => i18n::tr("streak-milestone", &[("sender", sender), ("streak", streak.to_string())]),
+                            false if streak > 1 => i18n::tr("streak-returning", &[("sender", sender), ("streak", streak.to_string())]),
+                            false => i18n::tr("streak-back", &[("sender", sender)]),
+                        };
+                        self.streak_callout = Some((callout, Local::now()));
                     }
                 }
             }
         }
+        self.trim_recent_boosts();
+    }
+
+    /// Drop the oldest boosts beyond `[recent_boosts]` keep and max_age_hours, so a long stream
+    /// doesn't grow the list without bound. Markers from before the oldest kept boost go too.
+    fn trim_recent_boosts(&mut self) {
+        let cfg = self.config.recent_boosts.clone().unwrap_or_default();
+        let excess = self.recent_boosts.len().saturating_sub(cfg.keep);
+        let cutoff = Local::now() - chrono::Duration::hours(cfg.max_age_hours as i64);
+        let expired = match cfg.max_age_hours {
+            0 => 0,
+            _ => self.recent_boosts.iter().take_while(|boost| boost.time < cutoff).count(),
+        };
+        let dropped = excess.max(expired);
+        if dropped == 0 {
+            return;
+        }
+        self.recent_boosts.drain(..dropped);
+        let oldest = self.recent_boosts.first().map_or(i64::MAX, |boost| boost.time.timestamp());
+        self.markers.retain(|marker| marker.at >= oldest);
     }
 
     fn toggle_component(&mut self, name: &str, enabled: bool) {
@@ -140,7 +465,10 @@ impl BlinkyBoostsApp {
                     cfg.nwc = None;
                 } else {
                     cfg.nwc = Some(orig_cfg.nwc.clone().unwrap_or_else(||
-                        NWC { uri: "".into(), filters: BoostFiltersConfig::default() }
+                        NWC {
+                            uri: "".into(), memo_boosts: true,
+                            wallet_check_secs: config::default_nwc_wallet_check(), filters: BoostFiltersConfig::default(),
+                        }
                     ));
                 }
             },
@@ -149,7 +477,10 @@ impl BlinkyBoostsApp {
                     cfg.boostboard = None;
                 } else {
                     cfg.boostboard = Some(orig_cfg.boostboard.clone().unwrap_or_else(||
-                        BoostBoard { relay_addrs: vec![], pubkey: "".into(), filters: BoostFiltersConfig::default() }
+                        BoostBoard {
+                            relay_addrs: vec![], pubkey: "".into(), resume_history: false,
+                            page_retries: config::default_page_retries(), filters: BoostFiltersConfig::default(),
+                        }
                     ));
                 }
             },
@@ -158,7 +489,11 @@ impl BlinkyBoostsApp {
                     cfg.zaps = None;
                 } else {
                     cfg.zaps = Some(orig_cfg.zaps.clone().unwrap_or_else(||
-                        Zaps { relay_addrs: vec![], naddr: String::new(), load_since: None }
+                        Zaps {
+                            relay_addrs: vec![], naddr: String::new(), load_since: None,
+                            splits: "separate".into(), local_pubkeys: vec![], split_window_secs: 5,
+                            exclude_senders: None, min_sats: None, max_sats: None,
+                        }
                     ));
                 }
             },
@@ -170,6 +505,10 @@ impl BlinkyBoostsApp {
                         WLed {
                             host: String::new(), boost_playlist: "BOOST".into(), brightness: 128,
                             segments: None, presets: None, playlists: None, setup: false, force: false,
+                            connect_timeout_ms: config::default_wled_connect_timeout(),
+                            request_timeout_ms: config::default_wled_request_timeout(),
+                            retries: config::default_wled_retries(), mock: false,
+                            while_playing: config::WhilePlaying::Defer,
                         }
                     ));
                 }
@@ -179,7 +518,7 @@ impl BlinkyBoostsApp {
                     cfg.osc = None;
                 } else {
                     cfg.osc = Some(orig_cfg.osc.clone().unwrap_or_else(||
-                        OSC { address: String::new() }
+                        OSC { address: String::new(), mock: false, ack_timeout_ms: None, reply_address: None, totals: None }
                     ));
                 }
             },
@@ -188,7 +527,7 @@ impl BlinkyBoostsApp {
                     cfg.artnet = None;
                 } else {
                     cfg.artnet = Some(orig_cfg.artnet.clone().unwrap_or_else(||
-                        ArtNet { broadcast_address: String::new(), local_address: None, universe: Some(0) }
+                        ArtNet { broadcast_address: String::new(), local_address: None, universe: Some(0), merge: config::Merge::Ltp, mock: false }
                     ));
                 }
             },
@@ -197,7 +536,10 @@ impl BlinkyBoostsApp {
                     cfg.sacn = None;
                 } else {
                     cfg.sacn = Some(orig_cfg.sacn.clone().unwrap_or_else(||
-                        Sacn { broadcast_address: String::new(), universe: Some(1) }
+                        Sacn {
+                            broadcast_address: String::new(), universe: Some(1),
+                            priority: config::default_sacn_priority(), merge: config::Merge::Ltp, mock: false,
+                        }
                     ));
                 }
             },
@@ -206,9 +548,9 @@ impl BlinkyBoostsApp {
 
         // Send start/stop message to control the listener
         if enabled {
-            let _ = self.tx.try_send(GuiMessage::StopListener(name.to_string()));
+            let _ = self.tx.try_send(Command::StopListener(name.to_string()));
         } else {
-            let _ = self.tx.try_send(GuiMessage::StartListener(name.to_string()));
+            let _ = self.tx.try_send(Command::StartListener(name.to_string()));
         }
 
         self.statuses.insert(
@@ -218,6 +560,74 @@ impl BlinkyBoostsApp {
         self.show_save_dialog = true;
     }
 
+    /// Draw the configured strip and fixtures as they'd look after the selected toggle
+    fn render_preview(&mut self, ui: &mut Ui) {
+        let toggles = self.config.toggles.clone().unwrap_or_default();
+        let label = |idx: usize| {
+            let toggle = &toggles[idx];
+            format!("{}: {}", toggle.name.clone().unwrap_or_else(|| idx.to_string()), effects::format_toggle_description(toggle))
+        };
+
+        ui.horizontal(|ui| {
+            ui.set_height(20.0);
+            ui.label(i18n::t("toggle"));
+            egui::ComboBox::from_id_source("preview_toggle")
+                .selected_text(self.preview_toggle.filter(|idx| *idx < toggles.len()).map_or(i18n::t("none"), label))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.preview_toggle, None, i18n::t("none"));
+                    for idx in 0..toggles.len() {
+                        ui.selectable_value(&mut self.preview_toggle, Some(idx), label(idx));
+                    }
+                });
+            ui.label(i18n::t("sender"));
+            ui.text_edit_singleline(&mut self.preview_sender);
+        });
+
+        let sender = Some(self.preview_sender.trim()).filter(|s| !s.is_empty()).map(sender_color::derive);
+        let preview = match self.preview_toggle.and_then(|idx| toggles.get(idx)) {
+            Some(toggle) => Preview::for_toggle(&self.config, toggle, sender),
+            None => Preview::blank(&self.config),
+        };
+
+        if !preview.leds.is_empty() {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 18.0), egui::Sense::hover());
+            let led_width = rect.width() / preview.leds.len() as f32;
+            let painter = ui.painter();
+            for (idx, [r, g, b]) in preview.leds.iter().enumerate() {
+                let led = egui::Rect::from_min_size(
+                    rect.min + egui::vec2(idx as f32 * led_width, 0.0),
+                    egui::vec2(led_width.max(1.0), rect.height()),
+                );
+                painter.rect_filled(led, 0.0, Color32::from_rgb(*r, *g, *b));
+            }
+
+            ui.horizontal_wrapped(|ui| {
+                for segment in &preview.segments {
+                    let text = match &segment.effect {
+                        Some(effect) => format!("{} [{}-{}] {}", segment.name, segment.start, segment.stop, effect),
+                        None => format!("{} [{}-{}]", segment.name, segment.start, segment.stop),
+                    };
+                    ui.small(text);
+                }
+            });
+        }
+
+        if !preview.fixtures.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                for fixture in &preview.fixtures {
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+                    let [r, g, b] = fixture.color;
+                    ui.painter().circle_filled(rect.center(), 7.0, Color32::from_rgb(r, g, b));
+                    ui.label(&fixture.name);
+                }
+            });
+        }
+
+        if let Some(note) = &preview.note {
+            ui.label(RichText::new(note).italics());
+        }
+    }
+
     fn render_component(&mut self, ui: &mut Ui, name: &str) {
         let status = self.statuses.get(name).cloned().unwrap_or(ComponentStatus::Disabled);
         let enabled = status != ComponentStatus::Disabled;
@@ -225,9 +635,12 @@ impl BlinkyBoostsApp {
         ui.horizontal(|ui| {
             ui.set_height(20.0);
             ui.label(name);
-            ui.label(RichText::new(status.text()).color(status.color()));
+            let status_label = ui.label(RichText::new(status_text(&status)).color(status_color(&status)));
+            if let Some(detail) = status.detail() {
+                status_label.on_hover_text(detail);
+            }
 
-            let btn_text = if enabled { "Disable" } else { "Enable" };
+            let btn_text = if enabled { i18n::t("disable") } else { i18n::t("enable") };
             if ui.add_sized([80.0, 20.0], egui::Button::new(btn_text)).clicked() {
                 self.toggle_component(name, enabled);
             }
@@ -251,24 +664,106 @@ impl BlinkyBoostsApp {
 
         match name {
             "NWC" => {
+                if let Some((wallet, checked)) = &self.wallet {
+                    let balance = wallet.balance_msats
+                        .map_or(i18n::t("hidden"), |msats| i18n::tr("amount-sats", &[("sats", amounts::format_sats(amounts::msats_from_u64(msats) / 1000))]));
+                    ui.label(i18n::tr("wallet-balance", &[("wallet", wallet.alias.clone()), ("balance", balance)]));
+                    let mode = match wallet.notifications {
+                        Some(true) => i18n::t("wallet-notifications"),
+                        Some(false) => i18n::t("wallet-polling"),
+                        None => i18n::t("wallet-connecting"),
+                    };
+                    ui.label(i18n::tr("wallet-check", &[
+                        ("latency", metrics::format_duration(wallet.latency)), ("mode", mode), ("checked", checked.format("%H:%M:%S").to_string()),
+                    ]));
+                }
                 if let Some(nwc) = &mut self.modified_config.nwc {
-                    ui.horizontal(|ui| {
-                        ui.label("URI:");
-                        if ui.text_edit_singleline(&mut nwc.uri).changed() {
-                            *changed = true;
-                        }
-                    });
+                    match nwc_setup::parse(&nwc.uri) {
+                        Ok(uri) => { ui.label(i18n::tr("nwc-current", &[("relay", uri.relay_url.to_string())])); }
+                        Err(_) => { ui.colored_label(Color32::YELLOW, i18n::t("nwc-none")); }
+                    }
+                    egui::CollapsingHeader::new(i18n::t("nwc-connect"))
+                        .default_open(nwc.uri.trim().is_empty())
+                        .show(ui, |ui| {
+                            let link = nwc_setup::new_connection_link(nwc_setup::ALBY_HUB);
+                            ui.label(i18n::t("nwc-step-create"));
+                            ui.horizontal(|ui| {
+                                ui.hyperlink_to(i18n::t("nwc-open-alby-hub"), &link);
+                                let label = if self.nwc_link_qr.is_some() { i18n::t("nwc-hide-qr") } else { i18n::t("nwc-show-qr") };
+                                if ui.button(label).clicked() {
+                                    self.nwc_link_qr = match self.nwc_link_qr {
+                                        Some(_) => None,
+                                        None => qr_texture(ui.ctx(), &link),
+                                    };
+                                }
+                            });
+                            if let Some(texture) = &self.nwc_link_qr {
+                                ui.image((texture.id(), egui::vec2(160.0, 160.0)));
+                            }
+                            ui.label(RichText::new(i18n::t("nwc-other-wallets")).small());
+
+                            ui.label(i18n::t("nwc-step-paste"));
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut self.nwc_secret).password(true).hint_text("nostr+walletconnect://..."));
+                                if self.nwc_checking {
+                                    ui.spinner();
+                                } else if ui.add_enabled(!self.remote, egui::Button::new(i18n::t("nwc-check")))
+                                    .on_disabled_hover_text(i18n::t("remote-unavailable"))
+                                    .clicked()
+                                {
+                                    match nwc_setup::parse(&self.nwc_secret) {
+                                        Ok(_) => {
+                                            self.nwc_checking = true;
+                                            self.nwc_check = None;
+                                            let _ = self.tx.try_send(Command::CheckNwc(self.nwc_secret.clone()));
+                                        }
+                                        Err(e) => self.nwc_check = Some(Err(format!("{:#}", e))),
+                                    }
+                                }
+                            });
+
+                            match &self.nwc_check {
+                                Some(Err(error)) => { ui.colored_label(Color32::RED, error); }
+                                Some(Ok(caps)) => {
+                                    ui.label(i18n::tr("nwc-step-review", &[("wallet", caps.alias.clone())]));
+                                    for (ok, needed, key) in [
+                                        (caps.receives_boosts(), true, "nwc-can-receive"),
+                                        (caps.notifies(), false, "nwc-can-notify"),
+                                        (caps.loads_history(), false, "nwc-can-history"),
+                                        (caps.makes_invoices(), false, "nwc-can-invoice"),
+                                        (caps.reads_balance(), false, "nwc-can-balance"),
+                                    ] {
+                                        match (ok, needed) {
+                                            (true, _) => ui.colored_label(Color32::GREEN, format!("✔ {}", i18n::t(key))),
+                                            (false, true) => ui.colored_label(Color32::RED, format!("✖ {}", i18n::t(key))),
+                                            (false, false) => ui.colored_label(Color32::GRAY, format!("– {}", i18n::t(key))),
+                                        };
+                                    }
+                                    if !caps.receives_boosts() {
+                                        ui.colored_label(Color32::RED, i18n::t("nwc-cannot-receive"));
+                                    } else if nwc.uri == caps.uri {
+                                        ui.label(i18n::t("nwc-in-use"));
+                                    } else if ui.button(i18n::t("nwc-use")).clicked() {
+                                        nwc.uri = caps.uri.clone();
+                                        self.nwc_secret.clear();
+                                        *changed = true;
+                                    }
+                                }
+                                None => {}
+                            }
+                        });
+                    render_history_window(ui, &self.tx, "NWC", &mut nwc.filters, changed);
                 }
             }
             "Boostboard" => {
                 if let Some(bb) = &mut self.modified_config.boostboard {
                     ui.horizontal(|ui| {
-                        ui.label("Pubkey:");
+                        ui.label(i18n::t("pubkey"));
                         if ui.text_edit_singleline(&mut bb.pubkey).changed() {
                             *changed = true;
                         }
                     });
-                    ui.label("Relays:");
+                    ui.label(i18n::t("relays"));
                     let mut remove_idx = None;
                     for (i, addr) in bb.relay_addrs.iter_mut().enumerate() {
                         ui.horizontal(|ui| {
@@ -284,21 +779,22 @@ impl BlinkyBoostsApp {
                         bb.relay_addrs.remove(i);
                         *changed = true;
                     }
-                    if ui.button("+ Add").clicked() {
+                    if ui.button(i18n::t("add")).clicked() {
                         bb.relay_addrs.push("".into());
                         *changed = true;
                     }
+                    render_history_window(ui, &self.tx, "Boostboard", &mut bb.filters, changed);
                 }
             }
             "Zaps" => {
                 if let Some(zaps) = &mut self.modified_config.zaps {
                     ui.horizontal(|ui| {
-                        ui.label("NADDR:");
+                        ui.label(i18n::t("naddr"));
                         if ui.text_edit_singleline(&mut zaps.naddr).changed() {
                             *changed = true;
                         }
                     });
-                    ui.label("Relays:");
+                    ui.label(i18n::t("relays"));
                     let mut remove_idx = None;
                     for (i, addr) in zaps.relay_addrs.iter_mut().enumerate() {
                         ui.horizontal(|ui| {
@@ -314,7 +810,7 @@ impl BlinkyBoostsApp {
                         zaps.relay_addrs.remove(i);
                         *changed = true;
                     }
-                    if ui.button("+ Add").clicked() {
+                    if ui.button(i18n::t("add")).clicked() {
                         zaps.relay_addrs.push("".into());
                         *changed = true;
                     }
@@ -323,31 +819,59 @@ impl BlinkyBoostsApp {
             "WLED" => {
                 if let Some(wled) = &mut self.modified_config.wled {
                     ui.horizontal(|ui| {
-                        ui.label("Host:");
+                        ui.label(i18n::t("host"));
                         if ui.text_edit_singleline(&mut wled.host).changed() {
                             *changed = true;
                         }
+                        if self.wled_discovering {
+                            ui.spinner();
+                        } else if ui.add_enabled(!self.remote, egui::Button::new(i18n::t("find-controllers")))
+                            .on_disabled_hover_text(i18n::t("remote-unavailable"))
+                            .clicked()
+                        {
+                            self.wled_discovering = true;
+                            let _ = self.tx.try_send(Command::DiscoverWled);
+                        }
                     });
+                    match &self.wled_controllers {
+                        Some(controllers) if controllers.is_empty() => { ui.label(i18n::t("no-controllers-found")); }
+                        Some(controllers) => {
+                            for controller in controllers {
+                                ui.horizontal(|ui| {
+                                    ui.label(i18n::tr("wled-controller", &[
+                                        ("name", controller.name.clone()), ("host", controller.host.clone()), ("version", controller.version.clone()),
+                                    ]));
+                                    if wled.host == controller.host {
+                                        ui.label(i18n::t("selected"));
+                                    } else if ui.button(i18n::t("use")).clicked() {
+                                        wled.host = controller.host.clone();
+                                        *changed = true;
+                                    }
+                                });
+                            }
+                        }
+                        None => {}
+                    }
                     ui.horizontal(|ui| {
-                        ui.label("Playlist:");
+                        ui.label(i18n::t("playlist"));
                         if ui.text_edit_singleline(&mut wled.boost_playlist).changed() {
                             *changed = true;
                         }
                     });
                     ui.horizontal(|ui| {
-                        ui.label("Brightness:");
+                        ui.label(i18n::t("brightness"));
                         if ui.add(egui::Slider::new(&mut wled.brightness, 0..=255)).changed() {
                             *changed = true;
                         }
                     });
                     ui.horizontal(|ui| {
-                        ui.label("Setup:");
+                        ui.label(i18n::t("setup"));
                         if ui.checkbox(&mut wled.setup, "").changed() {
                             *changed = true;
                         }
                     });
                     ui.horizontal(|ui| {
-                        ui.label("Force:");
+                        ui.label(i18n::t("force"));
                         if ui.checkbox(&mut wled.force, "").changed() {
                             *changed = true;
                         }
@@ -357,7 +881,7 @@ impl BlinkyBoostsApp {
             "OSC" => {
                 if let Some(osc) = &mut self.modified_config.osc {
                     ui.horizontal(|ui| {
-                        ui.label("Address:");
+                        ui.label(i18n::t("address"));
                         if ui.text_edit_singleline(&mut osc.address).changed() {
                             *changed = true;
                         }
@@ -367,13 +891,13 @@ impl BlinkyBoostsApp {
             "Art-Net" => {
                 if let Some(artnet) = &mut self.modified_config.artnet {
                     ui.horizontal(|ui| {
-                        ui.label("Broadcast:");
+                        ui.label(i18n::t("broadcast"));
                         if ui.text_edit_singleline(&mut artnet.broadcast_address).changed() {
                             *changed = true;
                         }
                     });
                     ui.horizontal(|ui| {
-                        ui.label("Local:");
+                        ui.label(i18n::t("local"));
                         let mut local = artnet.local_address.clone().unwrap_or_default();
                         if ui.text_edit_singleline(&mut local).changed() {
                             artnet.local_address = if local.is_empty() { None } else { Some(local) };
@@ -381,7 +905,7 @@ impl BlinkyBoostsApp {
                         }
                     });
                     ui.horizontal(|ui| {
-                        ui.label("Universe:");
+                        ui.label(i18n::t("universe"));
                         let mut s = artnet.universe.unwrap_or(0).to_string();
                         if ui.text_edit_singleline(&mut s).changed() {
                             if let Ok(n) = s.parse() {
@@ -395,7 +919,7 @@ impl BlinkyBoostsApp {
             "sACN" => {
                 if let Some(sacn) = &mut self.modified_config.sacn {
                     ui.horizontal(|ui| {
-                        ui.label("Universe:");
+                        ui.label(i18n::t("universe"));
                         let mut s = sacn.universe.unwrap_or(1).to_string();
                         if ui.text_edit_singleline(&mut s).changed() {
                             if let Ok(n) = s.parse() {
@@ -411,80 +935,1004 @@ impl BlinkyBoostsApp {
     }
 }
 
+/// A text box for an optional number, empty meaning unset
+fn optional_number(ui: &mut Ui, label: &str, value: &mut Option<u64>) -> bool {
+    ui.label(label);
+    let mut s = value.map(|n| n.to_string()).unwrap_or_default();
+    if !ui.add(egui::TextEdit::singleline(&mut s).desired_width(40.0)).changed() {
+        return false;
+    }
+    if s.trim().is_empty() {
+        *value = None;
+    } else if let Ok(n) = s.trim().parse() {
+        *value = Some(n);
+    }
+    true
+}
+
+/// A list of numbers, e.g. playlist durations, edited as "10, 10, 20"
+fn number_list(ui: &mut Ui, label: &str, values: &mut Vec<u64>) -> bool {
+    ui.label(label);
+    let mut s = values.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+    if !ui.add(egui::TextEdit::singleline(&mut s).desired_width(80.0)).changed() {
+        return false;
+    }
+    *values = s.split(',').filter_map(|n| n.trim().parse().ok()).collect();
+    true
+}
+
+impl BlinkyBoostsApp {
+    /// One of the [[shows]], as reported by its process
+    fn render_show(&mut self, ui: &mut Ui, name: &str) {
+        let Some(show) = self.shows.state(name) else { return };
+
+        ui.heading(&show.name);
+        ui.label(&show.dir);
+        ui.add_space(10.0);
+
+        ui.horizontal(|ui| {
+            ui.set_height(20.0);
+            match (&show.exit, show.running) {
+                (_, true) => ui.label(RichText::new(i18n::t("running")).color(Color32::LIGHT_BLUE)),
+                (Some(exit), false) => ui.label(RichText::new(exit).color(Color32::RED)),
+                (None, false) => ui.label(RichText::new(i18n::t("stopped")).color(Color32::GRAY)),
+            };
+            if ui.button(if show.running { i18n::t("restart") } else { i18n::t("start") }).clicked() {
+                self.shows.restart(name);
+            }
+            if ui.add_enabled(show.running, egui::Button::new(i18n::t("stop"))).clicked() {
+                self.shows.stop(name);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(i18n::t("total-sats")).size(18.0));
+            ui.label(RichText::new(amounts::format_sats(show.total)).size(18.0).color(Color32::LIGHT_GREEN));
+        });
+        if let Some(episode) = &show.episode {
+            ui.label(i18n::tr("episode", &[("episode", episode.clone())]));
+        }
+
+        if !show.statuses.is_empty() {
+            ui.add_space(20.0);
+            ui.heading(i18n::t("components"));
+            ui.separator();
+            for (component, (status, detail)) in &show.statuses {
+                let label = ui.label(format!("{}: {}", component, status));
+                if let Some(detail) = detail {
+                    label.on_hover_text(detail);
+                }
+            }
+        }
+
+        ui.add_space(20.0);
+        ui.heading(i18n::t("recent-boosts"));
+        ui.separator();
+        if show.boosts.is_empty() {
+            ui.label(i18n::t("no-recent-boosts"));
+        }
+        for boost in show.boosts.iter().rev().take(10) {
+            ui.label(boost);
+        }
+
+        ui.add_space(20.0);
+        ui.heading(i18n::t("log"));
+        ui.separator();
+        egui::ScrollArea::vertical().id_source("show_log").stick_to_bottom(true).show(ui, |ui| {
+            for line in &show.log {
+                ui.monospace(line);
+            }
+        });
+    }
+
+    /// Editor for the `[wled]` presets and playlists `setup` provisions on the controller
+    /// Charts over the saved show recaps, limited to the chosen dates
+    fn render_analytics(&mut self, ui: &mut Ui) {
+        ui.heading(i18n::t("analytics"));
+        ui.horizontal(|ui| {
+            for (label, value) in [(i18n::t("analytics-from"), &mut self.analytics_from), (i18n::t("analytics-to"), &mut self.analytics_to)] {
+                ui.label(label);
+                ui.add(egui::TextEdit::singleline(value).desired_width(90.0).hint_text("YYYY-MM-DD"));
+                if !value.trim().is_empty() && parse_date(value).is_none() {
+                    ui.colored_label(Color32::RED, i18n::t("not-a-date"));
+                }
+            }
+            if ui.button(i18n::t("reload-recaps")).clicked() {
+                self.recaps = summary::load_saved();
+            }
+        });
+        ui.separator();
+
+        let stats = analytics::Analytics::from_summaries(&self.recaps, parse_date(&self.analytics_from), parse_date(&self.analytics_to));
+        if stats.shows.is_empty() {
+            ui.label(i18n::t("no-recaps"));
+            return;
+        }
+        ui.label(RichText::new(i18n::tr("recap-total", &[("sats", amounts::format_sats(stats.total_sats)), ("boosts", stats.boosts.to_string())])).size(18.0));
+
+        ui.add_space(10.0);
+        ui.heading(i18n::t("sats-per-day"));
+        let days: Vec<(String, f32)> = stats.sats_per_day.iter()
+            .map(|(day, sats)| (i18n::tr("day-sats", &[("day", day.to_string()), ("sats", amounts::format_sats(*sats))]), *sats as f32))
+            .collect();
+        bar_chart(ui, &days, Color32::LIGHT_GREEN);
+
+        ui.add_space(10.0);
+        ui.heading(i18n::t("average-boost"));
+        let averages: Vec<(String, f32)> = stats.shows.iter()
+            .map(|show| (i18n::tr("day-sats", &[("day", show.date.to_string()), ("sats", format!("{:.0}", show.average))]), show.average as f32))
+            .collect();
+        bar_chart(ui, &averages, Color32::LIGHT_BLUE);
+
+        ui.add_space(10.0);
+        ui.heading(i18n::t("source-share"));
+        ui.horizontal(|ui| {
+            let shares = stats.source_shares();
+            pie_chart(ui, &shares);
+            ui.vertical(|ui| {
+                for (idx, (source, share)) in shares.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 2.0, slice_color(source, idx));
+                        ui.label(i18n::tr("share-percent", &[("source", source_labels::label(source)), ("percent", format!("{:.0}", share * 100.0))]));
+                    });
+                }
+            });
+        });
+
+        if !stats.boosts_per_episode.is_empty() {
+            ui.add_space(10.0);
+            ui.heading(i18n::t("boosts-per-episode"));
+            let most = stats.boosts_per_episode.first().map_or(1, |(_, boosts)| *boosts).max(1);
+            for (episode, boosts) in &stats.boosts_per_episode {
+                ui.horizontal(|ui| {
+                    ui.add_sized([240.0, 18.0], egui::Label::new(episode.as_str()).truncate(true));
+                    ui.add(egui::ProgressBar::new(*boosts as f32 / most as f32).desired_width(200.0).text(boosts.to_string()));
+                });
+            }
+        }
+    }
+
+    fn render_wled_setup(&mut self, ui: &mut Ui) {
+        let changed = &mut self.show_save_dialog;
+        let Some(wled) = &mut self.modified_config.wled else { return };
+        let segments: Vec<String> = wled.segments.iter().flatten().map(|segment| segment.name.clone()).collect();
+
+        ui.horizontal(|ui| {
+            if ui.button(i18n::t("load-effects")).clicked() {
+                let _ = self.tx.try_send(Command::LoadWledEffects(wled.clone()));
+            }
+            if self.wled_effects.is_empty() {
+                ui.label(i18n::t("effects-not-loaded"));
+            } else {
+                ui.label(i18n::tr("wled-effects-loaded", &[("count", self.wled_effects.len().to_string()), ("host", wled.host.clone())]));
+            }
+        });
+        if segments.is_empty() {
+            ui.label(i18n::t("no-segments"));
+        }
+
+        ui.label(i18n::t("presets"));
+        let presets = wled.presets.get_or_insert_with(Vec::new);
+        let mut remove_idx = None;
+        for (i, preset) in presets.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(i18n::t("name"));
+                if ui.text_edit_singleline(&mut preset.name).changed() {
+                    *changed = true;
+                }
+                if optional_number(ui, &i18n::t("speed"), &mut preset.speed) {
+                    *changed = true;
+                }
+                if optional_number(ui, &i18n::t("intensity"), &mut preset.intensity) {
+                    *changed = true;
+                }
+                if ui.button("✖").clicked() {
+                    remove_idx = Some(i);
+                }
+            });
+            ui.indent(("wled_preset", i), |ui| {
+                for (s, segment) in segments.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{}:", segment));
+                        let color = preset.colors.get(s);
+                        let mut rgb = [0u8; 3];
+                        for (c, value) in rgb.iter_mut().enumerate() {
+                            *value = color.and_then(|color| color.get(c)).copied().unwrap_or(0).min(255) as u8;
+                        }
+                        if ui.color_edit_button_srgb(&mut rgb).changed() {
+                            fit_segments(preset, segments.len());
+                            preset.colors[s] = rgb.iter().map(|&c| c as u64).collect();
+                            *changed = true;
+                        }
+
+                        let mut effect = preset.effects.get(s).cloned().unwrap_or_else(|| "Solid".to_string());
+                        let mut edited = false;
+                        if self.wled_effects.is_empty() {
+                            edited = ui.text_edit_singleline(&mut effect).changed();
+                        } else {
+                            egui::ComboBox::from_id_source(("wled_effect", i, s))
+                                .selected_text(effect.as_str())
+                                .show_ui(ui, |ui| {
+                                    for name in &self.wled_effects {
+                                        if ui.selectable_value(&mut effect, name.clone(), name).changed() {
+                                            edited = true;
+                                        }
+                                    }
+                                });
+                        }
+                        if edited {
+                            fit_segments(preset, segments.len());
+                            preset.effects[s] = effect;
+                            *changed = true;
+                        }
+                    });
+                }
+            });
+        }
+        if let Some(i) = remove_idx {
+            presets.remove(i);
+            *changed = true;
+        }
+        if ui.button(i18n::t("add-preset")).clicked() {
+            presets.push(config::WLedPreset {
+                name: format!("Preset {}", presets.len() + 1),
+                speed: None,
+                intensity: None,
+                colors: vec![vec![255, 255, 255]; segments.len()],
+                colors2: None,
+                colors3: None,
+                effects: vec!["Solid".to_string(); segments.len()],
+            });
+            *changed = true;
+        }
+        let preset_names: Vec<String> = presets.iter().map(|preset| preset.name.clone()).collect();
+
+        ui.label(i18n::t("playlists"));
+        let playlists = wled.playlists.get_or_insert_with(Vec::new);
+        let mut remove_idx = None;
+        for (i, playlist) in playlists.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(i18n::t("name"));
+                if ui.text_edit_singleline(&mut playlist.name).changed() {
+                    *changed = true;
+                }
+                ui.label(i18n::t("repeat"));
+                if ui.add(egui::DragValue::new(&mut playlist.repeat)).changed() {
+                    *changed = true;
+                }
+                ui.label(i18n::t("end"));
+                egui::ComboBox::from_id_source(("wled_playlist_end", i))
+                    .selected_text(playlist.end.as_str())
+                    .show_ui(ui, |ui| {
+                        for name in &preset_names {
+                            if ui.selectable_value(&mut playlist.end, name.clone(), name).changed() {
+                                *changed = true;
+                            }
+                        }
+                    });
+                if ui.button("✖").clicked() {
+                    remove_idx = Some(i);
+                }
+            });
+            ui.indent(("wled_playlist", i), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(i18n::tr("playlist-presets", &[("presets", playlist.presets.join(", "))]));
+                    egui::ComboBox::from_id_source(("wled_playlist_add", i))
+                        .selected_text(i18n::t("add"))
+                        .show_ui(ui, |ui| {
+                            for name in &preset_names {
+                                if ui.selectable_label(false, name).clicked() {
+                                    playlist.presets.push(name.clone());
+                                    *changed = true;
+                                }
+                            }
+                        });
+                    if ui.add_enabled(!playlist.presets.is_empty(), egui::Button::new(i18n::t("remove-last"))).clicked() {
+                        playlist.presets.pop();
+                        *changed = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if number_list(ui, &i18n::t("durations"), &mut playlist.durations) {
+                        *changed = true;
+                    }
+                    if number_list(ui, &i18n::t("transitions"), &mut playlist.transitions) {
+                        *changed = true;
+                    }
+                });
+            });
+        }
+        if let Some(i) = remove_idx {
+            playlists.remove(i);
+            *changed = true;
+        }
+        if ui.button(i18n::t("add-playlist")).clicked() {
+            playlists.push(config::WLedPlaylist {
+                name: format!("Playlist {}", playlists.len() + 1),
+                presets: Vec::new(),
+                durations: Vec::new(),
+                transitions: Vec::new(),
+                repeat: 0,
+                end: preset_names.first().cloned().unwrap_or_default(),
+            });
+            *changed = true;
+        }
+
+        ui.add_space(5.0);
+        if ui.add_enabled(!segments.is_empty(), egui::Button::new(i18n::t("apply-to-controller"))).clicked() {
+            let _ = self.tx.try_send(Command::ApplyWledSetup(wled.clone()));
+        }
+    }
+}
+
 impl eframe::App for BlinkyBoostsApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.process_messages();
+        self.handle_shortcuts(ctx);
         ctx.request_repaint_after(Duration::from_millis(100));
 
+        if std::mem::take(&mut self.bring_to_front) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+
+        let show_names = self.shows.names();
+        egui::TopBottomPanel::top("show_tabs").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.selectable_label(!self.analytics_tab && self.show_tab.is_none(), i18n::t("this-show")).clicked() {
+                    self.analytics_tab = false;
+                    self.show_tab = None;
+                }
+                for name in show_names {
+                    if ui.selectable_label(!self.analytics_tab && self.show_tab.as_ref() == Some(&name), &name).clicked() {
+                        self.analytics_tab = false;
+                        self.show_tab = Some(name);
+                    }
+                }
+                if ui.selectable_label(self.analytics_tab, i18n::t("analytics")).clicked() && !self.analytics_tab {
+                    self.analytics_tab = true;
+                    self.recaps = summary::load_saved();
+                }
+            });
+        });
+        if self.analytics_tab {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| self.render_analytics(ui));
+            });
+            return;
+        }
+        if let Some(name) = self.show_tab.clone() {
+            egui::CentralPanel::default().show(ctx, |ui| self.render_show(ui, &name));
+            return;
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("BlinkyBoosts");
             ui.add_space(10.0);
 
             // Display sat total
             ui.horizontal(|ui| {
-                ui.label(RichText::new("Total Sats:").size(18.0));
-                ui.label(RichText::new(format!("{}", self.sat_total)).size(18.0).color(Color32::LIGHT_GREEN));
+                ui.label(RichText::new(i18n::t("total-sats")).size(18.0));
+                ui.label(RichText::new(amounts::format_sats(self.sat_total)).size(18.0).color(Color32::LIGHT_GREEN));
+                if ui.button(i18n::t("finish-show")).clicked() {
+                    self.confirm_finish = true;
+                }
+            });
+            ui.horizontal(|ui| {
+                if let Some(episode) = &self.episode {
+                    ui.label(i18n::tr("episode", &[("episode", episode.clone())]));
+                }
+                match &self.locked_episode {
+                    Some(locked) => {
+                        ui.label(RichText::new(i18n::tr("episode-locked", &[("episode", locked.clone())])).color(Color32::GOLD));
+                        if ui.button(i18n::t("unlock")).clicked() {
+                            let _ = self.tx.try_send(Command::LockEpisode(false));
+                        }
+                    }
+                    None => {
+                        if ui.add_enabled(self.episode.is_some(), egui::Button::new(i18n::t("lock-to-episode")))
+                            .on_hover_text(i18n::t("lock-to-episode-hint"))
+                            .clicked()
+                        {
+                            let _ = self.tx.try_send(Command::LockEpisode(true));
+                        }
+                    }
+                }
             });
+            if let Some((callout, at)) = &self.streak_callout {
+                if Local::now() - *at < chrono::Duration::seconds(STREAK_CALLOUT_SECS) {
+                    ui.label(RichText::new(callout).color(Color32::GOLD));
+                }
+            }
             ui.add_space(10.0);
 
             ui.columns(2, |cols| {
-                cols[0].heading("Inputs");
+                cols[0].heading(i18n::t("inputs"));
                 cols[0].separator();
                 for name in ["NWC", "Boostboard", "Zaps"] {
                     self.render_component(&mut cols[0], name);
                 }
 
-                cols[1].heading("Outputs");
+                cols[1].heading(i18n::t("outputs"));
                 cols[1].separator();
                 for name in ["WLED", "OSC", "Art-Net", "sACN"] {
                     self.render_component(&mut cols[1], name);
                 }
+                for plugin in self.config.plugins.as_deref().unwrap_or_default() {
+                    let name = plugins::component_name(&plugin.name);
+                    let status = self.statuses.get(&name).cloned().unwrap_or(ComponentStatus::Enabled);
+                    cols[1].horizontal(|ui| {
+                        ui.set_height(20.0);
+                        ui.label(&name);
+                        let status_label = ui.label(RichText::new(status_text(&status)).color(status_color(&status)));
+                        if let Some(detail) = status.detail() {
+                            status_label.on_hover_text(detail);
+                        }
+                    });
+                }
             });
 
+            if !self.backfills.is_empty() {
+                ui.add_space(10.0);
+            }
+            for (source, (loaded, at, done)) in &self.backfills {
+                let at_str = at.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                    .map(|t| i18n::tr("history-up-to", &[("time", t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())]))
+                    .unwrap_or_default();
+                ui.horizontal(|ui| {
+                    ui.set_height(20.0);
+                    if *done {
+                        ui.label(i18n::tr("history-loaded", &[("source", source_labels::label(source)), ("count", loaded.to_string())]) + &at_str);
+                    } else {
+                        ui.spinner();
+                        ui.label(i18n::tr("history-loading", &[("source", source_labels::label(source)), ("count", loaded.to_string())]) + &at_str);
+                        if ui.button(i18n::t("cancel")).clicked() {
+                            backfill::cancel(source);
+                        }
+                    }
+                });
+            }
+
+            if let Some(campaign_cfgs) = self.config.campaigns.as_ref().filter(|c| !c.is_empty()) {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("campaigns"));
+                ui.separator();
+                let showing = campaigns::showing(&self.config);
+                for (idx, campaign) in campaign_cfgs.iter().enumerate() {
+                    let total = self.campaign_totals.get(&campaign.name).copied().unwrap_or(0);
+                    let fraction = total as f32 / campaign.target_sats.max(1) as f32;
+                    ui.horizontal(|ui| {
+                        ui.set_height(20.0);
+                        let name = RichText::new(&campaign.name);
+                        ui.label(if showing == Some(idx) { name.strong().color(Color32::LIGHT_GREEN) } else { name });
+                        ui.add(egui::ProgressBar::new(fraction.min(1.0))
+                            .text(format!("{} / {} sats", amounts::format_sats(total), amounts::format_sats(campaign.target_sats))));
+                    });
+                }
+            }
+
+            if let Some((uri, texture)) = &mut self.payment_qr {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("boost-us"));
+                ui.separator();
+                if texture.is_none() {
+                    *texture = qr_texture(ctx, uri);
+                }
+                if let Some(texture) = texture {
+                    ui.image((texture.id(), egui::vec2(200.0, 200.0)));
+                }
+                ui.label(uri.as_str());
+            }
+
+            if self.config.nwc.is_some() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("request-boost"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.set_height(20.0);
+                    ui.label(i18n::t("sats"));
+                    ui.text_edit_singleline(&mut self.request_amount);
+                    if ui.button(i18n::t("create-invoice")).clicked() {
+                        if let Some(sats) = self.request_amount.parse::<u64>().ok().filter(|sats| *sats > 0) {
+                            let _ = self.tx.try_send(Command::RequestInvoice(sats));
+                        }
+                    }
+                });
+                if let Some((uri, sats, texture)) = &mut self.requested_invoice {
+                    if texture.is_none() {
+                        *texture = qr_texture(ctx, uri);
+                    }
+                    if let Some(texture) = texture {
+                        ui.image((texture.id(), egui::vec2(200.0, 200.0)));
+                    }
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(i18n::tr("invoice-waiting", &[("sats", amounts::format_sats(*sats as i64))]));
+                    });
+                }
+                match self.invoice_outcome {
+                    Some((sats, true)) => { ui.colored_label(Color32::GREEN, i18n::tr("invoice-paid", &[("sats", amounts::format_sats(sats as i64))])); }
+                    Some((sats, false)) => { ui.colored_label(Color32::GRAY, i18n::tr("invoice-expired", &[("sats", amounts::format_sats(sats as i64))])); }
+                    None => {}
+                }
+            }
+
             ui.add_space(20.0);
-            ui.heading("Test");
+            ui.heading(i18n::t("master-dimmer"));
             ui.separator();
             ui.horizontal(|ui| {
                 ui.set_height(20.0);
-                ui.label("Sats:");
-                ui.text_edit_singleline(&mut self.test_amount);
-                if ui.add_sized([80.0, 20.0], egui::Button::new("Trigger")).clicked() {
-                    if let Ok(sats) = self.test_amount.parse::<i64>() {
-                        if sats > 0 {
-                            let _ = self.tx.try_send(GuiMessage::TestTrigger(sats));
+                let mut master = dimmer::master();
+                if ui.add(egui::Slider::new(&mut master, 0..=100).suffix("%")).changed() {
+                    dimmer::set_master(master);
+                }
+                let level = dimmer::level();
+                if level != master {
+                    ui.label(i18n::tr("dimmer-effective", &[("level", level.to_string())]));
+                }
+            });
+
+            if let Some(mut pattern) = idle_pattern::settings() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("idle-pattern"));
+                ui.separator();
+                let before = pattern;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut pattern.enabled, i18n::t("enabled"));
+                    egui::ComboBox::from_id_source("idle_pattern_style")
+                        .selected_text(i18n::t(&format!("pattern-{}", pattern.style.name())))
+                        .show_ui(ui, |ui| {
+                            for style in config::PatternStyle::ALL {
+                                ui.selectable_value(&mut pattern.style, style, i18n::t(&format!("pattern-{}", style.name())));
+                            }
+                        });
+                    if pattern.style != config::PatternStyle::Rainbow {
+                        ui.color_edit_button_srgb(&mut pattern.color);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(i18n::t("speed"));
+                    ui.add(egui::Slider::new(&mut pattern.speed, 1..=100));
+                    ui.label(i18n::t("brightness"));
+                    ui.add(egui::Slider::new(&mut pattern.brightness, 0..=100).suffix("%"));
+                });
+                if pattern != before {
+                    idle_pattern::set(pattern);
+                }
+            }
+
+            let toggles = toggle_stats::summary(&self.config);
+            if !toggles.is_empty() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("toggles"));
+                ui.separator();
+                for toggle in &toggles {
+                    let name = toggle.name.clone().unwrap_or_else(|| toggle.index.to_string());
+                    let threshold_str = if toggle.threshold > 0 { format!(" {}", i18n::tr("toggle-threshold", &[("sats", amounts::format_sats(toggle.threshold))])) } else { String::new() };
+                    let last_str = toggle.stats.last_fired.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                        .map(|t| i18n::tr("toggle-last-fired", &[("time", t.with_timezone(&Local).format("%H:%M:%S").to_string())]))
+                        .unwrap_or_default();
+                    let tags_str = if toggle.tags.is_empty() { String::new() } else { format!(" [{}]", toggle.tags.join(", ")) };
+                    let text = RichText::new(format!("{}: {}{}{} - {}{}", name, toggle.description, threshold_str, tags_str, i18n::tr("toggle-stats", &[
+                        ("fired", toggle.stats.fired.to_string()), ("sats", amounts::format_sats(toggle.stats.matched_sats)),
+                    ]), last_str));
+                    ui.horizontal(|ui| {
+                        if ui.small_button(i18n::t("fire")).clicked() {
+                            let _ = self.tx.try_send(Command::FireToggle(name.clone()));
+                        }
+                        ui.label(if toggle.active { text } else { text.weak().strikethrough() });
+                    });
+                }
+                let all_tags = tags::all(&self.config);
+                if !all_tags.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(i18n::t("tags"));
+                        for tag in all_tags {
+                            let mut active = tags::is_active(&tag);
+                            if ui.checkbox(&mut active, &tag).changed() {
+                                let _ = self.tx.try_send(Command::SetTagActive(tag.clone(), active));
+                            }
+                        }
+                    });
+                }
+            }
+
+            let outputs = mute::outputs(&self.config);
+            if !outputs.is_empty() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("outputs"));
+                ui.separator();
+                ui.horizontal_wrapped(|ui| {
+                    for output in outputs {
+                        let mut muted = mute::is_muted(&output);
+                        if ui.checkbox(&mut muted, i18n::tr("mute-output", &[("output", output.to_uppercase())])).changed() {
+                            mute::set_muted(&output, muted);
                         }
                     }
+                });
+                if let Some(input) = &self.config.artnet_input {
+                    ui.horizontal_wrapped(|ui| {
+                        for gate in &input.gates {
+                            let state = match artnet_input::channel(gate.channel) {
+                                Some(value) if value >= gate.min_value => i18n::tr("gate-open", &[("value", value.to_string())]),
+                                Some(value) => i18n::tr("gate-closed", &[("value", value.to_string())]),
+                                None if input.fail_open => i18n::t("gate-no-console-open"),
+                                None => i18n::t("gate-no-console-closed"),
+                            };
+                            ui.label(i18n::tr("gate", &[("channel", gate.channel.to_string()), ("state", state)]));
+                        }
+                    });
+                }
+            }
+
+            if self.config.wled.as_ref().is_some_and(|w| w.segments.is_some()) || self.config.fixtures.is_some() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("virtual-strip"));
+                ui.separator();
+                self.render_preview(ui);
+            }
+
+            if self.modified_config.wled.is_some() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("wled-presets"));
+                ui.separator();
+                self.render_wled_setup(ui);
+            }
+
+            ui.add_space(20.0);
+            ui.heading(i18n::t("scenes"));
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.set_height(20.0);
+                ui.label(i18n::t("name"));
+                ui.text_edit_singleline(&mut self.scene_name);
+                let name = self.scene_name.trim();
+                if ui.add_enabled(!name.is_empty(), egui::Button::new(i18n::t("capture"))).clicked() {
+                    let _ = self.tx.try_send(Command::CaptureScene(name.to_string()));
+                }
+            });
+            let saved = scenes::names();
+            if !saved.is_empty() {
+                ui.label(i18n::tr("scenes-saved", &[("scenes", saved.join(", "))]));
+            }
+
+            ui.add_space(20.0);
+            ui.heading(i18n::t("test"));
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.set_height(20.0);
+                ui.label(i18n::t("sats"));
+                ui.text_edit_singleline(&mut self.test_amount);
+                if ui.add_sized([80.0, 20.0], egui::Button::new(i18n::t("trigger"))).clicked() {
+                    self.fire_test();
+                }
+            });
+            if self.config.test_run.is_some() {
+                ui.horizontal(|ui| {
+                    if self.test_running {
+                        ui.spinner();
+                    } else if ui.button(i18n::t("run-test-sequence")).clicked() {
+                        self.test_running = true;
+                        let _ = self.tx.try_send(Command::RunTest);
+                    }
+                    if let Some(report) = &self.test_report {
+                        let at = chrono::DateTime::from_timestamp(report.at, 0)
+                            .map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+                            .unwrap_or_default();
+                        match report.passed() {
+                            true => ui.colored_label(Color32::GREEN, i18n::tr("test-run-passed", &[("time", at)])),
+                            false => ui.colored_label(Color32::RED, i18n::tr("test-run-failed", &[("time", at)])),
+                        };
+                    }
+                });
+                for check in self.test_report.iter().flat_map(|report| &report.checks) {
+                    match &check.error {
+                        None => ui.colored_label(Color32::GREEN, format!("✔ {}", check.name)),
+                        Some(error) => ui.colored_label(Color32::RED, format!("✖ {}", check.name)).on_hover_text(error),
+                    };
+                }
+            }
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut self.autostart, i18n::t("start-at-login")).changed() {
+                    let result = if self.autostart { autostart::install().map(|_| ()) } else { autostart::remove() };
+                    self.autostart_error = result.err().map(|e| format!("{:#}", e));
+                    self.autostart = autostart::is_installed();
+                }
+                if let Some(err) = &self.autostart_error {
+                    ui.colored_label(Color32::RED, err);
                 }
             });
+            ui.collapsing(i18n::t("keyboard-shortcuts"), |ui| self.render_shortcuts(ui));
+
+            let latency_rows = self.latency.summary();
+            if !latency_rows.is_empty() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("latency"));
+                ui.separator();
+                for (stage, avg, max) in latency_rows {
+                    ui.label(format!("{}: {}", stage, i18n::tr("latency-stage", &[
+                        ("avg", metrics::format_duration(avg)), ("max", metrics::format_duration(max)),
+                    ])));
+                }
+            }
+
+            let inputs = self.activity.inputs();
+            if !inputs.is_empty() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("input-activity"));
+                ui.separator();
+                for input in inputs {
+                    let events = self.activity.events_per_minute(input);
+                    let round_trips = self.activity.round_trips(input);
+                    ui.horizontal(|ui| {
+                        ui.add_sized([80.0, 18.0], egui::Label::new(source_text(input)));
+                        sparkline(ui, &events.iter().map(|count| *count as f32).collect::<Vec<_>>(), Color32::LIGHT_GREEN);
+                        let last_minute = events.last().copied().unwrap_or_default();
+                        ui.add_sized([140.0, 18.0], egui::Label::new(i18n::tr("input-events", &[("count", last_minute.to_string())])));
+                        if let Some(latest) = round_trips.last() {
+                            sparkline(ui, &round_trips.iter().map(Duration::as_secs_f32).collect::<Vec<_>>(), Color32::LIGHT_BLUE);
+                            ui.label(i18n::tr("input-round-trip", &[("round_trip", metrics::format_duration(*latest))]));
+                        }
+                    });
+                }
+            }
+
+            if !self.failed_effects.is_empty() {
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    ui.heading(i18n::t("failed-effects"));
+                    if ui.button(i18n::t("clear")).clicked() {
+                        self.failed_effects.clear();
+                    }
+                });
+                ui.separator();
+                for failed in self.failed_effects.iter().rev() {
+                    let time_str = failed.time.format("%Y-%m-%d %H:%M:%S").to_string();
+                    ui.colored_label(Color32::RED, format!("[{}] {}", time_str, i18n::tr("effect-failed-retries", &[
+                        ("effect", failed.description.clone()), ("attempts", failed.attempts.to_string()), ("error", failed.error.clone()),
+                    ])));
+                }
+            }
+
+            let executions = effect_log::recent();
+            if !executions.is_empty() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("effect-history"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.set_height(20.0);
+                    ui.label(i18n::t("filter"));
+                    ui.text_edit_singleline(&mut self.history_filter);
+                    ui.checkbox(&mut self.history_failures_only, i18n::t("failures-only"));
+                });
+                let filter = self.history_filter.trim().to_lowercase();
+                let matching = executions.iter().rev()
+                    .filter(|run| !self.history_failures_only || !run.succeeded())
+                    .filter(|run| filter.is_empty()
+                        || run.parameters.to_lowercase().contains(&filter)
+                        || run.output.contains(&filter)
+                        || run.toggle.as_ref().is_some_and(|name| name.to_lowercase().contains(&filter)));
+                egui::ScrollArea::vertical().id_source("effect_history").max_height(200.0).show(ui, |ui| {
+                    for run in matching {
+                        let time_str = chrono::DateTime::from_timestamp(run.at, 0)
+                            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                            .unwrap_or_default();
+                        let name_str = run.toggle.as_ref().map(|name| format!("{}: ", name)).unwrap_or_default();
+                        let latency_str = run.latency_ms.map(|ms| i18n::tr("history-queued", &[("ms", ms.to_string())])).unwrap_or_default();
+                        let retry_str = if run.attempt > 0 { format!(" {}", i18n::tr("history-retry", &[("attempt", run.attempt.to_string())])) } else { String::new() };
+                        let ack_str = match run.acknowledged {
+                            Some(true) => i18n::t("history-acknowledged"),
+                            Some(false) => i18n::t("history-not-acknowledged"),
+                            None => String::new(),
+                        };
+                        let line = format!("[{}] {}{}{} - {}ms{}{}", time_str, name_str, run.parameters, retry_str, run.duration_ms, latency_str, ack_str);
+                        match &run.error {
+                            None => ui.label(line),
+                            Some(error) => ui.colored_label(Color32::RED, format!("{} - {}", line, i18n::tr("history-failed", &[("error", error.clone())]))),
+                        };
+                    }
+                });
+            }
+
+            let scheduled = sequencer::pending();
+            if !scheduled.is_empty() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("pending-effects"));
+                ui.separator();
+                for effect in &scheduled {
+                    ui.horizontal(|ui| {
+                        ui.label(i18n::tr("pending-effect", &[
+                            ("secs", effect.remaining_secs.to_string()), ("effect", effect.description.clone()), ("sats", amounts::format_sats(effect.sats)),
+                            ("sender", effect.sender.clone().unwrap_or_else(|| i18n::t("anonymous"))),
+                        ]));
+                        if ui.button(i18n::t("cancel")).clicked() {
+                            sequencer::cancel(effect.id);
+                        }
+                    });
+                }
+            }
+
+            let held = moderation::pending();
+            if !held.is_empty() {
+                ui.add_space(20.0);
+                ui.heading(i18n::t("awaiting-approval"));
+                ui.separator();
+                for boost in &held {
+                    let time_str = chrono::DateTime::from_timestamp(boost.held_at, 0)
+                        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default();
+                    ui.horizontal(|ui| {
+                        ui.label(format!("[{}] {}", time_str, i18n::tr("held-boost", &[
+                            ("sats", amounts::format_sats(boost.sats)), ("sender", boost.sender.clone().unwrap_or_else(|| i18n::t("anonymous"))), ("source", source_labels::label(&boost.source)),
+                        ])));
+                        for (label, decision) in [("approve", Decision::Approve), ("reject", Decision::Reject), ("block", Decision::Block)] {
+                            if ui.button(i18n::t(label)).clicked() {
+                                let _ = self.tx.try_send(Command::ModerateBoost(boost.id, decision));
+                            }
+                        }
+                    });
+                    if let Some(message) = &boost.message {
+                        ui.label(format!("    \"{}\"", sanitize::clean("gui", message)));
+                    }
+                }
+            }
 
             ui.add_space(20.0);
-            ui.heading("Recent Boosts");
+            ui.heading(i18n::t("recent-boosts"));
             ui.separator();
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.marker_text).hint_text(i18n::t("marker-hint")));
+                if ui.add_enabled(!self.marker_text.trim().is_empty(), egui::Button::new(i18n::t("add-marker"))).clicked() {
+                    let _ = self.tx.try_send(Command::AddMarker(std::mem::take(&mut self.marker_text)));
+                }
+            });
+            let mut markers = self.markers.iter().rev().peekable();
             if self.recent_boosts.is_empty() {
-                ui.label("No recent boosts");
+                ui.label(i18n::t("no-recent-boosts"));
             } else {
-                for (src, amt, fx, time) in self.recent_boosts.iter().rev() {
-                    let fx_str = if fx.is_empty() { "none" } else { &fx.join(", ") };
-                    let time_str = time.format("%Y-%m-%d %H:%M:%S").to_string();
-                    ui.label(format!("[{}] {} sats from {} → {}",
-                        time_str, amt, src, fx_str));
+                let mut last_episode = None;
+                for boost in self.recent_boosts.iter().rev() {
+                    while let Some(marker) = markers.next_if(|marker| marker.at >= boost.time.timestamp()) {
+                        render_marker(ui, marker);
+                    }
+                    if boost.episode.is_some() && boost.episode != last_episode {
+                        let sats: i64 = self.recent_boosts.iter()
+                            .filter(|b| b.episode == boost.episode)
+                            .fold(0i64, |total, b| total.saturating_add(b.sats));
+                        ui.label(RichText::new(i18n::tr("episode-total", &[("episode", boost.episode.clone().unwrap_or_default()), ("sats", amounts::format_sats(sats))])).strong());
+                        last_episode = boost.episode.clone();
+                    }
+                    let fx_str = if boost.effects.is_empty() {
+                        i18n::t("no-effects")
+                    } else {
+                        boost.effects.iter()
+                            .map(|e| format!("{} ({})", e.description, metrics::format_duration(e.queued + e.sent)))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    let relay_str = boost.relay_delay
+                        .map(|d| format!(" {}", i18n::tr("relay-delay", &[("delay", metrics::format_duration(d))])))
+                        .unwrap_or_default();
+                    let time_str = boost.time.format("%Y-%m-%d %H:%M:%S").to_string();
+                    let sender_str = boost.sender.as_ref()
+                        .map(|name| format!("{} ", i18n::tr("boost-sender", &[("sender", name.clone())])))
+                        .unwrap_or_default();
+                    let remote_str = boost.remote_item.as_ref()
+                        .map(|item| format!(" {}", i18n::tr("boost-remote-item", &[("item", item.clone())])))
+                        .unwrap_or_default();
+                    ui.horizontal(|ui| {
+                        ui.label(format!("[{}] {}", time_str, i18n::tr("boost-from", &[("sats", amounts::format_sats(boost.sats)), ("sender", sender_str)]).trim_end()));
+                        ui.label(source_text(&boost.source));
+                        ui.label(format!("{} → {}{}", remote_str.trim_start(), fx_str, relay_str).trim_start());
+                        if let Some(artwork) = &boost.artwork {
+                            ui.hyperlink_to(i18n::t("artwork"), artwork);
+                        }
+                        if self.editing_note.is_none() && ui.small_button(i18n::t("note")).clicked() {
+                            self.editing_note = Some((boost.id, boost.note.clone().unwrap_or_default()));
+                        }
+                    });
+                    if let Some(message) = &boost.message {
+                        ui.label(format!("    \"{}\"", message));
+                    }
+                    match &mut self.editing_note {
+                        Some((id, text)) if *id == boost.id => {
+                            let (mut save, mut cancel) = (false, false);
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(text).hint_text(i18n::t("note-hint")));
+                                save = ui.button(i18n::t("save")).clicked();
+                                cancel = ui.button(i18n::t("cancel")).clicked();
+                            });
+                            if save {
+                                let _ = self.tx.try_send(Command::AnnotateBoost(boost.id, std::mem::take(text)));
+                            }
+                            if save || cancel {
+                                self.editing_note = None;
+                            }
+                        }
+                        _ => {
+                            if let Some(note) = &boost.note {
+                                ui.label(RichText::new(format!("    📝 {}", note)).italics());
+                            }
+                        }
+                    }
+                }
+            }
+            for marker in markers {
+                render_marker(ui, marker);
+            }
+            for record in &self.older_boosts {
+                render_logged_boost(ui, record);
+            }
+            if self.config.recent_boosts.clone().unwrap_or_default().log {
+                ui.horizontal(|ui| {
+                    if self.loading_older {
+                        ui.spinner();
+                    } else if !self.no_older_boosts && ui.add_enabled(!self.remote, egui::Button::new(i18n::t("load-older")))
+                        .on_disabled_hover_text(i18n::t("remote-unavailable"))
+                        .clicked()
+                    {
+                        self.loading_older = true;
+                        let cursor = match self.older_cursor {
+                            Some(offset) => boost_log::Cursor::Before(offset),
+                            None => boost_log::Cursor::OlderThan(self.recent_boosts.first().map(|boost| boost.id)),
+                        };
+                        let _ = self.tx.try_send(Command::LoadOlderBoosts(cursor, OLDER_BOOSTS_PAGE));
+                    }
+                    if !self.older_boosts.is_empty() && !self.loading_older && ui.button(i18n::t("hide-older")).clicked() {
+                        self.older_boosts.clear();
+                        self.older_cursor = None;
+                        self.no_older_boosts = false;
+                    }
+                    if self.no_older_boosts {
+                        ui.label(i18n::t("no-older-boosts"));
+                    }
+                });
+            }
+
+            if self.confirm_finish {
+                egui::Window::new(i18n::t("finish-show-title"))
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        ui.label(i18n::t("finish-show-confirm"));
+                        ui.horizontal(|ui| {
+                            if ui.button(i18n::t("finish")).clicked() {
+                                let _ = self.tx.try_send(Command::FinishShow);
+                                self.confirm_finish = false;
+                            }
+                            if ui.button(i18n::t("cancel")).clicked() {
+                                self.confirm_finish = false;
+                            }
+                        });
+                    });
+            }
+
+            if let Some(summary) = &self.summary {
+                let mut open = true;
+                egui::Window::new(i18n::t("show-recap"))
+                    .collapsible(false)
+                    .open(&mut open)
+                    .show(ctx, |ui| render_summary(ui, summary));
+                if !open {
+                    self.summary = None;
                 }
             }
 
             if self.show_save_dialog {
-                egui::Window::new("Save Configuration")
+                egui::Window::new(i18n::t("save-configuration"))
                     .collapsible(false)
                     .resizable(false)
                     .show(ctx, |ui| {
-                        ui.label("Save changes?");
+                        ui.label(i18n::t("save-changes"));
                         if let Some(err) = &self.save_error {
                             ui.colored_label(Color32::RED, err);
                         }
                         ui.horizontal(|ui| {
-                            if ui.button("Save").clicked() {
+                            if ui.button(i18n::t("save")).clicked() {
                                 self.save_config();
                             }
-                            if ui.button("Cancel").clicked() {
+                            if ui.button(i18n::t("cancel")).clicked() {
                                 self.modified_config = self.config.clone();
                                 self.show_save_dialog = false;
                                 self.save_error = None;
@@ -496,27 +1944,206 @@ impl eframe::App for BlinkyBoostsApp {
     }
 }
 
-pub fn run_gui(tx: mpsc::Sender<GuiMessage>, rx: mpsc::Receiver<GuiMessage>)
+/// Give a preset edited in the GUI a color and effect for every segment, since set_preset looks
+/// up an effect for every colored segment. Presets that aren't edited are saved as they were.
+fn fit_segments(preset: &mut config::WLedPreset, segments: usize) {
+    let len = segments.max(preset.colors.len()).max(preset.effects.len());
+    preset.colors.resize(len, vec![0, 0, 0]);
+    preset.effects.resize(len, "Solid".to_string());
+}
+
+/// A boost paged back in from the boost log, which keeps less detail than a live one
+fn render_logged_boost(ui: &mut Ui, record: &BoostRecord) {
+    let time = chrono::DateTime::from_timestamp(record.received_at, 0)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default();
+    let sender = record.sender.as_ref()
+        .map(|name| format!("{} ", i18n::tr("boost-sender", &[("sender", name.clone())])))
+        .unwrap_or_default();
+    let effects = match record.effects.is_empty() {
+        true => i18n::t("no-effects"),
+        false => record.effects.join(", "),
+    };
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(format!("[{}] {}", time, i18n::tr("boost-from", &[("sats", amounts::format_sats(record.sats)), ("sender", sender)]).trim_end())).weak());
+        ui.label(source_text(&record.source));
+        ui.label(RichText::new(format!("→ {}", effects)).weak());
+    });
+    if let Some(message) = &record.message {
+        ui.label(RichText::new(format!("    \"{}\"", sanitize::clean("gui", message))).weak());
+    }
+    if let Some(note) = &record.note {
+        ui.label(RichText::new(format!("    📝 {}", note)).italics());
+    }
+}
+
+/// An operator's marker in the recent boosts list
+fn render_marker(ui: &mut Ui, marker: &Note) {
+    let time = chrono::DateTime::from_timestamp(marker.at, 0)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default();
+    ui.label(RichText::new(format!("[{}] 📍 {}", time, marker.text)).color(Color32::LIGHT_BLUE));
+}
+
+/// A source's name with its `[sources]` icon, in its color when it has one
+fn source_text(source: &str) -> RichText {
+    let text = RichText::new(source_labels::label(source));
+    match source_labels::color(source) {
+        Some([r, g, b]) => text.color(Color32::from_rgb(r, g, b)),
+        None => text,
+    }
+}
+
+/// A small line graph of `values` scaled to the largest, oldest on the left
+fn sparkline(ui: &mut Ui, values: &[f32], color: Color32) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 18.0), egui::Sense::hover());
+    ui.painter().rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    if values.len() < 2 {
+        return;
+    }
+
+    let max = values.iter().copied().fold(0.0, f32::max);
+    let step = rect.width() / (values.len() - 1) as f32;
+    let points = values.iter().enumerate()
+        .map(|(idx, value)| egui::pos2(rect.left() + idx as f32 * step, rect.bottom() - value / max.max(f32::EPSILON) * rect.height()))
+        .collect();
+    ui.painter().add(egui::Shape::line(points, egui::Stroke::new(1.0, color)));
+}
+
+/// Bars for `values` scaled to the largest, oldest on the left, each labelled on hover
+fn bar_chart(ui: &mut Ui, values: &[(String, f32)], color: Color32) {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width().min(600.0), 100.0), egui::Sense::hover());
+    ui.painter().rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    if values.is_empty() {
+        return;
+    }
+
+    let max = values.iter().map(|(_, value)| *value).fold(0.0, f32::max).max(f32::EPSILON);
+    let width = rect.width() / values.len() as f32;
+    for (idx, (_, value)) in values.iter().enumerate() {
+        let left = rect.left() + idx as f32 * width;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left + width * 0.1, rect.bottom() - value / max * rect.height()),
+            egui::pos2(left + width * 0.9, rect.bottom()),
+        );
+        ui.painter().rect_filled(bar, 1.0, color);
+    }
+
+    let hovered = response.hover_pos()
+        .map(|pos| (((pos.x - rect.left()) / width) as usize).min(values.len() - 1));
+    if let Some(idx) = hovered {
+        response.on_hover_text_at_pointer(&values[idx].0);
+    }
+}
+
+/// A source's `[sources]` color, or one from a fixed palette
+fn slice_color(source: &str, idx: usize) -> Color32 {
+    const PALETTE: [Color32; 6] = [Color32::LIGHT_GREEN, Color32::LIGHT_BLUE, Color32::GOLD, Color32::LIGHT_RED, Color32::KHAKI, Color32::LIGHT_GRAY];
+    match source_labels::color(source) {
+        Some([r, g, b]) => Color32::from_rgb(r, g, b),
+        None => PALETTE[idx % PALETTE.len()],
+    }
+}
+
+/// A pie of (source, share) slices, in the order given
+fn pie_chart(ui: &mut Ui, shares: &[(String, f64)]) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 120.0), egui::Sense::hover());
+    let (center, radius) = (rect.center(), rect.width() / 2.0);
+    let point = |angle: f32| center + radius * egui::vec2(angle.cos(), angle.sin());
+
+    let mut start = -std::f32::consts::FRAC_PI_2;
+    for (idx, (source, share)) in shares.iter().enumerate() {
+        let sweep = *share as f32 * std::f32::consts::TAU;
+        let steps = ((sweep / 0.1).ceil() as usize).max(1);
+        for step in 0..steps {
+            let from = start + sweep * step as f32 / steps as f32;
+            let to = start + sweep * (step + 1) as f32 / steps as f32;
+            ui.painter().add(egui::Shape::convex_polygon(vec![center, point(from), point(to)], slice_color(source, idx), egui::Stroke::NONE));
+        }
+        start += sweep;
+    }
+}
+
+/// A date typed as YYYY-MM-DD
+fn parse_date(text: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok()
+}
+
+/// Edit a source's history window and load its history again with it, without a restart
+fn render_history_window(ui: &mut Ui, tx: &mpsc::Sender<Command>, source: &str, filters: &mut BoostFiltersConfig, changed: &mut bool) {
+    for (label, value) in [(i18n::t("load-since"), &mut filters.load_since), (i18n::t("after"), &mut filters.after), (i18n::t("before"), &mut filters.before)] {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            let mut text = value.clone().unwrap_or_default();
+            if ui.text_edit_singleline(&mut text).on_hover_text(i18n::t("unix-seconds-hint")).changed() {
+                *value = Some(text).filter(|text| !text.trim().is_empty());
+                *changed = true;
+            }
+        });
+    }
+    if ui.button(i18n::t("reload-history"))
+        .on_hover_text(i18n::t("reload-history-hint"))
+        .clicked()
+    {
+        let _ = tx.try_send(Command::ReloadHistory(source.to_string(), filters.clone()));
+    }
+}
+
+fn render_summary(ui: &mut Ui, summary: &Summary) {
+    ui.label(RichText::new(i18n::tr("recap-total", &[("sats", amounts::format_sats(summary.total_sats)), ("boosts", summary.boosts.to_string())])).size(18.0).color(Color32::LIGHT_GREEN));
+    if let Some(biggest) = &summary.biggest {
+        ui.label(i18n::tr("recap-biggest", &[
+            ("sats", amounts::format_sats(biggest.sats)), ("sender", biggest.sender.clone().unwrap_or_else(|| i18n::t("anonymous"))), ("source", source_labels::label(&biggest.source)),
+        ]));
+        if let Some(message) = &biggest.message {
+            ui.label(format!("    \"{}\"", message));
+        }
+    }
+    if !summary.top_boosters.is_empty() {
+        ui.add_space(10.0);
+        ui.label(RichText::new(i18n::t("top-boosters")).strong());
+        for (idx, booster) in summary.top_boosters.iter().enumerate() {
+            ui.label(format!("{}. {}: {}", idx + 1, booster.sender, i18n::tr("amount-sats", &[("sats", amounts::format_sats(booster.sats))])));
+        }
+    }
+    if !summary.by_source.is_empty() {
+        ui.add_space(10.0);
+        ui.label(RichText::new(i18n::t("by-source")).strong());
+        for (source, sats) in &summary.by_source {
+            ui.horizontal(|ui| {
+                ui.label(source_text(source));
+                ui.label(i18n::tr("amount-sats", &[("sats", amounts::format_sats(*sats))]));
+            });
+        }
+    }
+    ui.add_space(10.0);
+    for path in &summary.saved_to {
+        ui.label(format!("Saved to {}", path));
+    }
+    if !summary.notes.is_empty() {
+        ui.add_space(10.0);
+        ui.label(RichText::new(i18n::t("recap-notes")).strong());
+        for note in &summary.notes {
+            ui.label(summary::note_line(note));
+        }
+    }
+    if ui.button(i18n::t("copy-markdown")).clicked() {
+        ui.output_mut(|output| output.copied_text = summary.to_markdown());
+    }
+}
+
+pub fn run_gui(tx: mpsc::Sender<Command>, rx: mpsc::Receiver<Event>, shows: Shows, remote: bool)
     -> Result<(), Box<dyn std::error::Error>>
 {
-    let config = match crate::config::load_config() {
+    let config = match config::load_config() {
         Ok(cfg) => cfg,
         Err(e) => {
             eprintln!("Error loading config: {}", e);
-            Config {
-                nwc: None,
-                boostboard: None,
-                zaps: None,
-                osc: None,
-                artnet: None,
-                sacn: None,
-                wled: None,
-                toggles: None,
-            }
+            Config::default()
         }
     };
 
-    let app = BlinkyBoostsApp::new(config, tx, rx);
+    let app = BlinkyBoostsApp::new(config, tx, rx, shows, remote);
 
     eframe::run_native(
         "BlinkyBoosts",
@@ -0,0 +1,42 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddrV4, TcpListener, TcpStream};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use blinkyboosts_core::messages::Event;
+
+/// Loopback port held by the running instance; a second launch finds it taken
+const PORT: u16 = 47_621;
+const SHOW: &str = "show";
+
+/// Claim the single-instance lock. When another instance already holds it, ask that one to
+/// bring its window to the front and return None so this launch can exit instead of starting
+/// duplicate listeners that would count every boost twice.
+pub fn acquire() -> Option<TcpListener> {
+    let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+    match TcpListener::bind(addr) {
+        Ok(listener) => Some(listener),
+        Err(_) => {
+            match TcpStream::connect_timeout(&addr.into(), Duration::from_secs(1)) {
+                Ok(mut stream) => {
+                    let _ = writeln!(stream, "{}", SHOW);
+                }
+                Err(e) => eprintln!("Port {} is taken but nothing answered on it: {}", PORT, e),
+            }
+            None
+        }
+    }
+}
+
+/// Bring the window to the front whenever another launch reports in
+pub fn listen(listener: TcpListener, tx: mpsc::Sender<Event>) {
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut line = String::new();
+            if BufReader::new(stream).read_line(&mut line).is_ok() && line.trim() == SHOW {
+                println!("Another instance was launched, showing this one instead");
+                let _ = tx.blocking_send(Event::BringToFront);
+            }
+        }
+    });
+}
@@ -1,574 +1,275 @@
-use nostr_sdk::Timestamp;
 use std::sync::Arc;
 use std::collections::HashMap;
 use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
-use tokio_util::sync::CancellationToken;
-use anyhow::{Context, Result};
-
-mod boostboard;
-mod boosts;
-mod config;
-mod nwc;
-mod osc;
-mod artnet;
-mod sacn;
-mod wled;
-mod zaps;
-mod gui;
-mod sat_tracker;
-
-use gui::{ComponentStatus, GuiMessage};
-
-// ============================================================================
-// Utility Functions
-// ============================================================================
+use clap::Parser;
 
-fn parse_timestamp(s: &str) -> Result<Timestamp> {
-    s.parse::<u64>()
-        .map(Timestamp::from_secs)
-        .context("Failed to parse timestamp as unix seconds")
-}
+use blinkyboosts_core::listeners::{load_history, shutdown_gracefully, start_listener, supervise_listeners, ListenerHandles};
+use blinkyboosts_core::messages::{Command, ComponentStatus, Event};
+use blinkyboosts_core::{artnet_input, audio, boost_log, bus, config, dimmer, effects, goal, health, idle, idle_pattern, notify, osc, outbox, payment_qr, recovery, sat_tracker, show_sync, test_run, total_brackets};
 
-fn parse_load_since(load_since_str: Option<&String>, default: Timestamp) -> Timestamp {
-    load_since_str
-        .and_then(|s| parse_timestamp(s).ok().inspect(|_| println!("Loading since: {}", s)))
-        .unwrap_or(default)
-}
+mod gui;
+mod api;
+mod autostart;
+mod event_stream;
+mod cli;
+mod companion;
+mod controller;
+mod instance;
+mod mqtt;
+mod remote;
+mod shows;
 
 // ============================================================================
-// Effects Setup & Triggering
+// Main
 // ============================================================================
 
-async fn setup_effects(config: config::Config) -> Result<()> {
-    let Some(cfg) = config.wled else { return Ok(()) };
-    if !cfg.setup { return Ok(()) };
-
-    let mut wled = wled::WLed::new();
-    wled.load(&cfg.host).await.context("Unable to load from WLED")?;
-
-    if let Some(presets) = &cfg.presets {
-        for (idx, preset) in presets.iter().enumerate() {
-            wled.set_preset(idx, &cfg, preset).await?;
-        }
-    }
-
-    if let Some(playlists) = &cfg.playlists {
-        for (idx, playlist) in playlists.iter().enumerate() {
-            wled.set_playlist(idx, &cfg, playlist).await?;
+/// Log GUI messages to the console when running without a window
+async fn run_headless(mut gui_rx: tokio::sync::mpsc::Receiver<Event>) {
+    while let Some(msg) = gui_rx.recv().await {
+        match msg {
+            Event::UpdateStatus(name, status) => match status.detail() {
+                Some(detail) => println!("{}: {} ({})", name, status.text(), detail),
+                None => println!("{}: {}", name, status.text()),
+            },
+            Event::EffectFailed(description, error, attempts) => {
+                eprintln!("Effect failed after {} retries: {}: {}", attempts, description, error);
+            }
+            Event::ShowSummary(summary) => println!("{}", summary.to_markdown()),
+            _ => {}
         }
     }
-
-    Ok(())
 }
 
-fn format_toggle_description(toggle: &config::Toggle) -> String {
-    match toggle.output.to_lowercase().as_str() {
-        "osc" => toggle.osc.as_ref().map_or("OSC".to_string(), |osc| {
-            use crate::config::OscArgValue;
-            let value_str = match &osc.arg_value {
-                OscArgValue::String(s) => format!("\"{}\"", s),
-                OscArgValue::Int(i) => i.to_string(),
-                OscArgValue::Float(f) => f.to_string(),
-            };
-            format!("OSC {}: {}", osc.path, value_str)
-        }),
-        "artnet" => toggle.artnet.as_ref()
-            .map_or("Art-Net".to_string(), |a| format!("Art-Net ch{}: {}", a.channel, a.value)),
-        "sacn" => toggle.sacn.as_ref()
-            .map_or("sACN".to_string(), |s| format!("sACN ch{}: {}", s.channel, s.value)),
-        "wled" => toggle.wled.as_ref()
-            .map_or("WLED".to_string(), |w| format!("WLED: {}", w.preset)),
-        _ => toggle.output.clone()
-    }
+/// The GUI alone, relaying its commands to the agent at `[remote] url` and showing the agent's
+/// events
+fn run_controller(rt: &tokio::runtime::Runtime, config: config::Config) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(remote_cfg) = config.remote.clone() else {
+        return Err("Set [remote] url in config.toml to the agent's control API".into());
+    };
+    println!("Controlling the BlinkyBoosts agent at {}...", remote_cfg.url);
+    let (tx, rx) = tokio::sync::mpsc::channel::<Event>(100);
+    let (command_tx, command_rx) = tokio::sync::mpsc::channel::<Command>(100);
+    rt.spawn(remote::run(config, command_rx, tx));
+    gui::run_gui(command_tx, rx, shows::Shows::default(), true)
 }
 
-async fn trigger_single_toggle(config: &config::Config, toggle: &config::Toggle) -> Result<()> {
-    match toggle.output.to_lowercase().as_str() {
-        "osc" => {
-            let osc_cfg = config.osc.as_ref().context("OSC not configured")?;
-            osc::Osc::new(&osc_cfg.address)?.trigger_toggle(toggle)?;
-        },
-        "artnet" => {
-            let cfg = config.artnet.as_ref().context("Art-Net not configured")?;
-            artnet::ArtNet::trigger_toggle(
-                toggle, cfg.universe.unwrap_or(0),
-                cfg.broadcast_address.clone(), cfg.local_address.clone()
-            )?;
-        },
-        "sacn" => {
-            let cfg = config.sacn.as_ref().context("sACN not configured")?;
-            sacn::Sacn::trigger_toggle(toggle, cfg.universe.unwrap_or(1), cfg.broadcast_address.clone())?;
-        },
-        "wled" => {
-            let cfg = config.wled.as_ref().context("WLED not configured")?;
-            wled::WLed::trigger_toggle(toggle, &cfg.host).await?;
-        },
-        _ => eprintln!("Unknown toggle output type: {}", toggle.output),
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = cli::Cli::parse();
+    if let Some(cli::Command::ImportBundle { file }) = &cli.command {
+        return Ok(cli::import_bundle(file)?);
+    }
+    let config = config::load_config()?;
+    let rt = tokio::runtime::Runtime::new()?;
+    if let Some(cli::Command::Connect) = &cli.command {
+        return run_controller(&rt, config);
     }
-    Ok(())
-}
-
-async fn trigger_toggles(
-    config: &config::Config,
-    sats: i64,
-    tracker: Option<Arc<Mutex<sat_tracker::SatTracker>>>
-) -> Result<Vec<String>> {
-    let Some(toggles) = &config.toggles else { return Ok(Vec::new()) };
-
-    let last_digit = (sats % 10).abs() as u8;
-    let mut triggered_effects = Vec::new();
-
-    // Check threshold-based toggles
-    let threshold_toggles: Vec<_> = toggles.iter()
-        .filter(|t| !t.is_default && t.use_total && t.threshold > 0)
-        .collect();
-
-    let threshold_triggered = if !threshold_toggles.is_empty() {
-        if let Some(tracker_ref) = tracker.as_ref() {
-            let all_thresholds: Vec<i64> = threshold_toggles.iter().map(|t| t.threshold).collect();
-            let max_threshold = *all_thresholds.iter().max().unwrap();
-
-            let mut tracker_guard = tracker_ref.lock().await;
-            let thresholds_to_trigger = tracker_guard.get_thresholds_to_trigger(sats, &all_thresholds, max_threshold);
-            drop(tracker_guard);
-
-            if let Some(&max_crossed) = thresholds_to_trigger.iter().max() {
-                if thresholds_to_trigger.len() > 1 {
-                    println!("Multiple thresholds crossed ({:?}), applying only maximum: {} sats", thresholds_to_trigger, max_crossed);
-                } else {
-                    println!("Triggering threshold: {} sats", max_crossed);
-                }
 
-                if let Some(toggle) = threshold_toggles.iter().find(|t| t.threshold == max_crossed) {
-                    let should_trigger = toggle.endswith_range
-                        .map_or(true, |(start, end)| {
-                            let in_range = last_digit >= start && last_digit <= end;
-                            if !in_range {
-                                println!("Toggle skipped: {} sats threshold ends with {}, not in range {}-{}", max_crossed, last_digit, start, end);
-                            }
-                            in_range
-                        });
-
-                    if should_trigger {
-                        if let Err(e) = trigger_single_toggle(config, toggle).await {
-                            eprintln!("Failed to trigger toggle at {} sats: {:#}", max_crossed, e);
-                        } else {
-                            triggered_effects.push(format_toggle_description(toggle));
-                        }
-                    }
-                }
-                true
-            } else {
-                false
-            }
-        } else {
-            false
-        }
-    } else {
-        false
+    let (headless, show) = match &cli.command {
+        None => (false, None),
+        Some(cli::Command::Listen { headless, show }) => (*headless, show.clone()),
+        Some(command) => return Ok(rt.block_on(cli::run(command, config))?),
     };
 
-    // Trigger default toggles if no threshold was triggered
-    if !threshold_triggered {
-        for toggle in toggles.iter().filter(|t| t.is_default) {
-            let should_trigger = toggle.endswith_range
-                .map_or(true, |(start, end)| {
-                    let in_range = last_digit >= start && last_digit <= end;
-                    if !in_range {
-                        println!("Default toggle skipped: {} sats ends with {}, not in range {}-{}", sats, last_digit, start, end);
-                    }
-                    in_range
-                });
-
-            if should_trigger {
-                println!("Default toggle triggered for {} sats - {} output", sats, toggle.output);
-                if let Err(e) = trigger_single_toggle(config, toggle).await {
-                    eprintln!("Failed to trigger default toggle: {:#}", e);
-                } else {
-                    triggered_effects.push(format_toggle_description(toggle));
-                }
+    // A show is run by another instance, which holds the lock
+    let instance_lock = match show {
+        Some(_) => None,
+        None => match instance::acquire() {
+            Some(lock) => Some(lock),
+            None => {
+                println!("BlinkyBoosts is already running");
+                return Ok(());
             }
-        }
-    }
-
-    Ok(triggered_effects)
-}
-
-async fn trigger_effects(
-    config: config::Config,
-    sats: i64,
-    tracker: Option<Arc<Mutex<sat_tracker::SatTracker>>>
-) -> Result<Vec<String>> {
-    println!("Triggering effects for {} sats", sats);
-    trigger_toggles(&config, sats, tracker).await
-        .inspect_err(|e| eprintln!("Failed to trigger toggles: {:#}", e))
-        .or(Ok(Vec::new()))
-}
-
-// ============================================================================
-// Boost Processing
-// ============================================================================
-
-async fn process_boost(
-    source: &str,
-    sats: i64,
-    tx: &tokio::sync::mpsc::Sender<GuiMessage>,
-    tracker: &Arc<Mutex<sat_tracker::SatTracker>>,
-    config: &config::Config,
-    trigger_effects_flag: bool
-) {
-    let total = tracker.lock().await.add(source, sats);
-    println!("{} received: {} sats, total now: {} sats", source, sats, total);
-
-    let _ = tx.send(GuiMessage::UpdateSatTotal(total)).await;
-
-    let effects = if trigger_effects_flag {
-        trigger_effects(config.clone(), sats, Some(tracker.clone())).await.unwrap_or_default()
-    } else {
-        Vec::new()
+        },
     };
 
-    let _ = tx.send(GuiMessage::BoostReceived(source.to_string(), sats, effects)).await;
-}
-
-async fn sync_threshold_triggers(config: &config::Config, tracker: &Arc<Mutex<sat_tracker::SatTracker>>) {
-    if let Some(toggles) = &config.toggles {
-        let thresholds: Vec<i64> = toggles.iter()
-            .filter(|t| !t.is_default && t.use_total && t.threshold > 0)
-            .map(|t| t.threshold)
-            .collect();
-
-        if let Some(&max_threshold) = thresholds.iter().max() {
-            tracker.lock().await.sync_trigger_state(max_threshold);
-        }
+    match &show {
+        Some(name) => println!("Starting BlinkyBoosts show {}...", name),
+        None => println!("Starting BlinkyBoosts..."),
+    }
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Event>(100);
+    let (command_tx, command_rx) = tokio::sync::mpsc::channel::<Command>(100);
+    if let Some(lock) = instance_lock {
+        instance::listen(lock, tx.clone());
+    }
+    let sat_tracker = Arc::new(Mutex::new(sat_tracker::SatTracker::new()));
+    let (effects, retry_rx) = effects::Effects::new(config.clone());
+    let event_stream = event_stream::EventStream::new();
+
+    // Boost history, the boost log, push notifications and the GUI report follow the pipeline's bus
+    {
+        let _runtime = rt.enter();
+        bus::attach(effects.bus(), tx.clone(), sat_tracker.clone());
+        boost_log::attach(effects.bus(), effects.config());
+        notify::attach(effects.bus(), effects.config());
     }
-}
-
-// ============================================================================
-// Listeners
-// ============================================================================
-
-async fn initialize_listener(component_name: &str, tx: &tokio::sync::mpsc::Sender<GuiMessage>) {
-    let _ = tx.send(GuiMessage::UpdateStatus(component_name.to_string(), ComponentStatus::Running)).await;
-}
-
-async fn handle_connection_error(component: &str, error: anyhow::Error, tx: &tokio::sync::mpsc::Sender<GuiMessage>) {
-    let error_msg = format!("Connection error: {:#}", error);
-    eprintln!("Error connecting to {}: {}", component, error_msg);
-    let _ = tx.send(GuiMessage::UpdateStatus(component.to_string(), ComponentStatus::Error(error_msg))).await;
-}
-
-async fn listen_for_zaps(
-    config: config::Config,
-    tx: tokio::sync::mpsc::Sender<GuiMessage>,
-    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
-    cancel_token: CancellationToken
-) {
-    let cfg = config.zaps.clone().unwrap();
-    initialize_listener("Zaps", &tx).await;
-
-    let zap = match zaps::Zaps::new(&cfg.relay_addrs, &cfg.naddr).await {
-        Ok(z) => z,
-        Err(e) => return handle_connection_error("Zaps", e, &tx).await,
-    };
 
-    let load_since = match cfg.load_since {
-        Some(load_since_str) => match parse_timestamp(&load_since_str) {
-            Ok(ts) => Some(ts),
-            Err(_) => None,
-        },
-        None => None,
+    // Other shows run alongside this one, or the instance running this show
+    let shows = match show {
+        Some(_) => {
+            rt.spawn(shows::report_events(event_stream.subscribe()));
+            shows::Shows::default()
+        }
+        None => shows::Shows::start(&config),
     };
 
-    println!("Waiting for Zaps...");
-
-    tokio::select! {
-        result = zap.subscribe_zaps(load_since, |zap: zaps::Zap| {
-            let (config, tx, tracker) = (config.clone(), tx.clone(), tracker.clone());
-            async move {
-                println!("Zap: {:#?}", zap);
-                process_boost("Zaps", zap.value_msat_total / 1000, &tx, &tracker, &config, !zap.is_old).await;
+    // Setup effects, then pick up what a crashed run left behind
+    let (pending, crashed) = recovery::start();
+    if crashed {
+        println!("The last run didn't shut down cleanly, restoring the idle look");
+    }
+    rt.spawn({
+        let (config, effects) = (config.clone(), effects.clone());
+        let tx = tx.clone();
+        async move {
+            if let Err(e) = effects::setup_effects(config.clone()).await {
+                eprintln!("Error setting up effects: {:#}", e);
+                let _ = tx.send(Event::UpdateStatus("Effects".to_string(), ComponentStatus::Error(format!("{:#}", e)))).await;
             }
-        }) => {
-            if let Err(e) = result {
-                let error_msg = format!("Event error: {:#}", e);
-                eprintln!("Error handling zap events: {}", error_msg);
-                let _ = tx.send(GuiMessage::UpdateStatus("Zaps".to_string(), ComponentStatus::Error(error_msg))).await;
+            if config.idle.as_ref().is_some_and(|idle| idle.on_start || crashed) {
+                idle::restore(&config).await;
+            } else if config.idle.is_none() {
+                total_brackets::apply(&config).await;
             }
+            effects.resume(pending);
         }
-        _ = cancel_token.cancelled() => {
-            println!("Zaps listener cancelled");
-            let _ = tx.send(GuiMessage::UpdateStatus("Zaps".to_string(), ComponentStatus::Disabled)).await;
-        }
-    }
-}
-
-async fn listen_for_boostboard(
-    config: config::Config,
-    tx: tokio::sync::mpsc::Sender<GuiMessage>,
-    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
-    cancel_token: CancellationToken
-) {
-    let cfg = config.boostboard.clone().unwrap();
-    initialize_listener("Boostboard", &tx).await;
-
-    if cfg.relay_addrs.is_empty() {
-        eprintln!("Error: No relay addresses specified for boostboard");
-        let _ = tx.send(GuiMessage::UpdateStatus("Boostboard".to_string(), ComponentStatus::Error("No relay addresses specified".to_string()))).await;
-        return;
-    }
+    });
 
-    let filters = boostboard::BoostFilters {
-        podcasts: cfg.filters.podcasts.clone(),
-        episode_guids: cfg.filters.episode_guids.clone(),
-        event_guids: cfg.filters.event_guids.clone(),
-        before: cfg.filters.before.as_ref().and_then(|s| parse_timestamp(s).ok()),
-        after: cfg.filters.after.as_ref().and_then(|s| parse_timestamp(s).ok()),
-    };
+    // Posts kept while their service was unreachable
+    rt.spawn(outbox::run(config.clone()));
 
-    println!("Boostboard Filters: {:#?}", &filters);
+    // External output plugins
+    rt.spawn({
+        let (plugins, tx) = (effects.plugins().clone(), tx.clone());
+        async move { plugins.start(tx).await }
+    });
 
-    let board = match boostboard::BoostBoard::new(&cfg.relay_addrs, &cfg.pubkey, filters.clone()).await {
-        Ok(b) => b,
-        Err(e) => return handle_connection_error("Boostboard", e, &tx).await,
-    };
+    // Retry failed effect triggers
+    rt.spawn(effects.clone().run_retries(retry_rx, tx.clone()));
 
-    let load_since = Some(parse_load_since(cfg.filters.load_since.as_ref(), Timestamp::now()));
+    // Track listener tasks
+    let listener_handles: ListenerHandles = Arc::new(Mutex::new(HashMap::new()));
 
-    // Load stored boosts
-    println!("Loading stored boosts from API...");
-    let stored_boosts = boostboard::StoredBoosts::new(filters);
-    let _ = stored_boosts.load(|boost: boosts::Boostagram| {
-        let (tx, tracker, config) = (tx.clone(), tracker.clone(), config.clone());
+    // Start initial listeners
+    rt.spawn({
+        let (handles, effects, tx, tracker) = (listener_handles.clone(), effects.clone(), tx.clone(), sat_tracker.clone());
         async move {
-            if boost.action == "boost" {
-                process_boost("Boostboard", boost.sats, &tx, &tracker, &config, false).await;
+            let config = effects.config();
+            if config.zaps.is_some() {
+                start_listener("Zaps", &handles, &effects, &tx, &tracker).await;
+            }
+            load_history(&effects, &tx, &tracker).await;
+            if config.boostboard.is_some() {
+                start_listener("Boostboard", &handles, &effects, &tx, &tracker).await;
+            }
+            if config.nwc.is_some() {
+                start_listener("NWC", &handles, &effects, &tx, &tracker).await;
             }
         }
-    }).await;
-
-    sync_threshold_triggers(&config, &tracker).await;
-
-    let subscription_id = match board.subscribe(load_since).await {
-        Ok(id) => id,
-        Err(e) => {
-            let error_msg = format!("Subscription error: {:#}", e);
-            eprintln!("Error subscribing to board: {}", error_msg);
-            let _ = tx.send(GuiMessage::UpdateStatus("Boostboard".to_string(), ComponentStatus::Error(error_msg))).await;
-            return;
-        }
-    };
-
-    println!("Waiting for Boostboard boosts...");
-    let subscription_start_time = Timestamp::now();
-    let tx_clone = tx.clone();
+    });
 
-    tokio::select! {
-        result = board.handle_boosts(subscription_id, move |boost: boosts::Boostagram, event_ts: Timestamp| {
-            let (config, tx, tracker) = (config.clone(), tx_clone.clone(), tracker.clone());
+    // Control API
+    if let Some(api_cfg) = config.api.clone().filter(|api| api.enabled) {
+        rt.spawn({
+            let (effects, command_tx, tx, tracker, events) = (effects.clone(), command_tx.clone(), tx.clone(), sat_tracker.clone(), event_stream.clone());
             async move {
-                if boost.action == "boost" {
-                    println!("Boost: {:#?}", boost);
-                    let trigger = event_ts >= subscription_start_time;
-                    process_boost("Boostboard", boost.sats, &tx, &tracker, &config, trigger).await;
+                if let Err(e) = api::serve(api_cfg, command_tx, tx, tracker, effects, events).await {
+                    eprintln!("Control API stopped: {:#}", e);
                 }
             }
-        }) => {
-            if let Err(e) = result {
-                let error_msg = format!("Event error: {:#}", e);
-                eprintln!("Error handling boostboard events: {}", error_msg);
-                let _ = tx.send(GuiMessage::UpdateStatus("Boostboard".to_string(), ComponentStatus::Error(error_msg))).await;
-            }
-        }
-        _ = cancel_token.cancelled() => {
-            println!("Boostboard listener cancelled");
-            let _ = tx.send(GuiMessage::UpdateStatus("Boostboard".to_string(), ComponentStatus::Disabled)).await;
-        }
+        });
     }
-}
 
-async fn listen_for_nwc(
-    config: config::Config,
-    tx: tokio::sync::mpsc::Sender<GuiMessage>,
-    tracker: Arc<Mutex<sat_tracker::SatTracker>>,
-    cancel_token: CancellationToken
-) {
-    let cfg = config.nwc.clone().unwrap();
-    initialize_listener("NWC", &tx).await;
-
-    let filters = boostboard::BoostFilters {
-        podcasts: cfg.filters.podcasts.clone(),
-        episode_guids: cfg.filters.episode_guids.clone(),
-        event_guids: cfg.filters.event_guids.clone(),
-        before: cfg.filters.before.as_ref().and_then(|s| parse_timestamp(s).ok()),
-        after: cfg.filters.after.as_ref().and_then(|s| parse_timestamp(s).ok()),
-    };
+    // Companion / control surface TCP server
+    if let Some(companion_cfg) = config.companion.clone().filter(|c| c.enabled) {
+        rt.spawn({
+            let (effects, command_tx, tracker, events) = (effects.clone(), command_tx.clone(), sat_tracker.clone(), event_stream.clone());
+            async move {
+                if let Err(e) = companion::serve(companion_cfg, command_tx, tracker, effects, events).await {
+                    eprintln!("Companion server stopped: {:#}", e);
+                }
+            }
+        });
+    }
 
-    println!("NWC Filters: {:#?}", &filters);
+    // Home Assistant via MQTT
+    if let Some(mqtt_cfg) = config.mqtt.clone() {
+        rt.spawn(mqtt::run(mqtt_cfg, sat_tracker.clone(), effects.clone(), event_stream.clone()));
+    }
 
-    let nwc = match nwc::NWC::new(&cfg.uri, filters).await {
-        Ok(n) => n,
-        Err(e) => return handle_connection_error("NWC", e, &tx).await,
-    };
+    // Light show sync with other instances over nostr
+    rt.spawn(show_sync::run(config.clone(), effects.clone(), tx.clone(), sat_tracker.clone()));
 
-    let load_since = parse_load_since(cfg.filters.load_since.as_ref(), Timestamp::now());
+    // Console channels gating and scaling effects
+    rt.spawn(artnet_input::run(config.clone(), tx.clone()));
 
-    println!("Loading previous boosts from NWC...");
-    let latest_boost_timestamp = nwc.load_previous_boosts(Some(load_since), |boost: boosts::Boostagram| {
-        let (tx, tracker, config) = (tx.clone(), tracker.clone(), config.clone());
-        async move {
-            process_boost("NWC", boost.sats, &tx, &tracker, &config, false).await;
-        }
-    }).await.unwrap_or(None);
+    // Master dimmer schedule / GUI changes to WLED brightness
+    rt.spawn(dimmer::run(config.clone()));
 
-    sync_threshold_triggers(&config, &tracker).await;
+    // Generated idle look over DDP
+    rt.spawn(idle_pattern::run(config.clone()));
 
-    let subscription_start = latest_boost_timestamp.map(|ts| ts + 1).unwrap_or(load_since);
-    println!("Waiting for NWC boosts...");
-
-    tokio::select! {
-        result = nwc.subscribe_boosts(subscription_start, |boost: boosts::Boostagram| {
-            let (config, tx, tracker) = (config.clone(), tx.clone(), tracker.clone());
-            async move {
-                if boost.action == "boost" {
-                    println!("NWC Boost: {:#?}", boost);
-                    process_boost("NWC", boost.sats, &tx, &tracker, &config, true).await;
-                }
-            }
-        }) => {
-            if let Err(e) = result {
-                let error_msg = format!("Event error: {:#}", e);
-                eprintln!("Error handling NWC events: {}", error_msg);
-                let _ = tx.send(GuiMessage::UpdateStatus("NWC".to_string(), ComponentStatus::Error(error_msg))).await;
-            }
-        }
-        _ = cancel_token.cancelled() => {
-            println!("NWC listener cancelled");
-            let _ = tx.send(GuiMessage::UpdateStatus("NWC".to_string(), ComponentStatus::Disabled)).await;
-        }
-    }
-}
+    // Show audio modulating the rig between boost effects
+    rt.spawn(audio::run(config.clone()));
 
-// ============================================================================
-// Listener Management
-// ============================================================================
+    // Sat goal thermometer on a LED strip
+    rt.spawn(goal::run(config.clone(), sat_tracker.clone()));
 
-async fn start_listener(
-    name: &str,
-    handles: &Arc<Mutex<HashMap<String, (JoinHandle<()>, CancellationToken)>>>,
-    config: &config::Config,
-    tx: &tokio::sync::mpsc::Sender<GuiMessage>,
-    tracker: &Arc<Mutex<sat_tracker::SatTracker>>
-) {
-    stop_listener(name, handles).await;
-
-    let cancel_token = CancellationToken::new();
-    let cancel_clone = cancel_token.clone();
-
-    let handle = match name {
-        "Zaps" if config.zaps.is_some() => {
-            let (cfg, tx, tracker) = (config.clone(), tx.clone(), tracker.clone());
-            tokio::spawn(async move { listen_for_zaps(cfg, tx, tracker, cancel_clone).await })
-        },
-        "Boostboard" if config.boostboard.is_some() => {
-            let (cfg, tx, tracker) = (config.clone(), tx.clone(), tracker.clone());
-            tokio::spawn(async move { listen_for_boostboard(cfg, tx, tracker, cancel_clone).await })
-        },
-        "NWC" if config.nwc.is_some() => {
-            let (cfg, tx, tracker) = (config.clone(), tx.clone(), tracker.clone());
-            tokio::spawn(async move { listen_for_nwc(cfg, tx, tracker, cancel_clone).await })
-        },
-        _ => {
-            eprintln!("Cannot start {}: not configured or unknown", name);
-            return;
-        }
-    };
+    // Running totals for VJ software over OSC
+    rt.spawn(osc::broadcast_totals(config.clone(), sat_tracker.clone()));
 
-    handles.lock().await.insert(name.to_string(), (handle, cancel_token));
-}
+    // Payment QR for the audience
+    rt.spawn(payment_qr::run(config.clone(), tx.clone()));
 
-async fn stop_listener(
-    name: &str,
-    handles: &Arc<Mutex<HashMap<String, (JoinHandle<()>, CancellationToken)>>>
-) {
-    if let Some((handle, cancel_token)) = handles.lock().await.remove(name) {
-        println!("Cancelling {} listener...", name);
-        cancel_token.cancel();
-        let _ = tokio::time::timeout(std::time::Duration::from_secs(5), handle).await;
-        println!("{} listener stopped", name);
-    }
-}
+    // Output reachability checks
+    rt.spawn(health::poll_outputs(config.clone(), tx.clone()));
 
-// ============================================================================
-// Main
-// ============================================================================
+    // Scheduled test sequences
+    rt.spawn(test_run::run(effects.clone(), tx.clone()));
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    println!("Starting BlinkyBoosts...");
+    // Restart listeners that die unexpectedly
+    rt.spawn(supervise_listeners(listener_handles.clone(), effects.clone(), tx.clone(), sat_tracker.clone()));
 
-    let config = config::load_config()?;
-    let rt = tokio::runtime::Runtime::new()?;
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<GuiMessage>(100);
-    let sat_tracker = Arc::new(Mutex::new(sat_tracker::SatTracker::new()));
+    // Commands from the GUI, control API and Companion
+    rt.spawn(controller::run(command_rx, tx.clone(), effects.clone(), sat_tracker.clone(), listener_handles.clone()));
 
-    // Setup effects
-    rt.spawn({
-        let config = config.clone();
-        let tx = tx.clone();
-        async move {
-            if let Err(e) = setup_effects(config).await {
-                eprintln!("Error setting up effects: {:#}", e);
-                let _ = tx.send(GuiMessage::UpdateStatus("Effects".to_string(), ComponentStatus::Error(format!("{:#}", e)))).await;
-            }
+    // Relay events to the GUI, mirroring them onto the WebSocket event stream
+    let (gui_tx, gui_rx) = tokio::sync::mpsc::channel::<Event>(100);
+    rt.spawn(async move {
+        while let Some(event) = rx.recv().await {
+            event_stream.publish(&event);
+            let _ = gui_tx.send(event).await;
         }
     });
 
-    // Track listener tasks
-    let listener_handles: Arc<Mutex<HashMap<String, (JoinHandle<()>, CancellationToken)>>> =
-        Arc::new(Mutex::new(HashMap::new()));
-
-    // Start initial listeners
-    rt.spawn({
-        let (handles, config, tx, tracker) = (listener_handles.clone(), config.clone(), tx.clone(), sat_tracker.clone());
-        async move {
-            if config.zaps.is_some() {
-                start_listener("Zaps", &handles, &config, &tx, &tracker).await;
-            }
-            if config.boostboard.is_some() {
-                start_listener("Boostboard", &handles, &config, &tx, &tracker).await;
-            }
-            if config.nwc.is_some() {
-                start_listener("NWC", &handles, &config, &tx, &tracker).await;
-            }
-        }
-    });
+    // The instance running this show asking it to stop
+    if show.is_some() {
+        let (config, handles) = (config.clone(), listener_handles.clone());
+        rt.spawn(async move {
+            shows::await_stop().await;
+            shutdown_gracefully(&config, &handles).await;
+            std::process::exit(0);
+        });
+    }
 
-    // Message handler
-    let (gui_tx, gui_rx) = tokio::sync::mpsc::channel::<GuiMessage>(100);
+    // Ctrl-C
     rt.spawn({
-        let (config, tracker, handles) = (config.clone(), sat_tracker.clone(), listener_handles.clone());
+        let (config, handles, shows) = (config.clone(), listener_handles.clone(), shows.clone());
         async move {
-            while let Some(msg) = rx.recv().await {
-                match msg {
-                    GuiMessage::TestTrigger(sats) => {
-                        println!("Test trigger received for {} sats", sats);
-                        process_boost("Test", sats, &gui_tx, &tracker, &config, true).await;
-                    },
-                    GuiMessage::StartListener(name) => {
-                        println!("Starting listener: {}", name);
-                        start_listener(&name, &handles, &config, &gui_tx, &tracker).await;
-                    },
-                    GuiMessage::StopListener(name) => {
-                        println!("Stopping listener: {}", name);
-                        stop_listener(&name, &handles).await;
-                    },
-                    other => { let _ = gui_tx.send(other).await; }
-                }
+            if tokio::signal::ctrl_c().await.is_ok() {
+                shows.stop_all();
+                shutdown_gracefully(&config, &handles).await;
+                std::process::exit(0);
             }
         }
     });
 
-    gui::run_gui(tx, gui_rx)?;
-    Ok(())
+    let result = if headless {
+        rt.block_on(run_headless(gui_rx));
+        Ok(())
+    } else {
+        gui::run_gui(command_tx, gui_rx, shows.clone(), false)
+    };
+    shows.stop_all();
+    rt.block_on(shutdown_gracefully(&config, &listener_handles));
+    result
 }
\ No newline at end of file
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use serde_json::json;
+use tokio::sync::{broadcast, Mutex};
+
+use blinkyboosts_core::{config, templates};
+use blinkyboosts_core::effects::Effects;
+use blinkyboosts_core::sat_tracker::SatTracker;
+
+use crate::event_stream::{EventStream, StreamEvent};
+
+fn slug(name: &str) -> String {
+    name.to_lowercase().chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+struct Topics {
+    availability: String,
+    total: String,
+    boost: String,
+}
+
+impl Topics {
+    fn new(cfg: &config::Mqtt) -> Self {
+        Self {
+            availability: format!("{}/status", cfg.base_topic),
+            total: format!("{}/total", cfg.base_topic),
+            boost: format!("{}/boost", cfg.base_topic),
+        }
+    }
+
+    fn fire(cfg: &config::Mqtt, toggle: &str) -> String {
+        format!("{}/toggles/{}/fire", cfg.base_topic, slug(toggle))
+    }
+}
+
+/// Publish Home Assistant discovery for the sat total sensor and a button per named toggle,
+/// keep the total up to date, and fire toggles when their buttons are pressed
+pub async fn run(cfg: config::Mqtt, tracker: Arc<Mutex<SatTracker>>, effects: Effects, events: EventStream) {
+    let topics = Topics::new(&cfg);
+
+    let mut options = MqttOptions::new(cfg.client_id.clone(), cfg.host.clone(), cfg.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(&topics.availability, "offline", QoS::AtLeastOnce, true));
+    if let (Some(username), Some(password)) = (&cfg.username, &cfg.password) {
+        options.set_credentials(username, password);
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, 64);
+
+    // Command topic -> toggle name
+    let buttons: HashMap<String, String> = effects.config().toggles.as_deref().unwrap_or_default().iter()
+        .filter_map(|t| t.name.clone())
+        .map(|name| (Topics::fire(&cfg, &name), name))
+        .collect();
+
+    if let Some(template) = cfg.boost_template.clone() {
+        let config = effects.config().clone();
+        tokio::spawn(publish_boosts(client.clone(), topics.boost.clone(), template, config, tracker.clone(), events.clone()));
+    }
+    tokio::spawn(publish_totals(client.clone(), topics.total.clone(), events));
+
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                println!("Connected to MQTT broker {}:{}", cfg.host, cfg.port);
+                let total = tracker.lock().await.total();
+                tokio::spawn(announce(client.clone(), cfg.clone(), buttons.clone(), total));
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                if let Some(name) = buttons.get(&publish.topic) {
+                    let (effects, name) = (effects.clone(), name.clone());
+                    tokio::spawn(async move {
+                        println!("MQTT button pressed: {}", name);
+                        if let Some(Err(e)) = effects.fire_toggle(&name).await {
+                            eprintln!("Failed to fire toggle {} from MQTT: {:#}", name, e);
+                        }
+                    });
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("MQTT connection error: {}", e);
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+/// Publish discovery configs and current state, and subscribe to button commands.
+/// Runs on every (re)connect since the broker may have been restarted.
+async fn announce(client: AsyncClient, cfg: config::Mqtt, buttons: HashMap<String, String>, total: i64) {
+    let topics = Topics::new(&cfg);
+    let device = json!({
+        "identifiers": [cfg.client_id],
+        "name": "BlinkyBoosts",
+        "manufacturer": "BlinkyBoosts",
+    });
+
+    let mut configs = vec![(
+        format!("{}/sensor/{}/total/config", cfg.discovery_prefix, cfg.client_id),
+        json!({
+            "name": "Sat total",
+            "unique_id": format!("{}_total", cfg.client_id),
+            "state_topic": topics.total,
+            "availability_topic": topics.availability,
+            "unit_of_measurement": "sats",
+            "state_class": "total_increasing",
+            "icon": "mdi:lightning-bolt",
+            "device": device,
+        }),
+    )];
+
+    for (command_topic, name) in &buttons {
+        configs.push((
+            format!("{}/button/{}/{}/config", cfg.discovery_prefix, cfg.client_id, slug(name)),
+            json!({
+                "name": format!("Fire {}", name),
+                "unique_id": format!("{}_toggle_{}", cfg.client_id, slug(name)),
+                "command_topic": command_topic,
+                "availability_topic": topics.availability,
+                "device": device,
+            }),
+        ));
+    }
+
+    let result = async {
+        for (topic, payload) in configs {
+            client.publish(topic, QoS::AtLeastOnce, true, payload.to_string()).await?;
+        }
+        for command_topic in buttons.keys() {
+            client.subscribe(command_topic, QoS::AtLeastOnce).await?;
+        }
+        client.publish(&topics.availability, QoS::AtLeastOnce, true, "online").await?;
+        client.publish(&topics.total, QoS::AtLeastOnce, true, total.to_string()).await
+    }.await;
+
+    if let Err(e) = result {
+        eprintln!("Failed to publish Home Assistant discovery: {}", e);
+    }
+}
+
+async fn publish_totals(client: AsyncClient, topic: String, events: EventStream) {
+    let mut events = events.subscribe();
+
+    loop {
+        match events.recv().await {
+            Ok(StreamEvent::Total { total }) => {
+                if let Err(e) = client.publish(&topic, QoS::AtLeastOnce, true, total.to_string()).await {
+                    eprintln!("Failed to publish sat total to MQTT: {}", e);
+                }
+            }
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+/// Publish each boost rendered through `boost_template`
+async fn publish_boosts(client: AsyncClient, topic: String, template: String, config: config::Config, tracker: Arc<Mutex<SatTracker>>, events: EventStream) {
+    let mut events = events.subscribe();
+
+    loop {
+        match events.recv().await {
+            Ok(StreamEvent::Boost { source, sats, sender, message, episode, .. }) => {
+                let total = tracker.lock().await.total();
+                let vars = templates::Vars { source, sats, sender, message, episode, ..Default::default() }
+                    .with_total(&config, total);
+                let payload = templates::render(&template, &vars, "mqtt");
+                if let Err(e) = client.publish(&topic, QoS::AtLeastOnce, false, payload).await {
+                    eprintln!("Failed to publish boost to MQTT: {}", e);
+                }
+            }
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::time::Duration;
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use blinkyboosts_core::config::{self, Config};
+use blinkyboosts_core::messages::{Command, ComponentStatus, Event};
+use blinkyboosts_core::metrics::TriggeredEffect;
+use blinkyboosts_core::{i18n, tags};
+
+use crate::event_stream::StreamEvent;
+
+const COMPONENT: &str = "Agent";
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// What a controller asks an agent to do, sent over the agent's `/events` WebSocket as one JSON
+/// object per message tagged by `command`, e.g. `{"command": "fire_toggle", "name": "Party"}`.
+/// The agent only acts on them from operator tokens.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AgentCommand {
+    Trigger { sats: i64 },
+    StartListener { name: String },
+    StopListener { name: String },
+    FireToggle { name: String },
+    SetTag { tag: String, active: bool },
+    LockEpisode { lock: bool },
+    RunTest,
+    ReplayLastBoost,
+    CaptureScene { name: String },
+    RequestInvoice { sats: u64 },
+    AnnotateBoost { id: u64, text: String },
+    AddMarker { text: String },
+    FinishShow,
+}
+
+impl AgentCommand {
+    /// The agent's version of a GUI command, or None for those acting on the machine the GUI
+    /// runs on (WLED discovery and setup, wallet connection checks, history reloads, its boost
+    /// log, moderation of its own held boosts)
+    pub fn from_command(command: &Command) -> Option<Self> {
+        Some(match command {
+            Command::TestTrigger(sats) => Self::Trigger { sats: *sats },
+            Command::StartListener(name) => Self::StartListener { name: name.clone() },
+            Command::StopListener(name) => Self::StopListener { name: name.clone() },
+            Command::FireToggle(name) => Self::FireToggle { name: name.clone() },
+            Command::SetTagActive(tag, active) => Self::SetTag { tag: tag.clone(), active: *active },
+            Command::LockEpisode(lock) => Self::LockEpisode { lock: *lock },
+            Command::RunTest => Self::RunTest,
+            Command::ReplayLastBoost => Self::ReplayLastBoost,
+            Command::CaptureScene(name) => Self::CaptureScene { name: name.clone() },
+            Command::RequestInvoice(sats) => Self::RequestInvoice { sats: *sats },
+            Command::AnnotateBoost(id, text) => Self::AnnotateBoost { id: *id, text: text.clone() },
+            Command::AddMarker(text) => Self::AddMarker { text: text.clone() },
+            Command::FinishShow => Self::FinishShow,
+            Command::ModerateBoost(..) | Command::LoadWledEffects(_) | Command::DiscoverWled | Command::CheckNwc(_)
+                | Command::ApplyWledSetup(_) | Command::ReloadHistory(..) | Command::LoadOlderBoosts(..) => return None,
+        })
+    }
+
+    pub fn into_command(self) -> Command {
+        match self {
+            Self::Trigger { sats } => Command::TestTrigger(sats),
+            Self::StartListener { name } => Command::StartListener(name),
+            Self::StopListener { name } => Command::StopListener(name),
+            Self::FireToggle { name } => Command::FireToggle(name),
+            Self::SetTag { tag, active } => Command::SetTagActive(tag, active),
+            Self::LockEpisode { lock } => Command::LockEpisode(lock),
+            Self::RunTest => Command::RunTest,
+            Self::ReplayLastBoost => Command::ReplayLastBoost,
+            Self::CaptureScene { name } => Command::CaptureScene(name),
+            Self::RequestInvoice { sats } => Command::RequestInvoice(sats),
+            Self::AnnotateBoost { id, text } => Command::AnnotateBoost(id, text),
+            Self::AddMarker { text } => Command::AddMarker(text),
+            Self::FinishShow => Command::FinishShow,
+        }
+    }
+}
+
+/// A boost from the agent, waiting for the effect events that follow it
+struct PendingBoost {
+    event: StreamEvent,
+    effects: Vec<TriggeredEffect>,
+}
+
+impl PendingBoost {
+    fn expected(&self) -> usize {
+        match &self.event {
+            StreamEvent::Boost { effects, .. } => effects.len(),
+            _ => 0,
+        }
+    }
+
+    fn into_event(self) -> Option<Event> {
+        let StreamEvent::Boost { id, source, sats, sender, message, episode, remote_item, artwork, relay_delay_ms, .. } = self.event else {
+            return None;
+        };
+        let relay_delay = relay_delay_ms.map(|ms| Duration::from_millis(ms as u64));
+        Some(Event::BoostReceived(id, source, sats, sender, message, episode, remote_item, artwork, self.effects, relay_delay))
+    }
+}
+
+/// What settles a GUI waiting on a command the agent can't be asked for, e.g. a spinner
+fn unavailable(command: &Command) -> Option<Event> {
+    match command {
+        Command::DiscoverWled => Some(Event::WledControllers(Vec::new())),
+        Command::CheckNwc(_) => Some(Event::NwcChecked(Err(i18n::t("remote-unavailable")))),
+        Command::LoadOlderBoosts(..) => Some(Event::OlderBoosts(Vec::new(), 0)),
+        _ => None,
+    }
+}
+
+/// The GUI's view of an agent event. Boosts are held until their effects have arrived.
+fn to_events(event: StreamEvent, pending: &mut Option<PendingBoost>) -> Vec<Event> {
+    let mut events = Vec::new();
+    if let StreamEvent::Effect { description, output, queued_ms, sent_ms } = &event {
+        if let Some(boost) = pending.as_mut() {
+            boost.effects.push(TriggeredEffect {
+                description: description.clone(),
+                output: output.clone(),
+                queued: Duration::from_millis(*queued_ms as u64),
+                sent: Duration::from_millis(*sent_ms as u64),
+                skipped: false,
+            });
+            if boost.effects.len() >= boost.expected() {
+                events.extend(pending.take().and_then(PendingBoost::into_event));
+            }
+        }
+        return events;
+    }
+    events.extend(pending.take().and_then(PendingBoost::into_event));
+
+    let event = match event {
+        StreamEvent::Boost { .. } => {
+            let boost = PendingBoost { event, effects: Vec::new() };
+            match boost.expected() {
+                0 => boost.into_event(),
+                _ => {
+                    *pending = Some(boost);
+                    None
+                }
+            }
+        }
+        StreamEvent::Effect { .. } => None,
+        StreamEvent::EffectFailed { description, error, attempts } => Some(Event::EffectFailed(description, error, attempts)),
+        StreamEvent::Episode { episode } => Some(Event::EpisodeStarted(episode)),
+        StreamEvent::EpisodeLock { episode } => Some(Event::EpisodeLocked(episode)),
+        StreamEvent::Backfill { source, loaded, at, done } => Some(Event::BackfillProgress(source, loaded, at, done)),
+        StreamEvent::Campaign { name, total, target_sats } => Some(Event::CampaignTotal(name, total, target_sats)),
+        StreamEvent::Total { total } => Some(Event::UpdateSatTotal(total)),
+        StreamEvent::Summary { summary } => Some(Event::ShowSummary(summary)),
+        StreamEvent::Note { note } => Some(Event::Noted(note)),
+        StreamEvent::TestRun { report } => Some(Event::TestRun(report)),
+        StreamEvent::Streak { sender, streak, milestone } => Some(Event::Streak(sender, streak, milestone)),
+        StreamEvent::Wallet { alias, balance_msats, latency_ms, notifications } => Some(Event::WalletStatus(blinkyboosts_core::nwc::WalletStatus {
+            alias, balance_msats, latency: Duration::from_millis(latency_ms as u64), notifications,
+        })),
+        StreamEvent::Relay { source, round_trip_ms } => Some(Event::RelayRoundTrip(source, Duration::from_millis(round_trip_ms as u64))),
+        StreamEvent::Status { component, status, detail } => Some(Event::UpdateStatus(component, ComponentStatus::from_text(&status, detail))),
+        StreamEvent::PaymentQr { uri } => Some(Event::PaymentQr(uri)),
+        StreamEvent::InvoiceRequested { uri, sats } => Some(Event::InvoiceRequested(uri, sats)),
+        StreamEvent::InvoiceSettled { sats, paid } => Some(Event::InvoiceSettled(sats, paid)),
+    };
+    events.extend(event);
+    events
+}
+
+async fn connect(cfg: &config::Remote) -> Result<Socket> {
+    let url = format!("{}/events", cfg.url.trim_end_matches('/'));
+    let mut request = url.as_str().into_client_request().context(format!("Invalid agent URL {}", cfg.url))?;
+    if let Some(token) = &cfg.token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token)).context("Invalid agent token")?;
+        request.headers_mut().insert("Authorization", value);
+    }
+    let (socket, _) = tokio_tungstenite::connect_async(request).await
+        .context(format!("Failed to connect to agent at {}", url))?;
+    Ok(socket)
+}
+
+/// Pass commands and events until the connection drops (an error) or the GUI closes
+async fn session(config: &Config, socket: Socket, commands: &mut mpsc::Receiver<Command>, events: &mpsc::Sender<Event>) -> Result<()> {
+    let (mut outgoing, mut incoming) = socket.split();
+    let mut pending = None;
+
+    loop {
+        tokio::select! {
+            command = commands.recv() => {
+                let Some(command) = command else { return Ok(()) };
+                let Some(command) = AgentCommand::from_command(&command) else {
+                    eprintln!("That action isn't available while controlling an agent");
+                    if let Some(event) = unavailable(&command) {
+                        let _ = events.send(event).await;
+                    }
+                    continue;
+                };
+                // Mirrored here so the GUI shows the tags as set
+                if let AgentCommand::SetTag { tag, active } = &command {
+                    tags::set_active(config, tag, *active);
+                }
+                let json = serde_json::to_string(&command)?;
+                outgoing.send(Message::Text(json)).await.context("Failed to send to agent")?;
+            }
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(json))) => match serde_json::from_str::<StreamEvent>(&json) {
+                    Ok(event) => {
+                        for event in to_events(event, &mut pending) {
+                            let _ = events.send(event).await;
+                        }
+                    }
+                    Err(e) => eprintln!("Ignoring agent event: {:#}", e),
+                },
+                Some(Ok(Message::Close(_))) | None => anyhow::bail!("Agent closed the connection"),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e).context("Agent connection failed"),
+            },
+        }
+    }
+}
+
+/// Control the agent at `[remote] url` from this GUI: its commands go to the agent, and the
+/// agent's events come back as if the pipeline ran here. Reconnects until the GUI closes.
+pub async fn run(config: Config, mut commands: mpsc::Receiver<Command>, events: mpsc::Sender<Event>) {
+    let Some(cfg) = config.remote.clone() else { return };
+    tags::configure(&config);
+    loop {
+        let result = match connect(&cfg).await {
+            Ok(socket) => {
+                println!("Connected to agent at {}", cfg.url);
+                let _ = events.send(Event::UpdateStatus(COMPONENT.to_string(), ComponentStatus::Running)).await;
+                session(&config, socket, &mut commands, &events).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => return,
+            Err(e) => {
+                eprintln!("{:#}, retrying in {}s", e, RETRY_DELAY.as_secs());
+                let _ = events.send(Event::UpdateStatus(COMPONENT.to_string(), ComponentStatus::Unreachable(format!("{:#}", e)))).await;
+            }
+        }
+
+        // Commands can't be carried out late, so they're dropped while disconnected
+        let retry = tokio::time::sleep(RETRY_DELAY);
+        tokio::pin!(retry);
+        loop {
+            tokio::select! {
+                _ = &mut retry => break,
+                command = commands.recv() => match command {
+                    Some(_) => eprintln!("Not connected to the agent"),
+                    None => return,
+                },
+            }
+        }
+    }
+}
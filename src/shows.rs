@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use blinkyboosts_core::amounts;
+use blinkyboosts_core::config::{self, Config};
+
+use crate::event_stream::StreamEvent;
+
+/// Prefix for the event lines a show writes to stdout for the instance running it
+const EVENT_PREFIX: &str = "@event ";
+const LOG_LINES: usize = 500;
+const RECENT_BOOSTS: usize = 50;
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);  // To shut down gracefully before being killed
+
+/// What the GUI shows for one `[[shows]]` entry, kept up to date from its output
+#[derive(Clone, Default)]
+pub struct ShowState {
+    pub name: String,
+    pub dir: String,
+    pub running: bool,
+    pub runs: u64,  // Times started, so output from a replaced process can't mark the new one stopped
+    pub exit: Option<String>,  // Why the process stopped
+    pub total: i64,
+    pub episode: Option<String>,
+    pub statuses: BTreeMap<String, (String, Option<String>)>,  // component: status, detail
+    pub boosts: VecDeque<String>,  // Newest last
+    pub log: VecDeque<String>,
+}
+
+struct Show {
+    cfg: config::Show,
+    child: Option<Child>,
+    state: Arc<Mutex<ShowState>>,
+}
+
+/// The shows this instance runs, each as its own `listen --headless` process started in the
+/// show's directory, so its config.toml, tracker, toggles, outputs and state files are its own
+#[derive(Clone, Default)]
+pub struct Shows {
+    shows: Arc<Mutex<Vec<Show>>>,
+}
+
+impl Shows {
+    /// Start every configured show
+    pub fn start(config: &Config) -> Self {
+        let shows = Self::default();
+        for cfg in config.shows.as_deref().unwrap_or_default() {
+            let state = ShowState { name: cfg.name.clone(), dir: cfg.dir.clone(), ..Default::default() };
+            shows.shows.lock().unwrap().push(Show { cfg: cfg.clone(), child: None, state: Arc::new(Mutex::new(state)) });
+            shows.restart(&cfg.name);
+        }
+        shows
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.shows.lock().unwrap().iter().map(|show| show.cfg.name.clone()).collect()
+    }
+
+    pub fn state(&self, name: &str) -> Option<ShowState> {
+        self.shows.lock().unwrap().iter()
+            .find(|show| show.cfg.name == name)
+            .map(|show| show.state.lock().unwrap().clone())
+    }
+
+    /// Stop a show's process if it's running and start it again
+    pub fn restart(&self, name: &str) {
+        let mut shows = self.shows.lock().unwrap();
+        let Some(show) = shows.iter_mut().find(|show| show.cfg.name == name) else { return };
+        stop(show);
+
+        match spawn(&show.cfg, &show.state) {
+            Ok(child) => {
+                println!("Started show {} in {}", show.cfg.name, show.cfg.dir);
+                show.child = Some(child);
+            }
+            Err(e) => {
+                eprintln!("Failed to start show {}: {:#}", show.cfg.name, e);
+                let mut state = show.state.lock().unwrap();
+                state.running = false;
+                state.exit = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    pub fn stop(&self, name: &str) {
+        let mut shows = self.shows.lock().unwrap();
+        if let Some(show) = shows.iter_mut().find(|show| show.cfg.name == name) {
+            stop(show);
+        }
+    }
+
+    pub fn stop_all(&self) {
+        let mut shows = self.shows.lock().unwrap();
+        // Ask them all first so they shut down side by side
+        for show in shows.iter_mut() {
+            ask_to_stop(show);
+        }
+        for show in shows.iter_mut() {
+            stop(show);
+        }
+    }
+}
+
+/// Close the show's stdin, which it takes as the cue to shut down gracefully (see `await_stop`)
+fn ask_to_stop(show: &mut Show) {
+    if let Some(child) = show.child.as_mut() {
+        drop(child.stdin.take());
+    }
+}
+
+/// Ask the show to shut down, killing it if it hasn't within `STOP_TIMEOUT`
+fn stop(show: &mut Show) {
+    ask_to_stop(show);
+    let Some(mut child) = show.child.take() else { return };
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while matches!(child.try_wait(), Ok(None)) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    if matches!(child.try_wait(), Ok(None)) {
+        eprintln!("Show {} didn't shut down within {}s, killing it", show.cfg.name, STOP_TIMEOUT.as_secs());
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+    let mut state = show.state.lock().unwrap();
+    state.running = false;
+    state.exit = Some("Stopped".to_string());
+}
+
+fn spawn(cfg: &config::Show, state: &Arc<Mutex<ShowState>>) -> Result<Child> {
+    let exe = std::env::current_exe().context("Unable to find the BlinkyBoosts executable")?;
+    let mut child = Command::new(exe)
+        .args(["listen", "--headless", "--show", &cfg.name])
+        .current_dir(&cfg.dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Unable to start BlinkyBoosts in {}", cfg.dir))?;
+
+    let run = {
+        let mut state = state.lock().unwrap();
+        state.running = true;
+        state.runs += 1;
+        state.exit = None;
+        state.runs
+    };
+
+    let stdout = child.stdout.take().context("No stdout from show")?;
+    let stderr = child.stderr.take().context("No stderr from show")?;
+    read_output(stdout, state.clone(), Some(run));
+    read_output(stderr, state.clone(), None);
+    Ok(child)
+}
+
+/// Follow a show's output until it exits, applying its events and keeping the rest as log lines.
+/// The end of stdout for `run` marks the show stopped.
+fn read_output(output: impl Read + Send + 'static, state: Arc<Mutex<ShowState>>, run: Option<u64>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            let mut state = state.lock().unwrap();
+            match line.strip_prefix(EVENT_PREFIX).and_then(|json| serde_json::from_str::<Value>(json).ok()) {
+                Some(event) => apply(&mut state, &event),
+                None => {
+                    state.log.push_back(line);
+                    if state.log.len() > LOG_LINES {
+                        state.log.pop_front();
+                    }
+                }
+            }
+        }
+
+        if run.is_some() {
+            let mut state = state.lock().unwrap();
+            if state.running && Some(state.runs) == run {
+                state.running = false;
+                state.exit.get_or_insert_with(|| "Exited".to_string());
+            }
+        }
+    });
+}
+
+/// Update a show's state from one of its event stream events
+fn apply(state: &mut ShowState, event: &Value) {
+    let text = |key: &str| event.get(key).and_then(Value::as_str).map(str::to_string);
+    match event.get("type").and_then(Value::as_str) {
+        Some("total") => state.total = event.get("total").and_then(Value::as_i64).unwrap_or(state.total),
+        Some("episode") => state.episode = text("episode"),
+        Some("status") => {
+            if let (Some(component), Some(status)) = (text("component"), text("status")) {
+                state.statuses.insert(component, (status, text("detail")));
+            }
+        }
+        Some("boost") => {
+            let effects: Vec<_> = event.get("effects").and_then(Value::as_array).into_iter().flatten()
+                .filter_map(Value::as_str)
+                .collect();
+            let mut line = format!("{} sats from {} via {}",
+                amounts::format_sats(event.get("sats").and_then(Value::as_i64).unwrap_or(0)),
+                text("sender").unwrap_or_else(|| "anonymous".to_string()),
+                text("source").unwrap_or_default());
+            if let Some(item) = text("remote_item") {
+                line = format!("{} for {}", line, item);
+            }
+            if !effects.is_empty() {
+                line = format!("{} - {}", line, effects.join(", "));
+            }
+            state.boosts.push_back(line);
+            if state.boosts.len() > RECENT_BOOSTS {
+                state.boosts.pop_front();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Inside a show's process: wait for the instance that started it to close stdin, asking it to stop
+pub async fn await_stop() {
+    let mut stdin = tokio::io::stdin();
+    let mut buf = [0u8; 64];
+    while let Ok(1..) = tokio::io::AsyncReadExt::read(&mut stdin, &mut buf).await {}
+}
+
+/// Inside a show's process: write every event to stdout for the instance that started it
+pub async fn report_events(mut rx: broadcast::Receiver<StreamEvent>) {
+    loop {
+        match rx.recv().await {
+            Ok(event) => match serde_json::to_string(&event) {
+                Ok(json) => println!("{}{}", EVENT_PREFIX, json),
+                Err(e) => eprintln!("Failed to encode event: {}", e),
+            },
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}